@@ -0,0 +1,1804 @@
+//! Account contexts of the LeapfrogDAO governance instructions
+//!
+//! Each context takes the fixed accounts from the front of an instruction's account list, in
+//! the order documented on the matching LeapfrogInstruction variant, and checks their count,
+//! signer flags and owners before the handler reads them by name. Optional and variable
+//! accounts following them are left in `remaining` for the handler to take
+
+use crate::{assert_signer, next_account_with_role, GovernanceError};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey,
+    system_program,
+};
+
+/// Asserts the account is owned by the given program, logging its role otherwise
+fn assert_account_owner(account_info: &AccountInfo, owner: &Pubkey, role: &'static str) -> ProgramResult {
+    if account_info.owner != owner {
+        msg!("Invalid account owner: {}", role);
+        return Err(GovernanceError::InvalidAccountOwner.into());
+    }
+
+    Ok(())
+}
+
+/// Asserts the account is the given program, logging its role otherwise
+pub(crate) fn assert_program_account(
+    account_info: &AccountInfo,
+    program_id: &Pubkey,
+    role: &'static str,
+) -> ProgramResult {
+    if account_info.key != program_id {
+        msg!("Incorrect program id: {}", role);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(())
+}
+
+/// Accounts of the InitializeRealm instruction
+pub struct InitializeRealmAccounts<'a, 'b> {
+    /// Funding account, becomes the realm's bootstrap authority
+    pub funder: &'a AccountInfo<'b>,
+
+    /// Realm account to create
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Community token mint
+    pub community_mint: &'a AccountInfo<'b>,
+
+    /// Council token mint, if the realm has one
+    pub council_mint: Option<&'a AccountInfo<'b>>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Accounts following the fixed ones
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> InitializeRealmAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let funder = next_account_with_role(account_info_iter, "funder")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+        let council_mint = account_info_iter.next();
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(funder, "funder")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            funder,
+            realm,
+            community_mint,
+            council_mint,
+            system_program,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CreateProposal instruction
+pub struct CreateProposalAccounts<'a, 'b> {
+    /// Proposal owner account
+    pub proposal_owner: &'a AccountInfo<'b>,
+
+    /// Proposal account to create
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Token owner record of the proposal owner
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Governance authority of the token owner record
+    pub governance_authority: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Realm account the governance belongs to
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Treasury, prerequisite proposal and community mint, as far as the proposal needs them
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CreateProposalAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_owner = next_account_with_role(account_info_iter, "proposal_owner")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let governance_authority = next_account_with_role(account_info_iter, "governance_authority")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+
+        assert_signer(proposal_owner, "proposal_owner")?;
+        assert_signer(proposal, "proposal")?;
+        assert_signer(governance_authority, "governance_authority")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            proposal_owner,
+            proposal,
+            governance,
+            token_owner_record,
+            governance_authority,
+            system_program,
+            realm,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CastVote and CastDelegatedVote instructions
+pub struct CastVoteAccounts<'a, 'b> {
+    /// Token owner or governance delegate casting the vote
+    pub governance_authority: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Token owner record of the voter
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Governance token account of the voter
+    pub voter_token_account: &'a AccountInfo<'b>,
+
+    /// Empty vote record the vote is saved into
+    pub vote_record: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Community mint of the realm, read to check quorum once voting has ended
+    pub community_mint: &'a AccountInfo<'b>,
+
+    /// The realm's weight oracle, if it has one
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CastVoteAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance_authority = next_account_with_role(account_info_iter, "governance_authority")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let voter_token_account = next_account_with_role(account_info_iter, "voter_token_account")?;
+        let vote_record = next_account_with_role(account_info_iter, "vote_record")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+
+        assert_signer(governance_authority, "governance_authority")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            governance_authority,
+            proposal,
+            token_owner_record,
+            voter_token_account,
+            vote_record,
+            governance,
+            realm,
+            community_mint,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CreateVoteRecord instruction
+pub struct CreateVoteRecordAccounts<'a, 'b> {
+    /// Token owner or governance delegate, paying for the vote record
+    pub voter: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Token owner record of the voter
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Vote record account to create
+    pub vote_record: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CreateVoteRecordAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let voter = next_account_with_role(account_info_iter, "voter")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let vote_record = next_account_with_role(account_info_iter, "vote_record")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(voter, "voter")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            voter,
+            proposal,
+            token_owner_record,
+            vote_record,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the CastDelegatedVotes instruction
+pub struct CastDelegatedVotesAccounts<'a, 'b> {
+    /// Governance delegate casting the votes, paying for the vote records it creates
+    pub governance_delegate: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Token owner record and vote record pairs of the delegations voted with
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CastDelegatedVotesAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance_delegate = next_account_with_role(account_info_iter, "governance_delegate")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(governance_delegate, "governance_delegate")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            governance_delegate,
+            proposal,
+            governance,
+            realm,
+            system_program,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+/// Accounts of the ExecuteProposal instruction
+pub struct ExecuteProposalAccounts<'a, 'b> {
+    /// Governance authority
+    pub governance_authority: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Token owner record of the proposal owner
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Proposal transaction of the winning option
+    pub proposal_transaction: &'a AccountInfo<'b>,
+
+    /// The prerequisite proposal if there's one, followed by the accounts of the transaction's
+    /// instructions
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> ExecuteProposalAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance_authority = next_account_with_role(account_info_iter, "governance_authority")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let proposal_transaction = next_account_with_role(account_info_iter, "proposal_transaction")?;
+
+        assert_signer(governance_authority, "governance_authority")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(proposal_transaction, program_id, "proposal_transaction")?;
+
+        Ok(Self {
+            governance_authority,
+            proposal,
+            token_owner_record,
+            governance,
+            proposal_transaction,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CreateProposalBundle instruction
+pub struct CreateProposalBundleAccounts<'a, 'b> {
+    /// Owner of every bundled proposal, paying for the bundle
+    pub creator: &'a AccountInfo<'b>,
+
+    /// Proposal bundle account to create
+    pub proposal_bundle: &'a AccountInfo<'b>,
+
+    /// Governance account the proposals belong to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Draft proposals to bundle, in execution order
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CreateProposalBundleAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let creator = next_account_with_role(account_info_iter, "creator")?;
+        let proposal_bundle = next_account_with_role(account_info_iter, "proposal_bundle")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(creator, "creator")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            creator,
+            proposal_bundle,
+            governance,
+            system_program,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the ExecuteProposalBundle instruction
+pub struct ExecuteProposalBundleAccounts<'a, 'b> {
+    /// Proposal bundle account
+    pub proposal_bundle: &'a AccountInfo<'b>,
+
+    /// Governance account the bundle belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Keeper paid the executor bounty of each bundled proposal
+    pub keeper: &'a AccountInfo<'b>,
+
+    /// Each bundled proposal followed by its transactions and their instructions' accounts
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> ExecuteProposalBundleAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_bundle = next_account_with_role(account_info_iter, "proposal_bundle")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let keeper = next_account_with_role(account_info_iter, "keeper")?;
+
+        assert_account_owner(proposal_bundle, program_id, "proposal_bundle")?;
+        assert_account_owner(governance, program_id, "governance")?;
+
+        Ok(Self {
+            proposal_bundle,
+            governance,
+            keeper,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the StakeTokens instruction
+pub struct StakeTokensAccounts<'a, 'b> {
+    /// Token owner account
+    pub token_owner: &'a AccountInfo<'b>,
+
+    /// Token account to stake from
+    pub source_token: &'a AccountInfo<'b>,
+
+    /// Staking vault account
+    pub staking_vault: &'a AccountInfo<'b>,
+
+    /// Token owner record, created on the first stake
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> StakeTokensAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let token_owner = next_account_with_role(account_info_iter, "token_owner")?;
+        let source_token = next_account_with_role(account_info_iter, "source_token")?;
+        let staking_vault = next_account_with_role(account_info_iter, "staking_vault")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(token_owner, "token_owner")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            token_owner,
+            source_token,
+            staking_vault,
+            token_owner_record,
+            token_program,
+            realm,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the UnstakeTokens, EmergencyUnstake and UnstakeEarly instructions
+pub struct UnstakeTokensAccounts<'a, 'b> {
+    /// Token owner account
+    pub token_owner: &'a AccountInfo<'b>,
+
+    /// Staking vault account
+    pub staking_vault: &'a AccountInfo<'b>,
+
+    /// Token account receiving the unstaked tokens
+    pub destination_token: &'a AccountInfo<'b>,
+
+    /// Token owner record
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+
+    /// The clock sysvar
+    pub clock: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Staking vault authority
+    pub vault_authority: &'a AccountInfo<'b>,
+
+    /// The early unstake fee treasury, for UnstakeEarly
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> UnstakeTokensAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let token_owner = next_account_with_role(account_info_iter, "token_owner")?;
+        let staking_vault = next_account_with_role(account_info_iter, "staking_vault")?;
+        let destination_token = next_account_with_role(account_info_iter, "destination_token")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+        let clock = next_account_with_role(account_info_iter, "clock")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let vault_authority = next_account_with_role(account_info_iter, "vault_authority")?;
+
+        assert_signer(token_owner, "token_owner")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+
+        Ok(Self {
+            token_owner,
+            staking_vault,
+            destination_token,
+            token_owner_record,
+            token_program,
+            clock,
+            realm,
+            vault_authority,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CreateGovernance instruction
+pub struct CreateGovernanceAccounts<'a, 'b> {
+    /// Funding account
+    pub funder: &'a AccountInfo<'b>,
+
+    /// Governance account to create
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account the governance belongs to
+    pub realm: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// The realm bootstrap authority and its co-signers, when the governed account is the realm
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CreateGovernanceAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let funder = next_account_with_role(account_info_iter, "funder")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(funder, "funder")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            funder,
+            governance,
+            realm,
+            system_program,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the SignOffProposal instruction
+pub struct SignOffProposalAccounts<'a, 'b> {
+    /// Proposal owner account
+    pub proposal_owner: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account the governance belongs to
+    pub realm: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SignOffProposalAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_owner = next_account_with_role(account_info_iter, "proposal_owner")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+
+        assert_signer(proposal_owner, "proposal_owner")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            proposal_owner,
+            proposal,
+            governance,
+            realm,
+        })
+    }
+}
+
+/// Accounts of the FinalizeVote instruction
+pub struct FinalizeVoteAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// The community token mint
+    pub community_mint: &'a AccountInfo<'b>,
+
+    /// Latest proposal archive page
+    pub proposal_archive: &'a AccountInfo<'b>,
+
+    /// Proposal certificate account to create
+    pub proposal_certificate: &'a AccountInfo<'b>,
+
+    /// Payer of the certificate and of archive pages
+    pub payer: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Accounts an archive page rollover takes: the payer, the system program and the next page
+    pub archive_rollover: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> FinalizeVoteAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+        let proposal_archive = next_account_with_role(account_info_iter, "proposal_archive")?;
+        let proposal_certificate = next_account_with_role(account_info_iter, "proposal_certificate")?;
+        let archive_rollover = account_info_iter.as_slice();
+        let payer = next_account_with_role(account_info_iter, "payer")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            proposal,
+            governance,
+            realm,
+            community_mint,
+            proposal_archive,
+            proposal_certificate,
+            payer,
+            system_program,
+            archive_rollover,
+        })
+    }
+}
+
+/// Accounts of the FinalizeVotes instruction
+pub struct FinalizeVotesAccounts<'a, 'b> {
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// The community token mint
+    pub community_mint: &'a AccountInfo<'b>,
+
+    /// Latest proposal archive page
+    pub proposal_archive: &'a AccountInfo<'b>,
+
+    /// Payer of the certificates and archive pages the finalizations create
+    pub payer: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Next proposal archive page, created if the latest page fills up
+    pub next_proposal_archive: &'a AccountInfo<'b>,
+
+    /// Accounts an archive page rollover takes: the payer, the system program and the next page
+    pub archive_rollover: &'a [AccountInfo<'b>],
+
+    /// The proposal, governance and proposal certificate of every proposal to finalize
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> FinalizeVotesAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+        let proposal_archive = next_account_with_role(account_info_iter, "proposal_archive")?;
+        let archive_rollover = account_info_iter.as_slice();
+        let payer = next_account_with_role(account_info_iter, "payer")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+        let next_proposal_archive = next_account_with_role(account_info_iter, "next_proposal_archive")?;
+
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            realm,
+            community_mint,
+            proposal_archive,
+            payer,
+            system_program,
+            next_proposal_archive,
+            archive_rollover: &archive_rollover[..3],
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the InsertProposalTransaction instruction
+pub struct InsertProposalTransactionAccounts<'a, 'b> {
+    /// Proposal owner account
+    pub proposal_owner: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Proposal transaction account to create
+    pub proposal_transaction: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> InsertProposalTransactionAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_owner = next_account_with_role(account_info_iter, "proposal_owner")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let proposal_transaction = next_account_with_role(account_info_iter, "proposal_transaction")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+
+        assert_signer(proposal_owner, "proposal_owner")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            proposal_owner,
+            proposal,
+            proposal_transaction,
+            system_program,
+            governance,
+        })
+    }
+}
+
+/// Accounts of the instructions the realm authority signs without further accounts:
+/// SetRealmConfig, SetRealmAuthority, AddProposalCreator, RemoveProposalCreator,
+/// SetRealmLifecycle and SetMaxVoteWeightSource
+pub struct RealmAuthorityAccounts<'a, 'b> {
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Realm governance, or the bootstrap authority before it exists
+    pub realm_authority: &'a AccountInfo<'b>,
+
+    /// The other signing members of a multi-sig bootstrap authority
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> RealmAuthorityAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let realm_authority = next_account_with_role(account_info_iter, "realm_authority")?;
+
+        assert_signer(realm_authority, "realm_authority")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            realm,
+            realm_authority,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the SetVoteWeightSource instruction
+pub struct SetVoteWeightSourceAccounts<'a, 'b> {
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Realm governance, or the bootstrap authority before it exists
+    pub realm_authority: &'a AccountInfo<'b>,
+
+    /// Mint backing the new source, the community mint or the collection's NFT mint
+    pub source_mint: &'a AccountInfo<'b>,
+
+    /// The other signing members of a multi-sig bootstrap authority
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> SetVoteWeightSourceAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let realm_authority = next_account_with_role(account_info_iter, "realm_authority")?;
+        let source_mint = next_account_with_role(account_info_iter, "source_mint")?;
+
+        assert_signer(realm_authority, "realm_authority")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            realm,
+            realm_authority,
+            source_mint,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the MigrateGovernance instruction
+pub struct MigrateGovernanceAccounts<'a, 'b> {
+    /// Governance account to migrate
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Payer of the extra rent
+    pub payer: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> MigrateGovernanceAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let payer = next_account_with_role(account_info_iter, "payer")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(payer, "payer")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            governance,
+            payer,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the AddAccountDiscriminator instruction
+pub struct AddAccountDiscriminatorAccounts<'a, 'b> {
+    /// Governance program account to prefix with its discriminator
+    pub account: &'a AccountInfo<'b>,
+
+    /// Payer of the extra rent
+    pub payer: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> AddAccountDiscriminatorAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let account = next_account_with_role(account_info_iter, "account")?;
+        let payer = next_account_with_role(account_info_iter, "payer")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(payer, "payer")?;
+        assert_account_owner(account, program_id, "account")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            account,
+            payer,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the CloseRealm instruction
+pub struct CloseRealmAccounts<'a, 'b> {
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Realm governance, or the bootstrap authority before it exists
+    pub realm_authority: &'a AccountInfo<'b>,
+
+    /// Beneficiary receiving the realm's lamports
+    pub beneficiary: &'a AccountInfo<'b>,
+
+    /// Every governance account of the realm, then the other signing members of a multi-sig
+    /// bootstrap authority
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> CloseRealmAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let realm_authority = next_account_with_role(account_info_iter, "realm_authority")?;
+        let beneficiary = next_account_with_role(account_info_iter, "beneficiary")?;
+
+        assert_signer(realm_authority, "realm_authority")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            realm,
+            realm_authority,
+            beneficiary,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the SetSybilScore instruction
+pub struct SetSybilScoreAccounts<'a, 'b> {
+    /// Sybil oracle of the realm
+    pub sybil_oracle: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Token owner record to score
+    pub token_owner_record: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SetSybilScoreAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let sybil_oracle = next_account_with_role(account_info_iter, "sybil_oracle")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+
+        assert_signer(sybil_oracle, "sybil_oracle")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+
+        Ok(Self {
+            sybil_oracle,
+            realm,
+            token_owner_record,
+        })
+    }
+}
+
+/// Accounts of the ClaimInitialVotingPower instruction
+pub struct ClaimInitialVotingPowerAccounts<'a, 'b> {
+    /// Claimant account, paying for the created accounts
+    pub claimant: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Token owner record of the claimant, created if the claimant hasn't staked yet
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Voting power claim account to create
+    pub voting_power_claim: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ClaimInitialVotingPowerAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let claimant = next_account_with_role(account_info_iter, "claimant")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let voting_power_claim = next_account_with_role(account_info_iter, "voting_power_claim")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        assert_signer(claimant, "claimant")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            claimant,
+            realm,
+            token_owner_record,
+            voting_power_claim,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the CreateVestingStream instruction
+pub struct CreateVestingStreamAccounts<'a, 'b> {
+    /// Governance account paying the stream
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Vesting stream account to create
+    pub vesting_stream: &'a AccountInfo<'b>,
+
+    /// Treasury token account the stream is paid from
+    pub treasury: &'a AccountInfo<'b>,
+
+    /// Payer of the vesting stream account
+    pub payer: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CreateVestingStreamAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let vesting_stream = next_account_with_role(account_info_iter, "vesting_stream")?;
+        let treasury = next_account_with_role(account_info_iter, "treasury")?;
+        let payer = next_account_with_role(account_info_iter, "payer")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+
+        // Only a proposal transaction of the governance can sign for it
+        assert_signer(governance, "governance")?;
+        assert_signer(payer, "payer")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(treasury, &spl_token::id(), "treasury")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+
+        Ok(Self {
+            governance,
+            vesting_stream,
+            treasury,
+            payer,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of the ClaimVestedFunds instruction
+pub struct ClaimVestedFundsAccounts<'a, 'b> {
+    /// Recipient of the vesting stream
+    pub recipient: &'a AccountInfo<'b>,
+
+    /// Vesting stream account
+    pub vesting_stream: &'a AccountInfo<'b>,
+
+    /// Governance account paying the stream
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Treasury token account the stream is paid from
+    pub treasury: &'a AccountInfo<'b>,
+
+    /// Token account receiving the claimed tokens
+    pub destination_token: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ClaimVestedFundsAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let recipient = next_account_with_role(account_info_iter, "recipient")?;
+        let vesting_stream = next_account_with_role(account_info_iter, "vesting_stream")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let treasury = next_account_with_role(account_info_iter, "treasury")?;
+        let destination_token = next_account_with_role(account_info_iter, "destination_token")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+
+        assert_signer(recipient, "recipient")?;
+        assert_account_owner(vesting_stream, program_id, "vesting_stream")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+
+        Ok(Self {
+            recipient,
+            vesting_stream,
+            governance,
+            treasury,
+            destination_token,
+            token_program,
+        })
+    }
+}
+
+/// Accounts of the PrecheckExecution instruction
+pub struct PrecheckExecutionAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Proposal transaction of the winning option
+    pub proposal_transaction: &'a AccountInfo<'b>,
+
+    /// Source token account of every SPL Token transfer in the transaction
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> PrecheckExecutionAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let proposal_transaction = next_account_with_role(account_info_iter, "proposal_transaction")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(proposal_transaction, program_id, "proposal_transaction")?;
+
+        Ok(Self {
+            proposal,
+            proposal_transaction,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the SimulateExecution instruction
+pub struct SimulateExecutionAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Proposal transaction to simulate
+    pub proposal_transaction: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Community mint of the realm
+    pub community_mint: &'a AccountInfo<'b>,
+
+    /// Source token account of every SPL Token transfer in the transaction
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> SimulateExecutionAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let proposal_transaction = next_account_with_role(account_info_iter, "proposal_transaction")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(proposal_transaction, program_id, "proposal_transaction")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            proposal,
+            proposal_transaction,
+            governance,
+            realm,
+            community_mint,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the CancelVestingStream instruction
+pub struct CancelVestingStreamAccounts<'a, 'b> {
+    /// Governance account paying the stream
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Vesting stream account
+    pub vesting_stream: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CancelVestingStreamAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let vesting_stream = next_account_with_role(account_info_iter, "vesting_stream")?;
+
+        // Only a proposal transaction of the governance can sign for it
+        assert_signer(governance, "governance")?;
+        assert_account_owner(vesting_stream, program_id, "vesting_stream")?;
+
+        Ok(Self {
+            governance,
+            vesting_stream,
+        })
+    }
+}
+
+/// Accounts of the SetGovernanceDelegate instruction
+pub struct SetGovernanceDelegateAccounts<'a, 'b> {
+    /// Token owner
+    pub token_owner: &'a AccountInfo<'b>,
+
+    /// Token owner record
+    pub token_owner_record: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SetGovernanceDelegateAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let token_owner = next_account_with_role(account_info_iter, "token_owner")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+
+        assert_signer(token_owner, "token_owner")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+
+        Ok(Self {
+            token_owner,
+            token_owner_record,
+        })
+    }
+}
+
+/// Accounts of the AddSponsor instruction
+pub struct AddSponsorAccounts<'a, 'b> {
+    /// Sponsoring token owner
+    pub sponsor: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Token owner record of the sponsor
+    pub token_owner_record: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> AddSponsorAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let sponsor = next_account_with_role(account_info_iter, "sponsor")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+
+        assert_signer(sponsor, "sponsor")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+
+        Ok(Self {
+            sponsor,
+            proposal,
+            governance,
+            token_owner_record,
+        })
+    }
+}
+
+/// Accounts of the TallySnapshot instruction
+pub struct TallySnapshotAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Community mint of the realm
+    pub community_mint: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> TallySnapshotAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            proposal,
+            governance,
+            realm,
+            community_mint,
+        })
+    }
+}
+
+/// Accounts of the ExpireProposal instruction
+pub struct ExpireProposalAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ExpireProposalAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+
+        Ok(Self {
+            proposal,
+            governance,
+        })
+    }
+}
+
+/// Accounts of the VetoProposal and ReviewSybilFlag instructions
+pub struct CouncilReviewAccounts<'a, 'b> {
+    /// Council member
+    pub council_member: &'a AccountInfo<'b>,
+
+    /// Council token account of the council member
+    pub council_token_account: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CouncilReviewAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let council_member = next_account_with_role(account_info_iter, "council_member")?;
+        let council_token_account = next_account_with_role(account_info_iter, "council_token_account")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+
+        assert_signer(council_member, "council_member")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+
+        Ok(Self {
+            council_member,
+            council_token_account,
+            realm,
+            governance,
+            proposal,
+        })
+    }
+}
+
+/// Accounts of the CastCouncilVote instruction
+pub struct CastCouncilVoteAccounts<'a, 'b> {
+    /// Council member, paying for the council vote record
+    pub council_member: &'a AccountInfo<'b>,
+
+    /// Council token account of the council member
+    pub council_token_account: &'a AccountInfo<'b>,
+
+    /// Council mint of the realm
+    pub council_mint: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Council vote record account to create
+    pub council_vote_record: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Council vault the council member's tokens are locked in
+    pub council_vault: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CastCouncilVoteAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let council_member = next_account_with_role(account_info_iter, "council_member")?;
+        let council_token_account = next_account_with_role(account_info_iter, "council_token_account")?;
+        let council_mint = next_account_with_role(account_info_iter, "council_mint")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let council_vote_record = next_account_with_role(account_info_iter, "council_vote_record")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+        let council_vault = next_account_with_role(account_info_iter, "council_vault")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+
+        assert_signer(council_member, "council_member")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+
+        Ok(Self {
+            council_member,
+            council_token_account,
+            council_mint,
+            realm,
+            governance,
+            proposal,
+            council_vote_record,
+            system_program,
+            council_vault,
+            token_program,
+        })
+    }
+}
+
+/// Accounts of the RelinquishCouncilVote instruction
+pub struct RelinquishCouncilVoteAccounts<'a, 'b> {
+    /// Council member who cast the vote
+    pub council_member: &'a AccountInfo<'b>,
+
+    /// Council token account the tokens are returned to
+    pub council_token_account: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Council vote record account to close
+    pub council_vote_record: &'a AccountInfo<'b>,
+
+    /// Council vault the tokens are locked in
+    pub council_vault: &'a AccountInfo<'b>,
+
+    /// Vault authority of the realm
+    pub vault_authority: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> RelinquishCouncilVoteAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let council_member = next_account_with_role(account_info_iter, "council_member")?;
+        let council_token_account = next_account_with_role(account_info_iter, "council_token_account")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let council_vote_record = next_account_with_role(account_info_iter, "council_vote_record")?;
+        let council_vault = next_account_with_role(account_info_iter, "council_vault")?;
+        let vault_authority = next_account_with_role(account_info_iter, "vault_authority")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+
+        assert_signer(council_member, "council_member")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(council_vote_record, program_id, "council_vote_record")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+
+        Ok(Self {
+            council_member,
+            council_token_account,
+            realm,
+            proposal,
+            council_vote_record,
+            council_vault,
+            vault_authority,
+            token_program,
+        })
+    }
+}
+
+/// Accounts of the RecomputeTally instruction
+pub struct RecomputeTallyAccounts<'a, 'b> {
+    /// Realm governance, or the bootstrap authority before it exists
+    pub realm_authority: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Every vote record of the proposal, and the other signing members of a multi-sig
+    /// bootstrap authority
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> RecomputeTallyAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm_authority = next_account_with_role(account_info_iter, "realm_authority")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+
+        assert_signer(realm_authority, "realm_authority")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(governance, program_id, "governance")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+
+        Ok(Self {
+            realm_authority,
+            realm,
+            governance,
+            proposal,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the AutoRelinquishFinalized instruction
+pub struct AutoRelinquishFinalizedAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Vote record and token owner record pairs of the votes to relinquish
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> AutoRelinquishFinalizedAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+
+        Ok(Self {
+            proposal,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the ApplyParameterChange instruction
+pub struct ApplyParameterChangeAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// The prerequisite proposal, if there's one
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> ApplyParameterChangeAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+
+        Ok(Self {
+            proposal,
+            governance,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the ReleaseCreationFeeDeposit instruction
+pub struct ReleaseCreationFeeDepositAccounts<'a, 'b> {
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Proposal owner account
+    pub proposal_owner: &'a AccountInfo<'b>,
+
+    /// Treasury the deposit's fee is paid to
+    pub treasury: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ReleaseCreationFeeDepositAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let proposal_owner = next_account_with_role(account_info_iter, "proposal_owner")?;
+        let treasury = next_account_with_role(account_info_iter, "treasury")?;
+
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(governance, program_id, "governance")?;
+
+        Ok(Self {
+            proposal,
+            governance,
+            proposal_owner,
+            treasury,
+        })
+    }
+}
+
+/// Accounts of the MigrateGoverningMint instruction
+pub struct MigrateGoverningMintAccounts<'a, 'b> {
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Realm governance, or the bootstrap authority before it exists
+    pub realm_authority: &'a AccountInfo<'b>,
+
+    /// The new community token mint
+    pub new_community_mint: &'a AccountInfo<'b>,
+
+    /// Swap reserve, a token account of the new mint owned by the vault authority
+    pub swap_reserve: &'a AccountInfo<'b>,
+
+    /// The other signing members of a multi-sig bootstrap authority
+    pub remaining: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b> MigrateGoverningMintAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let realm_authority = next_account_with_role(account_info_iter, "realm_authority")?;
+        let new_community_mint = next_account_with_role(account_info_iter, "new_community_mint")?;
+        let swap_reserve = next_account_with_role(account_info_iter, "swap_reserve")?;
+
+        assert_signer(realm_authority, "realm_authority")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            realm,
+            realm_authority,
+            new_community_mint,
+            swap_reserve,
+            remaining: account_info_iter.as_slice(),
+        })
+    }
+}
+
+/// Accounts of the SwapDeposit instruction
+pub struct SwapDepositAccounts<'a, 'b> {
+    /// Token owner
+    pub token_owner: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Token owner record of the old mint
+    pub old_token_owner_record: &'a AccountInfo<'b>,
+
+    /// Token owner record of the new mint, created if the token owner has none yet
+    pub new_token_owner_record: &'a AccountInfo<'b>,
+
+    /// The system program
+    pub system_program: &'a AccountInfo<'b>,
+
+    /// Staking vault of the old mint
+    pub old_staking_vault: &'a AccountInfo<'b>,
+
+    /// Staking vault of the new mint
+    pub new_staking_vault: &'a AccountInfo<'b>,
+
+    /// Swap reserve of the mint migration
+    pub swap_reserve: &'a AccountInfo<'b>,
+
+    /// The old community token mint
+    pub old_community_mint: &'a AccountInfo<'b>,
+
+    /// Staking vault authority
+    pub vault_authority: &'a AccountInfo<'b>,
+
+    /// The SPL Token program
+    pub token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SwapDepositAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let token_owner = next_account_with_role(account_info_iter, "token_owner")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let old_token_owner_record = next_account_with_role(account_info_iter, "old_token_owner_record")?;
+        let new_token_owner_record = next_account_with_role(account_info_iter, "new_token_owner_record")?;
+        let system_program = next_account_with_role(account_info_iter, "system_program")?;
+        let old_staking_vault = next_account_with_role(account_info_iter, "old_staking_vault")?;
+        let new_staking_vault = next_account_with_role(account_info_iter, "new_staking_vault")?;
+        let swap_reserve = next_account_with_role(account_info_iter, "swap_reserve")?;
+        let old_community_mint = next_account_with_role(account_info_iter, "old_community_mint")?;
+        let vault_authority = next_account_with_role(account_info_iter, "vault_authority")?;
+        let token_program = next_account_with_role(account_info_iter, "token_program")?;
+
+        assert_signer(token_owner, "token_owner")?;
+        assert_account_owner(realm, program_id, "realm")?;
+        assert_account_owner(old_token_owner_record, program_id, "old_token_owner_record")?;
+        assert_program_account(system_program, &system_program::id(), "system_program")?;
+        assert_program_account(token_program, &spl_token::id(), "token_program")?;
+
+        Ok(Self {
+            token_owner,
+            realm,
+            old_token_owner_record,
+            new_token_owner_record,
+            system_program,
+            old_staking_vault,
+            new_staking_vault,
+            swap_reserve,
+            old_community_mint,
+            vault_authority,
+            token_program,
+        })
+    }
+}
+
+/// Accounts of the RelinquishVote instruction
+pub struct RelinquishVoteAccounts<'a, 'b> {
+    /// Token owner, or the governance delegate which cast the vote
+    pub vote_authority: &'a AccountInfo<'b>,
+
+    /// Proposal account
+    pub proposal: &'a AccountInfo<'b>,
+
+    /// Token owner record the vote was cast with
+    pub token_owner_record: &'a AccountInfo<'b>,
+
+    /// Vote record account
+    pub vote_record: &'a AccountInfo<'b>,
+
+    /// Governance account the proposal belongs to, checked against the proposal by the handler
+    pub governance: &'a AccountInfo<'b>,
+
+    /// Realm account
+    pub realm: &'a AccountInfo<'b>,
+
+    /// Community mint of the realm, read to check quorum once voting has ended
+    pub community_mint: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> RelinquishVoteAccounts<'a, 'b> {
+    /// Takes and validates the accounts of the instruction
+    pub fn from_accounts(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let vote_authority = next_account_with_role(account_info_iter, "vote_authority")?;
+        let proposal = next_account_with_role(account_info_iter, "proposal")?;
+        let token_owner_record = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let vote_record = next_account_with_role(account_info_iter, "vote_record")?;
+        let governance = next_account_with_role(account_info_iter, "governance")?;
+        let realm = next_account_with_role(account_info_iter, "realm")?;
+        let community_mint = next_account_with_role(account_info_iter, "community_mint")?;
+
+        assert_signer(vote_authority, "vote_authority")?;
+        assert_account_owner(proposal, program_id, "proposal")?;
+        assert_account_owner(token_owner_record, program_id, "token_owner_record")?;
+        assert_account_owner(realm, program_id, "realm")?;
+
+        Ok(Self {
+            vote_authority,
+            proposal,
+            token_owner_record,
+            vote_record,
+            governance,
+            realm,
+            community_mint,
+        })
+    }
+}
@@ -8,49 +8,195 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
-    sysvar::Sysvar,
-    program::invoke,
+    sysvar::{self, Sysvar},
+    program::{invoke, invoke_signed},
     program_pack::Pack,
     system_instruction,
+    system_program,
     clock::Clock,
+    keccak,
+    log::sol_log_data,
 };
 use spl_token::state::{Account as TokenAccount, Mint};
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap, ops::RangeInclusive};
+
+pub mod context;
+pub mod instruction;
+
+use context::{
+    assert_program_account, AddAccountDiscriminatorAccounts, AddSponsorAccounts, ApplyParameterChangeAccounts,
+    AutoRelinquishFinalizedAccounts, CancelVestingStreamAccounts, CastCouncilVoteAccounts, CastDelegatedVotesAccounts,
+    CastVoteAccounts, ClaimInitialVotingPowerAccounts, ClaimVestedFundsAccounts, CloseRealmAccounts,
+    CouncilReviewAccounts, CreateGovernanceAccounts, CreateProposalAccounts, CreateProposalBundleAccounts,
+    CreateVestingStreamAccounts, CreateVoteRecordAccounts, ExecuteProposalAccounts, ExecuteProposalBundleAccounts,
+    ExpireProposalAccounts, FinalizeVoteAccounts, FinalizeVotesAccounts, InitializeRealmAccounts,
+    InsertProposalTransactionAccounts, MigrateGovernanceAccounts, MigrateGoverningMintAccounts,
+    PrecheckExecutionAccounts, RealmAuthorityAccounts, RecomputeTallyAccounts, ReleaseCreationFeeDepositAccounts,
+    RelinquishCouncilVoteAccounts, RelinquishVoteAccounts, SetGovernanceDelegateAccounts, SetSybilScoreAccounts,
+    SetVoteWeightSourceAccounts, SignOffProposalAccounts, SimulateExecutionAccounts, StakeTokensAccounts,
+    SwapDepositAccounts, TallySnapshotAccounts, UnstakeTokensAccounts,
+};
 
 // Program ID would be set during deployment
-solana_program::declare_id!("LeapFD1stribuTedgoverNanceTokenXXXXXXXXX");
+solana_program::declare_id!("LeapFD1stribuTedgoverNanceToken111111111111");
+
+/// Seed prefix for Realm PDAs
+pub const REALM_SEED: &[u8] = b"realm";
+
+/// Seed prefix for Governance PDAs
+pub const GOVERNANCE_SEED: &[u8] = b"account-governance";
+
+/// Seed prefix for TokenOwnerRecord PDAs
+pub const TOKEN_OWNER_RECORD_SEED: &[u8] = b"token-owner-record";
+
+/// Seed prefix for VoteRecord PDAs
+pub const VOTE_RECORD_SEED: &[u8] = b"vote-record";
+
+/// Seed prefix for the PDA owning a realm's staking vault
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault-authority";
+
+/// Seed prefix for ProposalTransaction PDAs
+pub const PROPOSAL_TRANSACTION_SEED: &[u8] = b"proposal-transaction";
+
+/// Seed prefix for ProposalArchive page PDAs
+pub const PROPOSAL_ARCHIVE_SEED: &[u8] = b"proposal-archive";
+
+/// Seed prefix for VotingPowerClaim PDAs
+pub const VOTING_POWER_CLAIM_SEED: &[u8] = b"voting-power-claim";
+
+/// Seed prefix for VestingStream PDAs
+pub const VESTING_STREAM_SEED: &[u8] = b"vesting-stream";
+
+/// Seed prefix for ProposalBundle PDAs
+pub const PROPOSAL_BUNDLE_SEED: &[u8] = b"proposal-bundle";
+
+/// Seed prefix for ProposalCertificate PDAs
+pub const PROPOSAL_CERTIFICATE_SEED: &[u8] = b"proposal-certificate";
+
+/// Seed prefix for CouncilVoteRecord PDAs
+pub const COUNCIL_VOTE_RECORD_SEED: &[u8] = b"council-vote-record";
+
+/// Namespace hashed with an account type's name to derive its discriminator
+pub const ACCOUNT_DISCRIMINATOR_NAMESPACE: &[u8] = b"account:";
+
+/// Length of the discriminator every governance program account starts with
+pub const ACCOUNT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Max number of proposals recorded on a single proposal archive page
+pub const PROPOSAL_ARCHIVE_PAGE_SIZE: usize = 100;
+
+/// Max number of options of a ranked choice proposal, bounding its distinct ballots
+pub const MAX_RANKED_CHOICE_OPTIONS: usize = 5;
+
+/// Max number of separately unlocking stake tranches kept on a token owner record
+pub const MAX_STAKE_TRANCHES: usize = 8;
+
+/// Max number of co-sponsors recorded on a proposal
+pub const MAX_PROPOSAL_SPONSORS: usize = 10;
+
+/// Max number of impact tiers a governance can define
+pub const MAX_IMPACT_TIERS: usize = 4;
+
+/// Max number of programs a governance can allow its proposal transactions to invoke
+pub const MAX_ALLOWED_TARGET_PROGRAMS: usize = 8;
+
+/// Max number of proposals a proposal bundle can hold
+pub const MAX_BUNDLE_PROPOSALS: usize = 8;
+
+/// Basis points making up a whole, for thresholds, quorums and rejection refunds
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Fractional bits of the fixed point conviction decay factor
+pub const CONVICTION_DECAY_BITS: u32 = 32;
+
+/// Max number of members of a realm's multi-sig bootstrap authority
+pub const MAX_REALM_AUTHORITY_SET: usize = 10;
+
+/// Max number of proposal creators on a realm's allowlist
+pub const MAX_PROPOSAL_CREATORS: usize = 16;
+
+/// Max length of a proposal's description link
+pub const MAX_DESCRIPTION_LINK_LENGTH: usize = 256;
+
+/// Schemes a proposal's description link must use unless the realm allows plain text
+pub const DESCRIPTION_LINK_SCHEMES: [&str; 3] = ["ipfs://", "ar://", "https://"];
+
+/// Default time staked tokens stay locked before they can be unstaked (7 days)
+pub const DEFAULT_UNSTAKING_COOLDOWN_SECONDS: u64 = 7 * 86400;
+
+/// Default max number of vote records a single proposal accepts
+pub const DEFAULT_MAX_VOTE_RECORDS_PER_PROPOSAL: u32 = 10_000;
+
+/// Default voting period of proposals created without one
+pub const DEFAULT_VOTING_PERIOD_DAYS: u8 = 7;
+
+/// Longest voting period a proposal can use
+pub const MAX_VOTING_PERIOD_DAYS: u8 = 90;
+
+/// Longest veto window, execution delay or draft period a parameter change can set
+pub const MAX_PARAMETER_CHANGE_DELAY_SECONDS: u64 = 30 * 86400;
+
+/// Size of the reserved block closing every governance account
+pub const ACCOUNT_RESERVED_SIZE: usize = 64;
+
+/// Size of the used_reserved counter, the first field carved out of the reserved block
+pub const USED_RESERVED_SIZE: usize = 1;
+
+/// Denominator of MintMaxVoteWeightSource::SupplyFraction, i.e. 100% of the supply
+pub const SUPPLY_FRACTION_BASE: u64 = 10_000_000_000;
 
 /// LeapfrogDAO governance instruction types
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub enum LeapfrogInstruction {
     /// Initialize a new governance realm
+    ///
+    /// The nonce lets distinct realms with the same name and community mint coexist
     /// 
     /// Accounts expected:
-    /// 0. `[signer, writable]` Funding account (must be rent exempt)
-    /// 1. `[writable]` The realm account to create
+    /// 0. `[signer, writable]` Funding account (must be rent exempt), becomes the realm's bootstrap authority
+    /// 1. `[writable]` The realm account to create, PDA seeds: ['realm', keccak(name), community_mint, nonce]
     /// 2. `[]` The governance token mint
-    /// 3. `[]` The council token mint (optional, can be same as governance token)
+    /// 3. `[]` The council token mint (optional, can only be the governance token mint
+    ///    when allow_shared_council_mint is set)
     /// 4. `[]` The system program
     InitializeRealm {
         name: String,
-        min_community_tokens_to_create_proposal: u64,
+        min_community_tokens_to_create_proposal: ProposalThresholdSource,
         community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
         use_quadratic_voting: bool,
+        voting_power_merkle_root: Option<[u8; 32]>,
+        nonce: u64,
+        allow_shared_council_mint: bool,
     },
 
     /// Create a new proposal
     /// 
     /// Accounts expected:
     /// 0. `[signer, writable]` Proposal owner account
-    /// 1. `[writable]` Proposal account to create
-    /// 2. `[]` Governance account the proposal belongs to
+    /// 1. `[signer, writable]` Proposal account to create
+    /// 2. `[writable]` Governance account the proposal belongs to
     /// 3. `[]` Token owner record of the proposal owner
-    /// 4. `[]` Governance authority
+    /// 4. `[signer]` Governance authority of the token owner record
     /// 5. `[]` The system program
+    /// 6. `[]` Realm account the governance belongs to
+    /// 7. `[writable]` Realm treasury, required when the realm charges a proposal creation fee
+    /// 8. `[]` Prerequisite proposal, required when depends_on is set (follows the treasury
+    ///    when there is one)
+    /// 9. `[]` Community token mint, required when the proposal threshold is a supply fraction
+    ///    (follows the treasury and prerequisite proposal when there are any)
+    ///
+    /// A voting_period_days of 0 uses the realm's default voting period
+    ///
+    /// A parameter_change is checked against its parameter's allowed range and the governance
+    /// config it would produce, and is applied by ApplyParameterChange once its option wins
+    ///
+    /// A fallback_option_index names the option which wins when no option clears the quorum
+    /// and threshold, in place of the proposal failing
     CreateProposal {
         name: String,
         description_link: String,
@@ -58,28 +204,62 @@ pub enum LeapfrogInstruction {
         options: Vec<String>,
         use_denial_quorum: bool,
         voting_period_days: u8,
+        per_voter_option_cap: Option<OptionVoteCap>,
+        impact_tier: Option<u8>,
+        depends_on: Option<Pubkey>,
+        description_hash: Option<[u8; 32]>,
+        parameter_change: Option<ParameterChange>,
+        fallback_option_index: Option<u8>,
     },
 
     /// Cast a vote on a proposal
     /// 
+    /// The token owner votes with the voting power they haven't delegated, their governance
+    /// delegate with up to the delegated amount
+    ///
+    /// Votes fail once the voting period has ended, with VotingTimeExpired when the proposal
+    /// missed quorum and is left for FinalizeVote to expire
+    ///
+    /// The vote is written to the voter's empty vote record created by CreateVoteRecord, and
+    /// the Borsh encoded VoteCast event is logged with sol_log_data
+    ///
+    /// In realms with a weight oracle, and on proposals voted on with an NFT collection, the
+    /// voter votes with the oracle_weight the oracle signs the vote with, in place of their deposit
+    /// 
     /// Accounts expected:
-    /// 0. `[signer]` Governance authority account
+    /// 0. `[signer]` Token owner or governance delegate
     /// 1. `[writable]` Proposal account
     /// 2. `[writable]` Token owner record of the voter
     /// 3. `[]` Governance token account of the voter
-    /// 4. `[writable]` Vote record account
+    /// 4. `[writable]` Vote record account, PDA seeds: ['vote-record', proposal, token_owner_record]
+    ///    for the token owner, ['vote-record', proposal, token_owner_record, delegate] for the delegate
+    /// 5. `[]` Governance account the proposal belongs to
+    /// 6. `[]` Realm account
+    /// 7. `[]` Community mint of the realm
+    /// 8. `[signer]` The weight oracle, required when the realm or the proposal's NFT collection
+    ///    vote weight source has one
     CastVote {
         vote: Vote,
         staked_amount: u64,
+        oracle_weight: Option<u64>,
     },
 
-    /// Execute an approved proposal
+    /// Execute the next transaction of an approved proposal's winning option
+    ///
+    /// The option's transactions execute in the order they were inserted, the proposal is
+    /// executed once all of them have run
     /// 
     /// Accounts expected:
-    /// 0. `[signer]` Governance authority
+    /// 0. `[signer, writable]` Governance authority, paid the executor bounty once the
+    ///    proposal is executed
     /// 1. `[writable]` Proposal account
     /// 2. `[]` Token owner record of the proposal owner
-    /// Remaining accounts are the accounts required for the instructions being executed
+    /// 3. `[writable]` Governance account the proposal belongs to
+    /// 4. `[writable]` Proposal transaction of the winning option
+    /// 5. `[]` Prerequisite proposal, required when the proposal depends on one
+    ///
+    /// Remaining accounts are, for each of the transaction's instructions, its program followed
+    /// by its accounts with their stored signer and writable flags (the governance as a non-signer)
     ExecuteProposal,
 
     /// Stake tokens for voting
@@ -87,9 +267,11 @@ pub enum LeapfrogInstruction {
     /// Accounts expected:
     /// 0. `[signer, writable]` Token owner account  
     /// 1. `[writable]` Token account to stake from
-    /// 2. `[writable]` Staking vault account
-    /// 3. `[writable]` Token owner record
+    /// 2. `[writable]` Staking vault account, owned by PDA seeds: ['vault-authority', realm]
+    /// 3. `[writable]` Token owner record, PDA seeds: ['token-owner-record', realm, mint, owner]
     /// 4. `[]` The SPL Token program
+    /// 5. `[writable]` Realm account
+    /// 6. `[]` The system program
     StakeTokens {
         amount: u64,
     },
@@ -97,15 +279,648 @@ pub enum LeapfrogInstruction {
     /// Unstake tokens after a cooldown period
     /// 
     /// Accounts expected:
-    /// 0. `[signer, writable]` Token owner account
+    /// 0. `[signer, writable]` Token owner account, never the governance delegate
     /// 1. `[writable]` Staking vault account
     /// 2. `[writable]` Token account to receive unstaked tokens
     /// 3. `[writable]` Token owner record
     /// 4. `[]` The SPL Token program
     /// 5. `[]` The clock sysvar
+    /// 6. `[writable]` Realm account
+    /// 7. `[]` Staking vault authority, PDA seeds: ['vault-authority', realm]
     UnstakeTokens {
         amount: u64,
     },
+
+    /// Create a new governance within a realm
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Funding account
+    /// 1. `[writable]` Governance account, PDA seeds: ['account-governance', realm, governed_account]
+    /// 2. `[writable]` Realm account the governance belongs to
+    /// 3. `[]` The system program
+    /// 4. `[signer]` Realm bootstrap authority, required when the governed account is the realm itself
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    ///
+    /// Creating the governance of the realm itself hands the realm config over to it
+    /// and removes the bootstrap authority
+    CreateGovernance {
+        governed_account: Pubkey,
+        config: GovernanceConfig,
+    },
+
+    /// Sign off a Draft proposal and open it for voting
+    ///
+    /// The proposal needs the governance's required number of co-sponsors, and must have been
+    /// in Draft for the governance's min draft period
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Proposal owner account
+    /// 1. `[writable]` Proposal account
+    /// 2. `[writable]` Governance account the proposal belongs to
+    /// 3. `[]` Realm account the governance belongs to
+    SignOffProposal,
+
+    /// Finalize the vote on a proposal once its voting period has ended
+    ///
+    /// The outcome is certified in a new proposal certificate account
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal account
+    /// 1. `[writable]` Governance account the proposal belongs to
+    /// 2. `[writable]` Realm account
+    /// 3. `[]` The community token mint
+    /// 4. `[writable]` Latest proposal archive page, PDA seeds: ['proposal-archive', realm, page_index]
+    /// 5. `[writable]` Proposal certificate account, PDA seeds: ['proposal-certificate', proposal]
+    /// 6. `[signer, writable]` Payer of the certificate and of archive pages, paid the finalizer
+    ///    bounty
+    /// 7. `[]` The system program
+    /// 8. `[writable]` Next proposal archive page, required when the latest page is full
+    FinalizeVote,
+
+    /// Insert the transaction executed when an option of a Draft proposal wins
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Proposal owner account
+    /// 1. `[writable]` Proposal account
+    /// 2. `[writable]` Proposal transaction account,
+    ///    PDA seeds: ['proposal-transaction', proposal, option_index, transaction_index]
+    /// 3. `[]` The system program
+    /// 4. `[]` Governance account the proposal belongs to
+    InsertProposalTransaction {
+        option_index: u8,
+        instructions: Vec<InstructionData>,
+    },
+
+    /// Update the realm config
+    ///
+    /// Once the realm governance exists this can only be invoked by a proposal transaction
+    /// of that governance; before that only the bootstrap authority can invoke it
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, PDA seeds: ['account-governance', realm, realm], or the bootstrap authority
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    SetRealmConfig {
+        config_args: RealmConfigArgs,
+    },
+
+    /// Set the sybil resistance score of a token owner
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Sybil oracle of the realm
+    /// 1. `[]` Realm account
+    /// 2. `[writable]` Token owner record to score
+    SetSybilScore {
+        score: u8,
+    },
+
+    /// Update the community mint max vote weight source
+    ///
+    /// Proposals already signed off keep the source they were signed off with
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, PDA seeds: ['account-governance', realm, realm], or the bootstrap authority
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    SetMaxVoteWeightSource {
+        max_vote_weight_source: MintMaxVoteWeightSource,
+    },
+
+    /// Claim voting power from the realm's initial voting power snapshot
+    ///
+    /// The leaf keccak(leaf_index, claimant, amount) must be proven against the realm's
+    /// voting power merkle root, hashing sorted pairs up the tree. Each leaf can be claimed once
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Claimant account, pays for the created accounts
+    /// 1. `[]` Realm account
+    /// 2. `[writable]` Token owner record of the claimant, PDA seeds: ['token-owner-record', realm, mint, owner]
+    /// 3. `[writable]` Voting power claim account, PDA seeds: ['voting-power-claim', realm, leaf_index]
+    /// 4. `[]` The system program
+    ClaimInitialVotingPower {
+        leaf_index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Create a stream paying tokens from a governance treasury out linearly over time
+    ///
+    /// Invoked by a proposal transaction of the governance owning the treasury
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Governance account paying the stream
+    /// 1. `[writable]` Vesting stream account, PDA seeds: ['vesting-stream', governance, recipient, nonce]
+    /// 2. `[]` Treasury token account the stream is paid from, owned by the governance
+    /// 3. `[signer, writable]` Payer of the vesting stream account
+    /// 4. `[]` The system program
+    CreateVestingStream {
+        recipient: Pubkey,
+        total_amount: u64,
+        start_time: u64,
+        cliff_time: u64,
+        duration_seconds: u64,
+        nonce: u64,
+    },
+
+    /// Claim the vested and not yet claimed amount of a vesting stream
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Recipient of the vesting stream
+    /// 1. `[writable]` Vesting stream account
+    /// 2. `[]` Governance account paying the stream
+    /// 3. `[writable]` Treasury token account the stream is paid from
+    /// 4. `[writable]` Token account receiving the claimed tokens
+    /// 5. `[]` The SPL Token program
+    ClaimVestedFunds,
+
+    /// Confirm the treasury covers the token transfers of an UnderfundedApproved proposal
+    /// and make it executable
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal account
+    /// 1. `[]` Proposal transaction of the winning option
+    ///
+    /// Followed by the source token account of every SPL Token transfer in the transaction,
+    /// each once, in order of first appearance
+    PrecheckExecution,
+
+    /// Stop a vesting stream from vesting any further
+    ///
+    /// Amounts vested before the cancellation can still be claimed. Invoked by a proposal
+    /// transaction of the governance paying the stream
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Governance account paying the stream
+    /// 1. `[writable]` Vesting stream account
+    CancelVestingStream,
+
+    /// Delegate part or all of the token owner's voting power to a governance delegate
+    ///
+    /// Replaces any previous delegation; a None delegate revokes it. While the token owner has
+    /// unrelinquished votes the delegate can only be revoked, and the delegated amount stays
+    /// set aside until they're relinquished, as the votes were weighed against it. Votes a
+    /// former delegate cast stay valid, but only the token owner can relinquish them
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Token owner
+    /// 1. `[writable]` Token owner record
+    SetGovernanceDelegate {
+        new_governance_delegate: Option<Pubkey>,
+        delegated_amount: u64,
+    },
+
+    /// Log the live tallies of an active proposal and the outcome FinalizeVote would
+    /// resolve from them, without changing any account
+    ///
+    /// The Borsh encoded TallySnapshot is logged with sol_log_data. A proposal whose voting
+    /// has ended without reaching quorum is projected as Expired
+    ///
+    /// Accounts expected:
+    /// 0. `[]` Proposal account
+    /// 1. `[]` Governance account the proposal belongs to
+    /// 2. `[]` Realm account
+    /// 3. `[]` Community mint of the realm
+    TallySnapshot,
+
+    /// Expire an approved proposal whose execution window has elapsed
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal account
+    /// 1. `[writable]` Governance account the proposal belongs to
+    ExpireProposal,
+
+    /// Co-sponsor a Draft proposal
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Sponsoring token owner, other than the proposal owner
+    /// 1. `[writable]` Proposal account
+    /// 2. `[]` Governance account the proposal belongs to
+    /// 3. `[]` Token owner record of the sponsor
+    AddSponsor,
+
+    /// Finalize the votes on several proposals of a realm whose voting periods have ended
+    ///
+    /// Proposals still open for voting or already finalized are skipped, and the number of
+    /// finalized proposals is logged. Each finalized outcome is certified as by FinalizeVote
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[]` The community token mint
+    /// 2. `[writable]` Latest proposal archive page, PDA seeds: ['proposal-archive', realm, page_index]
+    /// 3. `[signer, writable]` Payer of the certificates and archive pages the finalizations
+    ///    create, paid the finalizer bounty of each finalized proposal
+    /// 4. `[]` The system program
+    /// 5. `[writable]` Next proposal archive page, created if the latest page fills up
+    ///
+    /// Followed by a group per proposal:
+    /// * `[writable]` Proposal account
+    /// * `[writable]` Governance account the proposal belongs to
+    /// * `[writable]` Proposal certificate account, PDA seeds: ['proposal-certificate', proposal]
+    FinalizeVotes,
+
+    /// Migrate the realm's community mint, recording the old and new mint for SwapDeposit
+    ///
+    /// The realm keeps its address, derived from the original mint. Deposits swapped to the
+    /// new mint are backed by new tokens taken from the swap reserve, a token account of the
+    /// new mint owned by the realm's vault authority which the realm must fund. Swaps fail
+    /// until the reserve holds the deposits being swapped
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, or the bootstrap authority before it exists
+    /// 2. `[]` The new community token mint
+    /// 3. `[]` Swap reserve, a token account of the new mint owned by the vault authority
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    MigrateGoverningMint,
+
+    /// Move a token owner's deposit from the old to the new community mint of a migrated realm
+    ///
+    /// Deposit amount, stake tranches and claimed voting power carry over, so voting power
+    /// is preserved. The old tokens are burned from the old mint staking vault and as many
+    /// new tokens move from the swap reserve into the new mint staking vault
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Token owner
+    /// 1. `[writable]` Realm account
+    /// 2. `[writable]` Token owner record of the old mint
+    /// 3. `[writable]` Token owner record of the new mint, PDA seeds: ['token-owner-record', realm, new_mint, owner]
+    /// 4. `[]` The system program
+    /// 5. `[writable]` Staking vault of the old mint
+    /// 6. `[writable]` Staking vault of the new mint
+    /// 7. `[writable]` Swap reserve of the mint migration
+    /// 8. `[writable]` The old community token mint
+    /// 9. `[]` Staking vault authority, PDA seeds: ['vault-authority', realm]
+    /// 10. `[]` The SPL Token program
+    SwapDeposit,
+
+    /// Rebuild the tallies of a proposal whose voting has ended from its vote records
+    ///
+    /// Every vote record of the proposal must be supplied, relinquished votes are left out
+    /// of the rebuilt tallies. A vote whose stake exceeds the voter's current voting power,
+    /// e.g. after an emergency unstake, is weighed again as if cast with that voting power,
+    /// unless a weight oracle weighed it
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Realm governance, or the bootstrap authority before it exists
+    /// 1. `[]` Realm account
+    /// 2. `[]` Governance account the proposal belongs to
+    /// 3. `[writable]` Proposal account
+    ///
+    /// Followed by a pair per vote record of the proposal, then the other signing members of
+    /// a multi-sig bootstrap authority:
+    /// * `[writable]` Vote record account
+    /// * `[]` Token owner record the vote was cast with
+    RecomputeTally,
+
+    /// Release the creation deposit of a finalized proposal
+    ///
+    /// A rejected proposal's owner is refunded the governance's rejection_refund_bps of the
+    /// deposit, and the rest goes to the treasury. Approved and executed proposals refund it
+    /// all, expired and vetoed ones pay it all to the treasury
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal account
+    /// 1. `[]` Governance account the proposal belongs to
+    /// 2. `[writable]` Proposal owner account
+    /// 3. `[writable]` Treasury the deposit is forfeited to
+    ReleaseCreationFeeDeposit,
+
+    /// Transfer the bootstrap authority of a realm whose realm governance doesn't exist yet
+    ///
+    /// A single member set makes that member the sole authority. Larger sets form a multi-sig
+    /// authority, which authority_threshold of the members must sign for
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Current bootstrap authority
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    SetRealmAuthority {
+        authority_set: Vec<Pubkey>,
+        authority_threshold: u8,
+    },
+
+    /// Close a realm without stakes or open proposals, refunding its rent to the beneficiary
+    ///
+    /// Every governance of the realm must be supplied to show none of them has a proposal
+    /// which isn't rejected, executed or expired yet
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, or the bootstrap authority before it exists
+    /// 2. `[writable]` Beneficiary receiving the realm's lamports
+    ///
+    /// Followed by every governance account of the realm, then the other signing members of
+    /// a multi-sig bootstrap authority
+    CloseRealm,
+
+    /// Unstake tokens from a realm which hasn't finalized a proposal for its inactivity threshold
+    ///
+    /// The token owner's outstanding votes are relinquished, the unstaking cooldown still applies.
+    /// Votes already cast keep the weight they added to their proposals' tallies, unless
+    /// RecomputeTally weighs them again before their proposal is finalized
+    ///
+    /// Accounts expected: as for UnstakeTokens
+    EmergencyUnstake {
+        amount: u64,
+    },
+
+    /// Add a token owner to the realm's allowlist of proposal creators
+    ///
+    /// The allowlist only restricts proposal creation while the realm config enables
+    /// permissioned proposal creation
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, or the bootstrap authority before it exists
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    AddProposalCreator {
+        creator: Pubkey,
+    },
+
+    /// Remove a token owner from the realm's allowlist of proposal creators
+    ///
+    /// Accounts expected: as for AddProposalCreator
+    RemoveProposalCreator {
+        creator: Pubkey,
+    },
+
+    /// Rewrite a governance stored with percentage thresholds in the current layout, with its
+    /// quorum and thresholds in basis points
+    ///
+    /// Anyone can migrate a governance, the payer only funding the rent of the larger account
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Governance account
+    /// 1. `[signer, writable]` Payer
+    /// 2. `[]` System program
+    MigrateGovernance,
+
+    /// Create the empty vote record a token owner or governance delegate casts their vote
+    /// on a proposal into, so it can be prepared ahead of CastVote
+    ///
+    /// The record is sized for any vote valid on the proposal
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Token owner or governance delegate (pays for the vote record)
+    /// 1. `[]` Proposal account
+    /// 2. `[]` Token owner record of the voter
+    /// 3. `[writable]` Vote record account, PDA seeds as for CastVote
+    /// 4. `[]` The system program
+    CreateVoteRecord,
+
+    /// Bundle draft proposals of a governance so they're executed together, and only once
+    /// every one of them has been approved
+    ///
+    /// Bundled proposals are voted on and finalized as usual, but can only be executed
+    /// through ExecuteProposalBundle
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Owner of every bundled proposal (pays for the bundle)
+    /// 1. `[writable]` Proposal bundle account, PDA seeds: ['proposal-bundle', first proposal]
+    /// 2. `[]` Governance account the proposals belong to
+    /// 3. `[]` The system program
+    ///
+    /// Followed by the `[writable]` draft proposals to bundle, in execution order
+    CreateProposalBundle,
+
+    /// Execute every transaction of every proposal in a bundle in a single instruction,
+    /// once all of them have been approved
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal bundle account
+    /// 1. `[writable]` Governance account the bundle belongs to
+    /// 2. `[writable]` Keeper paid the executor bounty of each bundled proposal
+    ///
+    /// Followed by each bundled proposal in bundle order: the `[writable]` proposal, then for
+    /// each transaction of its winning option in order, the `[writable]` proposal transaction
+    /// and the accounts of its instructions as ExecuteProposal expects them
+    ExecuteProposalBundle,
+
+    /// Prefix an account created before account discriminators with the discriminator of
+    /// its account type, so it can be loaded again
+    ///
+    /// Anyone can migrate an account, the payer only funding the rent of the larger account
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Governance program account
+    /// 1. `[signer, writable]` Payer
+    /// 2. `[]` System program
+    AddAccountDiscriminator,
+
+    /// Set the realm's lifecycle stage, pausing or winding down proposal creation
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, PDA seeds: ['account-governance', realm, realm], or the bootstrap authority
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    SetRealmLifecycle {
+        lifecycle: RealmLifecycle,
+    },
+
+    /// Veto an approved proposal within its governance's veto window
+    ///
+    /// Any holder of the realm's council tokens can veto, and a vetoed proposal can no longer
+    /// be executed
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Council member
+    /// 1. `[]` Council token account of the council member
+    /// 2. `[]` Realm account
+    /// 3. `[writable]` Governance account the proposal belongs to
+    /// 4. `[writable]` Proposal account
+    VetoProposal,
+
+    /// Record a council member's review of a proposal flagged as a possible sybil attack,
+    /// letting it be executed
+    ///
+    /// Council members who find the vote was manipulated veto the proposal instead
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Council member
+    /// 1. `[]` Council token account of the council member
+    /// 2. `[]` Realm account
+    /// 3. `[]` Governance account the proposal belongs to
+    /// 4. `[writable]` Proposal account
+    ReviewSybilFlag,
+
+    /// Unstake tokens before their cooldown ends, paying the realm's early unstake fee
+    ///
+    /// The fee is the realm's early_unstake_fee basis points of the amount and goes to its fee
+    /// treasury, the rest to the token owner. The token owner can't have outstanding votes
+    ///
+    /// Accounts expected:
+    /// 0-7. As for UnstakeTokens
+    /// 8. `[writable]` Early unstake fee treasury, a token account of the community mint
+    UnstakeEarly {
+        amount: u64,
+    },
+
+    /// Set the source of the voting power votes are cast with, e.g. moving the realm from its
+    /// community token to an NFT collection
+    ///
+    /// Proposals already signed off keep the source they were signed off with
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Realm account
+    /// 1. `[signer]` Realm governance, PDA seeds: ['account-governance', realm, realm], or the bootstrap authority
+    /// 2. `[]` Mint backing the source, the community mint or the collection's NFT mint
+    ///
+    /// Followed by the other signing members of a multi-sig bootstrap authority
+    SetVoteWeightSource {
+        vote_weight_source: VoteWeightSource,
+    },
+
+    /// Cast the same vote with the delegated voting power of several token owners who made the
+    /// signer their governance delegate
+    ///
+    /// Each delegation votes with all of its delegated voting power into the delegate's vote
+    /// record for it, created when it doesn't exist yet. The votes are tallied into the proposal
+    /// together, so either all of them are cast or none is
+    ///
+    /// Proposals voted on with a weight oracle take the delegate's votes through CastVote, as
+    /// the oracle signs the weight of each vote
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Governance delegate (pays for the vote records it creates)
+    /// 1. `[writable]` Proposal account
+    /// 2. `[]` Governance account the proposal belongs to
+    /// 3. `[]` Realm account
+    /// 4. `[]` The system program
+    ///
+    /// Followed by a pair per delegation:
+    /// * `[writable]` Token owner record that delegated to the signer
+    /// * `[writable]` Vote record account, PDA seeds: ['vote-record', proposal, token_owner_record, delegate]
+    CastDelegatedVotes {
+        vote: Vote,
+    },
+
+    /// Log the projected effect of executing a proposal transaction, without changing any
+    /// account
+    ///
+    /// The transaction isn't run. Its SPL Token transfers are checked against the current
+    /// balances of their source token accounts, and the Borsh encoded ExecutionSimulation is
+    /// logged with sol_log_data. Voters can simulate the transactions of any option, before
+    /// the vote as well as once the proposal is approved. A proposal whose voting has ended
+    /// without reaching quorum is reported as Expired
+    ///
+    /// Accounts expected:
+    /// 0. `[]` Proposal account
+    /// 1. `[]` Proposal transaction to simulate
+    /// 2. `[]` Governance account the proposal belongs to
+    /// 3. `[]` Realm account
+    /// 4. `[]` Community mint of the realm
+    ///
+    /// Followed by the source token account of every SPL Token transfer in the transaction,
+    /// each once, in order of first appearance
+    SimulateExecution,
+
+    /// Cast a council member's vote on a proposal of a governance requiring council approval
+    ///
+    /// The council member votes with their council token balance, which is locked in the
+    /// realm's council vault until RelinquishCouncilVote returns it, so the same tokens can't
+    /// vote again from another wallet. Once the approving weight reaches the governance's
+    /// council approval quorum of the council mint supply, the proposal is council approved;
+    /// FinalizeVote rejects proposals the community approved without it
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Council member (pays for the council vote record)
+    /// 1. `[writable]` Council token account of the council member
+    /// 2. `[]` Council mint of the realm
+    /// 3. `[]` Realm account
+    /// 4. `[]` Governance account the proposal belongs to
+    /// 5. `[writable]` Proposal account
+    /// 6. `[writable]` Council vote record account, PDA seeds: ['council-vote-record', proposal, council_member]
+    /// 7. `[]` The system program
+    /// 8. `[writable]` Council vault, a token account of the council mint owned by the realm's
+    ///    vault authority
+    /// 9. `[]` The SPL Token program
+    CastCouncilVote {
+        approve: bool,
+    },
+
+    /// Relinquish the votes of a proposal finalized while the realm had auto-relinquish enabled,
+    /// so their voters can unstake without relinquishing each vote themselves
+    ///
+    /// Anyone can submit the vote records. The proposal's tallies are left as they are, and
+    /// vote records already relinquished are skipped
+    ///
+    /// Accounts expected:
+    /// 0. `[]` Proposal account
+    ///
+    /// Followed by a pair per vote:
+    /// * `[writable]` Vote record account of the proposal
+    /// * `[writable]` Token owner record the vote was cast with
+    AutoRelinquishFinalized,
+
+    /// Apply the governance config change of an approved proposal whose parameter change
+    /// option won
+    ///
+    /// Anyone can apply the change, under the same veto window, execution delay, sybil review
+    /// and execution window as ExecuteProposal. The change is applied once, before or after the
+    /// option's proposal transactions are executed, and a proposal whose option has no
+    /// transactions is executed by it
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` Proposal account
+    /// 1. `[writable]` Governance account the proposal belongs to
+    /// 2. `[]` Prerequisite proposal, required when the proposal has depends_on set
+    ApplyParameterChange,
+
+    /// Return the council tokens a council vote locked once the proposal's voting is over,
+    /// closing the council vote record
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Council member who cast the vote, receiving the record's rent
+    /// 1. `[writable]` Council token account the tokens are returned to
+    /// 2. `[]` Realm account
+    /// 3. `[]` Proposal account
+    /// 4. `[writable]` Council vote record account
+    /// 5. `[writable]` Council vault the tokens are locked in
+    /// 6. `[]` Staking vault authority, PDA seeds: ['vault-authority', realm]
+    /// 7. `[]` The SPL Token program
+    RelinquishCouncilVote,
+
+    /// Relinquish a vote once its proposal is finalized, so the voter's stake no longer backs it
+    ///
+    /// The token owner can relinquish any of its record's votes, a governance delegate only the
+    /// votes it cast while it's still the delegate. A proposal whose voting ended without quorum
+    /// counts as finalized. Stake backing the winning option stays locked while the proposal
+    /// locks it, until the proposal is executed, vetoed or expired
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Token owner, or the governance delegate which cast the vote
+    /// 1. `[]` Proposal account
+    /// 2. `[writable]` Token owner record the vote was cast with
+    /// 3. `[writable]` Vote record account
+    /// 4. `[]` Governance account the proposal belongs to
+    /// 5. `[]` Realm account
+    /// 6. `[]` Community mint of the realm
+    RelinquishVote,
+}
+
+impl LeapfrogInstruction {
+    /// Number of instructions, one past the highest discriminant. Not derived, so a test
+    /// checks that it stays in step with the variants above
+    pub const COUNT: u8 = 49;
+
+    /// Decodes instruction data, telling an unknown instruction apart from a known one with a
+    /// payload that doesn't decode
+    ///
+    /// The leading byte is the Borsh discriminant of the instruction
+    pub fn unpack(instruction_data: &[u8]) -> Result<Self, ProgramError> {
+        match instruction_data.first() {
+            Some(discriminant) if *discriminant < Self::COUNT => {}
+            _ => return Err(GovernanceError::UnknownInstruction.into()),
+        }
+        Self::try_from_slice(instruction_data).map_err(|_| GovernanceError::MalformedInstructionData.into())
+    }
 }
 
 /// Vote types supported by the governance program
@@ -119,6 +934,12 @@ pub enum VoteType {
     
     /// Weighted vote (allocate weights to choices)
     Weighted,
+    
+    /// Ranked choice vote (rank all choices, tallied by instant-runoff)
+    RankedChoice,
+    
+    /// Conviction vote (support for a single option accrues weight over time)
+    Conviction,
 }
 
 /// Vote 
@@ -132,395 +953,7930 @@ pub enum Vote {
     
     /// Weighted vote
     Weighted { weights: Vec<(u8, u8)> }, // (option_index, weight)
+    
+    /// Ranked choice vote
+    RankedChoice { ranking: Vec<u8> }, // option indices, most preferred first
+    
+    /// Abstain from choosing an option, accepted for every vote type
+    Abstain,
+    
+    /// Veto a denial quorum proposal without options
+    Deny,
 }
 
-/// Mint max vote weight source
+/// Total vote weight cast with the same ranking on a ranked choice proposal
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct RankedBallot {
+    /// Option indices, most preferred first
+    pub ranking: Vec<u8>,
+    
+    /// Vote weight cast with the ranking
+    pub weight: u64,
+}
+
+/// What happens to vote weight a voter allocates to an option beyond the option cap
 #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
-pub enum MintMaxVoteWeightSource {
-    /// Use the full supply as a source of max vote weight
-    SupplyFraction { fraction: u64 },
+pub enum OptionCapExcessPolicy {
+    /// The excess weight is dropped
+    Discard,
     
-    /// Use a fixed absolute value as a source of max vote weight
-    Absolute { value: u64 },
+    /// The excess weight is spread over the voter's other options still below the cap,
+    /// anything left over is dropped
+    Redistribute,
 }
 
-/// Proposal state
+/// How a tie between the leading options of a proposal is broken
 #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
-pub enum ProposalState {
-    /// Draft - the proposal is being created and edited
-    Draft,
+pub enum OptionTieBreak {
+    /// The option with the lowest index wins
+    LowestIndex,
     
-    /// Active - the proposal is active for voting
-    Active,
+    /// The option which reached the leading weight first wins, the lowest index if they
+    /// reached it at the same time
+    EarliestLead,
+}
+
+/// Cap on the vote weight a single voter can add to any one option
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct OptionVoteCap {
+    /// Max vote weight a single voter can add to an option
+    pub max_weight_per_option: u64,
     
-    /// Approved - the proposal has been approved
-    Approved,
+    /// Handling of the weight above the cap
+    pub excess_policy: OptionCapExcessPolicy,
+}
+
+/// Governance config parameter a proposal can change through a parameter change option
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum GovernanceParameter {
+    /// The quorum_bps, from 0 to MAX_BASIS_POINTS
+    QuorumBps,
     
-    /// Rejected - the proposal has been rejected
-    Rejected,
+    /// The vote_threshold_bps, from 1 to MAX_BASIS_POINTS
+    VoteThresholdBps,
     
-    /// Executed - the proposal has been executed
-    Executed,
+    /// The max_concurrent_proposals, from 1 to 255
+    MaxConcurrentProposals,
     
-    /// Expired - the proposal has expired
-    Expired,
-}
-
-/// Realm account
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
-pub struct Realm {
-    /// Governance program account type
-    pub account_type: AccountType,
+    /// The min_voting_period_days, from 1 to MAX_VOTING_PERIOD_DAYS
+    MinVotingPeriodDays,
     
-    /// Name of the realm
-    pub name: String,
+    /// The required_sponsors, from 0 to MAX_PROPOSAL_SPONSORS
+    RequiredSponsors,
     
-    /// Community mint
-    pub community_mint: Pubkey,
+    /// The min_sponsor_tokens, any amount
+    MinSponsorTokens,
     
-    /// Optional council mint
-    pub council_mint: Option<Pubkey>,
+    /// The denial_threshold_bps, from 1 to MAX_BASIS_POINTS
+    DenialThresholdBps,
     
-    /// Min community tokens required to create a proposal
-    pub min_community_tokens_to_create_proposal: u64,
+    /// The rejection_refund_bps, from 0 to MAX_BASIS_POINTS
+    RejectionRefundBps,
     
-    /// Community mint max vote weight source
-    pub community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
+    /// The veto_window_seconds, from 0 to MAX_PARAMETER_CHANGE_DELAY_SECONDS
+    VetoWindowSeconds,
     
-    /// Whether to use quadratic voting
-    pub use_quadratic_voting: bool,
+    /// The execution_delay_seconds, from 0 to MAX_PARAMETER_CHANGE_DELAY_SECONDS
+    ExecutionDelaySeconds,
     
-    /// Reserved space for future versions
-    pub reserved: [u8; 64],
+    /// The min_draft_period_seconds, from 0 to MAX_PARAMETER_CHANGE_DELAY_SECONDS
+    MinDraftPeriodSeconds,
 }
 
-/// Proposal account
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
-pub struct Proposal {
-    /// Governance program account type
-    pub account_type: AccountType,
+/// Change of a governance config parameter carried by one of a proposal's options
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct ParameterChange {
+    /// Option whose win applies the change
+    pub option_index: u8,
     
-    /// Governance account the proposal belongs to
-    pub governance: Pubkey,
+    /// Parameter to change
+    pub parameter: GovernanceParameter,
     
-    /// Proposal owner who created the proposal
-    pub proposal_owner: Pubkey,
+    /// New value of the parameter, within its allowed range
+    pub value: u64,
+}
+
+/// SOL charged for creating a proposal: a non-refundable fee paid to the realm treasury, and
+/// a deposit the proposal holds until its outcome is known. Only lamports are supported, the
+/// fee can't be paid in governing or other SPL tokens
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct ProposalCreationFee {
+    /// Fee in lamports, paid to the treasury when the proposal is created
+    pub lamports: u64,
     
-    /// Name of the proposal
-    pub name: String,
+    /// Account receiving the fee and forfeited deposits
+    pub treasury: Pubkey,
     
-    /// Link to the description of the proposal
-    pub description_link: String,
+    /// Deposit in lamports, refunded or forfeited by ReleaseCreationFeeDeposit once the
+    /// proposal is finalized
+    pub deposit_lamports: u64,
+}
+
+/// Creation deposit held by a proposal until ReleaseCreationFeeDeposit
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct ProposalDeposit {
+    /// Deposit in lamports
+    pub lamports: u64,
     
-    /// The time the proposal was created
-    pub created_at: u64,
+    /// Account a forfeited deposit is paid to
+    pub treasury: Pubkey,
+}
+
+/// Fee charged on stake unstaked before its cooldown ends
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct EarlyUnstakeFee {
+    /// Fee in basis points of the unstaked amount
+    pub fee_bps: u16,
     
-    /// Current state of the proposal
-    pub state: ProposalState,
+    /// Community token account receiving the fee
+    pub treasury: Pubkey,
+}
+
+/// Option of a proposal along with the number of proposal transactions it executes
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalOption {
+    /// Label the option was created with
+    pub label: String,
     
-    /// Vote type for the proposal
-    pub vote_type: VoteType,
+    /// Number of proposal transactions inserted for the option
+    pub transaction_count: u8,
+}
+
+/// Scaling of vote weight by the time left until the voter's stake unlocks, so a voter's
+/// conviction decays as their lock runs out
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct LockupVoteWeight {
+    /// Remaining lock time which earns the full vote weight
+    pub max_lock_seconds: u64,
     
-    /// Options for the proposal
-    pub options: Vec<String>,
+    /// Percentage of the vote weight kept however soon the stake unlocks
+    pub min_multiplier_percentage: u8,
+}
+
+/// Credits each voter can spend on quadratic vote weight, a vote costing its weight squared
+///
+/// Budget periods are consecutive windows of period_seconds counted from the unix epoch, and
+/// spent credits are forgotten when a new period starts
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct QuadraticCreditBudget {
+    /// Credits available to each token owner record per period
+    pub credits_per_period: u64,
     
-    /// Whether the proposal uses a denial quorum
-    pub use_denial_quorum: bool,
+    /// Length of a budget period
+    pub period_seconds: u64,
+}
+
+/// Migration of a realm's community mint, recorded by MigrateGoverningMint
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct MintMigration {
+    /// Community mint deposits are migrated from
+    pub old_mint: Pubkey,
     
-    /// Timestamp when voting on the proposal starts
-    pub voting_starts_at: u64,
+    /// Community mint deposits are migrated to
+    pub new_mint: Pubkey,
     
-    /// Timestamp when voting on the proposal ends
-    pub voting_ends_at: u64,
+    /// Token account of the new mint, owned by the vault authority, holding the new tokens
+    /// deposits are swapped for
+    pub swap_reserve: Pubkey,
     
-    /// Vote results
-    pub vote_results: HashMap<u8, u64>,
+    /// Old mint tokens still staked, not counted in the realm's total_staked until swapped
+    pub unswapped_amount: u64,
+}
+
+/// How weighted votes are tallied under quadratic voting
+///
+/// Taking the square root of the whole stake and then splitting it by the weights would
+/// let voters spread influence at no quadratic cost, so weighted votes either apply the
+/// square root per option or aren't allowed at all
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum QuadraticWeightedVotePolicy {
+    /// Weighted proposals and votes are rejected
+    Disallowed,
     
-    /// Total vote weight cast
-    pub total_vote_weight: u64,
+    /// Each option receives the square root of the stake allocated to it
+    ///
+    /// Any weight w on a single option costs w² stake however the rest of the stake is
+    /// allocated, so concentrating on one option always yields the most weight for it:
+    /// splitting stake s over options raises the sum of their weights above sqrt(s) but
+    /// lowers the weight each of them receives. The stake allocated across all options is
+    /// the voted stake, which CastVote bounds by the voter's voting power
+    PerOptionSquareRoot,
+}
+
+/// Curve a vote's stake is turned into vote weight with, before sybil and lockup weighting
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum VoteWeightCurve {
+    /// Vote weight equals the stake
+    Linear,
+    
+    /// Vote weight is the square root of the stake
+    Quadratic,
+    
+    /// Each option of a weighted vote receives the square root of the stake allocated to it
+    PerOptionSquareRoot,
+}
+
+/// Mint max vote weight source
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum MintMaxVoteWeightSource {
+    /// Use the full supply as a source of max vote weight
+    SupplyFraction { fraction: u64 },
+    
+    /// Use a fixed absolute value as a source of max vote weight
+    Absolute { value: u64 },
+}
+
+/// Source of the voting power votes are cast with
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum VoteWeightSource {
+    /// Community tokens staked in the realm
+    CommunityToken,
+    
+    /// NFTs of a collection, identified by its collection NFT mint, with each voter's holdings
+    /// counted and signed for by the weight oracle
+    NftCollection { collection_mint: Pubkey, weight_oracle: Pubkey },
+}
+
+/// Source of the min community voting power required to create a proposal
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum ProposalThresholdSource {
+    /// Use a fixed absolute amount of tokens
+    Absolute { value: u64 },
+    
+    /// Use basis points of the community mint's supply at proposal creation
+    SupplyFraction { bps: u16 },
+}
+
+/// Lowering of a governance's quorum after consecutive proposals expire without reaching it,
+/// so a governance whose turnout has dropped doesn't get stuck
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct QuorumDecay {
+    /// Basis points the quorum is lowered by per consecutive quorum failure
+    pub step_bps: u16,
+    
+    /// Quorum, in basis points, the decay never lowers the quorum below
+    pub floor_bps: u16,
+}
+
+/// Heuristic flagging proposals whose vote weight per voter is suspiciously low, as a sudden
+/// flood of minimum stake voters can be a sybil attack
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct SybilVoteCheck {
+    /// Vote weight per unique voter below which a finalized proposal is flagged
+    pub min_vote_weight_per_voter: u64,
+    
+    /// Whether a flagged proposal can only be executed once a council member has reviewed it
+    pub require_council_review: bool,
+}
+
+/// Quorum and threshold required of proposals created with a given impact tier
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct ImpactTier {
+    /// Basis points of the max vote weight that must be cast for a vote to be valid
+    pub quorum_bps: u16,
+    
+    /// Basis points of the cast vote weight the winning option needs to be approved
+    pub vote_threshold_bps: u16,
+}
+
+/// Governance configuration
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct GovernanceConfig {
+    /// Basis points of the max vote weight that must be cast for a vote to be valid
+    pub quorum_bps: u16,
+    
+    /// Basis points of the cast vote weight the winning option needs to be approved
+    pub vote_threshold_bps: u16,
+    
+    /// Max number of proposals that can be active for voting at the same time
+    pub max_concurrent_proposals: u8,
+    
+    /// Shortest voting period proposals of the governance can use
+    pub min_voting_period_days: u8,
+    
+    /// Min community tokens required to create a proposal, None uses the realm's value
+    pub min_community_tokens_to_create_proposal: Option<ProposalThresholdSource>,
+    
+    /// Whether approved proposals must pass PrecheckExecution before they can be executed
+    pub require_funded_execution: bool,
+    
+    /// Time approved proposals stay executable after finalization, None if they never expire
+    pub execution_window_seconds: Option<u64>,
+    
+    /// Number of distinct co-sponsors a proposal needs before it can be signed off
+    pub required_sponsors: u8,
+    
+    /// Min voting power a token owner needs to co-sponsor a proposal
+    pub min_sponsor_tokens: u64,
+    
+    /// Quorum and threshold per impact tier, each tier at least as strict as the one before
+    pub impact_tiers: Vec<ImpactTier>,
+    
+    /// Basis points of the max vote weight whose deny votes veto a denial quorum proposal
+    /// without options
+    pub denial_threshold_bps: u16,
+    
+    /// Time for the gap between a conviction proposal's conviction and its support to halve,
+    /// 0 if conviction voting is disabled
+    pub conviction_half_life_seconds: u64,
+    
+    /// Basis points of the max vote weight a conviction proposal's conviction must reach to pass
+    pub conviction_threshold_bps: u16,
+    
+    /// Basis points of the creation deposit refunded to the owner of a rejected proposal, the
+    /// rest going to the treasury
+    pub rejection_refund_bps: u16,
+    
+    /// How ties between the leading options of choice proposals are broken
+    pub option_tie_break: OptionTieBreak,
+    
+    /// Whether proposals must commit to a non-zero description hash at creation
+    pub require_description_hash: bool,
+    
+    /// Programs the instructions of proposal transactions may invoke, empty if any program
+    /// is allowed
+    pub allowed_target_programs: Vec<Pubkey>,
+    
+    /// Lowering of the quorum after consecutive quorum failures, None if the quorum is fixed
+    pub quorum_decay: Option<QuorumDecay>,
+    
+    /// Time after approval during which a council member can veto the proposal, 0 if
+    /// proposals can't be vetoed
+    pub veto_window_seconds: u64,
+    
+    /// Time after approval before the proposal can be executed
+    pub execution_delay_seconds: u64,
+    
+    /// Flagging of proposals with a low vote weight per voter, None disables the check
+    pub sybil_vote_check: Option<SybilVoteCheck>,
+    
+    /// Basis points of the council mint supply that must approve a proposal in council votes
+    /// for the community's approval to stand, None if the community vote alone decides
+    pub council_approval_quorum_bps: Option<u16>,
+    
+    /// Time a proposal must stay in Draft after its creation before it can be signed off,
+    /// giving the community time to discuss it
+    pub min_draft_period_seconds: u64,
+    
+    /// Lamports paid out of a proposal's creation deposit to the payer finalizing it, 0 for
+    /// no bounty
+    pub finalizer_bounty_lamports: u64,
+    
+    /// Lamports paid out of a proposal's creation deposit to the keeper whose execution
+    /// leaves it Executed, 0 for no bounty
+    pub executor_bounty_lamports: u64,
+}
+
+/// Realm config values which can be updated after realm creation
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct RealmConfigArgs {
+    /// Min community tokens required to create a proposal
+    pub min_community_tokens_to_create_proposal: ProposalThresholdSource,
+    
+    /// Time staked tokens stay locked before they can be unstaked
+    pub unstaking_cooldown_seconds: u64,
+    
+    /// Oracle trusted to set sybil resistance scores, None disables sybil weighting
+    pub sybil_oracle: Option<Pubkey>,
+    
+    /// Sybil resistance score (0-100) of token owners the oracle hasn't scored
+    pub default_sybil_resistance_score: u8,
+    
+    /// How weighted votes are tallied under quadratic voting
+    pub quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy,
+    
+    /// Max number of vote records a single proposal accepts
+    pub max_vote_records_per_proposal: u32,
+    
+    /// Fee charged for creating a proposal, None if proposals are free
+    pub proposal_creation_fee: Option<ProposalCreationFee>,
+    
+    /// Whether abstaining vote weight counts towards quorum
+    pub abstain_counts_for_quorum: bool,
+    
+    /// Whether casting a vote locks the voter's stake until the unstaking cooldown after
+    /// the proposal's voting ends
+    pub voting_extends_cooldown: bool,
+    
+    /// Slots since a token owner record's last deposit or claim before it can vote, guarding
+    /// against flash-loaned deposits
+    pub min_record_age_slots: u64,
+    
+    /// Whether stakes are converted to whole community tokens before vote weights are computed
+    pub normalize_vote_weight_to_decimals: bool,
+    
+    /// Scaling of vote weight by remaining lock time, None if the lock doesn't affect vote weight
+    pub lockup_vote_weight: Option<LockupVoteWeight>,
+    
+    /// Whether proposal description links may be plain text instead of an off-chain pointer
+    pub allow_plain_text_description_link: bool,
+    
+    /// Time without finalized proposals after which stakers can emergency unstake, None
+    /// disables emergency unstaking
+    pub inactivity_threshold_seconds: Option<u64>,
+    
+    /// Credit budget quadratic votes are charged against, None if votes are only limited
+    /// by stake
+    pub quadratic_credit_budget: Option<QuadraticCreditBudget>,
+    
+    /// Whether only the realm's allowlisted proposal creators can create proposals
+    pub permissioned_proposal_creation: bool,
+    
+    /// Oracle trusted to sign the effective vote weight of voters, e.g. valuing LP positions,
+    /// None if voters vote with their deposit
+    pub weight_oracle: Option<Pubkey>,
+    
+    /// Fee for unstaking before the cooldown ends, None if stake can't be unstaked early
+    pub early_unstake_fee: Option<EarlyUnstakeFee>,
+    
+    /// Most vote weight a single vote can carry, None if votes aren't capped
+    pub max_vote_weight_per_voter: Option<u64>,
+    
+    /// Voting period of proposals created without one
+    pub default_voting_period_days: u8,
+    
+    /// Whether votes of proposals finalized from now on can be relinquished in bulk
+    pub auto_relinquish_finalized: bool,
+    
+    /// Whether votes backing the winning option of proposals finalized from now on stay
+    /// unrelinquishable until the proposal is executed, vetoed or expired
+    pub lock_stake_until_execution: bool,
+}
+
+/// Account meta stored as part of a proposal transaction instruction
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AccountMetaData {
+    /// Account pubkey
+    pub pubkey: Pubkey,
+    
+    /// Whether the account must sign the instruction
+    pub is_signer: bool,
+    
+    /// Whether the account is writable
+    pub is_writable: bool,
+}
+
+/// Instruction stored as part of a proposal transaction
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct InstructionData {
+    /// Program invoked by the instruction
+    pub program_id: Pubkey,
+    
+    /// Accounts passed to the program
+    pub accounts: Vec<AccountMetaData>,
+    
+    /// Instruction data passed to the program
+    pub data: Vec<u8>,
+}
+
+/// Proposal state
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum ProposalState {
+    /// Draft - the proposal is being created and edited
+    Draft,
+    
+    /// Active - the proposal is active for voting
+    Active,
+    
+    /// Approved - the proposal has been approved
+    Approved,
+    
+    /// Rejected - the proposal has been rejected
+    Rejected,
+    
+    /// Executed - the proposal has been executed
+    Executed,
+    
+    /// Expired - the proposal has expired
+    Expired,
+    
+    /// UnderfundedApproved - the proposal has been approved but can't be executed until
+    /// PrecheckExecution confirms the treasury covers its token transfers
+    UnderfundedApproved,
+    
+    /// Vetoed - the approved proposal has been vetoed by the council
+    Vetoed,
+}
+
+/// Lifecycle stage of a realm
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum RealmLifecycle {
+    /// Active - the realm accepts new proposals
+    Active,
+    
+    /// Paused - no proposals can be created or signed off until the realm is active again
+    Paused,
+    
+    /// Closing - the realm is being wound down, existing proposals can still be finalized
+    /// and stakes withdrawn but no new proposals can be created or signed off
+    Closing,
+}
+
+/// Realm account
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct Realm {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Name of the realm
+    pub name: String,
+    
+    /// Community mint
+    pub community_mint: Pubkey,
+    
+    /// Optional council mint
+    pub council_mint: Option<Pubkey>,
+    
+    /// Min community tokens required to create a proposal
+    pub min_community_tokens_to_create_proposal: ProposalThresholdSource,
+    
+    /// Community mint max vote weight source
+    pub community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
+    
+    /// Whether to use quadratic voting
+    pub use_quadratic_voting: bool,
+    
+    /// Bootstrap authority which can configure the realm until the realm governance is created
+    pub authority: Option<Pubkey>,
+    
+    /// Time staked tokens stay locked before they can be unstaked
+    pub unstaking_cooldown_seconds: u64,
+    
+    /// Oracle trusted to set sybil resistance scores, None disables sybil weighting
+    pub sybil_oracle: Option<Pubkey>,
+    
+    /// Sybil resistance score (0-100) of token owners the oracle hasn't scored
+    pub default_sybil_resistance_score: u8,
+    
+    /// How weighted votes are tallied under quadratic voting
+    pub quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy,
+    
+    /// Merkle root of the initial voting power snapshot, None if the realm has no snapshot
+    pub voting_power_merkle_root: Option<[u8; 32]>,
+    
+    /// Max number of vote records a single proposal accepts
+    pub max_vote_records_per_proposal: u32,
+    
+    /// Fee charged for creating a proposal, None if proposals are free
+    pub proposal_creation_fee: Option<ProposalCreationFee>,
+    
+    /// Nonce the realm address is derived with
+    pub nonce: u64,
+    
+    /// Whether abstaining vote weight counts towards quorum
+    pub abstain_counts_for_quorum: bool,
+    
+    /// Whether casting a vote locks the voter's stake until the unstaking cooldown after
+    /// the proposal's voting ends
+    pub voting_extends_cooldown: bool,
+    
+    /// Slots since a token owner record's last deposit or claim before it can vote, guarding
+    /// against flash-loaned deposits
+    pub min_record_age_slots: u64,
+    
+    /// Whether the council mint may be the community mint, giving every token holder
+    /// council powers
+    pub allow_shared_council_mint: bool,
+    
+    /// Decimals of the community mint, read when the realm is created
+    pub community_mint_decimals: u8,
+    
+    /// Whether stakes are converted to whole community tokens before vote weights are computed
+    pub normalize_vote_weight_to_decimals: bool,
+    
+    /// Community mint migration deposits can be swapped under, None if the mint never changed
+    pub mint_migration: Option<MintMigration>,
+    
+    /// Scaling of vote weight by remaining lock time, None if the lock doesn't affect vote weight
+    pub lockup_vote_weight: Option<LockupVoteWeight>,
+    
+    /// Whether proposal description links may be plain text instead of an off-chain pointer
+    pub allow_plain_text_description_link: bool,
+    
+    /// Members of a multi-sig bootstrap authority, the first being the authority, empty for a
+    /// single authority
+    pub authority_set: Vec<Pubkey>,
+    
+    /// Number of authority set members which must sign privileged realm instructions
+    pub authority_threshold: u8,
+    
+    /// Community tokens currently staked in the realm, of the new mint only once the mint
+    /// is migrated
+    pub total_staked: u64,
+    
+    /// Number of governances created in the realm
+    pub governance_count: u32,
+    
+    /// Time without finalized proposals after which stakers can emergency unstake, None
+    /// disables emergency unstaking
+    pub inactivity_threshold_seconds: Option<u64>,
+    
+    /// The time the realm was created or last finalized a proposal
+    pub last_activity_at: u64,
+    
+    /// Credit budget quadratic votes are charged against, None if votes are only limited
+    /// by stake
+    pub quadratic_credit_budget: Option<QuadraticCreditBudget>,
+    
+    /// Whether only the realm's allowlisted proposal creators can create proposals
+    pub permissioned_proposal_creation: bool,
+    
+    /// Token owners allowed to create proposals while proposal creation is permissioned
+    pub proposal_creators: Vec<Pubkey>,
+    
+    /// Oracle trusted to sign the effective vote weight of voters, e.g. valuing LP positions,
+    /// None if voters vote with their deposit
+    pub weight_oracle: Option<Pubkey>,
+    
+    /// Lifecycle stage of the realm
+    pub lifecycle: RealmLifecycle,
+    
+    /// Fee for unstaking before the cooldown ends, None if stake can't be unstaked early
+    pub early_unstake_fee: Option<EarlyUnstakeFee>,
+    
+    /// Source of the voting power of proposals signed off from now on
+    pub vote_weight_source: VoteWeightSource,
+    
+    /// Most vote weight a single vote can carry, after the vote weight curve, sybil and lockup
+    /// weighting, None if votes aren't capped
+    pub max_vote_weight_per_voter: Option<u64>,
+    
+    /// Voting period of proposals created with a voting period of 0 days
+    pub default_voting_period_days: u8,
+    
+    /// Whether finalization makes a proposal's votes relinquishable by AutoRelinquishFinalized
+    pub auto_relinquish_finalized: bool,
+    
+    /// Whether finalization keeps the stake backing an approved proposal's winning option
+    /// locked until the proposal is executed, vetoed or expired
+    pub lock_stake_until_execution: bool,
+    
+    /// Canonical bump of the realm PDA, stored at creation
+    pub bump_seed: u8,
+    
+    /// Canonical bump of the realm's staking vault authority PDA, stored at creation and 0 for
+    /// realms created before it was stored
+    pub vault_authority_bump_seed: u8,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
     
     /// Reserved space for future versions
-    pub reserved: [u8; 64],
+    pub reserved: [u8; 63],
 }
 
-/// Token owner record
+/// Governance account
 #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
-pub struct TokenOwnerRecord {
+pub struct Governance {
     /// Governance program account type
     pub account_type: AccountType,
     
-    /// Realm account the token owner belongs to
+    /// Realm the governance belongs to
     pub realm: Pubkey,
     
-    /// Governing token mint the token owner deposits belong to
-    pub governing_token_mint: Pubkey,
+    /// Account governed by this governance
+    pub governed_account: Pubkey,
     
-    /// Token owner
-    pub governing_token_owner: Pubkey,
+    /// Governance configuration
+    pub config: GovernanceConfig,
     
-    /// Deposit amount of governing tokens
-    pub governing_token_deposit_amount: u64,
+    /// Number of proposals currently active for voting
+    pub active_proposal_count: u8,
     
-    /// Unrelinquished vote count
-    pub unrelinquished_votes_count: u32,
+    /// Number of proposals which aren't rejected, executed or expired yet
+    pub open_proposal_count: u32,
     
-    /// The optimal time when tokens can be unstaked
-    pub earliest_unstaking_time: u64,
+    /// Number of consecutive finalized proposals which expired without reaching quorum
+    pub quorum_failure_count: u8,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
     
     /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Governance account as stored before its quorum and thresholds moved to basis points,
+/// read by MigrateGovernance
+#[derive(BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct LegacyGovernance {
+    pub account_type: AccountType,
+    pub realm: Pubkey,
+    pub governed_account: Pubkey,
+    pub config: LegacyGovernanceConfig,
+    pub active_proposal_count: u8,
+    pub open_proposal_count: u32,
+    pub quorum_failure_count: u8,
     pub reserved: [u8; 64],
 }
 
-/// Vote record
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
-pub struct VoteRecord {
-    /// Governance program account type
-    pub account_type: AccountType,
-    
-    /// Proposal being voted on
-    pub proposal: Pubkey,
+/// Governance configuration with its quorum and thresholds in percentages
+#[derive(BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct LegacyGovernanceConfig {
+    pub quorum_percentage: u8,
+    pub vote_threshold_percentage: u8,
+    pub max_concurrent_proposals: u8,
+    pub min_voting_period_days: u8,
+    pub min_community_tokens_to_create_proposal: Option<u64>,
+    pub require_funded_execution: bool,
+    pub execution_window_seconds: Option<u64>,
+    pub required_sponsors: u8,
+    pub min_sponsor_tokens: u64,
+    /// Quorum and threshold percentages per impact tier
+    pub impact_tiers: Vec<(u8, u8)>,
+    pub denial_threshold_percentage: u8,
+    pub conviction_half_life_seconds: u64,
+    pub conviction_threshold_percentage: u8,
+    pub rejection_refund_bps: u16,
+    pub option_tie_break: OptionTieBreak,
+    pub require_description_hash: bool,
+    pub allowed_target_programs: Vec<Pubkey>,
+    /// Quorum decay step and floor percentages
+    pub quorum_decay: Option<(u8, u8)>,
+}
+
+impl From<LegacyGovernance> for Governance {
+    fn from(legacy: LegacyGovernance) -> Self {
+        let to_bps = |percentage: u8| percentage as u16 * 100;
+        let config = legacy.config;
+        let [used_reserved, reserved @ ..] = legacy.reserved;
+        Governance {
+            account_type: AccountType::GovernanceV2,
+            realm: legacy.realm,
+            governed_account: legacy.governed_account,
+            config: GovernanceConfig {
+                quorum_bps: to_bps(config.quorum_percentage),
+                vote_threshold_bps: to_bps(config.vote_threshold_percentage),
+                max_concurrent_proposals: config.max_concurrent_proposals,
+                min_voting_period_days: config.min_voting_period_days,
+                min_community_tokens_to_create_proposal: config
+                    .min_community_tokens_to_create_proposal
+                    .map(|value| ProposalThresholdSource::Absolute { value }),
+                require_funded_execution: config.require_funded_execution,
+                execution_window_seconds: config.execution_window_seconds,
+                required_sponsors: config.required_sponsors,
+                min_sponsor_tokens: config.min_sponsor_tokens,
+                impact_tiers: config
+                    .impact_tiers
+                    .iter()
+                    .map(|(quorum, threshold)| ImpactTier {
+                        quorum_bps: to_bps(*quorum),
+                        vote_threshold_bps: to_bps(*threshold),
+                    })
+                    .collect(),
+                denial_threshold_bps: to_bps(config.denial_threshold_percentage),
+                conviction_half_life_seconds: config.conviction_half_life_seconds,
+                conviction_threshold_bps: to_bps(config.conviction_threshold_percentage),
+                rejection_refund_bps: config.rejection_refund_bps,
+                option_tie_break: config.option_tie_break,
+                require_description_hash: config.require_description_hash,
+                allowed_target_programs: config.allowed_target_programs,
+                quorum_decay: config.quorum_decay.map(|(step, floor)| QuorumDecay {
+                    step_bps: to_bps(step),
+                    floor_bps: to_bps(floor),
+                }),
+                veto_window_seconds: 0,
+                execution_delay_seconds: 0,
+                sybil_vote_check: None,
+                council_approval_quorum_bps: None,
+                min_draft_period_seconds: 0,
+                finalizer_bounty_lamports: 0,
+                executor_bounty_lamports: 0,
+            },
+            active_proposal_count: legacy.active_proposal_count,
+            open_proposal_count: legacy.open_proposal_count,
+            quorum_failure_count: legacy.quorum_failure_count,
+            // The counter takes the first reserved byte and is set by MigrateGovernance
+            used_reserved,
+            reserved,
+        }
+    }
+}
+
+/// Proposal account
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct Proposal {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Governance account the proposal belongs to
+    pub governance: Pubkey,
+    
+    /// Proposal owner who created the proposal
+    pub proposal_owner: Pubkey,
+    
+    /// Name of the proposal
+    pub name: String,
+    
+    /// Link to the description of the proposal
+    pub description_link: String,
+    
+    /// The time the proposal was created
+    pub created_at: u64,
+    
+    /// Current state of the proposal
+    pub state: ProposalState,
+    
+    /// Vote type for the proposal
+    pub vote_type: VoteType,
+    
+    /// Options for the proposal
+    pub options: Vec<String>,
+    
+    /// Whether the proposal uses a denial quorum
+    pub use_denial_quorum: bool,
+    
+    /// Cap on the vote weight a single voter can add to any one option
+    pub per_voter_option_cap: Option<OptionVoteCap>,
+    
+    /// Realm max vote weight source snapshotted at sign-off and used at finalization
+    pub max_vote_weight_source: Option<MintMaxVoteWeightSource>,
+    
+    /// Timestamp when voting on the proposal starts
+    pub voting_starts_at: u64,
+    
+    /// Timestamp when voting on the proposal ends
+    pub voting_ends_at: u64,
+    
+    /// Vote results, first preferences only on ranked choice proposals
+    ///
+    /// Borsh writes the map as a u32 length followed by (option_index, weight) pairs
+    /// sorted by option index, so the stored bytes don't depend on insertion order
+    pub vote_results: HashMap<u8, u64>,
+    
+    /// Total vote weight cast for the options
+    pub total_vote_weight: u64,
+    
+    /// Total vote weight of abstaining votes
+    pub abstain_vote_weight: u64,
+    
+    /// Number of vote records cast on the proposal
+    pub vote_record_count: u32,
+    
+    /// Option which won the vote, set when the proposal is approved
+    pub winning_option: Option<u8>,
+    
+    /// Vote weight per distinct ranking, used for the instant-runoff of ranked choice proposals
+    pub ranked_ballots: Vec<RankedBallot>,
+    
+    /// When the proposal was finalized as approved, starting its veto window and execution delay
+    pub executable_at: Option<u64>,
+    
+    /// Token owners co-sponsoring the proposal, in sponsoring order
+    pub sponsors: Vec<Pubkey>,
+    
+    /// Whether ExecuteProposal is invoking the proposal's instructions, guarding against re-entry
+    pub is_executing: bool,
+    
+    /// Index of the governance impact tier the proposal is resolved with, None uses the base config
+    pub impact_tier: Option<u8>,
+    
+    /// Total weight cast as deny votes
+    pub deny_vote_weight: u64,
+    
+    /// Conviction a conviction proposal has accrued, growing towards its total vote weight
+    pub conviction: u64,
+    
+    /// When the conviction was last accrued
+    pub conviction_updated_at: u64,
+    
+    /// Creation deposit held by the proposal until its outcome is known, None if the realm
+    /// charged none or it has been released
+    pub creation_fee_deposit: Option<ProposalDeposit>,
+    
+    /// Number of proposal transactions inserted per option, empty for proposals created before
+    /// options counted their transactions
+    pub option_transaction_counts: Vec<u8>,
+    
+    /// Number of the winning option's transactions executed so far
+    pub executed_transaction_count: u8,
+    
+    /// The time each option last took or joined the lead, empty for proposals created before
+    /// leads were recorded
+    pub option_lead_reached_at: Vec<u64>,
+    
+    /// Proposal of the same governance which must be executed before this one can be
+    pub depends_on: Option<Pubkey>,
+    
+    /// Hash of the off-chain content behind the description link, committed to at creation so
+    /// clients can check the fetched document, None if the owner committed to none
+    pub description_hash: Option<[u8; 32]>,
+    
+    /// Bundle the proposal is executed with, None if it's executed on its own
+    pub bundle: Option<Pubkey>,
+    
+    /// Whether the proposal was finalized with a vote weight per voter below the
+    /// governance's sybil vote check minimum
+    pub is_sybil_flagged: bool,
+    
+    /// Council member who reviewed the sybil flag, None until it's reviewed
+    pub sybil_reviewed_by: Option<Pubkey>,
+    
+    /// Realm vote weight source snapshotted at sign-off and used for every vote
+    pub vote_weight_source: Option<VoteWeightSource>,
+    
+    /// Council token weight of the council votes approving the proposal
+    pub council_approve_weight: u64,
+    
+    /// Council token weight of the council votes against the proposal
+    pub council_deny_weight: u64,
+    
+    /// Whether the council approval quorum was reached, required for approval by governances
+    /// with a council approval quorum
+    pub council_approved: bool,
+    
+    /// Whether the proposal was finalized while the realm had auto-relinquish enabled, letting
+    /// AutoRelinquishFinalized relinquish its votes
+    pub votes_relinquishable: bool,
+    
+    /// Governance config change applied by ApplyParameterChange when its option wins
+    pub parameter_change: Option<ParameterChange>,
+    
+    /// When the parameter change was applied, None until it is
+    pub parameter_change_applied_at: Option<u64>,
+    
+    /// Option which wins when no option clears the quorum and threshold, None if the proposal
+    /// fails then
+    pub fallback_option_index: Option<u8>,
+    
+    /// Whether the proposal was finalized while the realm locked stake until execution, keeping
+    /// votes for its winning option from being relinquished while it's pending execution
+    pub locks_backing_stake: bool,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Tokens staked in a single deposit and the time they unlock
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct StakeTranche {
+    /// Amount of staked tokens
+    pub amount: u64,
+    
+    /// Time the tokens can be unstaked
+    pub unlock_time: u64,
+}
+
+/// Token owner record
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TokenOwnerRecord {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Realm account the token owner belongs to
+    pub realm: Pubkey,
+    
+    /// Governing token mint the token owner deposits belong to
+    pub governing_token_mint: Pubkey,
+    
+    /// Token owner
+    pub governing_token_owner: Pubkey,
+    
+    /// Deposit amount of governing tokens
+    pub governing_token_deposit_amount: u64,
+    
+    /// Unrelinquished vote count
+    pub unrelinquished_votes_count: u32,
+    
+    /// The optimal time when tokens can be unstaked
+    pub earliest_unstaking_time: u64,
+    
+    /// Staked deposits in staking order, each unlocking after its own cooldown
+    pub stake_tranches: Vec<StakeTranche>,
+    
+    /// Sybil resistance score (0-100) set by the realm's sybil oracle
+    pub sybil_resistance_score: Option<u8>,
+    
+    /// Voting power claimed from the realm's initial snapshot, not backed by staked tokens
+    pub claimed_voting_power: u64,
+    
+    /// Account allowed to vote with the delegated part of the token owner's voting power
+    pub governance_delegate: Option<Pubkey>,
+    
+    /// Voting power the governance delegate can vote with, at most the deposit amount
+    pub delegated_amount: u64,
+    
+    /// Slot of the last deposit or claim, which the record must age past before it can vote
+    pub last_deposit_slot: u64,
+    
+    /// Quadratic voting credits spent in the current budget period
+    pub quadratic_credits_spent: u64,
+    
+    /// Start of the budget period the spent credits belong to
+    pub credit_period_started_at: u64,
+    
+    /// Integral of the deposit amount over time, in token-seconds, up to
+    /// stake_seconds_updated_at
+    pub cumulative_stake_seconds: u128,
+    
+    /// When the cumulative stake-seconds were last accrued, 0 for records predating them
+    pub stake_seconds_updated_at: u64,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Proposal transaction executed when its option wins
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalTransaction {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Proposal the transaction belongs to
+    pub proposal: Pubkey,
+    
+    /// Option the transaction is executed for
+    pub option_index: u8,
+    
+    /// Position of the transaction among the option's transactions, which execute in order
+    pub transaction_index: u8,
+    
+    /// Instructions invoked with the governance as signer
+    pub instructions: Vec<InstructionData>,
+    
+    /// The time the transaction was executed
+    pub executed_at: Option<u64>,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Final outcome of a proposal recorded in the proposal archive
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ArchivedProposal {
+    /// Finalized proposal
+    pub proposal: Pubkey,
+    
+    /// State the proposal was finalized in
+    pub state: ProposalState,
+    
+    /// Option which won the vote
+    pub winning_option: Option<u8>,
+}
+
+/// Live tallies of an active proposal, logged by TallySnapshot
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TallySnapshot {
+    /// Current weight of every option voted for, by option index
+    pub vote_results: Vec<(u8, u64)>,
+    
+    /// Current total weight cast for the options
+    pub total_vote_weight: u64,
+    
+    /// Current total weight cast as abstentions
+    pub abstain_vote_weight: u64,
+    
+    /// Whether the current tallies reach quorum
+    pub quorum_reached: bool,
+    
+    /// State the proposal would be finalized in with the current tallies
+    pub projected_state: ProposalState,
+    
+    /// Option which would win with the current tallies
+    pub projected_winning_option: Option<u8>,
+}
+
+/// Projected effect of executing a proposal transaction, logged by SimulateExecution
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ExecutionSimulation {
+    /// Simulated proposal transaction
+    pub proposal_transaction: Pubkey,
+    
+    /// Current state of the proposal
+    pub proposal_state: ProposalState,
+    
+    /// Whether the transaction has already been executed
+    pub executed: bool,
+    
+    /// Token accounts the transaction transfers out of, with their current balances
+    pub treasury_deltas: Vec<TreasuryDelta>,
+    
+    /// Whether every source token account holds the amount transferred out of it
+    pub is_funded: bool,
+}
+
+/// Net amount a proposal transaction transfers out of a token account
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TreasuryDelta {
+    /// Source token account of the transfers
+    pub token_account: Pubkey,
+    
+    /// Current balance of the token account
+    pub balance: u64,
+    
+    /// Total amount the transaction transfers out of the token account
+    pub amount: u64,
+}
+
+/// Page of the realm-wide, append-only index of finalized proposals
+///
+/// Pages are filled in order; once a page holds PROPOSAL_ARCHIVE_PAGE_SIZE proposals
+/// the next finalization opens the following page
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalArchive {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Realm the archived proposals belong to
+    pub realm: Pubkey,
+    
+    /// Index of the page within the realm's archive
+    pub page_index: u32,
+    
+    /// Finalized proposals in finalization order
+    pub proposals: Vec<ArchivedProposal>,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Proposals of a governance executed together, once every one of them has been approved
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalBundle {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Governance the bundled proposals belong to
+    pub governance: Pubkey,
+    
+    /// Owner of the bundled proposals, who created the bundle
+    pub creator: Pubkey,
+    
+    /// Bundled proposals in execution order
+    pub proposals: Vec<Pubkey>,
+    
+    /// The time the bundle was executed, None until then
+    pub executed_at: Option<u64>,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Tamper-evident record of the outcome a proposal was finalized with, letting relayers
+/// prove the outcome to other chains
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalCertificate {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Certified proposal
+    pub proposal: Pubkey,
+    
+    /// Governance the proposal belongs to
+    pub governance: Pubkey,
+    
+    /// State the proposal was finalized in
+    pub state: ProposalState,
+    
+    /// Option which won the vote
+    pub winning_option: Option<u8>,
+    
+    /// Final weight of every option voted for, by option index
+    pub vote_results: Vec<(u8, u64)>,
+    
+    /// Final total weight cast for the options
+    pub total_vote_weight: u64,
+    
+    /// Final total weight cast as abstentions
+    pub abstain_vote_weight: u64,
+    
+    /// Final total weight cast as deny votes
+    pub deny_vote_weight: u64,
+    
+    /// Max vote weight the outcome was resolved against
+    pub max_vote_weight: u64,
+    
+    /// The time the proposal was finalized
+    pub finalized_at: u64,
+    
+    /// Keccak hash of the program id and the certified outcome
+    pub attestation: [u8; 32],
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Council member's vote on a proposal of a governance requiring council approval
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct CouncilVoteRecord {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Proposal voted on
+    pub proposal: Pubkey,
+    
+    /// Council member who cast the vote
+    pub council_member: Pubkey,
+    
+    /// Whether the council member approved the proposal
+    pub approve: bool,
+    
+    /// Council token balance the vote was cast with, locked in the council vault until the
+    /// vote is relinquished
+    pub vote_weight: u64,
+    
+    /// The time the vote was cast
+    pub cast_at: u64,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Council vault the vote's tokens are locked in, taken from the reserved space and the
+    /// default key for votes cast before council tokens were locked
+    pub council_vault: Pubkey,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 31],
+}
+
+/// Record of a claimed leaf of the realm's initial voting power snapshot
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct VotingPowerClaim {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Realm the snapshot belongs to
+    pub realm: Pubkey,
+    
+    /// Token owner which claimed the leaf
+    pub governing_token_owner: Pubkey,
+    
+    /// Voting power claimed
+    pub amount: u64,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Tokens paid out of a governance treasury linearly between start time and duration end
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct VestingStream {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Governance paying the stream
+    pub governance: Pubkey,
+    
+    /// Recipient allowed to claim the vested tokens
+    pub recipient: Pubkey,
+    
+    /// Treasury token account the stream is paid from
+    pub treasury: Pubkey,
+    
+    /// Total amount paid out over the stream's duration
+    pub total_amount: u64,
+    
+    /// Time vesting starts
+    pub start_time: u64,
+    
+    /// Time before which nothing can be claimed
+    pub cliff_time: u64,
+    
+    /// Time from the start until the total amount is vested
+    pub duration_seconds: u64,
+    
+    /// Amount claimed so far
+    pub claimed_amount: u64,
+    
+    /// Time the stream was cancelled, vesting stops there
+    pub cancelled_at: Option<u64>,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for accounts written before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 63],
+}
+
+/// Vote record
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct VoteRecord {
+    /// Governance program account type
+    pub account_type: AccountType,
+    
+    /// Proposal being voted on
+    pub proposal: Pubkey,
+    
+    /// Token owner whose voting power the vote was cast with
+    pub governing_token_owner: Pubkey,
+    
+    /// How the vote was cast
+    pub vote: Vote,
+    
+    /// The amount of tokens used for the vote (before quadratic calculation)
+    pub stake_amount: u64,
+    
+    /// The weight of the vote after applying quadratic voting if enabled
+    pub vote_weight: u64,
+    
+    /// Whether the vote has been relinquished by the voter
+    pub is_relinquished: bool,
+    
+    /// Vote weight the vote added to each option, after the per voter option cap
+    pub option_vote_weights: Vec<(u8, u64)>,
+    
+    /// Token owner or governance delegate which cast the vote, taken from the reserved space
+    /// as vote records are created at their exact size
+    pub voter_authority: Pubkey,
+    
+    /// Curve the stake was turned into vote weight with, Linear for votes cast before it was
+    /// recorded
+    pub vote_weight_curve: VoteWeightCurve,
+    
+    /// The time the vote was cast, 0 for votes cast before it was recorded
+    pub cast_at: u64,
+    
+    /// Canonical bump of the vote record PDA, taken from the reserved space and 0 for records
+    /// created before it was stored
+    pub bump_seed: u8,
+    
+    /// Bytes of the reserved block taken by the fields carved out of it, this counter
+    /// included, and 0 for records created before it was kept
+    pub used_reserved: u8,
+    
+    /// Reserved space for future versions
+    pub reserved: [u8; 21],
+}
+
+/// Vote logged by CastVote, with the same stake, weight, curve and time as its vote record
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct VoteCast {
+    /// Proposal voted on
+    pub proposal: Pubkey,
+    
+    /// Token owner whose voting power the vote was cast with
+    pub governing_token_owner: Pubkey,
+    
+    /// Token owner or governance delegate which cast the vote
+    pub voter_authority: Pubkey,
+    
+    /// How the vote was cast
+    pub vote: Vote,
+    
+    /// The amount of tokens used for the vote
+    pub stake_amount: u64,
+    
+    /// The weight of the vote after the curve, sybil and lockup weighting
+    pub vote_weight: u64,
+    
+    /// Curve the stake was turned into vote weight with
+    pub vote_weight_curve: VoteWeightCurve,
+    
+    /// The time the vote was cast
+    pub cast_at: u64,
+}
+
+/// Sybil flag logged by FinalizeVote when a proposal is finalized with a vote weight per
+/// voter below its governance's sybil vote check minimum
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct SybilFlagRaised {
+    /// Flagged proposal
+    pub proposal: Pubkey,
+    
+    /// Total vote weight cast for the options
+    pub total_vote_weight: u64,
+    
+    /// Number of vote records cast on the proposal
+    pub unique_voter_count: u32,
+    
+    /// Vote weight per unique voter the proposal fell below
+    pub min_vote_weight_per_voter: u64,
+}
+
+/// Governance program account types
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum AccountType {
+    /// Uninitialized account
+    Uninitialized,
+    
+    /// Realm account
+    Realm,
+    
+    /// Proposal account
+    Proposal,
+    
+    /// Token owner record account
+    TokenOwnerRecord,
+    
+    /// Vote record account
+    VoteRecord,
+    
+    /// Governance account
+    Governance,
+    
+    /// Proposal transaction account
+    ProposalTransaction,
+    
+    /// Proposal archive page account
+    ProposalArchive,
+    
+    /// Voting power claim account
+    VotingPowerClaim,
+    
+    /// Vesting stream account
+    VestingStream,
+    
+    /// Governance account with quorum and thresholds in basis points
+    GovernanceV2,
+    
+    /// Vote record account created ahead of its vote
+    EmptyVoteRecord,
+    
+    /// Proposal bundle account
+    ProposalBundle,
+    
+    /// Proposal certificate account
+    ProposalCertificate,
+    
+    /// Council vote record account
+    CouncilVoteRecord,
+}
+
+impl AccountType {
+    /// Every account type, in declaration order
+    pub const ALL: [AccountType; 15] = [
+        AccountType::Uninitialized,
+        AccountType::Realm,
+        AccountType::Proposal,
+        AccountType::TokenOwnerRecord,
+        AccountType::VoteRecord,
+        AccountType::Governance,
+        AccountType::ProposalTransaction,
+        AccountType::ProposalArchive,
+        AccountType::VotingPowerClaim,
+        AccountType::VestingStream,
+        AccountType::GovernanceV2,
+        AccountType::EmptyVoteRecord,
+        AccountType::ProposalBundle,
+        AccountType::ProposalCertificate,
+        AccountType::CouncilVoteRecord,
+    ];
+
+    /// Returns the name the account type's discriminator is derived from
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccountType::Uninitialized => "Uninitialized",
+            AccountType::Realm => "Realm",
+            AccountType::Proposal => "Proposal",
+            AccountType::TokenOwnerRecord => "TokenOwnerRecord",
+            AccountType::VoteRecord => "VoteRecord",
+            AccountType::Governance => "Governance",
+            AccountType::ProposalTransaction => "ProposalTransaction",
+            AccountType::ProposalArchive => "ProposalArchive",
+            AccountType::VotingPowerClaim => "VotingPowerClaim",
+            AccountType::VestingStream => "VestingStream",
+            AccountType::GovernanceV2 => "GovernanceV2",
+            AccountType::EmptyVoteRecord => "EmptyVoteRecord",
+            AccountType::ProposalBundle => "ProposalBundle",
+            AccountType::ProposalCertificate => "ProposalCertificate",
+            AccountType::CouncilVoteRecord => "CouncilVoteRecord",
+        }
+    }
+
+    /// Returns the discriminator accounts of the type start with, the first 8 bytes of
+    /// keccak("account:" + name)
+    pub fn get_discriminator(&self) -> [u8; ACCOUNT_DISCRIMINATOR_LEN] {
+        let hash = keccak::hashv(&[ACCOUNT_DISCRIMINATOR_NAMESPACE, self.name().as_bytes()]).to_bytes();
+        let mut discriminator = [0; ACCOUNT_DISCRIMINATOR_LEN];
+        discriminator.copy_from_slice(&hash[..ACCOUNT_DISCRIMINATOR_LEN]);
+        discriminator
+    }
+
+    /// Returns the account type the data's discriminator belongs to, if any
+    pub fn from_discriminator(data: &[u8]) -> Option<AccountType> {
+        let prefix = data.get(..ACCOUNT_DISCRIMINATOR_LEN)?;
+        AccountType::ALL.into_iter().find(|account_type| account_type.get_discriminator() == prefix)
+    }
+}
+
+/// Errors that may be returned by the governance program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernanceError {
+    /// Account is not owned by the governance program
+    InvalidAccountOwner,
+    
+    /// Account is not of the expected account type
+    InvalidAccountType,
+    
+    /// Account address doesn't match the expected PDA
+    InvalidAccountAddress,
+    
+    /// Account is already initialized
+    AccountAlreadyInitialized,
+    
+    /// Governance config values are out of range
+    InvalidGovernanceConfig,
+    
+    /// Governance doesn't belong to the realm
+    InvalidRealmForGovernance,
+    
+    /// Proposal doesn't belong to the governance
+    InvalidGovernanceForProposal,
+    
+    /// Token owner record doesn't belong to the realm or the token owner
+    InvalidTokenOwnerRecord,
+    
+    /// Governing token mint doesn't match the realm
+    InvalidGoverningTokenMint,
+    
+    /// Staking vault isn't a token account of the realm's vault authority
+    InvalidStakingVault,
+    
+    /// Signer is not the proposal owner
+    InvalidProposalOwner,
+    
+    /// Operation is not allowed in the proposal's current state
+    InvalidProposalState,
+    
+    /// Governance already has the max number of active proposals
+    TooManyActiveProposals,
+    
+    /// Proposal voting period has ended
+    VotingPeriodEnded,
+    
+    /// Proposal voting period hasn't ended yet
+    VotingPeriodNotEnded,
+    
+    /// Vote doesn't match the proposal's vote type or options
+    InvalidVote,
+    
+    /// Vote has already been cast on the proposal
+    VoteAlreadyCast,
+    
+    /// Amount exceeds the token owner's deposit
+    InsufficientStake,
+    
+    /// Staked tokens are still in their cooldown period
+    UnstakingCooldownActive,
+    
+    /// Token owner still has unrelinquished votes
+    OutstandingVotes,
+    
+    /// Arithmetic overflow
+    MathOverflow,
+    
+    /// Signers are neither the realm governance nor enough of the bootstrap authority
+    InvalidRealmAuthority,
+    
+    /// Proposal transaction doesn't belong to the proposal's winning option
+    InvalidProposalTransaction,
+    
+    /// Proposal transaction has already been executed
+    TransactionAlreadyExecuted,
+    
+    /// Proposal archive page isn't the realm's latest page
+    InvalidProposalArchivePage,
+    
+    /// Per voter option cap must be positive
+    InvalidOptionVoteCap,
+    
+    /// Signer is not the realm's sybil oracle
+    InvalidSybilOracle,
+    
+    /// Sybil resistance score must be between 0 and 100
+    InvalidSybilResistanceScore,
+    
+    /// Account required by the instruction is missing, its role is logged
+    MissingAccount,
+    
+    /// Weighted votes aren't allowed under the realm's quadratic voting policy
+    WeightedVoteNotAllowed,
+    
+    /// Max vote weight source must yield a non-zero weight of at most the full supply
+    InvalidMaxVoteWeightSource,
+    
+    /// Proposal voting period is shorter than the governance minimum
+    VotingPeriodTooShort,
+    
+    /// Realm has no initial voting power snapshot
+    NoVotingPowerSnapshot,
+    
+    /// Merkle proof doesn't match the realm's voting power merkle root
+    InvalidMerkleProof,
+    
+    /// Snapshot leaf has already been claimed
+    VotingPowerAlreadyClaimed,
+    
+    /// Proposal has reached the realm's max number of vote records
+    TooManyVoteRecords,
+    
+    /// Max vote records per proposal must be positive
+    InvalidMaxVoteRecords,
+    
+    /// Clock sysvar returned a negative unix timestamp
+    ClockError,
+    
+    /// Ranked choice proposals need between 2 and MAX_RANKED_CHOICE_OPTIONS options
+    InvalidRankedChoiceOptions,
+    
+    /// Proposal creation fee must be positive
+    InvalidProposalCreationFee,
+    
+    /// Treasury doesn't match the realm's proposal creation or early unstake fee treasury
+    InvalidTreasury,
+    
+    /// Proposal owner can't cover the proposal creation fee and deposit
+    InsufficientFundsForFee,
+    
+    /// Token owner doesn't hold enough voting power to create a proposal in the governance
+    NotEnoughTokensToCreateProposal,
+    
+    /// Vesting schedule needs a positive amount and a cliff within the vesting period
+    InvalidVestingSchedule,
+    
+    /// Vesting stream doesn't belong to the governance, recipient or treasury
+    InvalidVestingStream,
+    
+    /// Vesting stream has already been cancelled
+    VestingStreamCancelled,
+    
+    /// Vesting stream has no vested tokens left to claim
+    NothingToClaim,
+    
+    /// Treasury doesn't hold enough tokens for the proposal's transfers
+    InsufficientTreasuryFunds,
+    
+    /// Field doesn't fit in the unused part of the account's reserved block
+    ReservedSpaceExhausted,
+    
+    /// Delegated amount exceeds the token owner's deposit
+    DelegatedAmountExceedsDeposit,
+    
+    /// Token owner record is younger than the realm's min record age
+    TokenOwnerRecordTooNew,
+    
+    /// Council mint is the community mint but the realm doesn't allow a shared council mint
+    SharedCouncilMintNotAllowed,
+    
+    /// Approved proposal's execution window has elapsed
+    ExecutionWindowElapsed,
+    
+    /// Approved proposal is still within its execution window
+    ExecutionWindowOpen,
+    
+    /// Proposal has fewer co-sponsors than the governance requires
+    NotEnoughSponsors,
+    
+    /// Sponsor is the proposal owner or has already sponsored the proposal
+    InvalidSponsor,
+    
+    /// Proposal already has the max number of co-sponsors
+    TooManySponsors,
+    
+    /// Token owner doesn't hold enough voting power to co-sponsor a proposal
+    NotEnoughTokensToSponsor,
+    
+    /// Proposal's instructions are being executed and can't be executed again from within
+    ProposalExecutionInProgress,
+    
+    /// Proposal has fewer options than its vote needs
+    TooFewOptions,
+    
+    /// Realm has already migrated its community mint, or the new mint is the current one
+    InvalidMintMigration,
+    
+    /// Realm has no community mint migration to swap deposits under
+    NoMintMigration,
+    
+    /// Impact tier is not defined by the governance
+    InvalidImpactTier,
+    
+    /// Vote record doesn't belong to the proposal
+    InvalidVoteRecord,
+    
+    /// Every vote record of the proposal must be supplied, each once
+    IncompleteVoteRecords,
+    
+    /// Lockup vote weight needs a positive max lock and a min multiplier of at most 100
+    InvalidLockupVoteWeight,
+    
+    /// Description link is too long or doesn't use an allowed scheme
+    InvalidDescriptionLink,
+    
+    /// Conviction proposals need a single option and can't use a denial quorum
+    InvalidConvictionOptions,
+    
+    /// Governance doesn't configure a conviction half-life and threshold
+    ConvictionVotingNotConfigured,
+    
+    /// Proposal doesn't hold a creation deposit
+    NoCreationFeeDeposit,
+    
+    /// Authority set needs 2 to MAX_REALM_AUTHORITY_SET distinct members and a threshold
+    /// between 1 and its size
+    InvalidRealmAuthoritySet,
+    
+    /// Every governance of the realm must be supplied, each once
+    IncompleteRealmGovernances,
+    
+    /// Realm still holds staked tokens
+    RealmHasStakedTokens,
+    
+    /// Realm still has proposals which aren't rejected, executed or expired
+    RealmHasOpenProposals,
+    
+    /// Account isn't an initialized SPL Token mint
+    InvalidMintAccount,
+    
+    /// Vote has no stake, or its stake carries no vote weight
+    ZeroStakeVote,
+    
+    /// Inactivity threshold must be positive
+    InvalidInactivityThreshold,
+    
+    /// Realm has been active within its inactivity threshold, or doesn't allow emergency unstaking
+    RealmNotInactive,
+    
+    /// Prerequisite proposal doesn't exist in the proposal's governance
+    InvalidProposalDependency,
+    
+    /// Prerequisite proposal hasn't been executed yet
+    ProposalDependencyNotExecuted,
+    
+    /// Quadratic credit budget needs positive credits and a positive period
+    InvalidQuadraticCreditBudget,
+    
+    /// Vote costs more quadratic credits than are left in the voter's budget for the period
+    QuadraticCreditsExhausted,
+    
+    /// Remaining accounts don't match the accounts of the proposal transaction's instructions
+    ExecutionAccountMismatch,
+    
+    /// Governance requires proposals to commit to a description hash
+    MissingDescriptionHash,
+    
+    /// Proposal transaction invokes a program the governance doesn't allow
+    TargetProgramNotAllowed,
+    
+    /// Governance the proposal belongs to no longer exists
+    GovernanceClosed,
+    
+    /// Proposal creation is permissioned and the token owner isn't an allowed proposal creator
+    ProposalCreatorNotAllowed,
+    
+    /// Token owner is already an allowed proposal creator
+    ProposalCreatorAlreadyAllowed,
+    
+    /// Realm already allows MAX_PROPOSAL_CREATORS proposal creators
+    TooManyProposalCreators,
+    
+    /// Vote record hasn't been created with CreateVoteRecord yet
+    VoteRecordNotCreated,
+    
+    /// Vote record isn't the voter's vote record for the proposal
+    VoteRecordMismatch,
+    
+    /// Bundle needs MAX_BUNDLE_PROPOSALS or fewer distinct draft proposals of the same
+    /// governance and owner, at least two, none of them bundled or depending on another proposal
+    InvalidProposalBundle,
+    
+    /// Not every proposal of the bundle has been approved
+    ProposalBundleNotApproved,
+    
+    /// Proposal bundle has already been executed
+    ProposalBundleAlreadyExecuted,
+    
+    /// Bundled proposal can only be executed with its bundle
+    ProposalBundled,
+    
+    /// Proposal threshold supply fraction exceeds the full supply
+    InvalidProposalThreshold,
+    
+    /// Account already starts with an account discriminator
+    AccountDiscriminatorAlreadySet,
+    
+    /// Signer is not the realm's weight oracle
+    InvalidWeightOracle,
+    
+    /// Oracle weight is missing in a realm with a weight oracle, or given in one without
+    InvalidOracleWeight,
+    
+    /// Approved proposal can't be executed before its veto window and execution delay elapse
+    ExecutionLocked,
+    
+    /// Approved proposal's veto window has elapsed
+    VetoWindowElapsed,
+    
+    /// Account isn't a token account of the signer holding the realm's council tokens
+    InvalidCouncilTokenAccount,
+    
+    /// Proposal certificate doesn't certify the proposal's outcome
+    InvalidProposalCertificate,
+    
+    /// Realm is paused or closing and doesn't accept new proposals
+    RealmNotAcceptingProposals,
+    
+    /// Proposal was flagged as a possible sybil attack and awaits council review
+    SybilReviewPending,
+    
+    /// Proposal isn't flagged as a possible sybil attack, or its flag was already reviewed
+    NoSybilFlagToReview,
+    
+    /// Early unstake fee must be between 1 and 10,000 basis points
+    InvalidEarlyUnstakeFee,
+    
+    /// Realm doesn't charge an early unstake fee, so stake can't be unstaked before its cooldown
+    EarlyUnstakeNotAllowed,
+    
+    /// Vote weight source's mint isn't the community mint or the collection's NFT mint
+    InvalidVoteWeightSource,
+    
+    /// Max vote weight per voter must be positive
+    InvalidMaxVoteWeightPerVoter,
+    
+    /// Token owner record hasn't delegated its voting power to the signer
+    NotGovernanceDelegate,
+    
+    /// Delegated votes need at least one token owner record and vote record pair, and each
+    /// token owner record once
+    InvalidDelegatedVotes,
+    
+    /// Proposal voting period is longer than the max voting period
+    VotingPeriodTooLong,
+    
+    /// Default voting period must be between 1 day and the max voting period
+    InvalidDefaultVotingPeriod,
+    
+    /// Governance doesn't require council approval, so its proposals take no council votes
+    CouncilApprovalNotRequired,
+    
+    /// Proposal wasn't finalized with auto-relinquish enabled, so its votes can't be
+    /// relinquished in bulk
+    AutoRelinquishNotEnabled,
+    
+    /// Parameter change targets a missing option, or sets a value outside the parameter's
+    /// allowed range or producing an invalid governance config
+    InvalidParameterChange,
+    
+    /// Proposal carries no parameter change
+    NoParameterChange,
+    
+    /// Proposal's parameter change has already been applied
+    ParameterChangeAlreadyApplied,
+    
+    /// Stake interval is empty or its cumulative stake-seconds decrease
+    InvalidStakeInterval,
+    
+    /// Proposal hasn't been in Draft for the governance's min draft period yet
+    DraftPeriodNotElapsed,
+    
+    /// Fallback option doesn't exist, or the proposal's vote type has no fallback
+    InvalidFallbackOption,
+    
+    /// Instruction data is empty or starts with a discriminant no instruction has
+    UnknownInstruction,
+    
+    /// Instruction data of a known instruction doesn't decode, e.g. a truncated payload
+    MalformedInstructionData,
+    
+    /// Council vault isn't a token account of the council mint owned by the realm's vault authority
+    InvalidCouncilVault,
+    
+    /// Council vote record doesn't belong to the proposal and council member
+    InvalidCouncilVoteRecord,
+    
+    /// Voting has ended without quorum, so the proposal is left for FinalizeVote to expire
+    VotingTimeExpired,
+    
+    /// Vote has already been relinquished
+    VoteAlreadyRelinquished,
+    
+    /// Vote backs the winning option of a proposal which locks its stake until execution
+    BackingStakeLocked,
+}
+
+impl GovernanceError {
+    /// Returns the custom program error code of the error
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+}
+
+impl From<GovernanceError> for ProgramError {
+    fn from(e: GovernanceError) -> Self {
+        ProgramError::Custom(e.code())
+    }
+}
+
+/// Account types persisted by the governance program
+pub trait GovernanceAccount: BorshDeserialize {
+    /// The account type every instance of the account is tagged with
+    const ACCOUNT_TYPE: AccountType;
+
+    /// Sizes of the fields carved out of the account's reserved block, in the order they were
+    /// added, starting with the used_reserved counter. Only these fields keep existing accounts
+    /// loadable as they are. Fields added to the body of the account, ahead of the reserved
+    /// block, are a separate migration path: they change the layout and max size, so accounts
+    /// written before them must be grown with realloc and rewritten, as MigrateGovernance does,
+    /// and they aren't listed here
+    const RESERVED_FIELD_SIZES: &'static [usize] = &[USED_RESERVED_SIZE];
+
+    /// Returns the account type the instance is tagged with
+    fn account_type(&self) -> AccountType;
+
+    /// Returns the reserved bytes the account's current layout takes, the value of its
+    /// used_reserved counter
+    fn get_used_reserved() -> Result<u8, ProgramError>
+    where
+        Self: Sized,
+    {
+        get_used_reserved_space(Self::RESERVED_FIELD_SIZES)
+    }
+}
+
+impl GovernanceAccount for Realm {
+    const ACCOUNT_TYPE: AccountType = AccountType::Realm;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for Governance {
+    const ACCOUNT_TYPE: AccountType = AccountType::GovernanceV2;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for Proposal {
+    const ACCOUNT_TYPE: AccountType = AccountType::Proposal;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for TokenOwnerRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::TokenOwnerRecord;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for ProposalTransaction {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProposalTransaction;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for ProposalArchive {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProposalArchive;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for ProposalBundle {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProposalBundle;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for ProposalCertificate {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProposalCertificate;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for CouncilVoteRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::CouncilVoteRecord;
+    // used_reserved and council_vault
+    const RESERVED_FIELD_SIZES: &'static [usize] = &[USED_RESERVED_SIZE, 32];
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for VotingPowerClaim {
+    const ACCOUNT_TYPE: AccountType = AccountType::VotingPowerClaim;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for VestingStream {
+    const ACCOUNT_TYPE: AccountType = AccountType::VestingStream;
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl GovernanceAccount for VoteRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::VoteRecord;
+    // voter_authority, vote_weight_curve, cast_at, bump_seed and used_reserved
+    const RESERVED_FIELD_SIZES: &'static [usize] = &[32, 1, 8, 1, USED_RESERVED_SIZE];
+
+    fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+}
+
+impl MintMaxVoteWeightSource {
+    /// Returns true if the source yields a non-zero max vote weight of at most the full supply
+    pub fn is_valid(&self) -> bool {
+        match self {
+            MintMaxVoteWeightSource::SupplyFraction { fraction } => {
+                *fraction > 0 && *fraction <= SUPPLY_FRACTION_BASE
+            }
+            MintMaxVoteWeightSource::Absolute { value } => *value > 0,
+        }
+    }
+
+    /// Returns the max vote weight for the given mint supply
+    pub fn get_max_vote_weight(&self, mint_supply: u64) -> Result<u64, ProgramError> {
+        match self {
+            MintMaxVoteWeightSource::SupplyFraction { fraction } => {
+                to_u64_amount(mint_supply as u128 * *fraction as u128 / SUPPLY_FRACTION_BASE as u128)
+            }
+            MintMaxVoteWeightSource::Absolute { value } => Ok(*value),
+        }
+    }
+}
+
+impl ProposalThresholdSource {
+    /// Returns true if a supply fraction is at most the full supply
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ProposalThresholdSource::Absolute { .. } => true,
+            ProposalThresholdSource::SupplyFraction { bps } => *bps <= MAX_BASIS_POINTS,
+        }
+    }
+
+    /// Returns the min community tokens required to create a proposal for the given mint supply
+    pub fn get_min_tokens(&self, mint_supply: u64) -> u64 {
+        match self {
+            ProposalThresholdSource::Absolute { value } => *value,
+            ProposalThresholdSource::SupplyFraction { bps } => apply_bps(mint_supply, *bps),
+        }
+    }
+}
+
+impl GovernanceParameter {
+    /// Returns the range of values the parameter can be changed to
+    pub fn get_allowed_range(&self) -> RangeInclusive<u64> {
+        match self {
+            GovernanceParameter::QuorumBps | GovernanceParameter::RejectionRefundBps => 0..=MAX_BASIS_POINTS as u64,
+            GovernanceParameter::VoteThresholdBps | GovernanceParameter::DenialThresholdBps => {
+                1..=MAX_BASIS_POINTS as u64
+            }
+            GovernanceParameter::MaxConcurrentProposals => 1..=u8::MAX as u64,
+            GovernanceParameter::MinVotingPeriodDays => 1..=MAX_VOTING_PERIOD_DAYS as u64,
+            GovernanceParameter::RequiredSponsors => 0..=MAX_PROPOSAL_SPONSORS as u64,
+            GovernanceParameter::MinSponsorTokens => 0..=u64::MAX,
+            GovernanceParameter::VetoWindowSeconds
+            | GovernanceParameter::ExecutionDelaySeconds
+            | GovernanceParameter::MinDraftPeriodSeconds => 0..=MAX_PARAMETER_CHANGE_DELAY_SECONDS,
+        }
+    }
+}
+
+impl ParameterChange {
+    /// Returns true if the value is within the parameter's allowed range
+    pub fn is_valid(&self) -> bool {
+        self.parameter.get_allowed_range().contains(&self.value)
+    }
+
+    /// Returns the governance config with the parameter set to the value, which must be valid
+    pub fn apply(&self, config: &GovernanceConfig) -> GovernanceConfig {
+        let mut config = config.clone();
+        match self.parameter {
+            GovernanceParameter::QuorumBps => config.quorum_bps = self.value as u16,
+            GovernanceParameter::VoteThresholdBps => config.vote_threshold_bps = self.value as u16,
+            GovernanceParameter::MaxConcurrentProposals => config.max_concurrent_proposals = self.value as u8,
+            GovernanceParameter::MinVotingPeriodDays => config.min_voting_period_days = self.value as u8,
+            GovernanceParameter::RequiredSponsors => config.required_sponsors = self.value as u8,
+            GovernanceParameter::MinSponsorTokens => config.min_sponsor_tokens = self.value,
+            GovernanceParameter::DenialThresholdBps => config.denial_threshold_bps = self.value as u16,
+            GovernanceParameter::RejectionRefundBps => config.rejection_refund_bps = self.value as u16,
+            GovernanceParameter::VetoWindowSeconds => config.veto_window_seconds = self.value,
+            GovernanceParameter::ExecutionDelaySeconds => config.execution_delay_seconds = self.value,
+            GovernanceParameter::MinDraftPeriodSeconds => config.min_draft_period_seconds = self.value,
+        }
+        config
+    }
+}
+
+impl Realm {
+    /// Returns the account size needed to store a realm with the given name
+    pub fn get_max_size(name: &str) -> Result<usize, ProgramError> {
+        Ok(1 // account_type
+            + (4 + name.len()) // name
+            + 32 // community_mint
+            + (1 + 32) // council_mint
+            + (1 + 8) // min_community_tokens_to_create_proposal
+            + (1 + 8) // community_mint_max_vote_weight_source
+            + 1 // use_quadratic_voting
+            + (1 + 32) // authority
+            + 8 // unstaking_cooldown_seconds
+            + (1 + 32) // sybil_oracle
+            + 1 // default_sybil_resistance_score
+            + 1 // quadratic_weighted_vote_policy
+            + (1 + 32) // voting_power_merkle_root
+            + 4 // max_vote_records_per_proposal
+            + (1 + 8 + 32 + 8) // proposal_creation_fee
+            + 8 // nonce
+            + 1 // abstain_counts_for_quorum
+            + 1 // voting_extends_cooldown
+            + 8 // min_record_age_slots
+            + 1 // allow_shared_council_mint
+            + 1 // community_mint_decimals
+            + 1 // normalize_vote_weight_to_decimals
+            + (1 + 32 + 32 + 32 + 8) // mint_migration
+            + (1 + 8 + 1) // lockup_vote_weight
+            + 1 // allow_plain_text_description_link
+            + (4 + MAX_REALM_AUTHORITY_SET * 32) // authority_set
+            + 1 // authority_threshold
+            + 8 // total_staked
+            + 4 // governance_count
+            + (1 + 8) // inactivity_threshold_seconds
+            + 8 // last_activity_at
+            + (1 + 8 + 8) // quadratic_credit_budget
+            + 1 // permissioned_proposal_creation
+            + (4 + MAX_PROPOSAL_CREATORS * 32) // proposal_creators
+            + (1 + 32) // weight_oracle
+            + 1 // lifecycle
+            + (1 + 2 + 32) // early_unstake_fee
+            + (1 + 32 + 32) // vote_weight_source
+            + (1 + 8) // max_vote_weight_per_voter
+            + 1 // default_voting_period_days
+            + 1 // auto_relinquish_finalized
+            + 1 // lock_stake_until_execution
+            + 1 // bump_seed
+            + 1 // vault_authority_bump_seed
+            + 1 // used_reserved
+            + 63) // reserved
+    }
+
+    /// Checks the mint is the realm's council mint, to be called by council-gated handlers
+    ///
+    /// A council mint shared with the community is only accepted if the realm explicitly allows it
+    pub fn assert_council_mint(&self, mint: &Pubkey) -> ProgramResult {
+        if self.council_mint != Some(*mint) {
+            return Err(GovernanceError::InvalidGoverningTokenMint.into());
+        }
+        if *mint == self.community_mint && !self.allow_shared_council_mint {
+            return Err(GovernanceError::SharedCouncilMintNotAllowed.into());
+        }
+        Ok(())
+    }
+
+    /// Returns the vote weight of a stake, applying quadratic voting if enabled
+    pub fn get_vote_weight(&self, stake_amount: u64) -> u64 {
+        let stake_amount = self.to_vote_weight_units(stake_amount);
+        if self.use_quadratic_voting {
+            integer_sqrt(stake_amount)
+        } else {
+            stake_amount
+        }
+    }
+
+    /// Converts a token amount to the units vote weights are computed in
+    ///
+    /// When the realm normalizes to decimals, amounts are rounded down to whole community
+    /// tokens, so stakes below one token carry no weight
+    pub fn to_vote_weight_units(&self, amount: u64) -> u64 {
+        if !self.normalize_vote_weight_to_decimals {
+            return amount;
+        }
+        match 10u64.checked_pow(self.community_mint_decimals as u32) {
+            Some(token_unit) => amount / token_unit,
+            None => 0,
+        }
+    }
+
+    /// Scales a quadratic vote weight by the voter's sybil resistance score when the realm
+    /// has a sybil oracle, so unverified accounts get reduced influence
+    pub fn apply_sybil_resistance_score(
+        &self,
+        vote_weight: u64,
+        token_owner_record: &TokenOwnerRecord,
+    ) -> u64 {
+        if !self.use_quadratic_voting || self.sybil_oracle.is_none() {
+            return vote_weight;
+        }
+
+        let score = token_owner_record
+            .sybil_resistance_score
+            .unwrap_or(self.default_sybil_resistance_score);
+        // The score is at most 100, so the scaled weight never exceeds the vote weight
+        (vote_weight as u128 * score.min(100) as u128 / 100) as u64
+    }
+
+    /// Scales a vote weight by the time left until the voter's stake can first be unstaked
+    /// when the realm has lockup vote weight
+    ///
+    /// The full weight needs at least max_lock_seconds of remaining lock, and the weight never
+    /// drops below min_multiplier_percentage of it
+    pub fn apply_lockup_multiplier(
+        &self,
+        vote_weight: u64,
+        token_owner_record: &TokenOwnerRecord,
+        now: u64,
+    ) -> u64 {
+        let Some(lockup) = self.lockup_vote_weight else {
+            return vote_weight;
+        };
+
+        let max_lock_seconds = lockup.max_lock_seconds.max(1) as u128;
+        let remaining_lock_seconds = token_owner_record
+            .earliest_unstaking_time
+            .saturating_sub(now)
+            .min(lockup.max_lock_seconds) as u128;
+        let lock_weight = vote_weight as u128 * remaining_lock_seconds / max_lock_seconds;
+        let min_weight = vote_weight as u128 * lockup.min_multiplier_percentage.min(100) as u128 / 100;
+        // Both are at most the vote weight
+        lock_weight.max(min_weight) as u64
+    }
+
+    /// Returns true if the description link is within MAX_DESCRIPTION_LINK_LENGTH and, unless
+    /// the realm allows plain text, points off-chain with one of DESCRIPTION_LINK_SCHEMES
+    pub fn is_valid_description_link(&self, description_link: &str) -> bool {
+        if description_link.len() > MAX_DESCRIPTION_LINK_LENGTH {
+            return false;
+        }
+        self.allow_plain_text_description_link
+            || DESCRIPTION_LINK_SCHEMES.iter().any(|scheme| {
+                description_link.len() > scheme.len() && description_link.starts_with(scheme)
+            })
+    }
+
+    /// Returns true if the token owner may create proposals in the realm, anyone may unless
+    /// proposal creation is permissioned
+    pub fn is_proposal_creator(&self, token_owner: &Pubkey) -> bool {
+        !self.permissioned_proposal_creation || self.proposal_creators.contains(token_owner)
+    }
+
+    /// Returns true if the realm allows emergency unstaking and hasn't finalized a proposal
+    /// within its inactivity threshold
+    pub fn is_inactive(&self, now: u64) -> bool {
+        match self.inactivity_threshold_seconds {
+            Some(threshold) => now.saturating_sub(self.last_activity_at) >= threshold,
+            None => false,
+        }
+    }
+
+    /// Returns true if the signers satisfy the realm authority
+    ///
+    /// That's the realm governance once it exists and the bootstrap authority before, or
+    /// authority_threshold members of a multi-sig bootstrap authority
+    pub fn is_signed_by_authority(
+        &self,
+        program_id: &Pubkey,
+        realm_key: &Pubkey,
+        signers: &[Pubkey],
+    ) -> bool {
+        match self.authority {
+            Some(_) if !self.authority_set.is_empty() => {
+                let signed_members = self
+                    .authority_set
+                    .iter()
+                    .filter(|member| signers.contains(member))
+                    .count();
+                signed_members >= self.authority_threshold.max(1) as usize
+            }
+            Some(bootstrap_authority) => signers.contains(&bootstrap_authority),
+            None => signers.contains(&get_governance_address(program_id, realm_key, realm_key)),
+        }
+    }
+
+    /// Returns the curve the vote's stake is turned into vote weight with
+    pub fn get_vote_weight_curve(&self, proposal: &Proposal, vote: &Vote) -> VoteWeightCurve {
+        if !self.use_quadratic_voting {
+            VoteWeightCurve::Linear
+        } else if proposal.vote_type == VoteType::Weighted && !matches!(vote, Vote::Abstain | Vote::Deny) {
+            VoteWeightCurve::PerOptionSquareRoot
+        } else {
+            VoteWeightCurve::Quadratic
+        }
+    }
+
+    /// Returns true if the realm accepts proposals and votes of the given vote type
+    pub fn allows_vote_type(&self, vote_type: &VoteType) -> bool {
+        !(self.use_quadratic_voting
+            && *vote_type == VoteType::Weighted
+            && self.quadratic_weighted_vote_policy == QuadraticWeightedVotePolicy::Disallowed)
+    }
+
+    /// Caps a vote's weight at the realm's max vote weight per voter
+    pub fn apply_max_vote_weight_per_voter(&self, vote_weight: u64) -> u64 {
+        match self.max_vote_weight_per_voter {
+            Some(max_vote_weight_per_voter) => vote_weight.min(max_vote_weight_per_voter),
+            None => vote_weight,
+        }
+    }
+
+    /// Returns the total weight of a vote and the weight it adds to each option
+    ///
+    /// Under quadratic voting weighted votes take the square root of the stake allocated
+    /// to each option, and their total weight is the sum of the option weights. The
+    /// allocations are percentages adding up to 100, so they never exceed the staked amount
+    ///
+    /// A vote above the realm's max vote weight per voter is capped, a weighted vote's option
+    /// weights being scaled down in proportion
+    pub fn get_vote_weights(
+        &self,
+        proposal: &Proposal,
+        vote: &Vote,
+        staked_amount: u64,
+        token_owner_record: &TokenOwnerRecord,
+        now: u64,
+    ) -> Result<(u64, Vec<(u8, u64)>), ProgramError> {
+        if !self.allows_vote_type(&proposal.vote_type) {
+            return Err(GovernanceError::WeightedVoteNotAllowed.into());
+        }
+
+        if self.get_vote_weight_curve(proposal, vote) == VoteWeightCurve::PerOptionSquareRoot {
+            let mut option_vote_weights: Vec<(u8, u64)> = proposal
+                .get_option_vote_weights(vote, staked_amount)?
+                .into_iter()
+                .map(|(option_index, option_stake)| {
+                    let option_weight = integer_sqrt(self.to_vote_weight_units(option_stake));
+                    let option_weight = self.apply_sybil_resistance_score(option_weight, token_owner_record);
+                    (option_index, self.apply_lockup_multiplier(option_weight, token_owner_record, now))
+                })
+                .collect();
+            let vote_weight: u64 = option_vote_weights.iter().map(|(_, weight)| weight).sum();
+            let capped_vote_weight = self.apply_max_vote_weight_per_voter(vote_weight);
+            if capped_vote_weight < vote_weight {
+                for (_, weight) in option_vote_weights.iter_mut() {
+                    *weight = (*weight as u128 * capped_vote_weight as u128 / vote_weight as u128) as u64;
+                }
+            }
+            let vote_weight = option_vote_weights.iter().map(|(_, weight)| weight).sum();
+            return Ok((vote_weight, option_vote_weights));
+        }
+
+        let vote_weight =
+            self.apply_sybil_resistance_score(self.get_vote_weight(staked_amount), token_owner_record);
+        let vote_weight = self.apply_lockup_multiplier(vote_weight, token_owner_record, now);
+        let vote_weight = self.apply_max_vote_weight_per_voter(vote_weight);
+        Ok((vote_weight, proposal.get_option_vote_weights(vote, vote_weight)?))
+    }
+
+    /// Returns the max vote weight from the given source in the same units as cast vote weight
+    pub fn get_max_vote_weight(
+        &self,
+        max_vote_weight_source: &MintMaxVoteWeightSource,
+        mint_supply: u64,
+    ) -> Result<u64, ProgramError> {
+        let max_vote_weight = max_vote_weight_source.get_max_vote_weight(mint_supply)?;
+        Ok(self.get_vote_weight(max_vote_weight))
+    }
+}
+
+impl Governance {
+    /// Returns the account size needed to store a governance
+    pub fn get_max_size() -> usize {
+        1 // account_type
+            + 32 // realm
+            + 32 // governed_account
+            + GovernanceConfig::get_max_size() // config
+            + 1 // active_proposal_count
+            + 4 // open_proposal_count
+            + 1 // quorum_failure_count
+            + 1 // used_reserved
+            + 63 // reserved
+    }
+}
+
+impl GovernanceConfig {
+    /// Returns the size of a stored governance config with the most impact tiers and allowed
+    /// target programs
+    pub fn get_max_size() -> usize {
+        2 // quorum_bps
+            + 2 // vote_threshold_bps
+            + 1 // max_concurrent_proposals
+            + 1 // min_voting_period_days
+            + (1 + 1 + 8) // min_community_tokens_to_create_proposal
+            + 1 // require_funded_execution
+            + (1 + 8) // execution_window_seconds
+            + 1 // required_sponsors
+            + 8 // min_sponsor_tokens
+            + (4 + MAX_IMPACT_TIERS * 4) // impact_tiers
+            + 2 // denial_threshold_bps
+            + 8 // conviction_half_life_seconds
+            + 2 // conviction_threshold_bps
+            + 2 // rejection_refund_bps
+            + 1 // option_tie_break
+            + 1 // require_description_hash
+            + (4 + MAX_ALLOWED_TARGET_PROGRAMS * 32) // allowed_target_programs
+            + (1 + 2 + 2) // quorum_decay
+            + 8 // veto_window_seconds
+            + 8 // execution_delay_seconds
+            + (1 + 8 + 1) // sybil_vote_check
+            + (1 + 2) // council_approval_quorum_bps
+            + 8 // min_draft_period_seconds
+            + 8 // finalizer_bounty_lamports
+            + 8 // executor_bounty_lamports
+    }
+
+    /// Splits the creation deposit of a rejected proposal into the owner's refund and the
+    /// treasury's share
+    pub fn split_rejected_deposit(&self, deposit_lamports: u64) -> (u64, u64) {
+        let refund_lamports = apply_bps(deposit_lamports, self.rejection_refund_bps);
+        (refund_lamports, deposit_lamports - refund_lamports)
+    }
+
+    /// Returns the quorum and threshold, in basis points, proposals of the given impact tier need
+    /// after the given number of consecutive quorum failures
+    pub fn get_vote_thresholds(&self, impact_tier: Option<u8>, quorum_failure_count: u8) -> (u16, u16) {
+        let (quorum_bps, vote_threshold_bps) =
+            match impact_tier.and_then(|tier| self.impact_tiers.get(tier as usize)) {
+                Some(tier) => (tier.quorum_bps, tier.vote_threshold_bps),
+                None => (self.quorum_bps, self.vote_threshold_bps),
+            };
+        let Some(decay) = self.quorum_decay else {
+            return (quorum_bps, vote_threshold_bps);
+        };
+        
+        let reduction = decay.step_bps as u32 * quorum_failure_count as u32;
+        let decayed_quorum_bps = (quorum_bps as u32).saturating_sub(reduction) as u16;
+        (
+            decayed_quorum_bps.max(decay.floor_bps.min(quorum_bps)),
+            vote_threshold_bps,
+        )
+    }
+
+    /// Returns true if proposal transactions may invoke the program
+    pub fn is_allowed_target_program(&self, program_id: &Pubkey) -> bool {
+        self.allowed_target_programs.is_empty() || self.allowed_target_programs.contains(program_id)
+    }
+}
+
+impl Proposal {
+    /// Returns the account size needed to store a proposal with the given name, link, vote type
+    /// and options
+    ///
+    /// Ranked choice proposals reserve room for a ballot per ordering of their options
+    pub fn get_max_size(
+        name: &str,
+        description_link: &str,
+        vote_type: &VoteType,
+        options: &[String],
+    ) -> Result<usize, ProgramError> {
+        let options_size: usize = options.iter().map(|option| 4 + option.len()).sum();
+        let ranked_ballots_size = match vote_type {
+            VoteType::RankedChoice => (1..=options.len()).product::<usize>() * (4 + options.len() + 8),
+            _ => 0,
+        };
+        Ok(1 // account_type
+            + 32 // governance
+            + 32 // proposal_owner
+            + (4 + name.len()) // name
+            + (4 + description_link.len()) // description_link
+            + 8 // created_at
+            + 1 // state
+            + 2 // vote_type
+            + (4 + options_size) // options
+            + 1 // use_denial_quorum
+            + (1 + 8 + 1) // per_voter_option_cap
+            + (1 + 1 + 8) // max_vote_weight_source
+            + 8 // voting_starts_at
+            + 8 // voting_ends_at
+            + (4 + options.len() * (1 + 8)) // vote_results
+            + 8 // total_vote_weight
+            + 8 // abstain_vote_weight
+            + 4 // vote_record_count
+            + 2 // winning_option
+            + (4 + ranked_ballots_size) // ranked_ballots
+            + (1 + 8) // executable_at
+            + (4 + MAX_PROPOSAL_SPONSORS * 32) // sponsors
+            + 1 // is_executing
+            + 2 // impact_tier
+            + 8 // deny_vote_weight
+            + 8 // conviction
+            + 8 // conviction_updated_at
+            + (1 + 8 + 32) // creation_fee_deposit
+            + (4 + options.len().max(1)) // option_transaction_counts
+            + 1 // executed_transaction_count
+            + (4 + options.len() * 8) // option_lead_reached_at
+            + (1 + 32) // depends_on
+            + (1 + 32) // description_hash
+            + (1 + 32) // bundle
+            + 1 // is_sybil_flagged
+            + (1 + 32) // sybil_reviewed_by
+            + (1 + 1 + 32 + 32) // vote_weight_source
+            + 8 // council_approve_weight
+            + 8 // council_deny_weight
+            + 1 // council_approved
+            + 1 // votes_relinquishable
+            + (1 + 1 + 1 + 8) // parameter_change
+            + (1 + 8) // parameter_change_applied_at
+            + (1 + 1) // fallback_option_index
+            + 1 // locks_backing_stake
+            + 1 // used_reserved
+            + 63) // reserved
+    }
+
+    /// Returns the min number of options a proposal needs to be resolvable
+    ///
+    /// Choice votes need two options to choose between, while denial quorum proposals can
+    /// have none and are then approved unless vetoed
+    pub fn get_min_options(use_denial_quorum: bool) -> usize {
+        if use_denial_quorum {
+            0
+        } else {
+            2
+        }
+    }
+
+    /// Returns true if the proposal is a denial quorum proposal without options, approved
+    /// unless vetoed with deny votes
+    ///
+    /// Its transactions belong to the single implicit option 0
+    pub fn is_approve_by_default(&self) -> bool {
+        self.use_denial_quorum && self.options.is_empty()
+    }
+
+    /// Returns the number of options proposal transactions can be inserted for
+    pub fn get_executable_option_count(&self) -> usize {
+        if self.is_approve_by_default() {
+            1
+        } else {
+            self.options.len()
+        }
+    }
+
+    /// Returns the number of proposal transactions inserted for the option
+    pub fn get_transaction_count(&self, option_index: u8) -> u8 {
+        self.option_transaction_counts
+            .get(option_index as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the options with their labels and transaction counts
+    ///
+    /// The implicit option of an approve-by-default proposal has an empty label
+    pub fn get_options(&self) -> Vec<ProposalOption> {
+        (0..self.get_executable_option_count())
+            .map(|index| ProposalOption {
+                label: self.options.get(index).cloned().unwrap_or_default(),
+                transaction_count: self.get_transaction_count(index as u8),
+            })
+            .collect()
+    }
+
+    /// Returns the time the approved proposal can first be executed, once both the
+    /// governance's veto window and execution delay have elapsed since its approval
+    pub fn get_execution_unlocks_at(&self, config: &GovernanceConfig) -> Option<u64> {
+        let lock_seconds = config.veto_window_seconds.max(config.execution_delay_seconds);
+        self.executable_at
+            .map(|executable_at| executable_at.saturating_add(lock_seconds))
+    }
+
+    /// Returns true if the proposal was approved and is still waiting to be executed, vetoed
+    /// or expired
+    pub fn is_pending_execution(&self) -> bool {
+        matches!(self.state, ProposalState::Approved | ProposalState::UnderfundedApproved)
+    }
+
+    /// Returns true if the vote's stake backs the winning option of a proposal which locks it
+    /// until it's executed, vetoed or expired
+    pub fn locks_stake_of(&self, vote_record: &VoteRecord) -> bool {
+        self.locks_backing_stake
+            && self.is_pending_execution()
+            && matches!(self.winning_option, Some(option_index) if vote_record.backs_option(option_index))
+    }
+
+    /// Returns true if the approved proposal can't be executed yet
+    pub fn is_execution_locked(&self, config: &GovernanceConfig, now: u64) -> bool {
+        matches!(self.get_execution_unlocks_at(config), Some(unlocks_at) if now < unlocks_at)
+    }
+
+    /// Returns true if the approved proposal is still within the governance's veto window
+    pub fn is_in_veto_window(&self, config: &GovernanceConfig, now: u64) -> bool {
+        matches!(
+            self.executable_at,
+            Some(executable_at) if now < executable_at.saturating_add(config.veto_window_seconds)
+        )
+    }
+
+    /// Returns true if the proposal's total vote weight per unique voter is below the minimum
+    pub fn is_below_vote_weight_per_voter(&self, min_vote_weight_per_voter: u64) -> bool {
+        self.vote_record_count > 0
+            && (self.total_vote_weight as u128)
+                < min_vote_weight_per_voter as u128 * self.vote_record_count as u128
+    }
+
+    /// Returns the weight oracle signing the weights votes on the proposal are cast with, if any
+    ///
+    /// Votes use the source the proposal was signed off with, proposals signed off before
+    /// vote weight sources existed being voted on with community tokens
+    pub fn get_weight_oracle(&self, realm: &Realm) -> Option<Pubkey> {
+        match self.vote_weight_source {
+            Some(VoteWeightSource::NftCollection { weight_oracle, .. }) => Some(weight_oracle),
+            Some(VoteWeightSource::CommunityToken) | None => realm.weight_oracle,
+        }
+    }
+
+    /// Returns true if the proposal was flagged as a possible sybil attack and can't be
+    /// executed until a council member reviews it
+    pub fn is_awaiting_sybil_review(&self, config: &GovernanceConfig) -> bool {
+        self.is_sybil_flagged
+            && self.sybil_reviewed_by.is_none()
+            && matches!(config.sybil_vote_check, Some(check) if check.require_council_review)
+    }
+
+    /// Returns true if the proposal became executable longer than the governance's execution
+    /// window ago and can no longer be executed
+    ///
+    /// The window opens once the veto window and execution delay have elapsed
+    pub fn is_execution_window_elapsed(&self, config: &GovernanceConfig, now: u64) -> bool {
+        match (self.get_execution_unlocks_at(config), config.execution_window_seconds) {
+            (Some(unlocks_at), Some(execution_window_seconds)) => {
+                now > unlocks_at.saturating_add(execution_window_seconds)
+            }
+            _ => false,
+        }
+    }
+
+    /// Accrues the conviction of a conviction proposal up to the given time
+    ///
+    /// The gap between the conviction and the proposal's support, its total vote weight,
+    /// halves every conviction_half_life_seconds, so support only builds full conviction
+    /// once it has been sustained for a few half-lives
+    pub fn accrue_conviction(&mut self, config: &GovernanceConfig, now: u64) {
+        if self.vote_type != VoteType::Conviction || now <= self.conviction_updated_at {
+            return;
+        }
+        
+        let elapsed_seconds = now - self.conviction_updated_at;
+        let decay = get_conviction_decay(elapsed_seconds, config.conviction_half_life_seconds);
+        let support = self.total_vote_weight as u128;
+        let conviction = self.conviction as u128;
+        // The decayed gap is rounded so conviction rounds down, and never exceeds the gap,
+        // keeping the result between conviction and support
+        self.conviction = if conviction <= support {
+            let decay_round_up = (1 << CONVICTION_DECAY_BITS) - 1;
+            let gap = ((support - conviction) * decay + decay_round_up) >> CONVICTION_DECAY_BITS;
+            (support - gap) as u64
+        } else {
+            let gap = ((conviction - support) * decay) >> CONVICTION_DECAY_BITS;
+            (support + gap) as u64
+        };
+        self.conviction_updated_at = now;
+    }
+
+    /// Returns true if the accrued conviction reaches the governance's conviction threshold
+    /// of the max vote weight
+    pub fn has_reached_conviction_threshold(&self, config: &GovernanceConfig, max_vote_weight: u64) -> bool {
+        self.conviction > 0
+            && reaches_bps(self.conviction as u128, max_vote_weight, config.conviction_threshold_bps)
+    }
+
+    /// Validates a vote against the proposal's vote type and options and returns
+    /// the weight it adds to each option
+    ///
+    /// Weighted votes allocate percentages of the vote weight which must add up to 100
+    pub fn get_option_vote_weights(
+        &self,
+        vote: &Vote,
+        vote_weight: u64,
+    ) -> Result<Vec<(u8, u64)>, ProgramError> {
+        let is_valid_option = |option_index: &u8| (*option_index as usize) < self.options.len();
+
+        match (&self.vote_type, vote) {
+            (VoteType::SingleChoice | VoteType::Conviction, Vote::SingleChoice { option_index }) => {
+                if !is_valid_option(option_index) {
+                    return Err(GovernanceError::InvalidVote.into());
+                }
+                Ok(vec![(*option_index, vote_weight)])
+            }
+            (VoteType::MultiChoice { max_voter_options }, Vote::MultiChoice { option_indices }) => {
+                if option_indices.is_empty()
+                    || option_indices.len() > *max_voter_options as usize
+                    || !option_indices.iter().all(is_valid_option)
+                    || has_duplicates(option_indices.iter())
+                {
+                    return Err(GovernanceError::InvalidVote.into());
+                }
+                Ok(option_indices.iter().map(|option_index| (*option_index, vote_weight)).collect())
+            }
+            (VoteType::Weighted, Vote::Weighted { weights }) => {
+                let total_percentage: u32 = weights.iter().map(|(_, weight)| *weight as u32).sum();
+                if total_percentage != 100
+                    || !weights.iter().all(|(option_index, _)| is_valid_option(option_index))
+                    || has_duplicates(weights.iter().map(|(option_index, _)| option_index))
+                {
+                    return Err(GovernanceError::InvalidVote.into());
+                }
+                weights
+                    .iter()
+                    .map(|(option_index, weight)| {
+                        Ok((*option_index, to_u64_amount(vote_weight as u128 * *weight as u128 / 100)?))
+                    })
+                    .collect()
+            }
+            (VoteType::RankedChoice, Vote::RankedChoice { ranking }) => {
+                if ranking.len() != self.options.len()
+                    || !ranking.iter().all(is_valid_option)
+                    || has_duplicates(ranking.iter())
+                {
+                    return Err(GovernanceError::InvalidVote.into());
+                }
+                Ok(vec![(ranking[0], vote_weight)])
+            }
+            (_, Vote::Abstain) => Ok(vec![]),
+            (_, Vote::Deny) if self.is_approve_by_default() => Ok(vec![]),
+            _ => Err(GovernanceError::InvalidVote.into()),
+        }
+    }
+
+    /// Clamps a single voter's option weights to the proposal's per voter option cap
+    ///
+    /// Under OptionCapExcessPolicy::Redistribute the excess is shared evenly, in vote order,
+    /// among the voter's options still below the cap
+    pub fn apply_option_vote_cap(&self, option_vote_weights: &mut [(u8, u64)]) {
+        let Some(cap) = self.per_voter_option_cap else {
+            return;
+        };
+
+        let mut excess: u64 = 0;
+        for (_, weight) in option_vote_weights.iter_mut() {
+            if *weight > cap.max_weight_per_option {
+                excess = excess.saturating_add(*weight - cap.max_weight_per_option);
+                *weight = cap.max_weight_per_option;
+            }
+        }
+
+        if cap.excess_policy == OptionCapExcessPolicy::Discard {
+            return;
+        }
+
+        loop {
+            let open_options = option_vote_weights
+                .iter()
+                .filter(|(_, weight)| *weight < cap.max_weight_per_option)
+                .count() as u64;
+            if excess == 0 || open_options == 0 {
+                break;
+            }
+
+            let share = (excess / open_options).max(1);
+            for (_, weight) in option_vote_weights.iter_mut() {
+                let added = share.min(cap.max_weight_per_option - *weight).min(excess);
+                *weight += added;
+                excess -= added;
+            }
+        }
+    }
+
+    /// Adds the weight of a ranked choice vote to the ballot with the same ranking
+    pub fn add_ranked_ballot(&mut self, ranking: &[u8], weight: u64) -> ProgramResult {
+        match self.ranked_ballots.iter_mut().find(|ballot| ballot.ranking == ranking) {
+            Some(ballot) => {
+                ballot.weight = ballot
+                    .weight
+                    .checked_add(weight)
+                    .ok_or(GovernanceError::MathOverflow)?;
+            }
+            None => self.ranked_ballots.push(RankedBallot {
+                ranking: ranking.to_vec(),
+                weight,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Adds a vote with the given option vote weights to the proposal's tallies
+    pub fn add_vote_tally(
+        &mut self,
+        vote: &Vote,
+        vote_weight: u64,
+        option_vote_weights: &[(u8, u64)],
+    ) -> ProgramResult {
+        if let Vote::RankedChoice { ranking } = vote {
+            let ballot_weight = option_vote_weights.iter().map(|(_, weight)| weight).sum();
+            self.add_ranked_ballot(ranking, ballot_weight)?;
+        }
+        for (option_index, option_weight) in option_vote_weights {
+            let option_result = self.vote_results.entry(*option_index).or_insert(0);
+            *option_result = option_result
+                .checked_add(*option_weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        }
+        if *vote == Vote::Abstain {
+            self.abstain_vote_weight = self
+                .abstain_vote_weight
+                .checked_add(vote_weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        } else if *vote == Vote::Deny {
+            self.deny_vote_weight = self
+                .deny_vote_weight
+                .checked_add(vote_weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        } else {
+            self.total_vote_weight = self
+                .total_vote_weight
+                .checked_add(vote_weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Records the vote time for each option the vote added weight to which now leads,
+    /// alone or tied
+    ///
+    /// Weights only grow, so an option tied for the lead at the end last reached the lead
+    /// when it reached its final weight
+    pub fn record_option_leads(&mut self, option_vote_weights: &[(u8, u64)], now: u64) {
+        let leading_weight = self.vote_results.values().max().copied().unwrap_or(0);
+        self.option_lead_reached_at.resize(self.options.len(), 0);
+        for (option_index, option_weight) in option_vote_weights {
+            if *option_weight == 0 || self.vote_results.get(option_index) != Some(&leading_weight) {
+                continue;
+            }
+            if let Some(lead_reached_at) = self.option_lead_reached_at.get_mut(*option_index as usize) {
+                *lead_reached_at = now;
+            }
+        }
+    }
+
+    /// Returns the time the option last took or joined the lead, 0 if it wasn't recorded
+    pub fn get_lead_reached_at(&self, option_index: u8) -> u64 {
+        self.option_lead_reached_at
+            .get(option_index as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the instant-runoff winner of a ranked choice proposal
+    ///
+    /// Each round counts every ballot for its most preferred option still in the race; an
+    /// option with more than half of the weight wins, otherwise the option with the least
+    /// weight is eliminated, the highest option index first on ties
+    pub fn get_instant_runoff_winner(&self) -> Option<u8> {
+        let mut eliminated = vec![false; self.options.len()];
+        loop {
+            let mut tallies = vec![0u128; self.options.len()];
+            for ballot in &self.ranked_ballots {
+                if let Some(option_index) = ballot
+                    .ranking
+                    .iter()
+                    .find(|option_index| !eliminated[**option_index as usize])
+                {
+                    tallies[*option_index as usize] += ballot.weight as u128;
+                }
+            }
+            let remaining_weight: u128 = tallies.iter().sum();
+            if remaining_weight == 0 {
+                return None;
+            }
+
+            let remaining_options = || (0..self.options.len()).filter(|index| !eliminated[*index]);
+            let leader = remaining_options()
+                .max_by(|a, b| tallies[*a].cmp(&tallies[*b]).then(b.cmp(a)))?;
+            if tallies[leader] * 2 > remaining_weight {
+                return Some(leader as u8);
+            }
+
+            let last = remaining_options()
+                .min_by(|a, b| tallies[*a].cmp(&tallies[*b]).then(b.cmp(a)))?;
+            eliminated[last] = true;
+        }
+    }
+
+    /// Resolves the final state and winning option of the proposal from its vote results
+    ///
+    /// Proposals which don't reach quorum expire; otherwise the leading option is approved
+    /// if it meets the vote threshold, with ties broken by the governance's option tie-break.
+    /// Ranked choice proposals approve their instant-runoff winner instead.
+    /// Abstentions never count towards the vote threshold, only optionally towards quorum.
+    /// Governances requiring council approval reject proposals the council hasn't approved,
+    /// governances requiring funded execution resolve approvals to UnderfundedApproved, and
+    /// the quorum is lowered by the governance's quorum decay after quorum failures
+    pub fn resolve_vote_outcome(
+        &self,
+        config: &GovernanceConfig,
+        quorum_failure_count: u8,
+        max_vote_weight: u64,
+        abstain_counts_for_quorum: bool,
+    ) -> (ProposalState, Option<u8>) {
+        let (state, winning_option) =
+            match self.resolve_vote_tallies(config, quorum_failure_count, max_vote_weight, abstain_counts_for_quorum) {
+                (ProposalState::Rejected | ProposalState::Expired, _) if self.fallback_option_index.is_some() => {
+                    (ProposalState::Approved, self.fallback_option_index)
+                }
+                outcome => outcome,
+            };
+        if state == ProposalState::Approved && config.council_approval_quorum_bps.is_some() && !self.council_approved {
+            return (ProposalState::Rejected, None);
+        }
+        if state == ProposalState::Approved && config.require_funded_execution {
+            return (ProposalState::UnderfundedApproved, winning_option);
+        }
+        (state, winning_option)
+    }
+
+    fn resolve_vote_tallies(
+        &self,
+        config: &GovernanceConfig,
+        quorum_failure_count: u8,
+        max_vote_weight: u64,
+        abstain_counts_for_quorum: bool,
+    ) -> (ProposalState, Option<u8>) {
+        if self.vote_type == VoteType::Conviction {
+            return if self.has_reached_conviction_threshold(config, max_vote_weight) {
+                (ProposalState::Approved, Some(0))
+            } else {
+                (ProposalState::Rejected, None)
+            };
+        }
+        if self.is_approve_by_default() {
+            let is_vetoed = self.deny_vote_weight > 0
+                && reaches_bps(self.deny_vote_weight as u128, max_vote_weight, config.denial_threshold_bps);
+            return if is_vetoed {
+                (ProposalState::Rejected, None)
+            } else {
+                (ProposalState::Approved, Some(0))
+            };
+        }
+        
+        let (quorum_bps, vote_threshold_bps) = config.get_vote_thresholds(self.impact_tier, quorum_failure_count);
+        if !has_reached_quorum(self, max_vote_weight, quorum_bps, abstain_counts_for_quorum) {
+            return (ProposalState::Expired, None);
+        }
+        if self.total_vote_weight == 0 {
+            return (ProposalState::Rejected, None);
+        }
+
+        if self.vote_type == VoteType::RankedChoice {
+            return match self.get_instant_runoff_winner() {
+                Some(option_index) => (ProposalState::Approved, Some(option_index)),
+                None => (ProposalState::Rejected, None),
+            };
+        }
+
+        let leading_option = self
+            .vote_results
+            .iter()
+            .max_by(|(a_index, a_weight), (b_index, b_weight)| {
+                let tie_break = match config.option_tie_break {
+                    OptionTieBreak::LowestIndex => Ordering::Equal,
+                    OptionTieBreak::EarliestLead => self
+                        .get_lead_reached_at(**b_index)
+                        .cmp(&self.get_lead_reached_at(**a_index)),
+                };
+                a_weight.cmp(b_weight).then(tie_break).then(b_index.cmp(a_index))
+            });
+
+        match leading_option {
+            Some((option_index, weight))
+                if reaches_bps(*weight as u128, self.total_vote_weight, vote_threshold_bps) =>
+            {
+                (ProposalState::Approved, Some(*option_index))
+            }
+            _ => (ProposalState::Rejected, None),
+        }
+    }
+}
+
+impl ProposalTransaction {
+    /// Returns the total amount of every source token account transferred from by the
+    /// transaction's SPL Token instructions, in order of first appearance
+    pub fn get_token_transfer_amounts(&self) -> Result<Vec<(Pubkey, u64)>, ProgramError> {
+        let mut transfer_amounts: Vec<(Pubkey, u64)> = vec![];
+        for instruction in &self.instructions {
+            if instruction.program_id != spl_token::id() {
+                continue;
+            }
+            let amount = match spl_token::instruction::TokenInstruction::unpack(&instruction.data)? {
+                spl_token::instruction::TokenInstruction::Transfer { amount }
+                | spl_token::instruction::TokenInstruction::TransferChecked { amount, .. } => amount,
+                _ => continue,
+            };
+            let source = instruction
+                .accounts
+                .first()
+                .ok_or(GovernanceError::InvalidProposalTransaction)?
+                .pubkey;
+
+            match transfer_amounts.iter_mut().find(|(pubkey, _)| *pubkey == source) {
+                Some((_, total_amount)) => {
+                    *total_amount = total_amount
+                        .checked_add(amount)
+                        .ok_or(GovernanceError::MathOverflow)?;
+                }
+                None => transfer_amounts.push((source, amount)),
+            }
+        }
+        Ok(transfer_amounts)
+    }
+}
+
+impl From<&InstructionData> for Instruction {
+    fn from(instruction: &InstructionData) -> Self {
+        Instruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        }
+    }
+}
+
+/// Returns the remaining accounts ExecuteProposal expects for the given instructions, each
+/// instruction's program followed by its accounts
+///
+/// The governance PDA signs through invoke_signed, so it's passed as a non-signer
+pub fn get_execution_account_metas(instructions: &[InstructionData], governance: &Pubkey) -> Vec<AccountMeta> {
+    let mut account_metas = vec![];
+    for instruction in instructions {
+        account_metas.push(AccountMeta::new_readonly(instruction.program_id, false));
+        account_metas.extend(instruction.accounts.iter().map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer && account.pubkey != *governance,
+            is_writable: account.is_writable,
+        }));
+    }
+    account_metas
+}
+
+impl ProposalArchive {
+    /// Returns the account size needed to store a full proposal archive page
+    pub fn get_max_size() -> usize {
+        1 + 32 + 4 + (4 + PROPOSAL_ARCHIVE_PAGE_SIZE * (32 + 1 + 2)) + 1 + 63
+    }
+
+    /// Returns true if no more proposals can be recorded on the page
+    pub fn is_full(&self) -> bool {
+        self.proposals.len() >= PROPOSAL_ARCHIVE_PAGE_SIZE
+    }
+}
+
+impl ProposalBundle {
+    /// Returns the account size needed to store a full proposal bundle
+    pub fn get_max_size() -> usize {
+        1 + 32 + 32 + (4 + MAX_BUNDLE_PROPOSALS * 32) + (1 + 8) + 1 + 63
+    }
+}
+
+impl ProposalCertificate {
+    /// Returns the account size needed to store the certificate of a proposal with the
+    /// given number of voted options
+    pub fn get_max_size(option_count: usize) -> usize {
+        1 + 32 + 32 + 1 + (1 + 1) + (4 + option_count * (1 + 8)) + 8 + 8 + 8 + 8 + 8 + 32 + 1 + 63
+    }
+
+    /// Certifies the outcome the proposal was just finalized with
+    pub fn new(
+        program_id: &Pubkey,
+        proposal_key: &Pubkey,
+        proposal: &Proposal,
+        max_vote_weight: u64,
+        finalized_at: u64,
+    ) -> Result<Self, ProgramError> {
+        let mut vote_results: Vec<(u8, u64)> = proposal.vote_results.iter().map(|(i, w)| (*i, *w)).collect();
+        vote_results.sort_unstable();
+        
+        let mut certificate = ProposalCertificate {
+            account_type: AccountType::ProposalCertificate,
+            proposal: *proposal_key,
+            governance: proposal.governance,
+            state: proposal.state,
+            winning_option: proposal.winning_option,
+            vote_results,
+            total_vote_weight: proposal.total_vote_weight,
+            abstain_vote_weight: proposal.abstain_vote_weight,
+            deny_vote_weight: proposal.deny_vote_weight,
+            max_vote_weight,
+            finalized_at,
+            attestation: [0; 32],
+            used_reserved: ProposalCertificate::get_used_reserved()?,
+            reserved: [0; 63],
+        };
+        certificate.attestation = certificate.compute_attestation(program_id)?;
+        Ok(certificate)
+    }
+
+    /// Returns the keccak hash of the program id and the Borsh encoded certificate with a
+    /// zeroed attestation, a compact commitment to the outcome relayers can carry elsewhere
+    pub fn compute_attestation(&self, program_id: &Pubkey) -> Result<[u8; 32], ProgramError> {
+        let outcome = ProposalCertificate {
+            attestation: [0; 32],
+            ..self.clone()
+        };
+        Ok(keccak::hashv(&[program_id.as_ref(), &outcome.try_to_vec()?]).to_bytes())
+    }
+
+    /// Verifies the certificate is the proposal's certificate account, its attestation is
+    /// intact and it still matches the proposal's outcome and tallies
+    ///
+    /// Only the outcome is compared, as the proposal's state may have moved on since, e.g.
+    /// to Executed
+    pub fn verify(
+        &self,
+        program_id: &Pubkey,
+        certificate_key: &Pubkey,
+        proposal_key: &Pubkey,
+        proposal: &Proposal,
+    ) -> ProgramResult {
+        let is_valid = *certificate_key == get_proposal_certificate_address(program_id, proposal_key)
+            && self.account_type == AccountType::ProposalCertificate
+            && self.proposal == *proposal_key
+            && self.governance == proposal.governance
+            && self.winning_option == proposal.winning_option
+            && self.vote_results.len() == proposal.vote_results.len()
+            && self
+                .vote_results
+                .iter()
+                .all(|(option_index, weight)| proposal.vote_results.get(option_index) == Some(weight))
+            && self.total_vote_weight == proposal.total_vote_weight
+            && self.abstain_vote_weight == proposal.abstain_vote_weight
+            && self.deny_vote_weight == proposal.deny_vote_weight
+            && self.attestation == self.compute_attestation(program_id)?;
+        if !is_valid {
+            return Err(GovernanceError::InvalidProposalCertificate.into());
+        }
+        
+        Ok(())
+    }
+}
+
+impl VotingPowerClaim {
+    /// Returns the account size needed to store a voting power claim
+    pub fn get_max_size() -> usize {
+        1 + 32 + 32 + 8 + 1 + 63
+    }
+}
+
+impl VestingStream {
+    /// Returns the account size needed to store a vesting stream
+    pub fn get_max_size() -> usize {
+        1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + (1 + 8) + 1 + 63
+    }
+
+    /// Returns the amount vested at the given time, which stops advancing at cancellation
+    pub fn get_vested_amount(&self, now: u64) -> u64 {
+        let now = self.cancelled_at.map_or(now, |cancelled_at| now.min(cancelled_at));
+        if now < self.cliff_time {
+            return 0;
+        }
+
+        let elapsed = now.saturating_sub(self.start_time);
+        if elapsed >= self.duration_seconds {
+            return self.total_amount;
+        }
+        (self.total_amount as u128 * elapsed as u128 / self.duration_seconds as u128) as u64
+    }
+}
+
+impl TokenOwnerRecord {
+    /// Returns the account size needed to store a token owner record
+    pub fn get_max_size() -> usize {
+        1 + 32 + 32 + 32 + 8 + 4 + 8 + (4 + MAX_STAKE_TRANCHES * (8 + 8)) + (1 + 1) + 8 + (1 + 32) + 8 + 8 + 8 + 8 + 16 + 8 + 1 + 63
+    }
+
+    /// Returns the voting power of the token owner, staked tokens plus claimed snapshot power
+    pub fn get_voting_power(&self) -> u64 {
+        self.governing_token_deposit_amount
+            .saturating_add(self.claimed_voting_power)
+    }
+
+    /// Returns the voting power the given voter can vote with, split between the token owner
+    /// and the governance delegate, or None if the voter is neither
+    pub fn get_voter_voting_power(&self, voter: &Pubkey) -> Option<u64> {
+        let delegated_power = self.delegated_amount.min(self.get_voting_power());
+        if *voter == self.governing_token_owner {
+            Some(self.get_voting_power() - delegated_power)
+        } else if self.governance_delegate == Some(*voter) {
+            Some(delegated_power)
+        } else {
+            None
+        }
+    }
+
+    /// Counts one of the record's votes as relinquished
+    pub fn relinquish_vote(&mut self) {
+        // Emergency unstaking may have cleared the count already
+        self.unrelinquished_votes_count = self.unrelinquished_votes_count.saturating_sub(1);
+        self.release_revoked_delegation();
+    }
+
+    /// Returns the amount of a revoked delegation to the token owner once no vote weighed
+    /// against it is outstanding
+    pub fn release_revoked_delegation(&mut self) {
+        if self.unrelinquished_votes_count == 0 && self.governance_delegate.is_none() {
+            self.delegated_amount = 0;
+        }
+    }
+
+    /// Returns the number of slots since the record's last deposit or claim
+    pub fn get_record_age_slots(&self, slot: u64) -> u64 {
+        slot.saturating_sub(self.last_deposit_slot)
+    }
+
+    /// Returns the cumulative stake-seconds as of the given time, the current deposit having
+    /// been held since the last accrual
+    ///
+    /// Records predating the accumulator start counting from their first accrual
+    pub fn get_cumulative_stake_seconds(&self, now: u64) -> u128 {
+        if self.stake_seconds_updated_at == 0 {
+            return self.cumulative_stake_seconds;
+        }
+        let elapsed_seconds = now.saturating_sub(self.stake_seconds_updated_at);
+        self.cumulative_stake_seconds
+            .saturating_add(self.governing_token_deposit_amount as u128 * elapsed_seconds as u128)
+    }
+
+    /// Accrues the deposit held since the last accrual, to be called before every change of
+    /// the deposit amount
+    pub fn accrue_stake_seconds(&mut self, now: u64) {
+        self.cumulative_stake_seconds = self.get_cumulative_stake_seconds(now);
+        self.stake_seconds_updated_at = self.stake_seconds_updated_at.max(now);
+    }
+
+    /// Charges the weight squared of a quadratic vote against the credit budget, starting
+    /// from a full budget once a new period has begun
+    pub fn spend_quadratic_credits(
+        &mut self,
+        budget: &QuadraticCreditBudget,
+        vote_weight: u64,
+        now: u64,
+    ) -> ProgramResult {
+        let period_started_at = now - now % budget.period_seconds.max(1);
+        if period_started_at > self.credit_period_started_at {
+            self.quadratic_credits_spent = 0;
+            self.credit_period_started_at = period_started_at;
+        }
+        
+        let credits_spent = self.quadratic_credits_spent as u128 + (vote_weight as u128).pow(2);
+        if credits_spent > budget.credits_per_period as u128 {
+            return Err(GovernanceError::QuadraticCreditsExhausted.into());
+        }
+        // Bounded by credits_per_period, so the cast can't truncate
+        self.quadratic_credits_spent = credits_spent as u64;
+        
+        Ok(())
+    }
+
+    /// Records a deposit as a new stake tranche
+    ///
+    /// Once MAX_STAKE_TRANCHES are held the deposit is consolidated into the newest tranche,
+    /// whose unlock time becomes the amount-weighted average of both
+    pub fn add_stake_tranche(&mut self, amount: u64, unlock_time: u64) -> ProgramResult {
+        if self.stake_tranches.len() < MAX_STAKE_TRANCHES {
+            self.stake_tranches.push(StakeTranche { amount, unlock_time });
+        } else if let Some(newest) = self.stake_tranches.last_mut() {
+            let total_amount = newest
+                .amount
+                .checked_add(amount)
+                .ok_or(GovernanceError::MathOverflow)?;
+            let weighted_unlock_time = (newest.amount as u128 * newest.unlock_time as u128
+                + amount as u128 * unlock_time as u128)
+                / total_amount.max(1) as u128;
+            
+            newest.amount = total_amount;
+            newest.unlock_time = weighted_unlock_time as u64;
+        }
+        
+        self.update_earliest_unstaking_time();
+        Ok(())
+    }
+
+    /// Keeps every staked tranche locked until at least the given unlock time
+    pub fn extend_stake_lock(&mut self, unlock_time: u64) {
+        for tranche in self.stake_tranches.iter_mut() {
+            tranche.unlock_time = tranche.unlock_time.max(unlock_time);
+        }
+        
+        self.update_earliest_unstaking_time();
+    }
+
+    /// Removes the amount from the tranches oldest first, whether or not they're unlocked
+    pub fn take_stake(&mut self, amount: u64) -> ProgramResult {
+        let staked_amount: u64 = self.stake_tranches.iter().map(|tranche| tranche.amount).sum();
+        if staked_amount < amount {
+            return Err(GovernanceError::InsufficientStake.into());
+        }
+        
+        self.take_unlocked_stake(amount, u64::MAX)
+    }
+
+    /// Removes the amount from unlocked tranches, oldest first
+    pub fn take_unlocked_stake(&mut self, amount: u64, now: u64) -> ProgramResult {
+        let unlocked_amount: u64 = self
+            .stake_tranches
+            .iter()
+            .filter(|tranche| tranche.unlock_time <= now)
+            .map(|tranche| tranche.amount)
+            .sum();
+        if unlocked_amount < amount {
+            return Err(GovernanceError::UnstakingCooldownActive.into());
+        }
+        
+        let mut remaining = amount;
+        for tranche in self.stake_tranches.iter_mut().filter(|tranche| tranche.unlock_time <= now) {
+            let taken = tranche.amount.min(remaining);
+            tranche.amount -= taken;
+            remaining -= taken;
+        }
+        self.stake_tranches.retain(|tranche| tranche.amount > 0);
+        
+        self.update_earliest_unstaking_time();
+        Ok(())
+    }
+
+    fn update_earliest_unstaking_time(&mut self) {
+        self.earliest_unstaking_time = self
+            .stake_tranches
+            .iter()
+            .map(|tranche| tranche.unlock_time)
+            .min()
+            .unwrap_or(0);
+    }
+}
+
+impl CouncilVoteRecord {
+    /// Returns the account size needed to store a council vote record
+    pub fn get_max_size() -> usize {
+        1 + 32 + 32 + 1 + 8 + 8 + 1 + 32 + 31
+    }
+}
+
+impl VoteRecord {
+    /// Returns the account size CreateVoteRecord allocates, enough for any vote on the proposal
+    pub fn get_max_size(proposal: &Proposal) -> usize {
+        // Proposals without options take votes for the implicit option 0
+        let option_count = proposal.options.len().max(1);
+        1 + 32 + 32 + (1 + 4 + option_count * 2) + 8 + 8 + 1 + (4 + option_count * (1 + 8)) + 32 + 1 + 8 + 1 + 1 + 21
+    }
+
+    /// Returns the token owner or governance delegate which cast the vote
+    pub fn get_voter_authority(&self) -> Pubkey {
+        // Records created before the voter authority was recorded stored the voter as the owner
+        if self.voter_authority == Pubkey::default() {
+            self.governing_token_owner
+        } else {
+            self.voter_authority
+        }
+    }
+
+    /// Returns true if the vote added weight to the given option
+    pub fn backs_option(&self, option_index: u8) -> bool {
+        self.option_vote_weights
+            .iter()
+            .any(|(index, weight)| *index == option_index && *weight > 0)
+    }
+
+    /// Returns true if the authority may relinquish or change the vote
+    ///
+    /// The token owner always may, a governance delegate only for votes it cast and while it's
+    /// still the token owner's delegate, so revoking a delegate leaves its votes to the owner
+    pub fn is_vote_authority(&self, token_owner_record: &TokenOwnerRecord, authority: &Pubkey) -> bool {
+        *authority == token_owner_record.governing_token_owner
+            || (*authority == self.get_voter_authority()
+                && token_owner_record.governance_delegate == Some(*authority))
+    }
+}
+
+// Program entrypoint
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+// Program entrypoint's implementation
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = LeapfrogInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        LeapfrogInstruction::InitializeRealm { 
+            name, 
+            min_community_tokens_to_create_proposal, 
+            community_mint_max_vote_weight_source,
+            use_quadratic_voting,
+            voting_power_merkle_root,
+            nonce,
+            allow_shared_council_mint,
+        } => {
+            msg!("Instruction: Initialize Realm");
+            process_initialize_realm(
+                program_id,
+                accounts,
+                name,
+                min_community_tokens_to_create_proposal,
+                community_mint_max_vote_weight_source,
+                use_quadratic_voting,
+                voting_power_merkle_root,
+                nonce,
+                allow_shared_council_mint,
+            )
+        }
+        LeapfrogInstruction::CreateProposal { 
+            name, 
+            description_link, 
+            vote_type, 
+            options, 
+            use_denial_quorum,
+            voting_period_days,
+            per_voter_option_cap,
+            impact_tier,
+            depends_on,
+            description_hash,
+            parameter_change,
+            fallback_option_index,
+        } => {
+            msg!("Instruction: Create Proposal");
+            process_create_proposal(
+                program_id,
+                accounts,
+                name,
+                description_link, 
+                vote_type,
+                options,
+                use_denial_quorum,
+                voting_period_days,
+                per_voter_option_cap,
+                impact_tier,
+                depends_on,
+                description_hash,
+                parameter_change,
+                fallback_option_index,
+            )
+        }
+        LeapfrogInstruction::CastVote { vote, staked_amount, oracle_weight } => {
+            msg!("Instruction: Cast Vote");
+            process_cast_vote(program_id, accounts, vote, staked_amount, oracle_weight)
+        }
+        LeapfrogInstruction::ExecuteProposal => {
+            msg!("Instruction: Execute Proposal");
+            process_execute_proposal(program_id, accounts)
+        }
+        LeapfrogInstruction::StakeTokens { amount } => {
+            msg!("Instruction: Stake Tokens");
+            process_stake_tokens(program_id, accounts, amount)
+        }
+        LeapfrogInstruction::UnstakeTokens { amount } => {
+            msg!("Instruction: Unstake Tokens");
+            process_unstake_tokens(program_id, accounts, amount)
+        }
+        LeapfrogInstruction::CreateGovernance { governed_account, config } => {
+            msg!("Instruction: Create Governance");
+            process_create_governance(program_id, accounts, governed_account, config)
+        }
+        LeapfrogInstruction::SignOffProposal => {
+            msg!("Instruction: Sign Off Proposal");
+            process_sign_off_proposal(program_id, accounts)
+        }
+        LeapfrogInstruction::FinalizeVote => {
+            msg!("Instruction: Finalize Vote");
+            process_finalize_vote(program_id, accounts)
+        }
+        LeapfrogInstruction::InsertProposalTransaction { option_index, instructions } => {
+            msg!("Instruction: Insert Proposal Transaction");
+            process_insert_proposal_transaction(program_id, accounts, option_index, instructions)
+        }
+        LeapfrogInstruction::SetRealmConfig { config_args } => {
+            msg!("Instruction: Set Realm Config");
+            process_set_realm_config(program_id, accounts, config_args)
+        }
+        LeapfrogInstruction::SetSybilScore { score } => {
+            msg!("Instruction: Set Sybil Score");
+            process_set_sybil_score(program_id, accounts, score)
+        }
+        LeapfrogInstruction::SetMaxVoteWeightSource { max_vote_weight_source } => {
+            msg!("Instruction: Set Max Vote Weight Source");
+            process_set_max_vote_weight_source(program_id, accounts, max_vote_weight_source)
+        }
+        LeapfrogInstruction::ClaimInitialVotingPower { leaf_index, amount, proof } => {
+            msg!("Instruction: Claim Initial Voting Power");
+            process_claim_initial_voting_power(program_id, accounts, leaf_index, amount, proof)
+        }
+        LeapfrogInstruction::CreateVestingStream {
+            recipient,
+            total_amount,
+            start_time,
+            cliff_time,
+            duration_seconds,
+            nonce,
+        } => {
+            msg!("Instruction: Create Vesting Stream");
+            process_create_vesting_stream(
+                program_id,
+                accounts,
+                recipient,
+                total_amount,
+                start_time,
+                cliff_time,
+                duration_seconds,
+                nonce,
+            )
+        }
+        LeapfrogInstruction::ClaimVestedFunds => {
+            msg!("Instruction: Claim Vested Funds");
+            process_claim_vested_funds(program_id, accounts)
+        }
+        LeapfrogInstruction::PrecheckExecution => {
+            msg!("Instruction: Precheck Execution");
+            process_precheck_execution(program_id, accounts)
+        }
+        LeapfrogInstruction::CancelVestingStream => {
+            msg!("Instruction: Cancel Vesting Stream");
+            process_cancel_vesting_stream(program_id, accounts)
+        }
+        LeapfrogInstruction::SetGovernanceDelegate {
+            new_governance_delegate,
+            delegated_amount,
+        } => {
+            msg!("Instruction: Set Governance Delegate");
+            process_set_governance_delegate(program_id, accounts, new_governance_delegate, delegated_amount)
+        }
+        LeapfrogInstruction::TallySnapshot => {
+            msg!("Instruction: Tally Snapshot");
+            process_tally_snapshot(program_id, accounts)
+        }
+        LeapfrogInstruction::ExpireProposal => {
+            msg!("Instruction: Expire Proposal");
+            process_expire_proposal(program_id, accounts)
+        }
+        LeapfrogInstruction::AddSponsor => {
+            msg!("Instruction: Add Sponsor");
+            process_add_sponsor(program_id, accounts)
+        }
+        LeapfrogInstruction::FinalizeVotes => {
+            msg!("Instruction: Finalize Votes");
+            process_finalize_votes(program_id, accounts)
+        }
+        LeapfrogInstruction::MigrateGoverningMint => {
+            msg!("Instruction: Migrate Governing Mint");
+            process_migrate_governing_mint(program_id, accounts)
+        }
+        LeapfrogInstruction::SwapDeposit => {
+            msg!("Instruction: Swap Deposit");
+            process_swap_deposit(program_id, accounts)
+        }
+        LeapfrogInstruction::RecomputeTally => {
+            msg!("Instruction: Recompute Tally");
+            process_recompute_tally(program_id, accounts)
+        }
+        LeapfrogInstruction::ReleaseCreationFeeDeposit => {
+            msg!("Instruction: Release Creation Fee Deposit");
+            process_release_creation_fee_deposit(program_id, accounts)
+        }
+        LeapfrogInstruction::SetRealmAuthority { authority_set, authority_threshold } => {
+            msg!("Instruction: Set Realm Authority");
+            process_set_realm_authority(program_id, accounts, authority_set, authority_threshold)
+        }
+        LeapfrogInstruction::CloseRealm => {
+            msg!("Instruction: Close Realm");
+            process_close_realm(program_id, accounts)
+        }
+        LeapfrogInstruction::EmergencyUnstake { amount } => {
+            msg!("Instruction: Emergency Unstake");
+            process_emergency_unstake(program_id, accounts, amount)
+        }
+        LeapfrogInstruction::AddProposalCreator { creator } => {
+            msg!("Instruction: Add Proposal Creator");
+            process_add_proposal_creator(program_id, accounts, creator)
+        }
+        LeapfrogInstruction::RemoveProposalCreator { creator } => {
+            msg!("Instruction: Remove Proposal Creator");
+            process_remove_proposal_creator(program_id, accounts, creator)
+        }
+        LeapfrogInstruction::MigrateGovernance => {
+            msg!("Instruction: Migrate Governance");
+            process_migrate_governance(program_id, accounts)
+        }
+        LeapfrogInstruction::CreateVoteRecord => {
+            msg!("Instruction: Create Vote Record");
+            process_create_vote_record(program_id, accounts)
+        }
+        LeapfrogInstruction::CreateProposalBundle => {
+            msg!("Instruction: Create Proposal Bundle");
+            process_create_proposal_bundle(program_id, accounts)
+        }
+        LeapfrogInstruction::ExecuteProposalBundle => {
+            msg!("Instruction: Execute Proposal Bundle");
+            process_execute_proposal_bundle(program_id, accounts)
+        }
+        LeapfrogInstruction::AddAccountDiscriminator => {
+            msg!("Instruction: Add Account Discriminator");
+            process_add_account_discriminator(program_id, accounts)
+        }
+        LeapfrogInstruction::SetRealmLifecycle { lifecycle } => {
+            msg!("Instruction: Set Realm Lifecycle");
+            process_set_realm_lifecycle(program_id, accounts, lifecycle)
+        }
+        LeapfrogInstruction::VetoProposal => {
+            msg!("Instruction: Veto Proposal");
+            process_veto_proposal(program_id, accounts)
+        }
+        LeapfrogInstruction::ReviewSybilFlag => {
+            msg!("Instruction: Review Sybil Flag");
+            process_review_sybil_flag(program_id, accounts)
+        }
+        LeapfrogInstruction::UnstakeEarly { amount } => {
+            msg!("Instruction: Unstake Early");
+            process_unstake_early(program_id, accounts, amount)
+        }
+        LeapfrogInstruction::SetVoteWeightSource { vote_weight_source } => {
+            msg!("Instruction: Set Vote Weight Source");
+            process_set_vote_weight_source(program_id, accounts, vote_weight_source)
+        }
+        LeapfrogInstruction::CastDelegatedVotes { vote } => {
+            msg!("Instruction: Cast Delegated Votes");
+            process_cast_delegated_votes(program_id, accounts, vote)
+        }
+        LeapfrogInstruction::SimulateExecution => {
+            msg!("Instruction: Simulate Execution");
+            process_simulate_execution(program_id, accounts)
+        }
+        LeapfrogInstruction::CastCouncilVote { approve } => {
+            msg!("Instruction: Cast Council Vote");
+            process_cast_council_vote(program_id, accounts, approve)
+        }
+        LeapfrogInstruction::AutoRelinquishFinalized => {
+            msg!("Instruction: Auto Relinquish Finalized");
+            process_auto_relinquish_finalized(program_id, accounts)
+        }
+        LeapfrogInstruction::ApplyParameterChange => {
+            msg!("Instruction: Apply Parameter Change");
+            process_apply_parameter_change(program_id, accounts)
+        }
+        LeapfrogInstruction::RelinquishCouncilVote => {
+            msg!("Instruction: Relinquish Council Vote");
+            process_relinquish_council_vote(program_id, accounts)
+        }
+        LeapfrogInstruction::RelinquishVote => {
+            msg!("Instruction: Relinquish Vote");
+            process_relinquish_vote(program_id, accounts)
+        }
+    }
+}
+
+/// Process InitializeRealm instruction
+#[allow(clippy::too_many_arguments)]
+pub fn process_initialize_realm(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    min_community_tokens_to_create_proposal: ProposalThresholdSource,
+    community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
+    use_quadratic_voting: bool,
+    voting_power_merkle_root: Option<[u8; 32]>,
+    nonce: u64,
+    allow_shared_council_mint: bool,
+) -> ProgramResult {
+    let ctx = InitializeRealmAccounts::from_accounts(accounts)?;
+    
+    let community_mint = unpack_mint(ctx.community_mint)?;
+    if let Some(council_mint_info) = ctx.council_mint {
+        unpack_mint(council_mint_info)?;
+    }
+    
+    // Create the realm account, failing if a realm with the same name, mint and nonce exists
+    let name_hash = keccak::hash(name.as_bytes()).to_bytes();
+    let nonce_bytes = nonce.to_le_bytes();
+    let bump_seed = create_pda_account(
+        ctx.funder,
+        ctx.realm,
+        ctx.system_program,
+        program_id,
+        Realm::get_max_size(&name)?,
+        &get_realm_address_seeds(&name_hash, ctx.community_mint.key, &nonce_bytes),
+    )?;
+    
+    // Create and save realm data
+    let realm = Realm {
+        account_type: AccountType::Realm,
+        name,
+        community_mint: *ctx.community_mint.key,
+        council_mint: ctx.council_mint.map(|info| *info.key),
+        min_community_tokens_to_create_proposal,
+        community_mint_max_vote_weight_source,
+        use_quadratic_voting,
+        authority: Some(*ctx.funder.key),
+        unstaking_cooldown_seconds: DEFAULT_UNSTAKING_COOLDOWN_SECONDS,
+        sybil_oracle: None,
+        default_sybil_resistance_score: 100,
+        quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy::Disallowed,
+        voting_power_merkle_root,
+        max_vote_records_per_proposal: DEFAULT_MAX_VOTE_RECORDS_PER_PROPOSAL,
+        proposal_creation_fee: None,
+        nonce,
+        abstain_counts_for_quorum: true,
+        voting_extends_cooldown: false,
+        min_record_age_slots: 0,
+        allow_shared_council_mint,
+        community_mint_decimals: community_mint.decimals,
+        normalize_vote_weight_to_decimals: false,
+        mint_migration: None,
+        lockup_vote_weight: None,
+        allow_plain_text_description_link: false,
+        authority_set: vec![],
+        authority_threshold: 0,
+        total_staked: 0,
+        governance_count: 0,
+        inactivity_threshold_seconds: None,
+        last_activity_at: to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?,
+        quadratic_credit_budget: None,
+        permissioned_proposal_creation: false,
+        proposal_creators: vec![],
+        weight_oracle: None,
+        lifecycle: RealmLifecycle::Active,
+        early_unstake_fee: None,
+        vote_weight_source: VoteWeightSource::CommunityToken,
+        max_vote_weight_per_voter: None,
+        default_voting_period_days: DEFAULT_VOTING_PERIOD_DAYS,
+        auto_relinquish_finalized: false,
+        lock_stake_until_execution: false,
+        bump_seed,
+        vault_authority_bump_seed: get_vault_authority_address(program_id, ctx.realm.key).1,
+        used_reserved: Realm::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    validate_realm_config(&realm)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process CreateProposal instruction
+#[allow(clippy::too_many_arguments)]
+pub fn process_create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    description_link: String,
+    vote_type: VoteType,
+    options: Vec<String>,
+    use_denial_quorum: bool,
+    voting_period_days: u8,
+    per_voter_option_cap: Option<OptionVoteCap>,
+    impact_tier: Option<u8>,
+    depends_on: Option<Pubkey>,
+    description_hash: Option<[u8; 32]>,
+    parameter_change: Option<ParameterChange>,
+    fallback_option_index: Option<u8>,
+) -> ProgramResult {
+    let ctx = CreateProposalAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    // Validate accounts and ensure the proposal creator has enough tokens
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if realm.lifecycle != RealmLifecycle::Active {
+        return Err(GovernanceError::RealmNotAcceptingProposals.into());
+    }
+    if !realm.allows_vote_type(&vote_type) {
+        return Err(GovernanceError::WeightedVoteNotAllowed.into());
+    }
+    // Proposals created without a voting period use the realm's default
+    let voting_period_days = match voting_period_days {
+        0 => realm.default_voting_period_days,
+        voting_period_days => voting_period_days,
+    };
+    if voting_period_days < governance.config.min_voting_period_days {
+        return Err(GovernanceError::VotingPeriodTooShort.into());
+    }
+    if voting_period_days > MAX_VOTING_PERIOD_DAYS {
+        return Err(GovernanceError::VotingPeriodTooLong.into());
+    }
+    if !realm.is_valid_description_link(&description_link) {
+        return Err(GovernanceError::InvalidDescriptionLink.into());
+    }
+    if !realm.is_proposal_creator(ctx.proposal_owner.key) {
+        return Err(GovernanceError::ProposalCreatorNotAllowed.into());
+    }
+    // A zeroed hash commits to nothing, which only matters when a commitment is required
+    if governance.config.require_description_hash && description_hash.unwrap_or_default() == [0; 32] {
+        return Err(GovernanceError::MissingDescriptionHash.into());
+    }
+    if vote_type == VoteType::Conviction {
+        if options.len() != 1 || use_denial_quorum {
+            return Err(GovernanceError::InvalidConvictionOptions.into());
+        }
+        if governance.config.conviction_half_life_seconds == 0
+            || governance.config.conviction_threshold_bps == 0
+        {
+            return Err(GovernanceError::ConvictionVotingNotConfigured.into());
+        }
+    } else if options.len() < Proposal::get_min_options(use_denial_quorum) {
+        return Err(GovernanceError::TooFewOptions.into());
+    }
+    if vote_type == VoteType::RankedChoice
+        && !(2..=MAX_RANKED_CHOICE_OPTIONS).contains(&options.len())
+    {
+        return Err(GovernanceError::InvalidRankedChoiceOptions.into());
+    }
+    let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    if token_owner_record.realm != governance.realm
+        || token_owner_record.governing_token_owner != *ctx.proposal_owner.key
+        || token_owner_record.governing_token_owner != *ctx.governance_authority.key
+    {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    if matches!(per_voter_option_cap, Some(cap) if cap.max_weight_per_option == 0) {
+        return Err(GovernanceError::InvalidOptionVoteCap.into());
+    }
+    if matches!(impact_tier, Some(tier) if tier as usize >= governance.config.impact_tiers.len()) {
+        return Err(GovernanceError::InvalidImpactTier.into());
+    }
+    
+    // Pay the proposal creation fee to the treasury, the deposit is held by the proposal once
+    // it's created
+    let mut creation_fee_deposit = None;
+    if let Some(fee) = realm.proposal_creation_fee {
+        let treasury_info = next_account_with_role(account_info_iter, "treasury")?;
+        if *treasury_info.key != fee.treasury {
+            return Err(GovernanceError::InvalidTreasury.into());
+        }
+        let charged_lamports = fee
+            .lamports
+            .checked_add(fee.deposit_lamports)
+            .ok_or(GovernanceError::MathOverflow)?;
+        if ctx.proposal_owner.lamports() < charged_lamports {
+            return Err(GovernanceError::InsufficientFundsForFee.into());
+        }
+        
+        if fee.lamports > 0 {
+            invoke(
+                &system_instruction::transfer(ctx.proposal_owner.key, treasury_info.key, fee.lamports),
+                &[ctx.proposal_owner.clone(), treasury_info.clone(), ctx.system_program.clone()],
+            )?;
+        }
+        if fee.deposit_lamports > 0 {
+            creation_fee_deposit = Some(ProposalDeposit { lamports: fee.deposit_lamports, treasury: fee.treasury });
+        }
+    }
+    
+    // The prerequisite must be another proposal of the same governance
+    if let Some(depends_on) = depends_on {
+        let prerequisite_info = next_account_with_role(account_info_iter, "prerequisite_proposal")?;
+        if *prerequisite_info.key != depends_on || depends_on == *ctx.proposal.key {
+            return Err(GovernanceError::InvalidProposalDependency.into());
+        }
+        let prerequisite = get_account_data::<Proposal>(program_id, prerequisite_info)?;
+        if prerequisite.governance != *ctx.governance.key {
+            return Err(GovernanceError::InvalidProposalDependency.into());
+        }
+    }
+    
+    // A supply fraction threshold follows the community mint's supply at creation
+    let proposal_threshold_source = governance
+        .config
+        .min_community_tokens_to_create_proposal
+        .unwrap_or(realm.min_community_tokens_to_create_proposal);
+    let min_community_tokens_to_create_proposal = match proposal_threshold_source {
+        ProposalThresholdSource::Absolute { value } => value,
+        ProposalThresholdSource::SupplyFraction { .. } => {
+            let community_mint_info = next_account_with_role(account_info_iter, "community_mint")?;
+            if *community_mint_info.key != realm.community_mint {
+                return Err(GovernanceError::InvalidGoverningTokenMint.into());
+            }
+            proposal_threshold_source.get_min_tokens(unpack_mint(community_mint_info)?.supply)
+        }
+    };
+    if token_owner_record.get_voting_power() < min_community_tokens_to_create_proposal {
+        return Err(GovernanceError::NotEnoughTokensToCreateProposal.into());
+    }
+    
+    // Create the proposal account, which must not hold an existing proposal
+    if !ctx.proposal.data_is_empty() {
+        return Err(GovernanceError::AccountAlreadyInitialized.into());
+    }
+    let proposal_size = Proposal::get_max_size(&name, &description_link, &vote_type, &options)?;
+    let rent = Rent::get()?;
+    let account_size = ACCOUNT_DISCRIMINATOR_LEN + proposal_size;
+    let rent_lamports = rent.minimum_balance(account_size);
+    invoke(
+        &system_instruction::create_account(
+            ctx.proposal_owner.key,
+            ctx.proposal.key,
+            rent_lamports,
+            account_size as u64,
+            program_id,
+        ),
+        &[ctx.proposal_owner.clone(), ctx.proposal.clone(), ctx.system_program.clone()],
+    )?;
+    if let Some(fee) = creation_fee_deposit {
+        invoke(
+            &system_instruction::transfer(ctx.proposal_owner.key, ctx.proposal.key, fee.lamports),
+            &[ctx.proposal_owner.clone(), ctx.proposal.clone(), ctx.system_program.clone()],
+        )?;
+    }
+    
+    // Get the current time for timestamps
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let voting_ends_at = now
+        .checked_add(voting_period_days as u64 * 86400)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Create and save proposal data
+    let mut vote_results = HashMap::new();
+    for i in 0..options.len() {
+        vote_results.insert(i as u8, 0);
+    }
+    
+    let mut proposal = Proposal {
+        account_type: AccountType::Proposal,
+        governance: *ctx.governance.key,
+        proposal_owner: *ctx.proposal_owner.key,
+        name,
+        description_link,
+        created_at: now,
+        state: ProposalState::Draft,
+        vote_type,
+        options,
+        use_denial_quorum,
+        per_voter_option_cap,
+        voting_starts_at: now,
+        voting_ends_at,
+        vote_results,
+        total_vote_weight: 0,
+        abstain_vote_weight: 0,
+        vote_record_count: 0,
+        winning_option: None,
+        max_vote_weight_source: None,
+        ranked_ballots: vec![],
+        executable_at: None,
+        sponsors: vec![],
+        is_executing: false,
+        impact_tier,
+        deny_vote_weight: 0,
+        conviction: 0,
+        conviction_updated_at: now,
+        creation_fee_deposit,
+        option_transaction_counts: vec![],
+        executed_transaction_count: 0,
+        option_lead_reached_at: vec![],
+        depends_on,
+        description_hash,
+        bundle: None,
+        is_sybil_flagged: false,
+        sybil_reviewed_by: None,
+        vote_weight_source: None,
+        council_approve_weight: 0,
+        council_deny_weight: 0,
+        council_approved: false,
+        votes_relinquishable: false,
+        parameter_change,
+        parameter_change_applied_at: None,
+        fallback_option_index,
+        locks_backing_stake: false,
+        used_reserved: Proposal::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    proposal.option_transaction_counts = vec![0; proposal.get_executable_option_count()];
+    proposal.option_lead_reached_at = vec![0; proposal.options.len()];
+    
+    // The change must produce a valid config at creation, and is checked again when applied
+    if let Some(change) = parameter_change {
+        if change.option_index as usize >= proposal.get_executable_option_count()
+            || !change.is_valid()
+            || assert_valid_governance_config(&change.apply(&governance.config)).is_err()
+        {
+            return Err(GovernanceError::InvalidParameterChange.into());
+        }
+    }
+    // Conviction and approve-by-default proposals don't pick between options
+    if let Some(fallback_option_index) = fallback_option_index {
+        if proposal.vote_type == VoteType::Conviction
+            || proposal.is_approve_by_default()
+            || fallback_option_index as usize >= proposal.options.len()
+        {
+            return Err(GovernanceError::InvalidFallbackOption.into());
+        }
+    }
+    
+    governance.open_proposal_count = governance
+        .open_proposal_count
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process CastVote instruction
+pub fn process_cast_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vote: Vote,
+    staked_amount: u64,
+    oracle_weight: Option<u64>,
+) -> ProgramResult {
+    let ctx = CastVoteAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    // Validate accounts
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if token_owner_record.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    let voting_power = token_owner_record
+        .get_voter_voting_power(ctx.governance_authority.key)
+        .ok_or(GovernanceError::InvalidTokenOwnerRecord)?;
+    
+    // A weight oracle's signed weight stands in for the deposit, e.g. for LP positions whose
+    // value isn't a token balance or the voter's NFTs of a collection
+    let voting_power = match proposal.get_weight_oracle(&realm) {
+        Some(weight_oracle) => {
+            let weight_oracle_info = next_account_with_role(account_info_iter, "weight_oracle")?;
+            assert_signer(weight_oracle_info, "weight_oracle")?;
+            if *weight_oracle_info.key != weight_oracle {
+                return Err(GovernanceError::InvalidWeightOracle.into());
+            }
+            oracle_weight.ok_or(GovernanceError::InvalidOracleWeight)?
+        }
+        None if oracle_weight.is_some() => return Err(GovernanceError::InvalidOracleWeight.into()),
+        None => voting_power,
+    };
+    
+    let clock = get_clock(accounts)?;
+    let now = to_u64_timestamp(clock.unix_timestamp)?;
+    if proposal.state == ProposalState::Active && now >= proposal.voting_ends_at {
+        // Late votes on a proposal the touch expires tell the voter it's left to FinalizeVote
+        if realm.community_mint != *ctx.community_mint.key {
+            return Err(GovernanceError::InvalidGoverningTokenMint.into());
+        }
+        let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+        if touch_proposal_state(ctx.proposal.key, &mut proposal, &governance, &realm, max_vote_weight, now)? {
+            return Err(GovernanceError::VotingTimeExpired.into());
+        }
+        return Err(GovernanceError::VotingPeriodEnded.into());
+    }
+    
+    let empty_vote_record = get_empty_vote_record(
+        program_id,
+        ctx.vote_record,
+        ctx.proposal.key,
+        ctx.token_owner_record.key,
+        &token_owner_record,
+        ctx.governance_authority.key,
+    )?;
+    cast_vote_record(
+        ctx.proposal.key,
+        &mut proposal,
+        &governance,
+        &realm,
+        ctx.token_owner_record,
+        token_owner_record,
+        ctx.vote_record,
+        empty_vote_record,
+        ctx.governance_authority.key,
+        vote,
+        staked_amount,
+        voting_power,
+        &clock,
+    )?;
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Casts the vote with the staked amount of the voter's voting power into their empty vote
+/// record and tallies it into the proposal, which the caller saves once all its votes are cast
+#[allow(clippy::too_many_arguments)]
+fn cast_vote_record(
+    proposal_key: &Pubkey,
+    proposal: &mut Proposal,
+    governance: &Governance,
+    realm: &Realm,
+    token_owner_record_info: &AccountInfo,
+    mut token_owner_record: TokenOwnerRecord,
+    vote_record_info: &AccountInfo,
+    empty_vote_record: VoteRecord,
+    voter: &Pubkey,
+    vote: Vote,
+    staked_amount: u64,
+    voting_power: u64,
+    clock: &Clock,
+) -> ProgramResult {
+    // Ensure the proposal is open for voting
+    let now = to_u64_timestamp(clock.unix_timestamp)?;
+    if proposal.state != ProposalState::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if now >= proposal.voting_ends_at {
+        return Err(GovernanceError::VotingPeriodEnded.into());
+    }
+    
+    // Deposits must age before voting, so flash-loaned tokens can't be staked and voted with at once
+    if token_owner_record.get_record_age_slots(clock.slot) < realm.min_record_age_slots {
+        return Err(GovernanceError::TokenOwnerRecordTooNew.into());
+    }
+    
+    if staked_amount == 0 {
+        return Err(GovernanceError::ZeroStakeVote.into());
+    }
+    if staked_amount > voting_power {
+        return Err(GovernanceError::InsufficientStake.into());
+    }
+    if proposal.vote_record_count >= realm.max_vote_records_per_proposal {
+        return Err(GovernanceError::TooManyVoteRecords.into());
+    }
+    
+    // Tally the vote
+    let (vote_weight, mut option_vote_weights) =
+        realm.get_vote_weights(proposal, &vote, staked_amount, &token_owner_record, now)?;
+    let vote_weight_curve = realm.get_vote_weight_curve(proposal, &vote);
+    // A stake below one vote weight unit would only take up a vote record
+    if vote_weight == 0 {
+        return Err(GovernanceError::ZeroStakeVote.into());
+    }
+    if let Some(budget) = realm.quadratic_credit_budget.filter(|_| realm.use_quadratic_voting) {
+        token_owner_record.spend_quadratic_credits(&budget, vote_weight, now)?;
+    }
+    proposal.apply_option_vote_cap(&mut option_vote_weights);
+    // Conviction accrued so far is settled before the new support starts building its own
+    proposal.accrue_conviction(&governance.config, now);
+    proposal.add_vote_tally(&vote, vote_weight, &option_vote_weights)?;
+    proposal.record_option_leads(&option_vote_weights, now);
+    proposal.vote_record_count = proposal
+        .vote_record_count
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    token_owner_record.unrelinquished_votes_count = token_owner_record
+        .unrelinquished_votes_count
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Committed stake can't be unstaked until the cooldown after the proposal concludes
+    if realm.voting_extends_cooldown {
+        let unlock_time = proposal
+            .voting_ends_at
+            .checked_add(realm.unstaking_cooldown_seconds)
+            .ok_or(GovernanceError::MathOverflow)?;
+        token_owner_record.extend_stake_lock(unlock_time);
+    }
+    
+    // Save the vote into the empty vote record
+    let vote_record = VoteRecord {
+        account_type: AccountType::VoteRecord,
+        proposal: *proposal_key,
+        governing_token_owner: token_owner_record.governing_token_owner,
+        vote,
+        stake_amount: staked_amount,
+        vote_weight,
+        is_relinquished: false,
+        option_vote_weights,
+        voter_authority: *voter,
+        vote_weight_curve,
+        cast_at: now,
+        bump_seed: empty_vote_record.bump_seed,
+        used_reserved: VoteRecord::get_used_reserved()?,
+        reserved: [0; 21],
+    };
+    
+    let vote_cast = VoteCast {
+        proposal: vote_record.proposal,
+        governing_token_owner: vote_record.governing_token_owner,
+        voter_authority: vote_record.voter_authority,
+        vote: vote_record.vote.clone(),
+        stake_amount: vote_record.stake_amount,
+        vote_weight: vote_record.vote_weight,
+        vote_weight_curve: vote_record.vote_weight_curve,
+        cast_at: vote_record.cast_at,
+    };
+    sol_log_data(&[&vote_cast.try_to_vec()?]);
+    
+    save_account_data(vote_record_info, &vote_record)?;
+    save_account_data(token_owner_record_info, &token_owner_record)?;
+    
+    Ok(())
+}
+
+/// Process CreateVoteRecord instruction
+pub fn process_create_vote_record(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CreateVoteRecordAccounts::from_accounts(program_id, accounts)?;
+    
+    let proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    if token_owner_record.get_voter_voting_power(ctx.voter.key).is_none() {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    if !matches!(proposal.state, ProposalState::Draft | ProposalState::Active) {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    create_empty_vote_record(
+        program_id,
+        ctx.voter,
+        ctx.vote_record,
+        ctx.system_program,
+        ctx.proposal.key,
+        &proposal,
+        ctx.token_owner_record.key,
+        &token_owner_record,
+    )
+}
+
+/// Creates the voter's empty vote record for the proposal, paid for by the voter
+#[allow(clippy::too_many_arguments)]
+fn create_empty_vote_record<'a>(
+    program_id: &Pubkey,
+    voter_info: &AccountInfo<'a>,
+    vote_record_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    proposal_key: &Pubkey,
+    proposal: &Proposal,
+    token_owner_record_key: &Pubkey,
+    token_owner_record: &TokenOwnerRecord,
+) -> ProgramResult {
+    let bump_seed = create_pda_account(
+        voter_info,
+        vote_record_info,
+        system_program_info,
+        program_id,
+        VoteRecord::get_max_size(proposal),
+        &get_voter_vote_record_address_seeds(proposal_key, token_owner_record_key, token_owner_record, voter_info.key),
+    )?;
+    
+    let vote_record = VoteRecord {
+        account_type: AccountType::EmptyVoteRecord,
+        proposal: *proposal_key,
+        governing_token_owner: token_owner_record.governing_token_owner,
+        vote: Vote::Abstain,
+        stake_amount: 0,
+        vote_weight: 0,
+        is_relinquished: false,
+        option_vote_weights: vec![],
+        voter_authority: *voter_info.key,
+        vote_weight_curve: VoteWeightCurve::Linear,
+        cast_at: 0,
+        bump_seed,
+        used_reserved: VoteRecord::get_used_reserved()?,
+        reserved: [0; 21],
+    };
+    
+    save_account_data(vote_record_info, &vote_record)?;
+    
+    Ok(())
+}
+
+/// Process CastDelegatedVotes instruction
+pub fn process_cast_delegated_votes(program_id: &Pubkey, accounts: &[AccountInfo], vote: Vote) -> ProgramResult {
+    let ctx = CastDelegatedVotesAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    
+    // The oracle signs the weight of each vote, so oracle weighted votes go through CastVote
+    if proposal.get_weight_oracle(&realm).is_some() {
+        return Err(GovernanceError::InvalidOracleWeight.into());
+    }
+    
+    let delegations = ctx.remaining.chunks_exact(2);
+    if delegations.len() == 0
+        || !delegations.remainder().is_empty()
+        || has_duplicates(delegations.clone().map(|delegation| delegation[0].key))
+    {
+        return Err(GovernanceError::InvalidDelegatedVotes.into());
+    }
+    
+    let clock = get_clock(accounts)?;
+    for delegation in delegations {
+        let (token_owner_record_info, vote_record_info) = (&delegation[0], &delegation[1]);
+        let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, token_owner_record_info)?;
+        if token_owner_record.realm != *ctx.realm.key {
+            return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+        }
+        if token_owner_record.governance_delegate != Some(*ctx.governance_delegate.key) {
+            return Err(GovernanceError::NotGovernanceDelegate.into());
+        }
+        // Each delegation votes with all of its delegated voting power
+        let voting_power = token_owner_record
+            .get_voter_voting_power(ctx.governance_delegate.key)
+            .ok_or(GovernanceError::NotGovernanceDelegate)?;
+        
+        if vote_record_info.data_is_empty() {
+            create_empty_vote_record(
+                program_id,
+                ctx.governance_delegate,
+                vote_record_info,
+                ctx.system_program,
+                ctx.proposal.key,
+                &proposal,
+                token_owner_record_info.key,
+                &token_owner_record,
+            )?;
+        }
+        let empty_vote_record = get_empty_vote_record(
+            program_id,
+            vote_record_info,
+            ctx.proposal.key,
+            token_owner_record_info.key,
+            &token_owner_record,
+            ctx.governance_delegate.key,
+        )?;
+        cast_vote_record(
+            ctx.proposal.key,
+            &mut proposal,
+            &governance,
+            &realm,
+            token_owner_record_info,
+            token_owner_record,
+            vote_record_info,
+            empty_vote_record,
+            ctx.governance_delegate.key,
+            vote.clone(),
+            voting_power,
+            voting_power,
+            &clock,
+        )?;
+    }
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process ExecuteProposal instruction
+pub fn process_execute_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ExecuteProposalAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    if proposal.bundle.is_some() {
+        return Err(GovernanceError::ProposalBundled.into());
+    }
+    if let Some(depends_on) = proposal.depends_on {
+        let prerequisite_info = next_account_with_role(account_info_iter, "prerequisite_proposal")?;
+        if *prerequisite_info.key != depends_on {
+            return Err(GovernanceError::InvalidProposalDependency.into());
+        }
+        let prerequisite = get_account_data::<Proposal>(program_id, prerequisite_info)?;
+        if prerequisite.state != ProposalState::Executed {
+            return Err(GovernanceError::ProposalDependencyNotExecuted.into());
+        }
+    }
+    let instruction_account_infos = account_info_iter.as_slice();
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let mut proposal_transaction = get_account_data::<ProposalTransaction>(program_id, ctx.proposal_transaction)?;
+    
+    if proposal.is_executing {
+        return Err(GovernanceError::ProposalExecutionInProgress.into());
+    }
+    if proposal.state != ProposalState::Approved {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if proposal_transaction.proposal != *ctx.proposal.key
+        || proposal.winning_option != Some(proposal_transaction.option_index)
+    {
+        return Err(GovernanceError::InvalidProposalTransaction.into());
+    }
+    if proposal_transaction.executed_at.is_some() {
+        return Err(GovernanceError::TransactionAlreadyExecuted.into());
+    }
+    if proposal_transaction.transaction_index != proposal.executed_transaction_count {
+        return Err(GovernanceError::InvalidProposalTransaction.into());
+    }
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if proposal.is_execution_locked(&governance.config, now) {
+        return Err(GovernanceError::ExecutionLocked.into());
+    }
+    if proposal.is_awaiting_sybil_review(&governance.config) {
+        return Err(GovernanceError::SybilReviewPending.into());
+    }
+    if proposal.is_execution_window_elapsed(&governance.config, now) {
+        return Err(GovernanceError::ExecutionWindowElapsed.into());
+    }
+    assert_execution_accounts(
+        &proposal_transaction.instructions,
+        ctx.governance.key,
+        accounts,
+        instruction_account_infos,
+    )?;
+    // The allowed programs may have changed since the transaction was inserted
+    if !proposal_transaction
+        .instructions
+        .iter()
+        .all(|instruction| governance.config.is_allowed_target_program(&instruction.program_id))
+    {
+        return Err(GovernanceError::TargetProgramNotAllowed.into());
+    }
+    
+    // Save the guard before invoking so instructions calling back into the program see it
+    proposal.is_executing = true;
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    // Invoke the instructions with the governance PDA as signer
+    let governance_seeds = get_governance_address_seeds(&governance.realm, &governance.governed_account);
+    let (_, bump_seed) = Pubkey::find_program_address(&governance_seeds, program_id);
+    let bump = [bump_seed];
+    let mut governance_signer_seeds = governance_seeds.to_vec();
+    governance_signer_seeds.push(&bump);
+    
+    for instruction in &proposal_transaction.instructions {
+        invoke_signed(
+            &Instruction::from(instruction),
+            instruction_account_infos,
+            &[&governance_signer_seeds],
+        )?;
+    }
+    
+    // The invoked instructions may have written to the proposal or governance, so continue
+    // from their saved data
+    proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    proposal_transaction.executed_at = Some(now);
+    proposal.is_executing = false;
+    proposal.executed_transaction_count += 1;
+    let transaction_count = proposal.get_transaction_count(proposal_transaction.option_index);
+    if proposal.executed_transaction_count >= transaction_count {
+        proposal.state = ProposalState::Executed;
+        governance.open_proposal_count = governance.open_proposal_count.saturating_sub(1);
+        pay_keeper_bounty(
+            ctx.proposal,
+            &mut proposal,
+            ctx.governance_authority,
+            governance.config.executor_bounty_lamports,
+        )?;
+    }
+    
+    save_account_data(ctx.proposal_transaction, &proposal_transaction)?;
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process CreateProposalBundle instruction
+pub fn process_create_proposal_bundle(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CreateProposalBundleAccounts::from_accounts(program_id, accounts)?;
+    
+    get_account_data::<Governance>(program_id, ctx.governance)?;
+    if !(2..=MAX_BUNDLE_PROPOSALS).contains(&ctx.remaining.len())
+        || has_duplicates(ctx.remaining.iter().map(|info| info.key))
+    {
+        return Err(GovernanceError::InvalidProposalBundle.into());
+    }
+    
+    let mut proposals = Vec::with_capacity(ctx.remaining.len());
+    for proposal_info in ctx.remaining {
+        let proposal = get_account_data::<Proposal>(program_id, proposal_info)?;
+        if proposal.governance != *ctx.governance.key
+            || proposal.proposal_owner != *ctx.creator.key
+            || proposal.state != ProposalState::Draft
+            || proposal.bundle.is_some()
+            || proposal.depends_on.is_some()
+        {
+            return Err(GovernanceError::InvalidProposalBundle.into());
+        }
+        proposals.push(proposal);
+    }
+    
+    create_pda_account(
+        ctx.creator,
+        ctx.proposal_bundle,
+        ctx.system_program,
+        program_id,
+        ProposalBundle::get_max_size(),
+        &get_proposal_bundle_address_seeds(ctx.remaining[0].key),
+    )?;
+    
+    let bundle = ProposalBundle {
+        account_type: AccountType::ProposalBundle,
+        governance: *ctx.governance.key,
+        creator: *ctx.creator.key,
+        proposals: ctx.remaining.iter().map(|info| *info.key).collect(),
+        executed_at: None,
+        used_reserved: ProposalBundle::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    save_account_data(ctx.proposal_bundle, &bundle)?;
+    
+    for (mut proposal, proposal_info) in proposals.into_iter().zip(ctx.remaining) {
+        proposal.bundle = Some(*ctx.proposal_bundle.key);
+        save_account_data(proposal_info, &proposal)?;
+    }
+    
+    Ok(())
+}
+
+/// Process ExecuteProposalBundle instruction
+pub fn process_execute_proposal_bundle(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ExecuteProposalBundleAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let mut bundle = get_account_data::<ProposalBundle>(program_id, ctx.proposal_bundle)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    if bundle.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if bundle.executed_at.is_some() {
+        return Err(GovernanceError::ProposalBundleAlreadyExecuted.into());
+    }
+    
+    // Marking the bundle and its proposals executed before invoking guards against
+    // instructions calling back into the program, and a failure reverts them all
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    bundle.executed_at = Some(now);
+    save_account_data(ctx.proposal_bundle, &bundle)?;
+    
+    let governance_seeds = get_governance_address_seeds(&governance.realm, &governance.governed_account);
+    let (_, bump_seed) = Pubkey::find_program_address(&governance_seeds, program_id);
+    let bump = [bump_seed];
+    let mut governance_signer_seeds = governance_seeds.to_vec();
+    governance_signer_seeds.push(&bump);
+    
+    for bundled_proposal in &bundle.proposals {
+        let proposal_info = next_account_with_role(account_info_iter, "proposal")?;
+        if proposal_info.key != bundled_proposal {
+            return Err(GovernanceError::InvalidProposalBundle.into());
+        }
+        let mut proposal = get_account_data::<Proposal>(program_id, proposal_info)?;
+        let Some(option_index) = proposal.winning_option.filter(|_| proposal.state == ProposalState::Approved)
+        else {
+            return Err(GovernanceError::ProposalBundleNotApproved.into());
+        };
+        if proposal.is_execution_locked(&governance.config, now) {
+            return Err(GovernanceError::ExecutionLocked.into());
+        }
+        if proposal.is_awaiting_sybil_review(&governance.config) {
+            return Err(GovernanceError::SybilReviewPending.into());
+        }
+        if proposal.is_execution_window_elapsed(&governance.config, now) {
+            return Err(GovernanceError::ExecutionWindowElapsed.into());
+        }
+        
+        let transaction_count = proposal.get_transaction_count(option_index);
+        proposal.executed_transaction_count = transaction_count;
+        proposal.state = ProposalState::Executed;
+        pay_keeper_bounty(proposal_info, &mut proposal, ctx.keeper, governance.config.executor_bounty_lamports)?;
+        save_account_data(proposal_info, &proposal)?;
+        
+        for transaction_index in 0..transaction_count {
+            let proposal_transaction_info = next_account_with_role(account_info_iter, "proposal_transaction")?;
+            let mut proposal_transaction =
+                get_account_data::<ProposalTransaction>(program_id, proposal_transaction_info)?;
+            if proposal_transaction.proposal != *proposal_info.key
+                || proposal_transaction.option_index != option_index
+                || proposal_transaction.transaction_index != transaction_index
+                || proposal_transaction.executed_at.is_some()
+            {
+                return Err(GovernanceError::InvalidProposalTransaction.into());
+            }
+            
+            // Each transaction is followed by the accounts of its own instructions
+            let account_count =
+                get_execution_account_metas(&proposal_transaction.instructions, ctx.governance.key).len();
+            let remaining_account_infos = account_info_iter.as_slice();
+            if remaining_account_infos.len() < account_count {
+                return Err(GovernanceError::ExecutionAccountMismatch.into());
+            }
+            let (instruction_account_infos, rest) = remaining_account_infos.split_at(account_count);
+            *account_info_iter = rest.iter();
+            assert_execution_accounts(
+                &proposal_transaction.instructions,
+                ctx.governance.key,
+                accounts,
+                instruction_account_infos,
+            )?;
+            if !proposal_transaction
+                .instructions
+                .iter()
+                .all(|instruction| governance.config.is_allowed_target_program(&instruction.program_id))
+            {
+                return Err(GovernanceError::TargetProgramNotAllowed.into());
+            }
+            
+            proposal_transaction.executed_at = Some(now);
+            save_account_data(proposal_transaction_info, &proposal_transaction)?;
+            
+            for instruction in &proposal_transaction.instructions {
+                invoke_signed(
+                    &Instruction::from(instruction),
+                    instruction_account_infos,
+                    &[&governance_signer_seeds],
+                )?;
+            }
+        }
+    }
+    
+    // The invoked instructions may have written to the governance, so continue from its saved data
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    governance.open_proposal_count = governance.open_proposal_count.saturating_sub(bundle.proposals.len() as u32);
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process StakeTokens instruction
+pub fn process_stake_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let ctx = StakeTokensAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_staking_vault(program_id, ctx.realm.key, &realm, ctx.staking_vault)?;
+    
+    // Move the tokens into the staking vault
+    invoke(
+        &spl_token::instruction::transfer(
+            ctx.token_program.key,
+            ctx.source_token.key,
+            ctx.staking_vault.key,
+            ctx.token_owner.key,
+            &[],
+            amount,
+        )?,
+        &[
+            ctx.source_token.clone(),
+            ctx.staking_vault.clone(),
+            ctx.token_owner.clone(),
+            ctx.token_program.clone(),
+        ],
+    )?;
+    
+    // Create the token owner record on first stake
+    let mut token_owner_record = if ctx.token_owner_record.data_is_empty() {
+        create_pda_account(
+            ctx.token_owner,
+            ctx.token_owner_record,
+            ctx.system_program,
+            program_id,
+            TokenOwnerRecord::get_max_size(),
+            &get_token_owner_record_address_seeds(
+                ctx.realm.key,
+                &realm.community_mint,
+                ctx.token_owner.key,
+            ),
+        )?;
+        
+        TokenOwnerRecord {
+            account_type: AccountType::TokenOwnerRecord,
+            realm: *ctx.realm.key,
+            governing_token_mint: realm.community_mint,
+            governing_token_owner: *ctx.token_owner.key,
+            governing_token_deposit_amount: 0,
+            unrelinquished_votes_count: 0,
+            earliest_unstaking_time: 0,
+            stake_tranches: vec![],
+            sybil_resistance_score: None,
+            claimed_voting_power: 0,
+            governance_delegate: None,
+            delegated_amount: 0,
+            last_deposit_slot: 0,
+            quadratic_credits_spent: 0,
+            credit_period_started_at: 0,
+            cumulative_stake_seconds: 0,
+            stake_seconds_updated_at: 0,
+            used_reserved: TokenOwnerRecord::get_used_reserved()?,
+            reserved: [0; 63],
+        }
+    } else {
+        let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+        if token_owner_record.realm != *ctx.realm.key
+            || token_owner_record.governing_token_mint != realm.community_mint
+            || token_owner_record.governing_token_owner != *ctx.token_owner.key
+        {
+            return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+        }
+        token_owner_record
+    };
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let unlock_time = now
+        .checked_add(realm.unstaking_cooldown_seconds)
+        .ok_or(GovernanceError::MathOverflow)?;
+    token_owner_record.accrue_stake_seconds(now);
+    token_owner_record.governing_token_deposit_amount = token_owner_record
+        .governing_token_deposit_amount
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    token_owner_record.add_stake_tranche(amount, unlock_time)?;
+    token_owner_record.last_deposit_slot = get_clock(accounts)?.slot;
+    realm.total_staked = realm
+        .total_staked
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process UnstakeTokens instruction
+pub fn process_unstake_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    unstake_tokens(program_id, accounts, amount, UnstakeKind::Regular)
+}
+
+/// Process EmergencyUnstake instruction
+pub fn process_emergency_unstake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    unstake_tokens(program_id, accounts, amount, UnstakeKind::Emergency)
+}
+
+/// Process UnstakeEarly instruction
+pub fn process_unstake_early(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    unstake_tokens(program_id, accounts, amount, UnstakeKind::Early)
+}
+
+/// Instruction a stake is being unstaked with
+#[derive(PartialEq, Clone, Copy)]
+enum UnstakeKind {
+    /// UnstakeTokens, once the cooldown has ended
+    Regular,
+    
+    /// EmergencyUnstake, from an inactive realm
+    Emergency,
+    
+    /// UnstakeEarly, before the cooldown has ended and for a fee
+    Early,
+}
+
+/// Moves stake out of the staking vault, for UnstakeTokens, EmergencyUnstake and UnstakeEarly
+///
+/// An emergency unstake from an inactive realm relinquishes the token owner's outstanding votes
+/// instead of failing on them. An early unstake ignores the cooldown but pays the realm's early
+/// unstake fee out of the amount
+fn unstake_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    kind: UnstakeKind,
+) -> ProgramResult {
+    let ctx = UnstakeTokensAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_staking_vault(program_id, ctx.realm.key, &realm, ctx.staking_vault)?;
+    
+    // Deposits of a migrated mint leave through SwapDeposit
+    let mut token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    if token_owner_record.realm != *ctx.realm.key || token_owner_record.governing_token_mint != realm.community_mint {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    assert_token_owner_signer(&token_owner_record, ctx.token_owner)?;
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if kind == UnstakeKind::Emergency {
+        if !realm.is_inactive(now) {
+            return Err(GovernanceError::RealmNotInactive.into());
+        }
+        token_owner_record.unrelinquished_votes_count = 0;
+        token_owner_record.release_revoked_delegation();
+    }
+    
+    // Staked tokens stay locked while they back votes, and during the cooldown unless the
+    // early unstake fee is paid
+    if token_owner_record.unrelinquished_votes_count > 0 {
+        return Err(GovernanceError::OutstandingVotes.into());
+    }
+    let early_unstake_fee = if kind == UnstakeKind::Early {
+        let fee = realm
+            .early_unstake_fee
+            .ok_or(GovernanceError::EarlyUnstakeNotAllowed)?;
+        let fee_treasury_info = next_account_with_role(account_info_iter, "early_unstake_fee_treasury")?;
+        if *fee_treasury_info.key != fee.treasury {
+            return Err(GovernanceError::InvalidTreasury.into());
+        }
+        
+        // Unstake from any tranche, oldest first
+        token_owner_record.take_stake(amount)?;
+        Some((apply_bps(amount, fee.fee_bps), fee_treasury_info))
+    } else {
+        if now < token_owner_record.earliest_unstaking_time {
+            return Err(GovernanceError::UnstakingCooldownActive.into());
+        }
+        
+        // Unstake from matured tranches, oldest first
+        token_owner_record.take_unlocked_stake(amount, now)?;
+        None
+    };
+    token_owner_record.accrue_stake_seconds(now);
+    token_owner_record.governing_token_deposit_amount = token_owner_record
+        .governing_token_deposit_amount
+        .checked_sub(amount)
+        .ok_or(GovernanceError::InsufficientStake)?;
+    realm.total_staked = realm.total_staked.saturating_sub(amount);
+    
+    // Move the tokens out of the staking vault, less any early unstake fee paid to its treasury
+    let mut payout_amount = amount;
+    if let Some((fee_amount, fee_treasury_info)) = early_unstake_fee {
+        if fee_amount > 0 {
+            transfer_from_pda_vault(
+                program_id,
+                ctx.token_program,
+                ctx.staking_vault,
+                fee_treasury_info,
+                ctx.vault_authority,
+                &get_vault_authority_address_seeds(ctx.realm.key),
+                realm.vault_authority_bump_seed,
+                fee_amount,
+            )?;
+        }
+        payout_amount -= fee_amount;
+    }
+    transfer_from_pda_vault(
+        program_id,
+        ctx.token_program,
+        ctx.staking_vault,
+        ctx.destination_token,
+        ctx.vault_authority,
+        &get_vault_authority_address_seeds(ctx.realm.key),
+        realm.vault_authority_bump_seed,
+        payout_amount,
+    )?;
+    
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process CreateGovernance instruction
+pub fn process_create_governance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    governed_account: Pubkey,
+    config: GovernanceConfig,
+) -> ProgramResult {
+    let ctx = CreateGovernanceAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_valid_governance_config(&config)?;
+    
+    // The realm governance takes over the realm config from the bootstrap authority
+    if governed_account == *ctx.realm.key {
+        let realm_authority_info = next_account_with_role(account_info_iter, "realm_authority")?;
+        assert_signer(realm_authority_info, "realm_authority")?;
+        if realm.authority.is_none() {
+            return Err(GovernanceError::InvalidRealmAuthority.into());
+        }
+        assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+        
+        realm.authority = None;
+        realm.authority_set.clear();
+        realm.authority_threshold = 0;
+    }
+    
+    // CloseRealm has to be shown every governance of the realm
+    realm.governance_count = realm
+        .governance_count
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    create_pda_account(
+        ctx.funder,
+        ctx.governance,
+        ctx.system_program,
+        program_id,
+        Governance::get_max_size(),
+        &get_governance_address_seeds(ctx.realm.key, &governed_account),
+    )?;
+    
+    // Create and save governance data
+    let governance = Governance {
+        account_type: AccountType::GovernanceV2,
+        realm: *ctx.realm.key,
+        governed_account,
+        config,
+        active_proposal_count: 0,
+        open_proposal_count: 0,
+        quorum_failure_count: 0,
+        used_reserved: Governance::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process SignOffProposal instruction
+pub fn process_sign_off_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = SignOffProposalAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if proposal.proposal_owner != *ctx.proposal_owner.key {
+        return Err(GovernanceError::InvalidProposalOwner.into());
+    }
+    if proposal.state != ProposalState::Draft {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if realm.lifecycle != RealmLifecycle::Active {
+        return Err(GovernanceError::RealmNotAcceptingProposals.into());
+    }
+    
+    // The community gets the draft period to review the proposal before it goes to a vote
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if now.saturating_sub(proposal.created_at) < governance.config.min_draft_period_seconds {
+        return Err(GovernanceError::DraftPeriodNotElapsed.into());
+    }
+    
+    // Proposals need backing from other token owners before voting opens
+    if proposal.sponsors.len() < governance.config.required_sponsors as usize {
+        return Err(GovernanceError::NotEnoughSponsors.into());
+    }
+    
+    // Only a limited number of proposals can compete for voters' attention at once
+    if governance.active_proposal_count >= governance.config.max_concurrent_proposals {
+        return Err(GovernanceError::TooManyActiveProposals.into());
+    }
+    governance.active_proposal_count += 1;
+    
+    // Voting opens now and lasts the period chosen at creation
+    let voting_period = proposal.voting_ends_at - proposal.voting_starts_at;
+    if voting_period < governance.config.min_voting_period_days as u64 * 86400 {
+        return Err(GovernanceError::VotingPeriodTooShort.into());
+    }
+    proposal.state = ProposalState::Active;
+    proposal.max_vote_weight_source = Some(realm.community_mint_max_vote_weight_source);
+    proposal.vote_weight_source = Some(realm.vote_weight_source);
+    proposal.voting_starts_at = now;
+    proposal.voting_ends_at = now
+        .checked_add(voting_period)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process FinalizeVote instruction
+pub fn process_finalize_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = FinalizeVoteAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let mut governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if realm.community_mint != *ctx.community_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    
+    // Ensure voting has ended, or a conviction proposal has already passed
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if proposal.state != ProposalState::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+    if !is_proposal_vote_finalizable(&mut proposal, &governance.config, max_vote_weight, now) {
+        return Err(GovernanceError::VotingPeriodNotEnded.into());
+    }
+    
+    let archived_proposal = finalize_proposal_vote(
+        ctx.proposal.key,
+        &mut proposal,
+        &mut governance,
+        &mut realm,
+        max_vote_weight,
+        now,
+    )?;
+    log_sybil_flag(ctx.proposal.key, &proposal, &governance.config)?;
+    
+    append_to_proposal_archive(
+        program_id,
+        ctx.realm.key,
+        ctx.proposal_archive,
+        &mut ctx.archive_rollover.iter(),
+        archived_proposal,
+    )?;
+    
+    let certificate = ProposalCertificate::new(program_id, ctx.proposal.key, &proposal, max_vote_weight, now)?;
+    create_proposal_certificate(
+        program_id,
+        &certificate,
+        ctx.proposal_certificate,
+        ctx.payer,
+        ctx.system_program,
+    )?;
+    
+    pay_keeper_bounty(ctx.proposal, &mut proposal, ctx.payer, governance.config.finalizer_bounty_lamports)?;
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process FinalizeVotes instruction
+pub fn process_finalize_votes(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = FinalizeVotesAccounts::from_accounts(program_id, accounts)?;
+    let proposal_groups = ctx.remaining.chunks_exact(3);
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    if realm.community_mint != *ctx.community_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    if !proposal_groups.remainder().is_empty() {
+        let role = match proposal_groups.remainder().len() {
+            1 => "governance",
+            _ => "proposal_certificate",
+        };
+        msg!("Missing account: {}", role);
+        return Err(GovernanceError::MissingAccount.into());
+    }
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let mut finalized_count: u32 = 0;
+    for proposal_group in proposal_groups {
+        let (proposal_info, governance_info, proposal_certificate_info) =
+            (&proposal_group[0], &proposal_group[1], &proposal_group[2]);
+        let mut proposal = get_account_data::<Proposal>(program_id, proposal_info)?;
+        let mut governance = get_proposal_governance(program_id, &proposal, governance_info)?;
+        
+        if governance.realm != *ctx.realm.key {
+            return Err(GovernanceError::InvalidRealmForGovernance.into());
+        }
+        
+        // Proposals still open for voting or already finalized are skipped
+        if proposal.state != ProposalState::Active {
+            continue;
+        }
+        let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+        if !is_proposal_vote_finalizable(&mut proposal, &governance.config, max_vote_weight, now) {
+            continue;
+        }
+        
+        let archived_proposal = finalize_proposal_vote(
+            proposal_info.key,
+            &mut proposal,
+            &mut governance,
+            &mut realm,
+            max_vote_weight,
+            now,
+        )?;
+        log_sybil_flag(proposal_info.key, &proposal, &governance.config)?;
+        
+        // Once a rollover has created the next archive page, it's the latest one
+        let latest_proposal_archive_info = if ctx.next_proposal_archive.data_is_empty() {
+            ctx.proposal_archive
+        } else {
+            ctx.next_proposal_archive
+        };
+        append_to_proposal_archive(
+            program_id,
+            ctx.realm.key,
+            latest_proposal_archive_info,
+            &mut ctx.archive_rollover.iter(),
+            archived_proposal,
+        )?;
+        
+        let certificate = ProposalCertificate::new(program_id, proposal_info.key, &proposal, max_vote_weight, now)?;
+        create_proposal_certificate(
+            program_id,
+            &certificate,
+            proposal_certificate_info,
+            &ctx.archive_rollover[0],
+            &ctx.archive_rollover[1],
+        )?;
+        
+        pay_keeper_bounty(proposal_info, &mut proposal, ctx.payer, governance.config.finalizer_bounty_lamports)?;
+        // Saved right away, as later groups can share the governance
+        save_account_data(proposal_info, &proposal)?;
+        save_account_data(governance_info, &governance)?;
+        finalized_count += 1;
+    }
+    
+    if finalized_count > 0 {
+        save_account_data(ctx.realm, &realm)?;
+    }
+    msg!("Finalized {} proposals", finalized_count);
+    
+    Ok(())
+}
+
+/// Process InsertProposalTransaction instruction
+pub fn process_insert_proposal_transaction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    option_index: u8,
+    instructions: Vec<InstructionData>,
+) -> ProgramResult {
+    let ctx = InsertProposalTransactionAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    if proposal.proposal_owner != *ctx.proposal_owner.key {
+        return Err(GovernanceError::InvalidProposalOwner.into());
+    }
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if !instructions
+        .iter()
+        .all(|instruction| governance.config.is_allowed_target_program(&instruction.program_id))
+    {
+        return Err(GovernanceError::TargetProgramNotAllowed.into());
+    }
+    
+    // Transactions are fixed once the proposal is signed off
+    if proposal.state != ProposalState::Draft {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if option_index as usize >= proposal.get_executable_option_count() {
+        return Err(GovernanceError::InvalidProposalTransaction.into());
+    }
+    
+    // Transactions are appended to the option, executing in insertion order
+    let transaction_index = proposal.get_transaction_count(option_index);
+    let transaction_count = transaction_index
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    proposal
+        .option_transaction_counts
+        .resize(proposal.get_executable_option_count(), 0);
+    proposal.option_transaction_counts[option_index as usize] = transaction_count;
+    
+    let proposal_transaction = ProposalTransaction {
+        account_type: AccountType::ProposalTransaction,
+        proposal: *ctx.proposal.key,
+        option_index,
+        transaction_index,
+        instructions,
+        executed_at: None,
+        used_reserved: ProposalTransaction::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    // Sized for the transaction once executed, when executed_at holds an 8 byte time
+    create_pda_account(
+        ctx.proposal_owner,
+        ctx.proposal_transaction,
+        ctx.system_program,
+        program_id,
+        proposal_transaction.try_to_vec()?.len() + 8,
+        &get_proposal_transaction_address_seeds(ctx.proposal.key, &[option_index], &[transaction_index]),
+    )?;
+    
+    save_account_data(ctx.proposal_transaction, &proposal_transaction)?;
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process SetRealmConfig instruction
+pub fn process_set_realm_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config_args: RealmConfigArgs,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    realm.min_community_tokens_to_create_proposal = config_args.min_community_tokens_to_create_proposal;
+    realm.unstaking_cooldown_seconds = config_args.unstaking_cooldown_seconds;
+    realm.sybil_oracle = config_args.sybil_oracle;
+    realm.default_sybil_resistance_score = config_args.default_sybil_resistance_score;
+    realm.quadratic_weighted_vote_policy = config_args.quadratic_weighted_vote_policy;
+    realm.max_vote_records_per_proposal = config_args.max_vote_records_per_proposal;
+    realm.proposal_creation_fee = config_args.proposal_creation_fee;
+    realm.abstain_counts_for_quorum = config_args.abstain_counts_for_quorum;
+    realm.voting_extends_cooldown = config_args.voting_extends_cooldown;
+    realm.min_record_age_slots = config_args.min_record_age_slots;
+    realm.normalize_vote_weight_to_decimals = config_args.normalize_vote_weight_to_decimals;
+    realm.lockup_vote_weight = config_args.lockup_vote_weight;
+    realm.allow_plain_text_description_link = config_args.allow_plain_text_description_link;
+    realm.inactivity_threshold_seconds = config_args.inactivity_threshold_seconds;
+    realm.quadratic_credit_budget = config_args.quadratic_credit_budget;
+    realm.permissioned_proposal_creation = config_args.permissioned_proposal_creation;
+    realm.weight_oracle = config_args.weight_oracle;
+    realm.early_unstake_fee = config_args.early_unstake_fee;
+    realm.max_vote_weight_per_voter = config_args.max_vote_weight_per_voter;
+    realm.default_voting_period_days = config_args.default_voting_period_days;
+    realm.auto_relinquish_finalized = config_args.auto_relinquish_finalized;
+    realm.lock_stake_until_execution = config_args.lock_stake_until_execution;
+    
+    validate_realm_config(&realm)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process SetRealmAuthority instruction
+pub fn process_set_realm_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority_set: Vec<Pubkey>,
+    authority_threshold: u8,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    // Realms governed by their realm governance don't go back to a bootstrap authority
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    if realm.authority.is_none() {
+        return Err(GovernanceError::InvalidRealmAuthority.into());
+    }
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    let first_member = *authority_set.first().ok_or(GovernanceError::InvalidRealmAuthoritySet)?;
+    realm.authority = Some(first_member);
+    if authority_set.len() == 1 {
+        realm.authority_set = vec![];
+        realm.authority_threshold = 0;
+    } else {
+        realm.authority_set = authority_set;
+        realm.authority_threshold = authority_threshold;
+    }
+    
+    validate_realm_config(&realm)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process AddProposalCreator instruction
+pub fn process_add_proposal_creator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    creator: Pubkey,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    if realm.proposal_creators.contains(&creator) {
+        return Err(GovernanceError::ProposalCreatorAlreadyAllowed.into());
+    }
+    if realm.proposal_creators.len() >= MAX_PROPOSAL_CREATORS {
+        return Err(GovernanceError::TooManyProposalCreators.into());
+    }
+    realm.proposal_creators.push(creator);
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process RemoveProposalCreator instruction
+pub fn process_remove_proposal_creator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    creator: Pubkey,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    let creator_count = realm.proposal_creators.len();
+    realm.proposal_creators.retain(|proposal_creator| *proposal_creator != creator);
+    if realm.proposal_creators.len() == creator_count {
+        return Err(GovernanceError::ProposalCreatorNotAllowed.into());
+    }
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process MigrateGovernance instruction
+pub fn process_migrate_governance(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = MigrateGovernanceAccounts::from_accounts(program_id, accounts)?;
+    
+    let data = ctx.governance.data.borrow();
+    if AccountType::from_discriminator(&data) != Some(AccountType::Governance) {
+        return Err(GovernanceError::InvalidAccountType.into());
+    }
+    let legacy = LegacyGovernance::deserialize(&mut &data[ACCOUNT_DISCRIMINATOR_LEN..])?;
+    if legacy.account_type != AccountType::Governance {
+        return Err(GovernanceError::InvalidAccountType.into());
+    }
+    drop(data);
+    let mut governance = Governance::from(legacy);
+    
+    // Basis points take more space than percentages, so the account grows to the current max size
+    let size = ACCOUNT_DISCRIMINATOR_LEN + Governance::get_max_size();
+    if ctx.governance.data_len() < size {
+        let rent_shortfall = Rent::get()?.minimum_balance(size).saturating_sub(ctx.governance.lamports());
+        if rent_shortfall > 0 {
+            invoke(
+                &system_instruction::transfer(ctx.payer.key, ctx.governance.key, rent_shortfall),
+                &[ctx.payer.clone(), ctx.governance.clone(), ctx.system_program.clone()],
+            )?;
+        }
+        ctx.governance.realloc(size, false)?;
+    }
+    governance.used_reserved = Governance::get_used_reserved()?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process AddAccountDiscriminator instruction
+pub fn process_add_account_discriminator(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = AddAccountDiscriminatorAccounts::from_accounts(program_id, accounts)?;
+    
+    if AccountType::from_discriminator(&ctx.account.data.borrow()).is_some() {
+        return Err(GovernanceError::AccountDiscriminatorAlreadySet.into());
+    }
+    let account_type = AccountType::deserialize(&mut &ctx.account.data.borrow()[..])
+        .map_err(|_| GovernanceError::InvalidAccountType)?;
+    if account_type == AccountType::Uninitialized {
+        return Err(GovernanceError::InvalidAccountType.into());
+    }
+    
+    // Grow the account by the discriminator and shift its data behind it
+    let data_len = ctx.account.data_len();
+    let size = ACCOUNT_DISCRIMINATOR_LEN + data_len;
+    let rent_shortfall = Rent::get()?.minimum_balance(size).saturating_sub(ctx.account.lamports());
+    if rent_shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.payer.key, ctx.account.key, rent_shortfall),
+            &[ctx.payer.clone(), ctx.account.clone(), ctx.system_program.clone()],
+        )?;
+    }
+    ctx.account.realloc(size, false)?;
+    
+    let mut data = ctx.account.data.borrow_mut();
+    data.copy_within(..data_len, ACCOUNT_DISCRIMINATOR_LEN);
+    data[..ACCOUNT_DISCRIMINATOR_LEN].copy_from_slice(&account_type.get_discriminator());
+    
+    Ok(())
+}
+
+/// Process CloseRealm instruction
+pub fn process_close_realm(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CloseRealmAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    let unswapped_amount = realm.mint_migration.map_or(0, |migration| migration.unswapped_amount);
+    if realm.total_staked > 0 || unswapped_amount > 0 {
+        return Err(GovernanceError::RealmHasStakedTokens.into());
+    }
+    
+    let governance_infos: Vec<&AccountInfo> = account_info_iter
+        .take(realm.governance_count as usize)
+        .collect();
+    if governance_infos.len() != realm.governance_count as usize
+        || has_duplicates(governance_infos.iter().map(|info| info.key))
+    {
+        return Err(GovernanceError::IncompleteRealmGovernances.into());
+    }
+    for governance_info in governance_infos {
+        let governance = get_account_data::<Governance>(program_id, governance_info)?;
+        if governance.realm != *ctx.realm.key {
+            return Err(GovernanceError::InvalidRealmForGovernance.into());
+        }
+        
+        // A signing governance signs through ExecuteProposal, whose proposal is still open
+        let executing_proposal_count = if governance_info.is_signer { 1 } else { 0 };
+        if governance.open_proposal_count > executing_proposal_count {
+            return Err(GovernanceError::RealmHasOpenProposals.into());
+        }
+    }
+    
+    // Zero the data so the realm can't be read back before the runtime reclaims the account
+    ctx.realm.data.borrow_mut().fill(0);
+    **ctx.beneficiary.try_borrow_mut_lamports()? = ctx.beneficiary
+        .lamports()
+        .checked_add(ctx.realm.lamports())
+        .ok_or(GovernanceError::MathOverflow)?;
+    **ctx.realm.try_borrow_mut_lamports()? = 0;
+    
+    Ok(())
+}
+
+/// Process SetRealmLifecycle instruction
+pub fn process_set_realm_lifecycle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lifecycle: RealmLifecycle,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    realm.lifecycle = lifecycle;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process SetMaxVoteWeightSource instruction
+pub fn process_set_max_vote_weight_source(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_vote_weight_source: MintMaxVoteWeightSource,
+) -> ProgramResult {
+    let ctx = RealmAuthorityAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    realm.community_mint_max_vote_weight_source = max_vote_weight_source;
+    
+    validate_realm_config(&realm)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process SetVoteWeightSource instruction
+pub fn process_set_vote_weight_source(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vote_weight_source: VoteWeightSource,
+) -> ProgramResult {
+    let ctx = SetVoteWeightSourceAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    // The source's mint has to exist, and a collection is identified by its NFT's mint
+    let source_mint = match vote_weight_source {
+        VoteWeightSource::CommunityToken => realm.community_mint,
+        VoteWeightSource::NftCollection { collection_mint, .. } => collection_mint,
+    };
+    if *ctx.source_mint.key != source_mint {
+        return Err(GovernanceError::InvalidVoteWeightSource.into());
+    }
+    let mint = unpack_mint(ctx.source_mint)?;
+    let is_nft_collection = matches!(vote_weight_source, VoteWeightSource::NftCollection { .. });
+    if is_nft_collection && (mint.decimals != 0 || mint.supply != 1) {
+        return Err(GovernanceError::InvalidVoteWeightSource.into());
+    }
+    
+    realm.vote_weight_source = vote_weight_source;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process SetSybilScore instruction
+pub fn process_set_sybil_score(program_id: &Pubkey, accounts: &[AccountInfo], score: u8) -> ProgramResult {
+    let ctx = SetSybilScoreAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    if realm.sybil_oracle != Some(*ctx.sybil_oracle.key) {
+        return Err(GovernanceError::InvalidSybilOracle.into());
+    }
+    
+    let mut token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    if token_owner_record.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    if score > 100 {
+        return Err(GovernanceError::InvalidSybilResistanceScore.into());
+    }
+    
+    token_owner_record.sybil_resistance_score = Some(score);
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    
+    Ok(())
+}
+
+/// Process ClaimInitialVotingPower instruction
+pub fn process_claim_initial_voting_power(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    leaf_index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let ctx = ClaimInitialVotingPowerAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let merkle_root = realm
+        .voting_power_merkle_root
+        .ok_or(GovernanceError::NoVotingPowerSnapshot)?;
+    
+    let leaf = get_voting_power_claim_leaf(leaf_index, ctx.claimant.key, amount);
+    if !verify_merkle_proof(&proof, &merkle_root, leaf) {
+        return Err(GovernanceError::InvalidMerkleProof.into());
+    }
+    
+    // The claim account marks the leaf as claimed
+    if !ctx.voting_power_claim.data_is_empty() {
+        return Err(GovernanceError::VotingPowerAlreadyClaimed.into());
+    }
+    let leaf_index_bytes = leaf_index.to_le_bytes();
+    create_pda_account(
+        ctx.claimant,
+        ctx.voting_power_claim,
+        ctx.system_program,
+        program_id,
+        VotingPowerClaim::get_max_size(),
+        &get_voting_power_claim_address_seeds(ctx.realm.key, &leaf_index_bytes),
+    )?;
+    
+    let voting_power_claim = VotingPowerClaim {
+        account_type: AccountType::VotingPowerClaim,
+        realm: *ctx.realm.key,
+        governing_token_owner: *ctx.claimant.key,
+        amount,
+        used_reserved: VotingPowerClaim::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    // Credit the token owner record, creating it if the claimant hasn't staked yet
+    let mut token_owner_record = if ctx.token_owner_record.data_is_empty() {
+        create_pda_account(
+            ctx.claimant,
+            ctx.token_owner_record,
+            ctx.system_program,
+            program_id,
+            TokenOwnerRecord::get_max_size(),
+            &get_token_owner_record_address_seeds(
+                ctx.realm.key,
+                &realm.community_mint,
+                ctx.claimant.key,
+            ),
+        )?;
+        
+        TokenOwnerRecord {
+            account_type: AccountType::TokenOwnerRecord,
+            realm: *ctx.realm.key,
+            governing_token_mint: realm.community_mint,
+            governing_token_owner: *ctx.claimant.key,
+            governing_token_deposit_amount: 0,
+            unrelinquished_votes_count: 0,
+            earliest_unstaking_time: 0,
+            stake_tranches: vec![],
+            sybil_resistance_score: None,
+            claimed_voting_power: 0,
+            governance_delegate: None,
+            delegated_amount: 0,
+            last_deposit_slot: 0,
+            quadratic_credits_spent: 0,
+            credit_period_started_at: 0,
+            cumulative_stake_seconds: 0,
+            stake_seconds_updated_at: 0,
+            used_reserved: TokenOwnerRecord::get_used_reserved()?,
+            reserved: [0; 63],
+        }
+    } else {
+        let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+        if token_owner_record.realm != *ctx.realm.key
+            || token_owner_record.governing_token_owner != *ctx.claimant.key
+        {
+            return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+        }
+        token_owner_record
+    };
+    
+    token_owner_record.claimed_voting_power = token_owner_record
+        .claimed_voting_power
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    token_owner_record.last_deposit_slot = get_clock(accounts)?.slot;
+    
+    save_account_data(ctx.voting_power_claim, &voting_power_claim)?;
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    
+    Ok(())
+}
+
+/// Process CreateVestingStream instruction
+#[allow(clippy::too_many_arguments)]
+pub fn process_create_vesting_stream(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recipient: Pubkey,
+    total_amount: u64,
+    start_time: u64,
+    cliff_time: u64,
+    duration_seconds: u64,
+    nonce: u64,
+) -> ProgramResult {
+    let ctx = CreateVestingStreamAccounts::from_accounts(program_id, accounts)?;
+    
+    get_account_data::<Governance>(program_id, ctx.governance)?;
+    
+    let treasury = TokenAccount::unpack(&ctx.treasury.data.borrow())?;
+    if treasury.owner != *ctx.governance.key {
+        return Err(GovernanceError::InvalidTreasury.into());
+    }
+    
+    let vesting_end_time = start_time
+        .checked_add(duration_seconds)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if total_amount == 0 || cliff_time < start_time || cliff_time > vesting_end_time {
+        return Err(GovernanceError::InvalidVestingSchedule.into());
+    }
+    
+    let nonce_bytes = nonce.to_le_bytes();
+    create_pda_account(
+        ctx.payer,
+        ctx.vesting_stream,
+        ctx.system_program,
+        program_id,
+        VestingStream::get_max_size(),
+        &get_vesting_stream_address_seeds(ctx.governance.key, &recipient, &nonce_bytes),
+    )?;
+    
+    let vesting_stream = VestingStream {
+        account_type: AccountType::VestingStream,
+        governance: *ctx.governance.key,
+        recipient,
+        treasury: *ctx.treasury.key,
+        total_amount,
+        start_time,
+        cliff_time,
+        duration_seconds,
+        claimed_amount: 0,
+        cancelled_at: None,
+        used_reserved: VestingStream::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    save_account_data(ctx.vesting_stream, &vesting_stream)?;
+    
+    Ok(())
+}
+
+/// Process ClaimVestedFunds instruction
+pub fn process_claim_vested_funds(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ClaimVestedFundsAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut vesting_stream = get_account_data::<VestingStream>(program_id, ctx.vesting_stream)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    if vesting_stream.recipient != *ctx.recipient.key
+        || vesting_stream.governance != *ctx.governance.key
+        || vesting_stream.treasury != *ctx.treasury.key
+    {
+        return Err(GovernanceError::InvalidVestingStream.into());
+    }
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let amount = vesting_stream
+        .get_vested_amount(now)
+        .saturating_sub(vesting_stream.claimed_amount);
+    if amount == 0 {
+        return Err(GovernanceError::NothingToClaim.into());
+    }
+    vesting_stream.claimed_amount = vesting_stream
+        .claimed_amount
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Pay out of the treasury with the governance PDA as signer
+    transfer_from_pda_vault(
+        program_id,
+        ctx.token_program,
+        ctx.treasury,
+        ctx.destination_token,
+        ctx.governance,
+        &get_governance_address_seeds(&governance.realm, &governance.governed_account),
+        0,
+        amount,
+    )?;
+    
+    save_account_data(ctx.vesting_stream, &vesting_stream)?;
+    
+    Ok(())
+}
+
+/// Process PrecheckExecution instruction
+pub fn process_precheck_execution(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = PrecheckExecutionAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let proposal_transaction = get_account_data::<ProposalTransaction>(program_id, ctx.proposal_transaction)?;
+    
+    if proposal.state != ProposalState::UnderfundedApproved {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if proposal_transaction.proposal != *ctx.proposal.key
+        || proposal.winning_option != Some(proposal_transaction.option_index)
+    {
+        return Err(GovernanceError::InvalidProposalTransaction.into());
+    }
+    
+    // Every transfer source must hold the total the transaction moves out of it
+    let treasury_deltas = get_treasury_deltas(&proposal_transaction, ctx.remaining)?;
+    if treasury_deltas.iter().any(|delta| delta.balance < delta.amount) {
+        return Err(GovernanceError::InsufficientTreasuryFunds.into());
+    }
+    
+    proposal.state = ProposalState::Approved;
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process SimulateExecution instruction
+pub fn process_simulate_execution(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = SimulateExecutionAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let proposal_transaction = get_account_data::<ProposalTransaction>(program_id, ctx.proposal_transaction)?;
+    if proposal_transaction.proposal != *ctx.proposal.key {
+        return Err(GovernanceError::InvalidProposalTransaction.into());
+    }
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if realm.community_mint != *ctx.community_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+    touch_proposal_state(ctx.proposal.key, &mut proposal, &governance, &realm, max_vote_weight, now)?;
+    
+    let treasury_deltas = get_treasury_deltas(&proposal_transaction, ctx.remaining)?;
+    let simulation = ExecutionSimulation {
+        proposal_transaction: *ctx.proposal_transaction.key,
+        proposal_state: proposal.state,
+        executed: proposal_transaction.executed_at.is_some(),
+        is_funded: treasury_deltas.iter().all(|delta| delta.balance >= delta.amount),
+        treasury_deltas,
+    };
+    sol_log_data(&[&simulation.try_to_vec()?]);
+    
+    Ok(())
+}
+
+/// Returns the amount the proposal transaction transfers out of each source token account
+/// and its current balance, taking the source token accounts in order of first appearance
+fn get_treasury_deltas(
+    proposal_transaction: &ProposalTransaction,
+    source_token_infos: &[AccountInfo],
+) -> Result<Vec<TreasuryDelta>, ProgramError> {
+    let account_info_iter = &mut source_token_infos.iter();
+    let mut treasury_deltas = vec![];
+    for (source, amount) in proposal_transaction.get_token_transfer_amounts()? {
+        let source_token_info = next_account_with_role(account_info_iter, "source_token")?;
+        if *source_token_info.key != source {
+            return Err(GovernanceError::InvalidAccountAddress.into());
+        }
+        let source_token = TokenAccount::unpack(&source_token_info.data.borrow())?;
+        treasury_deltas.push(TreasuryDelta {
+            token_account: source,
+            balance: source_token.amount,
+            amount,
+        });
+    }
+    Ok(treasury_deltas)
+}
+
+/// Process CancelVestingStream instruction
+pub fn process_cancel_vesting_stream(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CancelVestingStreamAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut vesting_stream = get_account_data::<VestingStream>(program_id, ctx.vesting_stream)?;
+    if vesting_stream.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidVestingStream.into());
+    }
+    if vesting_stream.cancelled_at.is_some() {
+        return Err(GovernanceError::VestingStreamCancelled.into());
+    }
+    
+    vesting_stream.cancelled_at = Some(to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?);
+    save_account_data(ctx.vesting_stream, &vesting_stream)?;
+    
+    Ok(())
+}
+
+/// Process SetGovernanceDelegate instruction
+pub fn process_set_governance_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_governance_delegate: Option<Pubkey>,
+    delegated_amount: u64,
+) -> ProgramResult {
+    let ctx = SetGovernanceDelegateAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    if token_owner_record.governing_token_owner != *ctx.token_owner.key {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    
+    // Votes already cast were weighed against the current split, so it's kept until they're
+    // relinquished and the delegate can only be revoked meanwhile
+    if token_owner_record.unrelinquished_votes_count > 0 {
+        if new_governance_delegate.is_some() {
+            return Err(GovernanceError::OutstandingVotes.into());
+        }
+        token_owner_record.governance_delegate = None;
+        save_account_data(ctx.token_owner_record, &token_owner_record)?;
+        return Ok(());
+    }
+    if delegated_amount > token_owner_record.governing_token_deposit_amount {
+        return Err(GovernanceError::DelegatedAmountExceedsDeposit.into());
+    }
+    
+    token_owner_record.delegated_amount = if new_governance_delegate.is_some() {
+        delegated_amount
+    } else {
+        0
+    };
+    token_owner_record.governance_delegate = new_governance_delegate;
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    
+    Ok(())
+}
+
+/// Process AddSponsor instruction
+pub fn process_add_sponsor(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = AddSponsorAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if token_owner_record.realm != governance.realm
+        || token_owner_record.governing_token_owner != *ctx.sponsor.key
+    {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    if proposal.state != ProposalState::Draft {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Sponsors are distinct token owners backing someone else's proposal
+    if *ctx.sponsor.key == proposal.proposal_owner || proposal.sponsors.contains(ctx.sponsor.key) {
+        return Err(GovernanceError::InvalidSponsor.into());
+    }
+    if proposal.sponsors.len() >= MAX_PROPOSAL_SPONSORS {
+        return Err(GovernanceError::TooManySponsors.into());
+    }
+    if token_owner_record.get_voting_power() < governance.config.min_sponsor_tokens {
+        return Err(GovernanceError::NotEnoughTokensToSponsor.into());
+    }
+    
+    proposal.sponsors.push(*ctx.sponsor.key);
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process TallySnapshot instruction
+pub fn process_tally_snapshot(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = TallySnapshotAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if realm.community_mint != *ctx.community_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    if proposal.state != ProposalState::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Project the outcome exactly as FinalizeVote would resolve it now, so an ended proposal
+    // without quorum is already reported as Expired
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+    let quorum_failure_count = governance.quorum_failure_count;
+    proposal.accrue_conviction(&governance.config, now.min(proposal.voting_ends_at));
+    let (projected_state, projected_winning_option) = proposal.resolve_vote_outcome(
+        &governance.config,
+        quorum_failure_count,
+        max_vote_weight,
+        realm.abstain_counts_for_quorum,
+    );
+    
+    let mut vote_results: Vec<(u8, u64)> = proposal.vote_results.iter().map(|(i, w)| (*i, *w)).collect();
+    vote_results.sort_unstable();
+    let (quorum_bps, _) = governance
+        .config
+        .get_vote_thresholds(proposal.impact_tier, quorum_failure_count);
+    
+    let snapshot = TallySnapshot {
+        vote_results,
+        total_vote_weight: proposal.total_vote_weight,
+        abstain_vote_weight: proposal.abstain_vote_weight,
+        quorum_reached: has_reached_quorum(
+            &proposal,
+            max_vote_weight,
+            quorum_bps,
+            realm.abstain_counts_for_quorum,
+        ),
+        projected_state,
+        projected_winning_option,
+    };
+    sol_log_data(&[&snapshot.try_to_vec()?]);
+    
+    Ok(())
+}
+
+/// Process ExpireProposal instruction
+pub fn process_expire_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ExpireProposalAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if !matches!(proposal.state, ProposalState::Approved | ProposalState::UnderfundedApproved) {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if !proposal.is_execution_window_elapsed(&governance.config, now) {
+        return Err(GovernanceError::ExecutionWindowOpen.into());
+    }
+    
+    proposal.state = ProposalState::Expired;
+    governance.open_proposal_count = governance.open_proposal_count.saturating_sub(1);
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process VetoProposal instruction
+pub fn process_veto_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CouncilReviewAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let mut governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    
+    assert_council_member(&realm, ctx.council_member, ctx.council_token_account)?;
+    
+    if !matches!(proposal.state, ProposalState::Approved | ProposalState::UnderfundedApproved) {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if !proposal.is_in_veto_window(&governance.config, now) {
+        return Err(GovernanceError::VetoWindowElapsed.into());
+    }
+    
+    proposal.state = ProposalState::Vetoed;
+    governance.open_proposal_count = governance.open_proposal_count.saturating_sub(1);
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process ReviewSybilFlag instruction
+pub fn process_review_sybil_flag(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CouncilReviewAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    assert_council_member(&realm, ctx.council_member, ctx.council_token_account)?;
+    
+    if !proposal.is_sybil_flagged || proposal.sybil_reviewed_by.is_some() {
+        return Err(GovernanceError::NoSybilFlagToReview.into());
+    }
+    
+    proposal.sybil_reviewed_by = Some(*ctx.council_member.key);
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process CastCouncilVote instruction
+pub fn process_cast_council_vote(program_id: &Pubkey, accounts: &[AccountInfo], approve: bool) -> ProgramResult {
+    let ctx = CastCouncilVoteAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    let Some(council_approval_quorum_bps) = governance.config.council_approval_quorum_bps else {
+        return Err(GovernanceError::CouncilApprovalNotRequired.into());
+    };
+    
+    // Council members vote with their council token balance, locked in the council vault so
+    // the same tokens can't be moved to another wallet and vote again
+    let council_token_account = get_council_token_account(&realm, ctx.council_member, ctx.council_token_account)?;
+    if council_token_account.mint != *ctx.council_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    assert_vault_token_account(program_id, ctx.realm.key, &realm, ctx.council_vault, ctx.council_mint.key)
+        .map_err(|_| GovernanceError::InvalidCouncilVault)?;
+    let council_supply = unpack_mint(ctx.council_mint)?.supply;
+    
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if proposal.state != ProposalState::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if now >= proposal.voting_ends_at {
+        return Err(GovernanceError::VotingPeriodEnded.into());
+    }
+    if !ctx.council_vote_record.data_is_empty() {
+        return Err(GovernanceError::VoteAlreadyCast.into());
+    }
+    
+    let vote_weight = council_token_account.amount;
+    if approve {
+        proposal.council_approve_weight = proposal
+            .council_approve_weight
+            .checked_add(vote_weight)
+            .ok_or(GovernanceError::MathOverflow)?;
+    } else {
+        proposal.council_deny_weight = proposal
+            .council_deny_weight
+            .checked_add(vote_weight)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    // The quorum is checked against the supply at each vote, and once reached the approval stands
+    if reaches_bps(proposal.council_approve_weight as u128, council_supply, council_approval_quorum_bps) {
+        proposal.council_approved = true;
+    }
+    
+    let council_vote_record = CouncilVoteRecord {
+        account_type: AccountType::CouncilVoteRecord,
+        proposal: *ctx.proposal.key,
+        council_member: *ctx.council_member.key,
+        approve,
+        vote_weight,
+        cast_at: now,
+        used_reserved: CouncilVoteRecord::get_used_reserved()?,
+        council_vault: *ctx.council_vault.key,
+        reserved: [0; 31],
+    };
+    create_pda_account(
+        ctx.council_member,
+        ctx.council_vote_record,
+        ctx.system_program,
+        program_id,
+        CouncilVoteRecord::get_max_size(),
+        &get_council_vote_record_address_seeds(ctx.proposal.key, ctx.council_member.key),
+    )?;
+    
+    invoke(
+        &spl_token::instruction::transfer(
+            ctx.token_program.key,
+            ctx.council_token_account.key,
+            ctx.council_vault.key,
+            ctx.council_member.key,
+            &[],
+            vote_weight,
+        )?,
+        &[
+            ctx.council_token_account.clone(),
+            ctx.council_vault.clone(),
+            ctx.council_member.clone(),
+            ctx.token_program.clone(),
+        ],
+    )?;
+    
+    save_account_data(ctx.council_vote_record, &council_vote_record)?;
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process RelinquishCouncilVote instruction
+pub fn process_relinquish_council_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = RelinquishCouncilVoteAccounts::from_accounts(program_id, accounts)?;
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let council_vote_record = get_account_data::<CouncilVoteRecord>(program_id, ctx.council_vote_record)?;
+    if council_vote_record.proposal != *ctx.proposal.key
+        || council_vote_record.council_member != *ctx.council_member.key
+    {
+        return Err(GovernanceError::InvalidCouncilVoteRecord.into());
+    }
+    
+    // The locked tokens count towards the council approval until voting is over
+    if matches!(proposal.state, ProposalState::Draft | ProposalState::Active) {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Votes cast before council tokens were locked have nothing to return
+    if council_vote_record.council_vault != Pubkey::default() {
+        if *ctx.council_vault.key != council_vote_record.council_vault {
+            return Err(GovernanceError::InvalidCouncilVault.into());
+        }
+        transfer_from_pda_vault(
+            program_id,
+            ctx.token_program,
+            ctx.council_vault,
+            ctx.council_token_account,
+            ctx.vault_authority,
+            &get_vault_authority_address_seeds(ctx.realm.key),
+            realm.vault_authority_bump_seed,
+            council_vote_record.vote_weight,
+        )?;
+    }
+    
+    // Zero the data so the record can't be read back before the runtime reclaims the account
+    ctx.council_vote_record.data.borrow_mut().fill(0);
+    **ctx.council_member.try_borrow_mut_lamports()? = ctx.council_member
+        .lamports()
+        .checked_add(ctx.council_vote_record.lamports())
+        .ok_or(GovernanceError::MathOverflow)?;
+    **ctx.council_vote_record.try_borrow_mut_lamports()? = 0;
+    
+    Ok(())
+}
+
+/// Process RelinquishVote instruction
+pub fn process_relinquish_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = RelinquishVoteAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let mut token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.token_owner_record)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if realm.community_mint != *ctx.community_mint.key {
+        return Err(GovernanceError::InvalidGoverningTokenMint.into());
+    }
+    if token_owner_record.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    let mut vote_record = get_cast_vote_record(
+        program_id,
+        ctx.vote_record,
+        ctx.proposal.key,
+        ctx.token_owner_record.key,
+        &token_owner_record,
+    )?;
+    // Revoking a delegate leaves the votes it cast to the token owner
+    if !vote_record.is_vote_authority(&token_owner_record, ctx.vote_authority.key) {
+        return Err(GovernanceError::NotGovernanceDelegate.into());
+    }
+    if vote_record.is_relinquished {
+        return Err(GovernanceError::VoteAlreadyRelinquished.into());
+    }
+    
+    // Votes count until the proposal is finalized, or the touch finds it expired
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    let max_vote_weight = get_proposal_max_vote_weight(&realm, &proposal, ctx.community_mint)?;
+    touch_proposal_state(ctx.proposal.key, &mut proposal, &governance, &realm, max_vote_weight, now)?;
+    if matches!(proposal.state, ProposalState::Draft | ProposalState::Active) {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if proposal.locks_stake_of(&vote_record) {
+        return Err(GovernanceError::BackingStakeLocked.into());
+    }
+    
+    vote_record.is_relinquished = true;
+    token_owner_record.relinquish_vote();
+    save_account_data(ctx.vote_record, &vote_record)?;
+    save_account_data(ctx.token_owner_record, &token_owner_record)?;
+    
+    Ok(())
+}
+
+/// Process RecomputeTally instruction
+pub fn process_recompute_tally(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = RecomputeTallyAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    // Vote records and token owner records never sign, unlike multi-sig authority members
+    let vote_infos: Vec<&AccountInfo> = account_info_iter
+        .filter(|account_info| !account_info.is_signer)
+        .collect();
+    let vote_pairs = vote_infos.chunks_exact(2);
+    if !vote_pairs.remainder().is_empty() {
+        msg!("Missing account: token_owner_record");
+        return Err(GovernanceError::MissingAccount.into());
+    }
+    
+    let realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    
+    if governance.realm != *ctx.realm.key {
+        return Err(GovernanceError::InvalidRealmForGovernance.into());
+    }
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    
+    // Tallies are only repaired between the end of voting and finalization
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if proposal.state != ProposalState::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if now < proposal.voting_ends_at {
+        return Err(GovernanceError::VotingPeriodNotEnded.into());
+    }
+    
+    // Every cast vote has a record, so a partial set would drop votes from the tallies
+    if vote_pairs.len() != proposal.vote_record_count as usize
+        || has_duplicates(vote_pairs.clone().map(|vote_pair| vote_pair[0].key))
+    {
+        return Err(GovernanceError::IncompleteVoteRecords.into());
+    }
+    
+    // Oracle weights don't come from the deposit, so there's no stake to weigh them again with
+    let is_oracle_weighted = proposal.get_weight_oracle(&realm).is_some();
+    proposal.vote_results = (0..proposal.options.len()).map(|i| (i as u8, 0)).collect();
+    proposal.total_vote_weight = 0;
+    proposal.abstain_vote_weight = 0;
+    proposal.deny_vote_weight = 0;
+    proposal.ranked_ballots.clear();
+    for vote_pair in vote_pairs {
+        let (vote_record_info, token_owner_record_info) = (vote_pair[0], vote_pair[1]);
+        let token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, token_owner_record_info)?;
+        let mut vote_record = get_cast_vote_record(
+            program_id,
+            vote_record_info,
+            ctx.proposal.key,
+            token_owner_record_info.key,
+            &token_owner_record,
+        )?;
+        if vote_record.is_relinquished {
+            continue;
+        }
+        
+        // A voting power which dropped below the vote's stake since, e.g. through an emergency
+        // unstake, only backs the vote up to what's left. The vote is weighed again as it would
+        // have been cast with it, the same curve, sybil, lockup and cap weighting included
+        let voting_power = token_owner_record
+            .get_voter_voting_power(&vote_record.get_voter_authority())
+            .unwrap_or(0);
+        if !is_oracle_weighted && voting_power < vote_record.stake_amount {
+            let (vote_weight, mut option_vote_weights) = realm.get_vote_weights(
+                &proposal,
+                &vote_record.vote,
+                voting_power,
+                &token_owner_record,
+                vote_record.cast_at,
+            )?;
+            proposal.apply_option_vote_cap(&mut option_vote_weights);
+            vote_record.vote_weight_curve = realm.get_vote_weight_curve(&proposal, &vote_record.vote);
+            vote_record.stake_amount = voting_power;
+            vote_record.vote_weight = vote_weight;
+            vote_record.option_vote_weights = option_vote_weights;
+            save_account_data(vote_record_info, &vote_record)?;
+        }
+        proposal.add_vote_tally(&vote_record.vote, vote_record.vote_weight, &vote_record.option_vote_weights)?;
+    }
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    Ok(())
+}
+
+/// Process AutoRelinquishFinalized instruction
+pub fn process_auto_relinquish_finalized(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = AutoRelinquishFinalizedAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    if !proposal.votes_relinquishable {
+        return Err(GovernanceError::AutoRelinquishNotEnabled.into());
+    }
+    
+    while let Some(vote_record_info) = account_info_iter.next() {
+        let token_owner_record_info = next_account_with_role(account_info_iter, "token_owner_record")?;
+        let mut token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, token_owner_record_info)?;
+        let mut vote_record = get_cast_vote_record(
+            program_id,
+            vote_record_info,
+            ctx.proposal.key,
+            token_owner_record_info.key,
+            &token_owner_record,
+        )?;
+        if vote_record.is_relinquished {
+            continue;
+        }
+        // Stake backing the winning option stays locked until the proposal is executed, vetoed
+        // or expired, and is released by a later call
+        if proposal.locks_stake_of(&vote_record) {
+            continue;
+        }
+        
+        // Tallies are final, only the voter's outstanding vote is released
+        vote_record.is_relinquished = true;
+        token_owner_record.relinquish_vote();
+        save_account_data(vote_record_info, &vote_record)?;
+        save_account_data(token_owner_record_info, &token_owner_record)?;
+    }
+    
+    Ok(())
+}
+
+/// Process ApplyParameterChange instruction
+pub fn process_apply_parameter_change(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ApplyParameterChangeAccounts::from_accounts(program_id, accounts)?;
+    let account_info_iter = &mut ctx.remaining.iter();
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let mut governance = get_proposal_governance(program_id, &proposal, ctx.governance)?;
+    let change = proposal.parameter_change.ok_or(GovernanceError::NoParameterChange)?;
+    if proposal.parameter_change_applied_at.is_some() {
+        return Err(GovernanceError::ParameterChangeAlreadyApplied.into());
+    }
+    // The option's transactions may have executed the proposal already
+    if !matches!(proposal.state, ProposalState::Approved | ProposalState::Executed)
+        || proposal.winning_option != Some(change.option_index)
+    {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    // Bundled proposals take effect with their bundle
+    if proposal.bundle.is_some() && proposal.state != ProposalState::Executed {
+        return Err(GovernanceError::ProposalBundled.into());
+    }
+    if let Some(depends_on) = proposal.depends_on {
+        let prerequisite_info = next_account_with_role(account_info_iter, "prerequisite_proposal")?;
+        if *prerequisite_info.key != depends_on {
+            return Err(GovernanceError::InvalidProposalDependency.into());
+        }
+        let prerequisite = get_account_data::<Proposal>(program_id, prerequisite_info)?;
+        if prerequisite.state != ProposalState::Executed {
+            return Err(GovernanceError::ProposalDependencyNotExecuted.into());
+        }
+    }
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    if proposal.is_execution_locked(&governance.config, now) {
+        return Err(GovernanceError::ExecutionLocked.into());
+    }
+    if proposal.is_awaiting_sybil_review(&governance.config) {
+        return Err(GovernanceError::SybilReviewPending.into());
+    }
+    if proposal.state == ProposalState::Approved && proposal.is_execution_window_elapsed(&governance.config, now) {
+        return Err(GovernanceError::ExecutionWindowElapsed.into());
+    }
+    
+    // The config may have changed since the proposal was created
+    let config = change.apply(&governance.config);
+    assert_valid_governance_config(&config)?;
+    governance.config = config;
+    proposal.parameter_change_applied_at = Some(now);
+    if proposal.state == ProposalState::Approved && proposal.get_transaction_count(change.option_index) == 0 {
+        proposal.state = ProposalState::Executed;
+        governance.open_proposal_count = governance.open_proposal_count.saturating_sub(1);
+    }
+    
+    save_account_data(ctx.proposal, &proposal)?;
+    save_account_data(ctx.governance, &governance)?;
+    
+    Ok(())
+}
+
+/// Process ReleaseCreationFeeDeposit instruction
+pub fn process_release_creation_fee_deposit(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = ReleaseCreationFeeDepositAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut proposal = get_account_data::<Proposal>(program_id, ctx.proposal)?;
+    let governance = get_account_data::<Governance>(program_id, ctx.governance)?;
+    
+    if proposal.governance != *ctx.governance.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if proposal.proposal_owner != *ctx.proposal_owner.key {
+        return Err(GovernanceError::InvalidProposalOwner.into());
+    }
+    let deposit = proposal
+        .creation_fee_deposit
+        .ok_or(GovernanceError::NoCreationFeeDeposit)?;
+    if *ctx.treasury.key != deposit.treasury {
+        return Err(GovernanceError::InvalidTreasury.into());
+    }
+    
+    // A rejection splits the deposit, proposals which didn't reach quorum or were vetoed
+    // forfeit it
+    let (refund_lamports, treasury_lamports) = match proposal.state {
+        ProposalState::Draft | ProposalState::Active => {
+            return Err(GovernanceError::InvalidProposalState.into());
+        }
+        ProposalState::Rejected => governance.config.split_rejected_deposit(deposit.lamports),
+        ProposalState::Approved | ProposalState::UnderfundedApproved | ProposalState::Executed => {
+            (deposit.lamports, 0)
+        }
+        ProposalState::Expired | ProposalState::Vetoed => (0, deposit.lamports),
+    };
+    
+    proposal.creation_fee_deposit = None;
+    save_account_data(ctx.proposal, &proposal)?;
+    
+    **ctx.proposal.try_borrow_mut_lamports()? = ctx.proposal
+        .lamports()
+        .checked_sub(deposit.lamports)
+        .ok_or(GovernanceError::MathOverflow)?;
+    **ctx.proposal_owner.try_borrow_mut_lamports()? = ctx.proposal_owner
+        .lamports()
+        .checked_add(refund_lamports)
+        .ok_or(GovernanceError::MathOverflow)?;
+    **ctx.treasury.try_borrow_mut_lamports()? = ctx.treasury
+        .lamports()
+        .checked_add(treasury_lamports)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+/// Process MigrateGoverningMint instruction
+pub fn process_migrate_governing_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = MigrateGoverningMintAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    assert_realm_authority(program_id, ctx.realm.key, &realm, accounts)?;
+    
+    // A single migration keeps every old deposit swappable
+    if realm.mint_migration.is_some() || *ctx.new_community_mint.key == realm.community_mint {
+        return Err(GovernanceError::InvalidMintMigration.into());
+    }
+    let new_community_mint = unpack_mint(ctx.new_community_mint)?;
+    assert_vault_token_account(program_id, ctx.realm.key, &realm, ctx.swap_reserve, ctx.new_community_mint.key)?;
+    
+    // Staked old tokens move out of total_staked until they're swapped for new ones
+    realm.mint_migration = Some(MintMigration {
+        old_mint: realm.community_mint,
+        new_mint: *ctx.new_community_mint.key,
+        swap_reserve: *ctx.swap_reserve.key,
+        unswapped_amount: realm.total_staked,
+    });
+    realm.community_mint = *ctx.new_community_mint.key;
+    realm.community_mint_decimals = new_community_mint.decimals;
+    realm.total_staked = 0;
+    
+    validate_realm_config(&realm)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Process SwapDeposit instruction
+pub fn process_swap_deposit(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = SwapDepositAccounts::from_accounts(program_id, accounts)?;
+    
+    let mut realm = get_account_data::<Realm>(program_id, ctx.realm)?;
+    let mut mint_migration = realm.mint_migration.ok_or(GovernanceError::NoMintMigration)?;
+    assert_vault_token_account(program_id, ctx.realm.key, &realm, ctx.old_staking_vault, &mint_migration.old_mint)?;
+    assert_staking_vault(program_id, ctx.realm.key, &realm, ctx.new_staking_vault)?;
+    if *ctx.swap_reserve.key != mint_migration.swap_reserve || *ctx.old_community_mint.key != mint_migration.old_mint {
+        return Err(GovernanceError::InvalidMintMigration.into());
+    }
+    
+    let mut old_token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.old_token_owner_record)?;
+    if old_token_owner_record.realm != *ctx.realm.key
+        || old_token_owner_record.governing_token_mint != mint_migration.old_mint
+        || old_token_owner_record.governing_token_owner != *ctx.token_owner.key
+    {
+        return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+    }
+    
+    // Votes are recorded per token owner record, so moving a voting deposit would let it vote twice
+    if old_token_owner_record.unrelinquished_votes_count > 0 {
+        return Err(GovernanceError::OutstandingVotes.into());
+    }
+    
+    let mut new_token_owner_record = if ctx.new_token_owner_record.data_is_empty() {
+        create_pda_account(
+            ctx.token_owner,
+            ctx.new_token_owner_record,
+            ctx.system_program,
+            program_id,
+            TokenOwnerRecord::get_max_size(),
+            &get_token_owner_record_address_seeds(ctx.realm.key, &mint_migration.new_mint, ctx.token_owner.key),
+        )?;
+        
+        TokenOwnerRecord {
+            governing_token_mint: mint_migration.new_mint,
+            governing_token_deposit_amount: 0,
+            stake_tranches: vec![],
+            claimed_voting_power: 0,
+            cumulative_stake_seconds: 0,
+            stake_seconds_updated_at: 0,
+            ..old_token_owner_record.clone()
+        }
+    } else {
+        let new_token_owner_record = get_account_data::<TokenOwnerRecord>(program_id, ctx.new_token_owner_record)?;
+        if new_token_owner_record.realm != *ctx.realm.key
+            || new_token_owner_record.governing_token_mint != mint_migration.new_mint
+            || new_token_owner_record.governing_token_owner != *ctx.token_owner.key
+        {
+            return Err(GovernanceError::InvalidTokenOwnerRecord.into());
+        }
+        new_token_owner_record
+    };
+    
+    // Burn the old tokens and back the new deposit with as many new ones from the swap reserve
+    let amount = old_token_owner_record.governing_token_deposit_amount;
+    let vault_authority_seeds = get_vault_authority_address_seeds(ctx.realm.key);
+    burn_from_pda_vault(
+        program_id,
+        ctx.token_program,
+        ctx.old_staking_vault,
+        ctx.old_community_mint,
+        ctx.vault_authority,
+        &vault_authority_seeds,
+        realm.vault_authority_bump_seed,
+        amount,
+    )?;
+    transfer_from_pda_vault(
+        program_id,
+        ctx.token_program,
+        ctx.swap_reserve,
+        ctx.new_staking_vault,
+        ctx.vault_authority,
+        &vault_authority_seeds,
+        realm.vault_authority_bump_seed,
+        amount,
+    )?;
+    mint_migration.unswapped_amount = mint_migration
+        .unswapped_amount
+        .checked_sub(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    realm.mint_migration = Some(mint_migration);
+    realm.total_staked = realm
+        .total_staked
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Each record accrues the stake-seconds of its own mint's deposit, the old one's ending here
+    let now = to_u64_timestamp(get_clock(accounts)?.unix_timestamp)?;
+    old_token_owner_record.accrue_stake_seconds(now);
+    new_token_owner_record.accrue_stake_seconds(now);
+    
+    // Carry the deposit over tranche by tranche so unlock times are kept
+    new_token_owner_record.governing_token_deposit_amount = new_token_owner_record
+        .governing_token_deposit_amount
+        .checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    for tranche in &old_token_owner_record.stake_tranches {
+        new_token_owner_record.add_stake_tranche(tranche.amount, tranche.unlock_time)?;
+    }
+    new_token_owner_record.claimed_voting_power = new_token_owner_record
+        .claimed_voting_power
+        .checked_add(old_token_owner_record.claimed_voting_power)
+        .ok_or(GovernanceError::MathOverflow)?;
+    // Swapped tokens aren't a new deposit, they keep the age of the last one
+    new_token_owner_record.last_deposit_slot = new_token_owner_record
+        .last_deposit_slot
+        .max(old_token_owner_record.last_deposit_slot);
+    
+    old_token_owner_record.governing_token_deposit_amount = 0;
+    old_token_owner_record.stake_tranches.clear();
+    old_token_owner_record.earliest_unstaking_time = 0;
+    old_token_owner_record.claimed_voting_power = 0;
+    old_token_owner_record.governance_delegate = None;
+    old_token_owner_record.delegated_amount = 0;
+    
+    save_account_data(ctx.old_token_owner_record, &old_token_owner_record)?;
+    save_account_data(ctx.new_token_owner_record, &new_token_owner_record)?;
+    save_account_data(ctx.realm, &realm)?;
+    
+    Ok(())
+}
+
+/// Resolves the outcome of an active proposal whose voting has ended and frees its active
+/// slot, returning the entry to record in the proposal archive
+///
+/// The outcome only reads the aggregate tallies, so finalizing costs the same however many voted.
+/// Proposals failing the governance's sybil vote check are flagged, which log_sybil_flag logs
+fn finalize_proposal_vote(
+    proposal_key: &Pubkey,
+    proposal: &mut Proposal,
+    governance: &mut Governance,
+    realm: &mut Realm,
+    max_vote_weight: u64,
+    now: u64,
+) -> Result<ArchivedProposal, ProgramError> {
+    let (mut state, winning_option) = proposal.resolve_vote_outcome(
+        &governance.config,
+        governance.quorum_failure_count,
+        max_vote_weight,
+        realm.abstain_counts_for_quorum,
+    );
+    if matches!(state, ProposalState::Approved | ProposalState::UnderfundedApproved) {
+        proposal.executable_at = Some(now);
+        // A winning fallback without transactions has nothing left to execute
+        if let Some(option_index) = winning_option {
+            if Some(option_index) == proposal.fallback_option_index
+                && proposal.get_transaction_count(option_index) == 0
+            {
+                state = ProposalState::Executed;
+            }
+        }
+    }
+    proposal.state = state;
+    proposal.winning_option = winning_option;
+    
+    governance.active_proposal_count = governance.active_proposal_count.saturating_sub(1);
+    if matches!(state, ProposalState::Rejected | ProposalState::Expired | ProposalState::Executed) {
+        governance.open_proposal_count = governance.open_proposal_count.saturating_sub(1);
+    }
+    // Only proposals measured against quorum count towards the quorum decay, and they expire
+    // exactly when they miss it
+    if proposal.vote_type != VoteType::Conviction && !proposal.is_approve_by_default() {
+        governance.quorum_failure_count = if state == ProposalState::Expired {
+            governance.quorum_failure_count.saturating_add(1)
+        } else {
+            0
+        };
+    }
+    realm.last_activity_at = now;
+    proposal.votes_relinquishable = realm.auto_relinquish_finalized;
+    proposal.locks_backing_stake = realm.lock_stake_until_execution;
+    
+    if let Some(check) = governance.config.sybil_vote_check {
+        proposal.is_sybil_flagged = proposal.is_below_vote_weight_per_voter(check.min_vote_weight_per_voter);
+    }
+    
+    Ok(ArchivedProposal {
+        proposal: *proposal_key,
+        state,
+        winning_option,
+    })
+}
+
+/// Logs the SybilFlagRaised event of a proposal its finalization flagged
+fn log_sybil_flag(proposal_key: &Pubkey, proposal: &Proposal, config: &GovernanceConfig) -> ProgramResult {
+    if let Some(check) = config.sybil_vote_check.filter(|_| proposal.is_sybil_flagged) {
+        let sybil_flag_raised = SybilFlagRaised {
+            proposal: *proposal_key,
+            total_vote_weight: proposal.total_vote_weight,
+            unique_voter_count: proposal.vote_record_count,
+            min_vote_weight_per_voter: check.min_vote_weight_per_voter,
+        };
+        sol_log_data(&[&sybil_flag_raised.try_to_vec()?]);
+    }
+    
+    Ok(())
+}
+
+/// Returns true if the proposal's vote can be finalized, accruing the conviction of conviction
+/// proposals up to the end of voting
+///
+/// Votes are finalized once voting has ended, conviction proposals also as soon as their
+/// conviction reaches the threshold
+fn is_proposal_vote_finalizable(
+    proposal: &mut Proposal,
+    config: &GovernanceConfig,
+    max_vote_weight: u64,
+    now: u64,
+) -> bool {
+    proposal.accrue_conviction(config, now.min(proposal.voting_ends_at));
+    now >= proposal.voting_ends_at
+        || (proposal.vote_type == VoteType::Conviction
+            && proposal.has_reached_conviction_threshold(config, max_vote_weight))
+}
+
+/// Finalizes an Active proposal whose voting has ended without reaching quorum as Expired,
+/// returning true if it did
+///
+/// Proposals don't expire on their own, so instructions reading a proposal touch it first
+/// rather than act on a stale Active state. The touched proposal is never saved: the expiry
+/// is only kept by FinalizeVote, which also records it in the proposal archive and certifies it
+pub fn touch_proposal_state(
+    proposal_key: &Pubkey,
+    proposal: &mut Proposal,
+    governance: &Governance,
+    realm: &Realm,
+    max_vote_weight: u64,
+    now: u64,
+) -> Result<bool, ProgramError> {
+    if proposal.state != ProposalState::Active || now < proposal.voting_ends_at {
+        return Ok(false);
+    }
+    let (state, _) = proposal.resolve_vote_outcome(
+        &governance.config,
+        governance.quorum_failure_count,
+        max_vote_weight,
+        realm.abstain_counts_for_quorum,
+    );
+    if state != ProposalState::Expired {
+        return Ok(false);
+    }
+    
+    finalize_proposal_vote(
+        proposal_key,
+        proposal,
+        &mut governance.clone(),
+        &mut realm.clone(),
+        max_vote_weight,
+        now,
+    )?;
+    Ok(true)
+}
+
+/// Records a finalized proposal on the latest page of the realm's proposal archive,
+/// opening a new page when the latest one is full
+///
+/// Only the latest page is read and written, so appends don't grow with the archive size
+fn append_to_proposal_archive<'a>(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    proposal_archive_info: &AccountInfo<'a>,
+    account_info_iter: &mut std::slice::Iter<AccountInfo<'a>>,
+    archived_proposal: ArchivedProposal,
+) -> ProgramResult {
+    // The realm's first archive page is created on its first finalization
+    if proposal_archive_info.data_is_empty() {
+        let payer_info = next_account_with_role(account_info_iter, "payer")?;
+        let system_program_info = next_account_with_role(account_info_iter, "system_program")?;
+        return create_proposal_archive_page(
+            program_id,
+            realm,
+            0,
+            proposal_archive_info,
+            payer_info,
+            system_program_info,
+            archived_proposal,
+        );
+    }
+    
+    let mut proposal_archive = get_account_data::<ProposalArchive>(program_id, proposal_archive_info)?;
+    if proposal_archive.realm != *realm {
+        return Err(GovernanceError::InvalidProposalArchivePage.into());
+    }
+    
+    if !proposal_archive.is_full() {
+        proposal_archive.proposals.push(archived_proposal);
+        save_account_data(proposal_archive_info, &proposal_archive)?;
+        return Ok(());
+    }
+    
+    // Roll over into the next page, which fails if the given page wasn't the latest one
+    let payer_info = next_account_with_role(account_info_iter, "payer")?;
+    let system_program_info = next_account_with_role(account_info_iter, "system_program")?;
+    let next_proposal_archive_info = next_account_with_role(account_info_iter, "next_proposal_archive")?;
+    let next_page_index = proposal_archive
+        .page_index
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    create_proposal_archive_page(
+        program_id,
+        realm,
+        next_page_index,
+        next_proposal_archive_info,
+        payer_info,
+        system_program_info,
+        archived_proposal,
+    )
+}
+
+/// Creates the proposal certificate account holding the given certificate
+fn create_proposal_certificate<'a>(
+    program_id: &Pubkey,
+    certificate: &ProposalCertificate,
+    proposal_certificate_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    create_pda_account(
+        payer_info,
+        proposal_certificate_info,
+        system_program_info,
+        program_id,
+        ProposalCertificate::get_max_size(certificate.vote_results.len()),
+        &get_proposal_certificate_address_seeds(&certificate.proposal),
+    )?;
+    
+    save_account_data(proposal_certificate_info, certificate)
+}
+
+/// Creates a proposal archive page holding the given proposal
+fn create_proposal_archive_page<'a>(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    page_index: u32,
+    proposal_archive_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    archived_proposal: ArchivedProposal,
+) -> ProgramResult {
+    assert_signer(payer_info, "payer")?;
+    
+    let page_index_seed = page_index.to_le_bytes();
+    create_pda_account(
+        payer_info,
+        proposal_archive_info,
+        system_program_info,
+        program_id,
+        ProposalArchive::get_max_size(),
+        &get_proposal_archive_address_seeds(realm, &page_index_seed),
+    )?;
+    
+    let proposal_archive = ProposalArchive {
+        account_type: AccountType::ProposalArchive,
+        realm: *realm,
+        page_index,
+        proposals: vec![archived_proposal],
+        used_reserved: ProposalArchive::get_used_reserved()?,
+        reserved: [0; 63],
+    };
+    
+    save_account_data(proposal_archive_info, &proposal_archive)?;
+    
+    Ok(())
+}
+
+/// Checks the remaining accounts of ExecuteProposal are exactly the accounts of the proposal
+/// transaction's instructions, in order and with their stored signer and writable flags
+///
+/// The runtime merges the flags of an account passed more than once, so an account repeated
+/// within ExecuteProposal may carry the flags of its other occurrences
+fn assert_execution_accounts(
+    instructions: &[InstructionData],
+    governance: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_account_infos: &[AccountInfo],
+) -> ProgramResult {
+    let account_metas = get_execution_account_metas(instructions, governance);
+    if account_metas.len() != instruction_account_infos.len() {
+        return Err(GovernanceError::ExecutionAccountMismatch.into());
+    }
+    
+    for (account_meta, account_info) in account_metas.iter().zip(instruction_account_infos) {
+        let is_repeated = accounts.iter().filter(|account| account.key == account_info.key).count() > 1;
+        let flag_matches = |expected: bool, actual: bool| actual == expected || (is_repeated && actual);
+        if *account_info.key != account_meta.pubkey
+            || !flag_matches(account_meta.is_signer, account_info.is_signer)
+            || !flag_matches(account_meta.is_writable, account_info.is_writable)
+        {
+            return Err(GovernanceError::ExecutionAccountMismatch.into());
+        }
+    }
+    
+    Ok(())
+}
+
+/// Returns the next account of an instruction's account list, failing with MissingAccount
+/// and logging the role of the missing account when the list is too short
+pub fn next_account_with_role<'a, 'b>(
+    account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    role: &'static str,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    next_account_info(account_info_iter).map_err(|_| {
+        msg!("Missing account: {}", role);
+        GovernanceError::MissingAccount.into()
+    })
+}
+
+/// Deserializes a governance program account, checking its owner, discriminator and account type
+pub fn get_account_data<T: GovernanceAccount>(
+    program_id: &Pubkey,
+    account_info: &AccountInfo,
+) -> Result<T, ProgramError> {
+    if account_info.owner != program_id {
+        return Err(GovernanceError::InvalidAccountOwner.into());
+    }
+    
+    let account_data = account_info.data.borrow();
+    if AccountType::from_discriminator(&account_data) != Some(T::ACCOUNT_TYPE) {
+        return Err(GovernanceError::InvalidAccountType.into());
+    }
+    let data = T::deserialize(&mut &account_data[ACCOUNT_DISCRIMINATOR_LEN..])?;
+    if data.account_type() != T::ACCOUNT_TYPE {
+        return Err(GovernanceError::InvalidAccountType.into());
+    }
+    
+    Ok(data)
+}
+
+/// Serializes a governance program account behind the discriminator of its account type
+///
+/// Fails with AccountDataTooSmall, logging the required and actual size, when the account
+/// can't hold the serialized data
+pub fn save_account_data<T: GovernanceAccount + BorshSerialize>(
+    account_info: &AccountInfo,
+    data: &T,
+) -> ProgramResult {
+    let serialized_data = data.try_to_vec()?;
+    let required_len = ACCOUNT_DISCRIMINATOR_LEN + serialized_data.len();
+    
+    let mut account_data = account_info.data.borrow_mut();
+    if account_data.len() < required_len {
+        msg!(
+            "Account data too small: {} needs {} bytes, has {}",
+            account_info.key,
+            required_len,
+            account_data.len()
+        );
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    
+    account_data[..ACCOUNT_DISCRIMINATOR_LEN].copy_from_slice(&data.account_type().get_discriminator());
+    account_data[ACCOUNT_DISCRIMINATOR_LEN..required_len].copy_from_slice(&serialized_data);
+    
+    Ok(())
+}
+
+/// Returns the governance the proposal belongs to, failing with GovernanceClosed when its
+/// account no longer holds a governance, e.g. after being closed
+fn get_proposal_governance(
+    program_id: &Pubkey,
+    proposal: &Proposal,
+    governance_info: &AccountInfo,
+) -> Result<Governance, ProgramError> {
+    if proposal.governance != *governance_info.key {
+        return Err(GovernanceError::InvalidGovernanceForProposal.into());
+    }
+    if governance_info.owner != program_id {
+        return Err(GovernanceError::GovernanceClosed.into());
+    }
+    
+    get_account_data::<Governance>(program_id, governance_info)
+        .map_err(|_| GovernanceError::GovernanceClosed.into())
+}
+
+/// Returns the max vote weight the proposal's tallies are measured against
+fn get_proposal_max_vote_weight(
+    realm: &Realm,
+    proposal: &Proposal,
+    community_mint_info: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    let community_mint = Mint::unpack(&community_mint_info.data.borrow())?;
+    let max_vote_weight_source = proposal
+        .max_vote_weight_source
+        .unwrap_or(realm.community_mint_max_vote_weight_source);
+    realm.get_max_vote_weight(&max_vote_weight_source, community_mint.supply)
+}
+
+/// Returns the size of the realm account InitializeRealm creates, for clients funding its rent
+pub fn estimate_realm_size(name: &str) -> Result<usize, ProgramError> {
+    Ok(ACCOUNT_DISCRIMINATOR_LEN + Realm::get_max_size(name)?)
+}
+
+/// Returns the size of the proposal account CreateProposal creates, for clients funding its rent
+pub fn estimate_proposal_size(
+    name: &str,
+    description_link: &str,
+    vote_type: &VoteType,
+    options: &[String],
+) -> Result<usize, ProgramError> {
+    Ok(ACCOUNT_DISCRIMINATOR_LEN + Proposal::get_max_size(name, description_link, vote_type, options)?)
+}
+
+/// Returns the size of the vote record account CreateVoteRecord creates for the proposal, for
+/// clients funding its rent
+pub fn estimate_vote_record_size(proposal: &Proposal) -> usize {
+    ACCOUNT_DISCRIMINATOR_LEN + VoteRecord::get_max_size(proposal)
+}
+
+/// Returns the PDA seeds of a realm, keyed by the keccak hash of its name
+pub fn get_realm_address_seeds<'a>(
+    name_hash: &'a [u8; 32],
+    community_mint: &'a Pubkey,
+    nonce: &'a [u8; 8],
+) -> [&'a [u8]; 4] {
+    [REALM_SEED, name_hash, community_mint.as_ref(), nonce]
+}
+
+/// Returns the PDA address of a realm
+pub fn get_realm_address(program_id: &Pubkey, name: &str, community_mint: &Pubkey, nonce: u64) -> Pubkey {
+    let name_hash = keccak::hash(name.as_bytes()).to_bytes();
+    Pubkey::find_program_address(
+        &get_realm_address_seeds(&name_hash, community_mint, &nonce.to_le_bytes()),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a governance
+pub fn get_governance_address_seeds<'a>(realm: &'a Pubkey, governed_account: &'a Pubkey) -> [&'a [u8]; 3] {
+    [GOVERNANCE_SEED, realm.as_ref(), governed_account.as_ref()]
+}
+
+/// Returns the PDA address of a governance
+pub fn get_governance_address(program_id: &Pubkey, realm: &Pubkey, governed_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_governance_address_seeds(realm, governed_account), program_id).0
+}
+
+/// Returns the PDA seeds of a token owner record
+pub fn get_token_owner_record_address_seeds<'a>(
+    realm: &'a Pubkey,
+    governing_token_mint: &'a Pubkey,
+    governing_token_owner: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [
+        TOKEN_OWNER_RECORD_SEED,
+        realm.as_ref(),
+        governing_token_mint.as_ref(),
+        governing_token_owner.as_ref(),
+    ]
+}
+
+/// Returns the PDA address of a token owner record
+pub fn get_token_owner_record_address(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_token_owner_record_address_seeds(realm, governing_token_mint, governing_token_owner),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a vote record
+pub fn get_vote_record_address_seeds<'a>(proposal: &'a Pubkey, token_owner_record: &'a Pubkey) -> [&'a [u8]; 3] {
+    [VOTE_RECORD_SEED, proposal.as_ref(), token_owner_record.as_ref()]
+}
+
+/// Returns the PDA address of a vote record
+pub fn get_vote_record_address(program_id: &Pubkey, proposal: &Pubkey, token_owner_record: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_vote_record_address_seeds(proposal, token_owner_record), program_id).0
+}
+
+/// Returns the PDA seeds of a vote record cast by a token owner's governance delegate
+pub fn get_delegate_vote_record_address_seeds<'a>(
+    proposal: &'a Pubkey,
+    token_owner_record: &'a Pubkey,
+    governance_delegate: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [VOTE_RECORD_SEED, proposal.as_ref(), token_owner_record.as_ref(), governance_delegate.as_ref()]
+}
+
+/// Returns the PDA address of a vote record cast by a token owner's governance delegate
+pub fn get_delegate_vote_record_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    token_owner_record: &Pubkey,
+    governance_delegate: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_delegate_vote_record_address_seeds(proposal, token_owner_record, governance_delegate),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a council member's vote record
+pub fn get_council_vote_record_address_seeds<'a>(proposal: &'a Pubkey, council_member: &'a Pubkey) -> [&'a [u8]; 3] {
+    [COUNCIL_VOTE_RECORD_SEED, proposal.as_ref(), council_member.as_ref()]
+}
+
+/// Returns the PDA address of a council member's vote record
+pub fn get_council_vote_record_address(program_id: &Pubkey, proposal: &Pubkey, council_member: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_council_vote_record_address_seeds(proposal, council_member), program_id).0
+}
+
+/// Returns the PDA seeds of the vote record the voter casts with the token owner record, as
+/// the token owner and the delegate each hold their own vote record
+fn get_voter_vote_record_address_seeds<'a>(
+    proposal: &'a Pubkey,
+    token_owner_record_key: &'a Pubkey,
+    token_owner_record: &TokenOwnerRecord,
+    voter: &'a Pubkey,
+) -> Vec<&'a [u8]> {
+    if *voter == token_owner_record.governing_token_owner {
+        get_vote_record_address_seeds(proposal, token_owner_record_key).to_vec()
+    } else {
+        get_delegate_vote_record_address_seeds(proposal, token_owner_record_key, voter).to_vec()
+    }
+}
+
+/// Returns the vote record of a vote cast on the proposal with the token owner record
+///
+/// The record's address is checked with the bump it was created with, so it's read first
+fn get_cast_vote_record(
+    program_id: &Pubkey,
+    vote_record_info: &AccountInfo,
+    proposal: &Pubkey,
+    token_owner_record_key: &Pubkey,
+    token_owner_record: &TokenOwnerRecord,
+) -> Result<VoteRecord, ProgramError> {
+    let vote_record = get_account_data::<VoteRecord>(program_id, vote_record_info)?;
+    if vote_record.proposal != *proposal {
+        return Err(GovernanceError::InvalidVoteRecord.into());
+    }
+    
+    // The vote record's address ties it to the token owner record it was cast with
+    let voter_authority = vote_record.get_voter_authority();
+    let seeds =
+        get_voter_vote_record_address_seeds(proposal, token_owner_record_key, token_owner_record, &voter_authority);
+    // Another voter's seeds with this record's bump may not derive a valid address at all
+    if !get_pda_address_with_bump(&seeds, vote_record.bump_seed, program_id)
+        .is_ok_and(|(address, _)| address == *vote_record_info.key)
+    {
+        return Err(GovernanceError::VoteRecordMismatch.into());
+    }
+    
+    Ok(vote_record)
+}
+
+/// Returns the voter's empty vote record for the proposal, created by CreateVoteRecord and
+/// not yet cast into
+///
+/// The record's address is checked with the bump it was created with, so it's read first
+fn get_empty_vote_record(
+    program_id: &Pubkey,
+    vote_record_info: &AccountInfo,
+    proposal: &Pubkey,
+    token_owner_record_key: &Pubkey,
+    token_owner_record: &TokenOwnerRecord,
+    voter: &Pubkey,
+) -> Result<VoteRecord, ProgramError> {
+    if vote_record_info.data_is_empty() {
+        return Err(GovernanceError::VoteRecordNotCreated.into());
+    }
+    if vote_record_info.owner != program_id {
+        return Err(GovernanceError::InvalidAccountOwner.into());
+    }
+    let account_data = vote_record_info.data.borrow();
+    match AccountType::from_discriminator(&account_data) {
+        Some(AccountType::EmptyVoteRecord) => {}
+        Some(AccountType::VoteRecord) => return Err(GovernanceError::VoteAlreadyCast.into()),
+        _ => return Err(GovernanceError::InvalidAccountType.into()),
+    }
+    let vote_record = VoteRecord::deserialize(&mut &account_data[ACCOUNT_DISCRIMINATOR_LEN..])?;
+    
+    let seeds = get_voter_vote_record_address_seeds(proposal, token_owner_record_key, token_owner_record, voter);
+    // Another voter's seeds with this record's bump may not derive a valid address at all
+    if !get_pda_address_with_bump(&seeds, vote_record.bump_seed, program_id)
+        .is_ok_and(|(address, _)| address == *vote_record_info.key)
+    {
+        return Err(GovernanceError::VoteRecordMismatch.into());
+    }
+    
+    Ok(vote_record)
+}
+
+/// Returns the PDA seeds of a proposal transaction
+pub fn get_proposal_transaction_address_seeds<'a>(
+    proposal: &'a Pubkey,
+    option_index: &'a [u8; 1],
+    transaction_index: &'a [u8; 1],
+) -> [&'a [u8]; 4] {
+    [PROPOSAL_TRANSACTION_SEED, proposal.as_ref(), option_index, transaction_index]
+}
+
+/// Returns the PDA address of a proposal transaction
+pub fn get_proposal_transaction_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    option_index: u8,
+    transaction_index: u8,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_proposal_transaction_address_seeds(proposal, &[option_index], &[transaction_index]),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a proposal archive page
+pub fn get_proposal_archive_address_seeds<'a>(realm: &'a Pubkey, page_index: &'a [u8; 4]) -> [&'a [u8]; 3] {
+    [PROPOSAL_ARCHIVE_SEED, realm.as_ref(), page_index]
+}
+
+/// Returns the PDA address of a proposal archive page
+pub fn get_proposal_archive_address(program_id: &Pubkey, realm: &Pubkey, page_index: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_proposal_archive_address_seeds(realm, &page_index.to_le_bytes()),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a proposal bundle, keyed by its first proposal as a proposal
+/// belongs to at most one bundle
+pub fn get_proposal_bundle_address_seeds(first_proposal: &Pubkey) -> [&[u8]; 2] {
+    [PROPOSAL_BUNDLE_SEED, first_proposal.as_ref()]
+}
+
+/// Returns the PDA address of a proposal bundle
+pub fn get_proposal_bundle_address(program_id: &Pubkey, first_proposal: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_proposal_bundle_address_seeds(first_proposal), program_id).0
+}
+
+/// Returns the PDA seeds of a proposal certificate
+pub fn get_proposal_certificate_address_seeds(proposal: &Pubkey) -> [&[u8]; 2] {
+    [PROPOSAL_CERTIFICATE_SEED, proposal.as_ref()]
+}
+
+/// Returns the PDA address of a proposal certificate
+pub fn get_proposal_certificate_address(program_id: &Pubkey, proposal: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_proposal_certificate_address_seeds(proposal), program_id).0
+}
+
+/// Returns the PDA seeds of a voting power claim
+pub fn get_voting_power_claim_address_seeds<'a>(realm: &'a Pubkey, leaf_index: &'a [u8; 4]) -> [&'a [u8]; 3] {
+    [VOTING_POWER_CLAIM_SEED, realm.as_ref(), leaf_index]
+}
+
+/// Returns the PDA address of a voting power claim
+pub fn get_voting_power_claim_address(program_id: &Pubkey, realm: &Pubkey, leaf_index: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_voting_power_claim_address_seeds(realm, &leaf_index.to_le_bytes()),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA seeds of a vesting stream
+pub fn get_vesting_stream_address_seeds<'a>(
+    governance: &'a Pubkey,
+    recipient: &'a Pubkey,
+    nonce: &'a [u8; 8],
+) -> [&'a [u8]; 4] {
+    [VESTING_STREAM_SEED, governance.as_ref(), recipient.as_ref(), nonce]
+}
+
+/// Returns the PDA address of a vesting stream
+pub fn get_vesting_stream_address(program_id: &Pubkey, governance: &Pubkey, recipient: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_vesting_stream_address_seeds(governance, recipient, &nonce.to_le_bytes()),
+        program_id,
+    )
+    .0
+}
+
+/// Returns the PDA address and bump seed of the authority owning a realm's staking vault
+pub fn get_vault_authority_address(program_id: &Pubkey, realm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&get_vault_authority_address_seeds(realm), program_id)
+}
+
+/// Returns the seeds of the staking vault authority PDA of a realm
+pub fn get_vault_authority_address_seeds(realm: &Pubkey) -> [&[u8]; 2] {
+    [VAULT_AUTHORITY_SEED, realm.as_ref()]
+}
+
+/// Pays a keeper bounty out of the proposal's creation deposit, held in the proposal account
+///
+/// The paid lamports are taken off the deposit, so ReleaseCreationFeeDeposit splits what's
+/// left. Nothing is paid when the proposal has no deposit or what's left can't cover the
+/// bounty, so an unfunded bounty never holds up finalization or execution
+fn pay_keeper_bounty(
+    proposal_info: &AccountInfo,
+    proposal: &mut Proposal,
+    keeper_info: &AccountInfo,
+    bounty_lamports: u64,
+) -> ProgramResult {
+    if bounty_lamports == 0 {
+        return Ok(());
+    }
+    let Some(deposit) = proposal.creation_fee_deposit.as_mut().filter(|deposit| deposit.lamports >= bounty_lamports)
+    else {
+        msg!("Proposal deposit can't cover the keeper bounty of {} lamports", bounty_lamports);
+        return Ok(());
+    };
+    deposit.lamports -= bounty_lamports;
     
-    /// Token owner which cast this vote
-    pub governing_token_owner: Pubkey,
+    **proposal_info.try_borrow_mut_lamports()? = proposal_info
+        .lamports()
+        .checked_sub(bounty_lamports)
+        .ok_or(GovernanceError::MathOverflow)?;
+    **keeper_info.try_borrow_mut_lamports()? = keeper_info
+        .lamports()
+        .checked_add(bounty_lamports)
+        .ok_or(GovernanceError::MathOverflow)?;
     
-    /// How the vote was cast
-    pub vote: Vote,
+    Ok(())
+}
+
+/// Transfers tokens out of a vault or treasury owned by a program PDA, checking the authority
+/// account against the given seeds and signing for it with their bump
+///
+/// The bump stored for the authority is used when there is one, 0 derives the canonical bump
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_pda_vault<'a>(
+    program_id: &Pubkey,
+    token_program_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+    stored_bump_seed: u8,
+    amount: u64,
+) -> ProgramResult {
+    assert_program_account(token_program_info, &spl_token::id(), "token_program")?;
+    let (authority, bump_seed) = get_pda_address_with_bump(seeds, stored_bump_seed, program_id)?;
+    if authority != *authority_info.key {
+        return Err(GovernanceError::InvalidAccountAddress.into());
+    }
     
-    /// The amount of tokens used for the vote (before quadratic calculation)
-    pub stake_amount: u64,
+    let bump = [bump_seed];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump);
     
-    /// The weight of the vote after applying quadratic voting if enabled
-    pub vote_weight: u64,
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program_info.key,
+            source_info.key,
+            destination_info.key,
+            authority_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            source_info.clone(),
+            destination_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&signer_seeds],
+    )
+}
+
+/// Burns tokens held by a vault owned by a program PDA, checking the authority account
+/// against the given seeds and signing for it with their bump
+#[allow(clippy::too_many_arguments)]
+fn burn_from_pda_vault<'a>(
+    program_id: &Pubkey,
+    token_program_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+    stored_bump_seed: u8,
+    amount: u64,
+) -> ProgramResult {
+    assert_program_account(token_program_info, &spl_token::id(), "token_program")?;
+    let (authority, bump_seed) = get_pda_address_with_bump(seeds, stored_bump_seed, program_id)?;
+    if authority != *authority_info.key {
+        return Err(GovernanceError::InvalidAccountAddress.into());
+    }
     
-    /// Whether the vote has been relinquished by the voter
-    pub is_relinquished: bool,
+    let bump = [bump_seed];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump);
     
-    /// Reserved space for future versions
-    pub reserved: [u8; 64],
+    invoke_signed(
+        &spl_token::instruction::burn(
+            token_program_info.key,
+            vault_info.key,
+            mint_info.key,
+            authority_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_info.clone(),
+            mint_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&signer_seeds],
+    )
 }
 
-/// Governance program account types
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
-pub enum AccountType {
-    /// Uninitialized account
-    Uninitialized,
-    
-    /// Realm account
-    Realm,
+/// Creates a program owned PDA account, checking its address against the given seeds and
+/// leaving room for the account discriminator ahead of the given size
+///
+/// Returns the canonical bump of the address, for the account to store
+fn create_pda_account<'a>(
+    payer_info: &AccountInfo<'a>,
+    account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    size: usize,
+    seeds: &[&[u8]],
+) -> Result<u8, ProgramError> {
+    assert_program_account(system_program_info, &system_program::id(), "system_program")?;
+    let (address, bump_seed) = Pubkey::find_program_address(seeds, program_id);
+    if address != *account_info.key {
+        return Err(GovernanceError::InvalidAccountAddress.into());
+    }
+    if !account_info.data_is_empty() {
+        return Err(GovernanceError::AccountAlreadyInitialized.into());
+    }
     
-    /// Proposal account
-    Proposal,
+    let rent = Rent::get()?;
+    let bump = [bump_seed];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump);
+    let size = ACCOUNT_DISCRIMINATOR_LEN + size;
     
-    /// Token owner record account
-    TokenOwnerRecord,
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_info.key,
+            account_info.key,
+            rent.minimum_balance(size),
+            size as u64,
+            program_id,
+        ),
+        &[payer_info.clone(), account_info.clone(), system_program_info.clone()],
+        &[&signer_seeds],
+    )?;
     
-    /// Vote record account
-    VoteRecord,
+    Ok(bump_seed)
 }
 
-// Program entrypoint
-entrypoint!(process_instruction);
-
-// Program entrypoint's implementation
-pub fn process_instruction(
+/// Returns the address and bump of the PDA with the given seeds, using the bump stored on its
+/// account instead of searching for the canonical bump
+///
+/// Accounts created before their bump was stored hold 0, which falls back to the search
+pub fn get_pda_address_with_bump(
+    seeds: &[&[u8]],
+    stored_bump_seed: u8,
     program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    instruction_data: &[u8],
+) -> Result<(Pubkey, u8), ProgramError> {
+    if stored_bump_seed == 0 {
+        return Ok(Pubkey::find_program_address(seeds, program_id));
+    }
+    
+    let bump = [stored_bump_seed];
+    let mut seeds_with_bump = seeds.to_vec();
+    seeds_with_bump.push(&bump);
+    let address = Pubkey::create_program_address(&seeds_with_bump, program_id)
+        .map_err(|_| GovernanceError::InvalidAccountAddress)?;
+    Ok((address, stored_bump_seed))
+}
+
+/// Asserts the council member holds the realm's council tokens in the given token account,
+/// council membership being holding council tokens
+fn assert_council_member(
+    realm: &Realm,
+    council_member_info: &AccountInfo,
+    council_token_account_info: &AccountInfo,
 ) -> ProgramResult {
-    let instruction = LeapfrogInstruction::try_from_slice(instruction_data)
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    get_council_token_account(realm, council_member_info, council_token_account_info).map(|_| ())
+}
 
-    match instruction {
-        LeapfrogInstruction::InitializeRealm { 
-            name, 
-            min_community_tokens_to_create_proposal, 
-            community_mint_max_vote_weight_source,
-            use_quadratic_voting,
-        } => {
-            msg!("Instruction: Initialize Realm");
-            process_initialize_realm(
-                program_id,
-                accounts,
-                name,
-                min_community_tokens_to_create_proposal,
-                community_mint_max_vote_weight_source,
-                use_quadratic_voting,
-            )
-        }
-        LeapfrogInstruction::CreateProposal { 
-            name, 
-            description_link, 
-            vote_type, 
-            options, 
-            use_denial_quorum,
-            voting_period_days,
-        } => {
-            msg!("Instruction: Create Proposal");
-            process_create_proposal(
-                program_id,
-                accounts,
-                name,
-                description_link, 
-                vote_type,
-                options,
-                use_denial_quorum,
-                voting_period_days,
-            )
-        }
-        LeapfrogInstruction::CastVote { vote, staked_amount } => {
-            msg!("Instruction: Cast Vote");
-            process_cast_vote(program_id, accounts, vote, staked_amount)
-        }
-        LeapfrogInstruction::ExecuteProposal => {
-            msg!("Instruction: Execute Proposal");
-            process_execute_proposal(program_id, accounts)
-        }
-        LeapfrogInstruction::StakeTokens { amount } => {
-            msg!("Instruction: Stake Tokens");
-            process_stake_tokens(program_id, accounts, amount)
-        }
-        LeapfrogInstruction::UnstakeTokens { amount } => {
-            msg!("Instruction: Unstake Tokens");
-            process_unstake_tokens(program_id, accounts, amount)
-        }
+/// Returns the council member's token account holding the realm's council tokens
+fn get_council_token_account(
+    realm: &Realm,
+    council_member_info: &AccountInfo,
+    council_token_account_info: &AccountInfo,
+) -> Result<TokenAccount, ProgramError> {
+    if *council_token_account_info.owner != spl_token::id() {
+        return Err(GovernanceError::InvalidCouncilTokenAccount.into());
+    }
+    let council_token_account = TokenAccount::unpack(&council_token_account_info.data.borrow())
+        .map_err(|_| GovernanceError::InvalidCouncilTokenAccount)?;
+    if council_token_account.owner != *council_member_info.key || council_token_account.amount == 0 {
+        return Err(GovernanceError::InvalidCouncilTokenAccount.into());
     }
+    
+    realm.assert_council_mint(&council_token_account.mint)?;
+    Ok(council_token_account)
 }
 
-/// Process InitializeRealm instruction
-pub fn process_initialize_realm(
+/// Asserts the staking vault is a token account of the realm's community mint owned by the vault authority
+///
+/// The swap reserve of a mint migration backs unswapped deposits and isn't a staking vault
+fn assert_staking_vault(
     program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    name: String,
-    min_community_tokens_to_create_proposal: u64,
-    community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
-    use_quadratic_voting: bool,
+    realm_key: &Pubkey,
+    realm: &Realm,
+    staking_vault_info: &AccountInfo,
 ) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
+    if matches!(realm.mint_migration, Some(migration) if migration.swap_reserve == *staking_vault_info.key) {
+        return Err(GovernanceError::InvalidStakingVault.into());
+    }
     
-    // Extract accounts
-    let funder_info = next_account_info(account_info_iter)?;
-    let realm_info = next_account_info(account_info_iter)?;
-    let community_mint_info = next_account_info(account_info_iter)?;
-    let council_mint_info = next_account_info(account_info_iter).ok();
-    let system_program_info = next_account_info(account_info_iter)?;
+    assert_vault_token_account(program_id, realm_key, realm, staking_vault_info, &realm.community_mint)
+}
 
-    // Check signer
-    if !funder_info.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+/// Asserts the account is a token account of the given mint owned by the realm's vault authority
+fn assert_vault_token_account(
+    program_id: &Pubkey,
+    realm_key: &Pubkey,
+    realm: &Realm,
+    token_account_info: &AccountInfo,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if *token_account_info.owner != spl_token::id() {
+        return Err(GovernanceError::InvalidStakingVault.into());
     }
+    let token_account = TokenAccount::unpack(&token_account_info.data.borrow())?;
+    let (vault_authority, _) = get_pda_address_with_bump(
+        &get_vault_authority_address_seeds(realm_key),
+        realm.vault_authority_bump_seed,
+        program_id,
+    )?;
     
-    // Validate realm account
-    if realm_info.owner != program_id {
-        // Create realm account if it doesn't exist
-        let realm_size = Realm::get_max_size(&name)?;
-        let rent = Rent::get()?;
-        let rent_lamports = rent.minimum_balance(realm_size);
-        
-        // Create account
-        invoke(
-            &system_instruction::create_account(
-                funder_info.key,
-                realm_info.key,
-                rent_lamports,
-                realm_size as u64,
-                program_id,
-            ),
-            &[funder_info.clone(), realm_info.clone(), system_program_info.clone()],
-        )?;
+    if token_account.owner != vault_authority || token_account.mint != *mint {
+        return Err(GovernanceError::InvalidStakingVault.into());
     }
     
-    // Create and save realm data
-    let realm = Realm {
-        account_type: AccountType::Realm,
-        name,
-        community_mint: *community_mint_info.key,
-        council_mint: council_mint_info.map(|info| *info.key),
-        min_community_tokens_to_create_proposal,
-        community_mint_max_vote_weight_source,
-        use_quadratic_voting,
-        reserved: [0; 64],
-    };
+    Ok(())
+}
+
+/// Unpacks a governing token mint, checking the account is an SPL Token mint rather than
+/// an account of another program with the same layout
+fn unpack_mint(mint_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    if *mint_info.owner != spl_token::id() {
+        return Err(GovernanceError::InvalidMintAccount.into());
+    }
     
-    realm.serialize(&mut *realm_info.data.borrow_mut())?;
+    Mint::unpack(&mint_info.data.borrow()).map_err(|_| GovernanceError::InvalidMintAccount.into())
+}
+
+/// Asserts the account signed the instruction, logging its role otherwise
+fn assert_signer(account_info: &AccountInfo, role: &'static str) -> ProgramResult {
+    if !account_info.is_signer {
+        msg!("Missing required signature: {}", role);
+        return Err(ProgramError::MissingRequiredSignature);
+    }
     
     Ok(())
 }
 
-/// Process CreateProposal instruction
-pub fn process_create_proposal(
+/// Asserts the signer is the record's token owner itself
+///
+/// Instructions moving tokens out of the program call this instead of accepting any voter of
+/// the record, as a governance delegate can vote with the owner's stake but never withdraw it
+fn assert_token_owner_signer(
+    token_owner_record: &TokenOwnerRecord,
+    token_owner_info: &AccountInfo,
+) -> ProgramResult {
+    if token_owner_record.governing_token_owner != *token_owner_info.key {
+        msg!("Missing required signature: token_owner");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    Ok(())
+}
+
+/// Asserts the instruction is signed by the realm's bootstrap authority, enough members of a
+/// multi-sig bootstrap authority or, once the realm governance exists, the realm governance PDA
+/// which can only sign through ExecuteProposal
+fn assert_realm_authority(
     program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    name: String,
-    description_link: String,
-    vote_type: VoteType,
-    options: Vec<String>,
-    use_denial_quorum: bool,
-    voting_period_days: u8,
+    realm_key: &Pubkey,
+    realm: &Realm,
+    account_infos: &[AccountInfo],
 ) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
+    let signers: Vec<Pubkey> = account_infos
+        .iter()
+        .filter(|account_info| account_info.is_signer)
+        .map(|account_info| *account_info.key)
+        .collect();
+    if !realm.is_signed_by_authority(program_id, realm_key, &signers) {
+        return Err(GovernanceError::InvalidRealmAuthority.into());
+    }
     
-    // Extract accounts
-    let proposal_owner_info = next_account_info(account_info_iter)?;
-    let proposal_info = next_account_info(account_info_iter)?;
-    let governance_info = next_account_info(account_info_iter)?;
-    let token_owner_record_info = next_account_info(account_info_iter)?;
-    let governance_authority_info = next_account_info(account_info_iter)?;
-    let system_program_info = next_account_info(account_info_iter)?;
+    Ok(())
+}
 
-    // Check signer
-    if !proposal_owner_info.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+/// Checks the realm's config values are within bounds and consistent with each other
+///
+/// Run on the realm as it will be saved by InitializeRealm and every realm config update
+pub fn validate_realm_config(realm: &Realm) -> Result<(), GovernanceError> {
+    if !realm.community_mint_max_vote_weight_source.is_valid() {
+        return Err(GovernanceError::InvalidMaxVoteWeightSource);
+    }
+    if !realm.min_community_tokens_to_create_proposal.is_valid() {
+        return Err(GovernanceError::InvalidProposalThreshold);
+    }
+    if realm.default_sybil_resistance_score > 100 {
+        return Err(GovernanceError::InvalidSybilResistanceScore);
+    }
+    if realm.max_vote_records_per_proposal == 0 {
+        return Err(GovernanceError::InvalidMaxVoteRecords);
+    }
+    if matches!(realm.proposal_creation_fee, Some(fee) if fee.lamports == 0 && fee.deposit_lamports == 0) {
+        return Err(GovernanceError::InvalidProposalCreationFee);
+    }
+    if matches!(
+        realm.lockup_vote_weight,
+        Some(lockup) if lockup.max_lock_seconds == 0 || lockup.min_multiplier_percentage > 100
+    ) {
+        return Err(GovernanceError::InvalidLockupVoteWeight);
+    }
+    if realm.inactivity_threshold_seconds == Some(0) {
+        return Err(GovernanceError::InvalidInactivityThreshold);
+    }
+    if matches!(
+        realm.quadratic_credit_budget,
+        Some(budget) if budget.credits_per_period == 0 || budget.period_seconds == 0
+    ) {
+        return Err(GovernanceError::InvalidQuadraticCreditBudget);
+    }
+    if matches!(realm.early_unstake_fee, Some(fee) if fee.fee_bps == 0 || fee.fee_bps > MAX_BASIS_POINTS) {
+        return Err(GovernanceError::InvalidEarlyUnstakeFee);
+    }
+    if realm.max_vote_weight_per_voter == Some(0) {
+        return Err(GovernanceError::InvalidMaxVoteWeightPerVoter);
+    }
+    if realm.default_voting_period_days == 0 || realm.default_voting_period_days > MAX_VOTING_PERIOD_DAYS {
+        return Err(GovernanceError::InvalidDefaultVotingPeriod);
+    }
+    // A council sharing the community mint would hand council powers to every token holder
+    if realm.council_mint == Some(realm.community_mint) && !realm.allow_shared_council_mint {
+        return Err(GovernanceError::SharedCouncilMintNotAllowed);
+    }
+    let is_valid_authority_set = match realm.authority_set.first() {
+        Some(first_member) => {
+            (2..=MAX_REALM_AUTHORITY_SET).contains(&realm.authority_set.len())
+                && (1..=realm.authority_set.len()).contains(&(realm.authority_threshold as usize))
+                && !has_duplicates(realm.authority_set.iter())
+                && realm.authority == Some(*first_member)
+        }
+        None => realm.authority_threshold == 0,
+    };
+    if !is_valid_authority_set {
+        return Err(GovernanceError::InvalidRealmAuthoritySet);
     }
     
-    // Validate accounts and ensure the proposal creator has enough tokens
-    let token_owner_record = TokenOwnerRecord::deserialize(&token_owner_record_info.data.borrow())?;
+    Ok(())
+}
+
+/// Asserts the governance config values are within range
+fn assert_valid_governance_config(config: &GovernanceConfig) -> ProgramResult {
+    if config.quorum_bps > MAX_BASIS_POINTS
+        || config.vote_threshold_bps == 0
+        || config.vote_threshold_bps > MAX_BASIS_POINTS
+        || config.max_concurrent_proposals == 0
+        || config.required_sponsors as usize > MAX_PROPOSAL_SPONSORS
+        || config.denial_threshold_bps == 0
+        || config.denial_threshold_bps > MAX_BASIS_POINTS
+        || config.conviction_threshold_bps > MAX_BASIS_POINTS
+        || config.rejection_refund_bps > MAX_BASIS_POINTS
+        || matches!(config.min_community_tokens_to_create_proposal, Some(source) if !source.is_valid())
+        || matches!(
+            config.quorum_decay,
+            Some(decay) if decay.step_bps == 0 || decay.floor_bps > MAX_BASIS_POINTS
+        )
+        || matches!(config.sybil_vote_check, Some(check) if check.min_vote_weight_per_voter == 0)
+        || matches!(config.council_approval_quorum_bps, Some(bps) if bps == 0 || bps > MAX_BASIS_POINTS)
+    {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
     
-    // Create proposal account if it doesn't exist
-    if proposal_info.owner != program_id {
-        let proposal_size = Proposal::get_max_size(&name, &description_link, &options)?;
-        let rent = Rent::get()?;
-        let rent_lamports = rent.minimum_balance(proposal_size);
-        
-        // Create account
-        invoke(
-            &system_instruction::create_account(
-                proposal_owner_info.key,
-                proposal_info.key,
-                rent_lamports,
-                proposal_size as u64,
-                program_id,
-            ),
-            &[proposal_owner_info.clone(), proposal_info.clone(), system_program_info.clone()],
-        )?;
+    if config.impact_tiers.len() > MAX_IMPACT_TIERS
+        || config.allowed_target_programs.len() > MAX_ALLOWED_TARGET_PROGRAMS
+        || has_duplicates(config.allowed_target_programs.iter())
+    {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
+    let mut previous_tier = ImpactTier {
+        quorum_bps: config.quorum_bps,
+        vote_threshold_bps: config.vote_threshold_bps,
+    };
+    for tier in &config.impact_tiers {
+        if tier.quorum_bps < previous_tier.quorum_bps
+            || tier.quorum_bps > MAX_BASIS_POINTS
+            || tier.vote_threshold_bps < previous_tier.vote_threshold_bps
+            || tier.vote_threshold_bps > MAX_BASIS_POINTS
+        {
+            return Err(GovernanceError::InvalidGovernanceConfig.into());
+        }
+        previous_tier = *tier;
     }
     
-    // Get the current clock for timestamps
-    let clock = Clock::get()?;
+    Ok(())
+}
+
+/// Returns the factor, with CONVICTION_DECAY_BITS fractional bits, conviction gaps decay by
+/// over the elapsed time, i.e. 2^(-elapsed / half_life)
+///
+/// The fraction of a half-life is resolved to 16 bits, each applying a precomputed root of 1/2
+pub fn get_conviction_decay(elapsed_seconds: u64, half_life_seconds: u64) -> u128 {
+    // 2^(-1 / 2^i) for i in 1..=16, with CONVICTION_DECAY_BITS fractional bits
+    const HALF_ROOTS: [u128; 16] = [
+        3037000500, 3611622603, 3938502376, 4112874773, 4202935003, 4248701965, 4271771996, 4283353945,
+        4289156690, 4292061010, 4293513907, 4294240540, 4294603903, 4294785595, 4294876445, 4294921870,
+    ];
     
-    // Create and save proposal data
-    let mut vote_results = HashMap::new();
-    for i in 0..options.len() {
-        vote_results.insert(i as u8, 0);
+    let half_life_seconds = half_life_seconds.max(1);
+    let halvings = elapsed_seconds / half_life_seconds;
+    if halvings >= CONVICTION_DECAY_BITS as u64 {
+        return 0;
     }
     
-    let proposal = Proposal {
-        account_type: AccountType::Proposal,
-        governance: *governance_info.key,
-        proposal_owner: *proposal_owner_info.key,
-        name,
-        description_link,
-        created_at: clock.unix_timestamp as u64,
-        state: ProposalState::Draft,
-        vote_type,
-        options,
-        use_denial_quorum,
-        voting_starts_at: clock.unix_timestamp as u64,
-        voting_ends_at: (clock.unix_timestamp + (voting_period_days as i64 * 86400)) as u64,
-        vote_results,
-        total_vote_weight: 0,
-        reserved: [0; 64],
-    };
-    
-    proposal.serialize(&mut *proposal_info.data.borrow_mut())?;
+    let mut decay = (1u128 << CONVICTION_DECAY_BITS) >> halvings;
+    let remainder_seconds = (elapsed_seconds % half_life_seconds) as u128;
+    let fraction = (remainder_seconds << HALF_ROOTS.len()) / half_life_seconds as u128;
+    for (i, half_root) in HALF_ROOTS.iter().enumerate() {
+        if fraction & (1 << (HALF_ROOTS.len() - 1 - i)) != 0 {
+            decay = (decay * half_root) >> CONVICTION_DECAY_BITS;
+        }
+    }
+    decay
+}
+
+/// Returns the average stake held over an interval, from the cumulative stake-seconds of a
+/// token owner record read at its start and end
+pub fn get_average_stake(
+    start_stake_seconds: u128,
+    started_at: u64,
+    end_stake_seconds: u128,
+    ended_at: u64,
+) -> Result<u64, ProgramError> {
+    let elapsed_seconds = ended_at.saturating_sub(started_at);
+    if elapsed_seconds == 0 || end_stake_seconds < start_stake_seconds {
+        return Err(GovernanceError::InvalidStakeInterval.into());
+    }
     
-    Ok(())
+    // At most the largest deposit held during the interval, so it fits a u64
+    Ok(((end_stake_seconds - start_stake_seconds) / elapsed_seconds as u128) as u64)
+}
+
+/// Returns the integer square root of a value, rounded down
+pub fn integer_sqrt(value: u64) -> u64 {
+    let value = value as u128;
+    let mut root = value;
+    let mut next = root / 2 + root % 2;
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    }
+    root as u64
+}
+
+/// Returns true if the proposal's tallies meet the quorum basis points of the max vote weight
+///
+/// Abstentions count towards quorum only when the realm allows it, and a proposal without
+/// any counted vote never reaches quorum
+pub fn has_reached_quorum(
+    proposal: &Proposal,
+    max_vote_weight: u64,
+    quorum_bps: u16,
+    abstain_counts_for_quorum: bool,
+) -> bool {
+    let mut quorum_vote_weight = proposal.total_vote_weight as u128;
+    if abstain_counts_for_quorum {
+        quorum_vote_weight += proposal.abstain_vote_weight as u128;
+    }
+    quorum_vote_weight > 0 && reaches_bps(quorum_vote_weight, max_vote_weight, quorum_bps)
+}
+
+/// Returns the basis points of the value, rounded down, with bps capped at MAX_BASIS_POINTS
+///
+/// The product is taken in u128 so it can't overflow, and the result is at most the value
+pub fn apply_bps(value: u64, bps: u16) -> u64 {
+    (value as u128 * bps.min(MAX_BASIS_POINTS) as u128 / MAX_BASIS_POINTS as u128) as u64
+}
+
+/// Returns true if the weight is at least the basis points of the total, compared without
+/// rounding
+fn reaches_bps(weight: u128, total: u64, bps: u16) -> bool {
+    weight * MAX_BASIS_POINTS as u128 >= total as u128 * bps as u128
+}
+
+/// Returns the leaf of the initial voting power snapshot granting the amount to the token owner
+pub fn get_voting_power_claim_leaf(leaf_index: u32, governing_token_owner: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &leaf_index.to_le_bytes(),
+        governing_token_owner.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Returns true if the proof leads from the leaf to the root, hashing sorted pairs of nodes
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed_root = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed_root == *root
+}
+
+/// Returns the current clock, read from the Clock sysvar account when the instruction was
+/// given one and from the runtime otherwise
+///
+/// Passing the sysvar lets time dependent handlers run against a fixed clock, e.g. in unit
+/// tests without a full runtime. Only the runtime can provide an account at the sysvar's
+/// address, so it can't be forged
+pub fn get_clock(accounts: &[AccountInfo]) -> Result<Clock, ProgramError> {
+    match accounts.iter().find(|account_info| sysvar::clock::check_id(account_info.key)) {
+        Some(clock_info) => Clock::from_account_info(clock_info),
+        None => Clock::get(),
+    }
+}
+
+/// Converts a clock unix timestamp, rejecting negative values instead of wrapping them
+pub fn to_u64_timestamp(unix_timestamp: i64) -> Result<u64, ProgramError> {
+    u64::try_from(unix_timestamp).map_err(|_| GovernanceError::ClockError.into())
+}
+
+/// Narrows a token amount or vote weight computed with u128 intermediates, rejecting
+/// values beyond u64 instead of truncating them
+pub fn to_u64_amount(amount: u128) -> Result<u64, ProgramError> {
+    u64::try_from(amount).map_err(|_| GovernanceError::MathOverflow.into())
+}
+
+/// Returns the reserved bytes in use once a migration carves a field of the given size out
+/// of an account's reserved block, failing instead of overrunning the block
+pub fn take_reserved_space(used_reserved: u8, field_size: usize) -> Result<u8, ProgramError> {
+    let used_reserved = (used_reserved as usize)
+        .checked_add(field_size)
+        .filter(|used_reserved| *used_reserved <= ACCOUNT_RESERVED_SIZE)
+        .ok_or(GovernanceError::ReservedSpaceExhausted)?;
+    Ok(used_reserved as u8)
+}
+
+/// Returns the reserved bytes taken by fields of the given sizes, carved out of the reserved
+/// block one migration after another
+pub fn get_used_reserved_space(field_sizes: &[usize]) -> Result<u8, ProgramError> {
+    field_sizes
+        .iter()
+        .try_fold(0, |used_reserved, field_size| take_reserved_space(used_reserved, *field_size))
+}
+
+/// Returns true if any item occurs more than once
+fn has_duplicates<'a, T: PartialEq + 'a>(items: impl Iterator<Item = &'a T> + Clone) -> bool {
+    items
+        .clone()
+        .enumerate()
+        .any(|(i, item)| items.clone().skip(i + 1).any(|other| other == item))
+}
+
+#[cfg(test)]
+mod tests;
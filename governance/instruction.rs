@@ -0,0 +1,1420 @@
+//! Instruction builders for LeapfrogDAO governance clients
+//!
+//! Each builder returns an instruction with the accounts in the order, and with the
+//! signer and writable flags, documented on the matching LeapfrogInstruction variant
+
+use crate::{
+    get_council_vote_record_address, get_delegate_vote_record_address, get_execution_account_metas, get_governance_address,
+    get_proposal_archive_address, get_proposal_bundle_address, get_proposal_certificate_address,
+    get_realm_address,
+    get_proposal_transaction_address,
+    get_token_owner_record_address, get_vault_authority_address, get_vesting_stream_address, get_vote_record_address,
+    get_voting_power_claim_address, GovernanceConfig, InstructionData, LeapfrogInstruction,
+    MintMaxVoteWeightSource, OptionVoteCap, ParameterChange, ProposalThresholdSource, RealmConfigArgs, RealmLifecycle, Vote,
+    VoteType, VoteWeightSource,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// Builds the instruction with the Borsh encoded instruction data
+fn build_instruction(program_id: &Pubkey, accounts: Vec<AccountMeta>, instruction: LeapfrogInstruction) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates InitializeRealm instruction
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_realm(
+    program_id: &Pubkey,
+    // Accounts
+    funder: &Pubkey,
+    community_mint: &Pubkey,
+    council_mint: &Pubkey,
+    // Args
+    name: String,
+    min_community_tokens_to_create_proposal: ProposalThresholdSource,
+    community_mint_max_vote_weight_source: MintMaxVoteWeightSource,
+    use_quadratic_voting: bool,
+    voting_power_merkle_root: Option<[u8; 32]>,
+    nonce: u64,
+    allow_shared_council_mint: bool,
+) -> Instruction {
+    let realm = get_realm_address(program_id, &name, community_mint, nonce);
+
+    let accounts = vec![
+        AccountMeta::new(*funder, true),
+        AccountMeta::new(realm, false),
+        AccountMeta::new_readonly(*community_mint, false),
+        AccountMeta::new_readonly(*council_mint, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::InitializeRealm {
+            name,
+            min_community_tokens_to_create_proposal,
+            community_mint_max_vote_weight_source,
+            use_quadratic_voting,
+            voting_power_merkle_root,
+            nonce,
+            allow_shared_council_mint,
+        },
+    )
+}
+
+/// Creates CreateProposal instruction
+///
+/// The treasury is only passed when the realm charges a proposal creation fee, and the
+/// community mint when the proposal threshold is a supply fraction
+#[allow(clippy::too_many_arguments)]
+pub fn create_proposal(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    proposal_owner: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governance_authority: &Pubkey,
+    treasury: Option<&Pubkey>,
+    community_mint: Option<&Pubkey>,
+    // Args
+    name: String,
+    description_link: String,
+    vote_type: VoteType,
+    options: Vec<String>,
+    use_denial_quorum: bool,
+    voting_period_days: u8,
+    per_voter_option_cap: Option<OptionVoteCap>,
+    impact_tier: Option<u8>,
+    depends_on: Option<Pubkey>,
+    description_hash: Option<[u8; 32]>,
+    parameter_change: Option<ParameterChange>,
+    fallback_option_index: Option<u8>,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, proposal_owner);
+
+    let mut accounts = vec![
+        AccountMeta::new(*proposal_owner, true),
+        AccountMeta::new(*proposal, true),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new_readonly(token_owner_record, false),
+        AccountMeta::new_readonly(*governance_authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*realm, false),
+    ];
+    if let Some(treasury) = treasury {
+        accounts.push(AccountMeta::new(*treasury, false));
+    }
+    if let Some(depends_on) = depends_on {
+        accounts.push(AccountMeta::new_readonly(depends_on, false));
+    }
+    if let Some(community_mint) = community_mint {
+        accounts.push(AccountMeta::new_readonly(*community_mint, false));
+    }
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::CreateProposal {
+            name,
+            description_link,
+            vote_type,
+            options,
+            use_denial_quorum,
+            voting_period_days,
+            per_voter_option_cap,
+            impact_tier,
+            depends_on,
+            description_hash,
+            parameter_change,
+            fallback_option_index,
+        },
+    )
+}
+
+/// Creates CreateVoteRecord instruction
+///
+/// The voter is the token owner, or their governance delegate for the delegate's own vote record
+pub fn create_vote_record(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    voter: &Pubkey,
+) -> Instruction {
+    let token_owner_record =
+        get_token_owner_record_address(program_id, realm, governing_token_mint, governing_token_owner);
+    let vote_record = if voter == governing_token_owner {
+        get_vote_record_address(program_id, proposal, &token_owner_record)
+    } else {
+        get_delegate_vote_record_address(program_id, proposal, &token_owner_record, voter)
+    };
+
+    let accounts = vec![
+        AccountMeta::new(*voter, true),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new_readonly(token_owner_record, false),
+        AccountMeta::new(vote_record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CreateVoteRecord)
+}
+
+/// Creates CastVote instruction
+///
+/// The vote record must have been created with create_vote_record. In realms with a weight
+/// oracle, the oracle co-signs the instruction and its weight is passed as oracle_weight
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    voter: &Pubkey,
+    voter_token_account: &Pubkey,
+    weight_oracle: Option<&Pubkey>,
+    // Args
+    vote: Vote,
+    staked_amount: u64,
+    oracle_weight: Option<u64>,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, voter);
+    let vote_record = get_vote_record_address(program_id, proposal, &token_owner_record);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*voter, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(*voter_token_account, false),
+        AccountMeta::new(vote_record, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governing_token_mint, false),
+    ];
+    if let Some(weight_oracle) = weight_oracle {
+        accounts.push(AccountMeta::new_readonly(*weight_oracle, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CastVote { vote, staked_amount, oracle_weight })
+}
+
+/// Creates CastVote instruction for a governance delegate voting with a token owner's
+/// delegated voting power
+#[allow(clippy::too_many_arguments)]
+pub fn cast_delegated_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    governance_delegate: &Pubkey,
+    voter_token_account: &Pubkey,
+    weight_oracle: Option<&Pubkey>,
+    // Args
+    vote: Vote,
+    staked_amount: u64,
+    oracle_weight: Option<u64>,
+) -> Instruction {
+    let token_owner_record =
+        get_token_owner_record_address(program_id, realm, governing_token_mint, governing_token_owner);
+    let vote_record =
+        get_delegate_vote_record_address(program_id, proposal, &token_owner_record, governance_delegate);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*governance_delegate, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(*voter_token_account, false),
+        AccountMeta::new(vote_record, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governing_token_mint, false),
+    ];
+    if let Some(weight_oracle) = weight_oracle {
+        accounts.push(AccountMeta::new_readonly(*weight_oracle, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CastVote { vote, staked_amount, oracle_weight })
+}
+
+/// Creates ExecuteProposal instruction
+///
+/// The accounts of the transaction's instructions are appended as remaining accounts;
+/// the governance signs for itself so it's never passed as a signer
+#[allow(clippy::too_many_arguments)]
+pub fn execute_proposal(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    token_owner_record: &Pubkey,
+    governance_authority: &Pubkey,
+    depends_on: Option<&Pubkey>,
+    // Args
+    option_index: u8,
+    transaction_index: u8,
+    instructions: &[InstructionData],
+) -> Instruction {
+    let proposal_transaction = get_proposal_transaction_address(program_id, proposal, option_index, transaction_index);
+
+    let mut accounts = vec![
+        AccountMeta::new(*governance_authority, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(*token_owner_record, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(proposal_transaction, false),
+    ];
+    if let Some(depends_on) = depends_on {
+        accounts.push(AccountMeta::new_readonly(*depends_on, false));
+    }
+    accounts.extend(get_execution_account_metas(instructions, governance));
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ExecuteProposal)
+}
+
+/// Creates StakeTokens instruction
+pub fn stake_tokens(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    token_owner: &Pubkey,
+    source_token: &Pubkey,
+    staking_vault: &Pubkey,
+    // Args
+    amount: u64,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, token_owner);
+
+    let accounts = vec![
+        AccountMeta::new(*token_owner, true),
+        AccountMeta::new(*source_token, false),
+        AccountMeta::new(*staking_vault, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::StakeTokens { amount })
+}
+
+/// Creates UnstakeTokens instruction
+pub fn unstake_tokens(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    token_owner: &Pubkey,
+    staking_vault: &Pubkey,
+    destination_token: &Pubkey,
+    // Args
+    amount: u64,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, token_owner);
+    let (vault_authority, _) = get_vault_authority_address(program_id, realm);
+
+    let accounts = vec![
+        AccountMeta::new(*token_owner, true),
+        AccountMeta::new(*staking_vault, false),
+        AccountMeta::new(*destination_token, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(vault_authority, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::UnstakeTokens { amount })
+}
+
+/// Creates CreateGovernance instruction
+///
+/// The realm authority is only passed when creating the governance of the realm itself
+pub fn create_governance(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    funder: &Pubkey,
+    realm_authority: Option<&Pubkey>,
+    realm_co_signers: &[Pubkey],
+    // Args
+    governed_account: Pubkey,
+    config: GovernanceConfig,
+) -> Instruction {
+    let governance = get_governance_address(program_id, realm, &governed_account);
+
+    let mut accounts = vec![
+        AccountMeta::new(*funder, true),
+        AccountMeta::new(governance, false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(realm_authority) = realm_authority {
+        accounts.push(AccountMeta::new_readonly(*realm_authority, true));
+    }
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::CreateGovernance { governed_account, config },
+    )
+}
+
+/// Creates SignOffProposal instruction
+pub fn sign_off_proposal(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    proposal_owner: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposal_owner, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SignOffProposal)
+}
+
+/// Creates FinalizeVote instruction
+///
+/// The payer funds the proposal certificate, and an archive page when the realm has no
+/// archive yet or its latest page is full
+pub fn finalize_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    community_mint: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    latest_archive_page_index: u32,
+) -> Instruction {
+    let proposal_archive = get_proposal_archive_address(program_id, realm, latest_archive_page_index);
+    let next_proposal_archive =
+        get_proposal_archive_address(program_id, realm, latest_archive_page_index.saturating_add(1));
+    let proposal_certificate = get_proposal_certificate_address(program_id, proposal);
+
+    let accounts = vec![
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*community_mint, false),
+        AccountMeta::new(proposal_archive, false),
+        AccountMeta::new(proposal_certificate, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(next_proposal_archive, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::FinalizeVote)
+}
+
+/// Creates InsertProposalTransaction instruction
+///
+/// transaction_index is the option's current transaction count, the position the transaction
+/// is appended at
+pub fn insert_proposal_transaction(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    proposal_owner: &Pubkey,
+    // Args
+    option_index: u8,
+    transaction_index: u8,
+    instructions: Vec<InstructionData>,
+) -> Instruction {
+    let proposal_transaction = get_proposal_transaction_address(program_id, proposal, option_index, transaction_index);
+
+    let accounts = vec![
+        AccountMeta::new(*proposal_owner, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(proposal_transaction, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*governance, false),
+    ];
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::InsertProposalTransaction { option_index, instructions },
+    )
+}
+
+/// Creates SetRealmConfig instruction
+///
+/// The authority is the realm governance once it exists, otherwise the bootstrap authority
+pub fn set_realm_config(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    config_args: RealmConfigArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SetRealmConfig { config_args })
+}
+
+/// Creates SetSybilScore instruction
+pub fn set_sybil_score(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    sybil_oracle: &Pubkey,
+    token_owner_record: &Pubkey,
+    // Args
+    score: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*sybil_oracle, true),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(*token_owner_record, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SetSybilScore { score })
+}
+
+/// Creates SetMaxVoteWeightSource instruction
+pub fn set_max_vote_weight_source(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    max_vote_weight_source: MintMaxVoteWeightSource,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::SetMaxVoteWeightSource { max_vote_weight_source },
+    )
+}
+
+/// Creates ClaimInitialVotingPower instruction
+pub fn claim_initial_voting_power(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    claimant: &Pubkey,
+    // Args
+    leaf_index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, claimant);
+    let voting_power_claim = get_voting_power_claim_address(program_id, realm, leaf_index);
+
+    let accounts = vec![
+        AccountMeta::new(*claimant, true),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new(voting_power_claim, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::ClaimInitialVotingPower { leaf_index, amount, proof },
+    )
+}
+
+/// Creates CreateVestingStream instruction, to be stored as a proposal transaction
+#[allow(clippy::too_many_arguments)]
+pub fn create_vesting_stream(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    treasury: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    recipient: Pubkey,
+    total_amount: u64,
+    start_time: u64,
+    cliff_time: u64,
+    duration_seconds: u64,
+    nonce: u64,
+) -> Instruction {
+    let vesting_stream = get_vesting_stream_address(program_id, governance, &recipient, nonce);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*governance, true),
+        AccountMeta::new(vesting_stream, false),
+        AccountMeta::new_readonly(*treasury, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::CreateVestingStream {
+            recipient,
+            total_amount,
+            start_time,
+            cliff_time,
+            duration_seconds,
+            nonce,
+        },
+    )
+}
+
+/// Creates ClaimVestedFunds instruction
+pub fn claim_vested_funds(
+    program_id: &Pubkey,
+    // Accounts
+    vesting_stream: &Pubkey,
+    governance: &Pubkey,
+    treasury: &Pubkey,
+    recipient: &Pubkey,
+    destination_token: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*recipient, true),
+        AccountMeta::new(*vesting_stream, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*destination_token, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ClaimVestedFunds)
+}
+
+/// Creates PrecheckExecution instruction
+///
+/// The source token accounts are those returned by ProposalTransaction::get_token_transfer_amounts
+pub fn precheck_execution(
+    program_id: &Pubkey,
+    // Accounts
+    proposal: &Pubkey,
+    option_index: u8,
+    transaction_index: u8,
+    source_token_accounts: &[Pubkey],
+) -> Instruction {
+    let proposal_transaction = get_proposal_transaction_address(program_id, proposal, option_index, transaction_index);
+
+    let mut accounts = vec![
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(proposal_transaction, false),
+    ];
+    accounts.extend(
+        source_token_accounts
+            .iter()
+            .map(|source_token| AccountMeta::new_readonly(*source_token, false)),
+    );
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::PrecheckExecution)
+}
+
+/// Creates CancelVestingStream instruction, to be stored as a proposal transaction
+pub fn cancel_vesting_stream(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    vesting_stream: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*governance, true),
+        AccountMeta::new(*vesting_stream, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CancelVestingStream)
+}
+
+/// Creates SetGovernanceDelegate instruction
+pub fn set_governance_delegate(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    // Args
+    new_governance_delegate: Option<Pubkey>,
+    delegated_amount: u64,
+) -> Instruction {
+    let token_owner_record =
+        get_token_owner_record_address(program_id, realm, governing_token_mint, governing_token_owner);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*governing_token_owner, true),
+        AccountMeta::new(token_owner_record, false),
+    ];
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::SetGovernanceDelegate {
+            new_governance_delegate,
+            delegated_amount,
+        },
+    )
+}
+
+/// Creates TallySnapshot instruction
+pub fn tally_snapshot(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    community_mint: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*community_mint, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::TallySnapshot)
+}
+
+/// Creates ExpireProposal instruction
+pub fn expire_proposal(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    proposal: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(*governance, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ExpireProposal)
+}
+
+/// Creates AddSponsor instruction
+pub fn add_sponsor(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    sponsor: &Pubkey,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, sponsor);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*sponsor, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(token_owner_record, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::AddSponsor)
+}
+
+/// Creates FinalizeVotes instruction for the given (proposal, governance) pairs
+pub fn finalize_votes(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    community_mint: &Pubkey,
+    payer: &Pubkey,
+    proposals: &[(Pubkey, Pubkey)],
+    // Args
+    latest_archive_page_index: u32,
+) -> Instruction {
+    let proposal_archive = get_proposal_archive_address(program_id, realm, latest_archive_page_index);
+    let next_proposal_archive =
+        get_proposal_archive_address(program_id, realm, latest_archive_page_index.saturating_add(1));
+
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*community_mint, false),
+        AccountMeta::new(proposal_archive, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(next_proposal_archive, false),
+    ];
+    for (proposal, governance) in proposals {
+        accounts.push(AccountMeta::new(*proposal, false));
+        accounts.push(AccountMeta::new(*governance, false));
+        accounts.push(AccountMeta::new(get_proposal_certificate_address(program_id, proposal), false));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::FinalizeVotes)
+}
+
+/// Creates MigrateGoverningMint instruction
+pub fn migrate_governing_mint(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    new_community_mint: &Pubkey,
+    swap_reserve: &Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new_readonly(*new_community_mint, false),
+        AccountMeta::new_readonly(*swap_reserve, false),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::MigrateGoverningMint)
+}
+
+/// Creates SwapDeposit instruction
+#[allow(clippy::too_many_arguments)]
+pub fn swap_deposit(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    old_community_mint: &Pubkey,
+    new_community_mint: &Pubkey,
+    token_owner: &Pubkey,
+    old_staking_vault: &Pubkey,
+    new_staking_vault: &Pubkey,
+    swap_reserve: &Pubkey,
+) -> Instruction {
+    let old_token_owner_record = get_token_owner_record_address(program_id, realm, old_community_mint, token_owner);
+    let new_token_owner_record = get_token_owner_record_address(program_id, realm, new_community_mint, token_owner);
+    let (vault_authority, _) = get_vault_authority_address(program_id, realm);
+
+    let accounts = vec![
+        AccountMeta::new(*token_owner, true),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new(old_token_owner_record, false),
+        AccountMeta::new(new_token_owner_record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*old_staking_vault, false),
+        AccountMeta::new(*new_staking_vault, false),
+        AccountMeta::new(*swap_reserve, false),
+        AccountMeta::new(*old_community_mint, false),
+        AccountMeta::new_readonly(vault_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SwapDeposit)
+}
+
+/// Creates RecomputeTally instruction for the given votes of the proposal, each a vote record
+/// with the token owner record it was cast with
+pub fn recompute_tally(
+    program_id: &Pubkey,
+    // Accounts
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    votes: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*proposal, false),
+    ];
+    for (vote_record, token_owner_record) in votes {
+        accounts.push(AccountMeta::new(*vote_record, false));
+        accounts.push(AccountMeta::new_readonly(*token_owner_record, false));
+    }
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::RecomputeTally)
+}
+
+/// Creates ReleaseCreationFeeDeposit instruction
+pub fn release_creation_fee_deposit(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    proposal_owner: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*proposal_owner, false),
+        AccountMeta::new(*treasury, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ReleaseCreationFeeDeposit)
+}
+
+/// Creates SetRealmAuthority instruction
+pub fn set_realm_authority(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    authority_set: Vec<Pubkey>,
+    authority_threshold: u8,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::SetRealmAuthority { authority_set, authority_threshold },
+    )
+}
+
+/// Creates CloseRealm instruction
+pub fn close_realm(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    beneficiary: &Pubkey,
+    governances: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new(*beneficiary, false),
+    ];
+    for governance in governances {
+        accounts.push(AccountMeta::new_readonly(*governance, false));
+    }
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CloseRealm)
+}
+
+/// Creates EmergencyUnstake instruction
+pub fn emergency_unstake(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    token_owner: &Pubkey,
+    staking_vault: &Pubkey,
+    destination_token: &Pubkey,
+    // Args
+    amount: u64,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, token_owner);
+    let (vault_authority, _) = get_vault_authority_address(program_id, realm);
+
+    let accounts = vec![
+        AccountMeta::new(*token_owner, true),
+        AccountMeta::new(*staking_vault, false),
+        AccountMeta::new(*destination_token, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(vault_authority, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::EmergencyUnstake { amount })
+}
+
+/// Creates AddProposalCreator instruction
+///
+/// The authority is the realm governance once it exists, otherwise the bootstrap authority
+pub fn add_proposal_creator(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    creator: Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::AddProposalCreator { creator })
+}
+
+/// Creates RemoveProposalCreator instruction
+///
+/// The authority is the realm governance once it exists, otherwise the bootstrap authority
+pub fn remove_proposal_creator(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    creator: Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::RemoveProposalCreator { creator })
+}
+
+/// Creates MigrateGovernance instruction
+pub fn migrate_governance(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::MigrateGovernance)
+}
+
+/// Creates CreateProposalBundle instruction
+///
+/// The proposals are executed in the given order
+pub fn create_proposal_bundle(
+    program_id: &Pubkey,
+    // Accounts
+    creator: &Pubkey,
+    governance: &Pubkey,
+    proposals: &[Pubkey],
+) -> Instruction {
+    let bundle = get_proposal_bundle_address(program_id, &proposals[0]);
+
+    let mut accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(bundle, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(proposals.iter().map(|proposal| AccountMeta::new(*proposal, false)));
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CreateProposalBundle)
+}
+
+/// Creates ExecuteProposalBundle instruction
+///
+/// Each bundled proposal is given in bundle order with its winning option and the
+/// instructions of each of that option's transactions, in order
+pub fn execute_proposal_bundle(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    keeper: &Pubkey,
+    proposals: &[(Pubkey, u8, Vec<Vec<InstructionData>>)],
+) -> Instruction {
+    let bundle = get_proposal_bundle_address(program_id, &proposals[0].0);
+
+    let mut accounts = vec![
+        AccountMeta::new(bundle, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(*keeper, false),
+    ];
+    for (proposal, option_index, transactions) in proposals {
+        accounts.push(AccountMeta::new(*proposal, false));
+        for (transaction_index, instructions) in transactions.iter().enumerate() {
+            let proposal_transaction =
+                get_proposal_transaction_address(program_id, proposal, *option_index, transaction_index as u8);
+            accounts.push(AccountMeta::new(proposal_transaction, false));
+            accounts.extend(get_execution_account_metas(instructions, governance));
+        }
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ExecuteProposalBundle)
+}
+
+/// Creates AddAccountDiscriminator instruction
+pub fn add_account_discriminator(
+    program_id: &Pubkey,
+    // Accounts
+    account: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::AddAccountDiscriminator)
+}
+
+/// Creates VetoProposal instruction
+pub fn veto_proposal(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    council_member: &Pubkey,
+    council_token_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*council_member, true),
+        AccountMeta::new_readonly(*council_token_account, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(*proposal, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::VetoProposal)
+}
+
+/// Creates SetRealmLifecycle instruction
+pub fn set_realm_lifecycle(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    lifecycle: RealmLifecycle,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SetRealmLifecycle { lifecycle })
+}
+
+/// Creates ReviewSybilFlag instruction
+pub fn review_sybil_flag(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    council_member: &Pubkey,
+    council_token_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*council_member, true),
+        AccountMeta::new_readonly(*council_token_account, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*proposal, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ReviewSybilFlag)
+}
+
+/// Creates UnstakeEarly instruction
+#[allow(clippy::too_many_arguments)]
+pub fn unstake_early(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    token_owner: &Pubkey,
+    staking_vault: &Pubkey,
+    destination_token: &Pubkey,
+    early_unstake_fee_treasury: &Pubkey,
+    // Args
+    amount: u64,
+) -> Instruction {
+    let token_owner_record = get_token_owner_record_address(program_id, realm, governing_token_mint, token_owner);
+    let (vault_authority, _) = get_vault_authority_address(program_id, realm);
+
+    let accounts = vec![
+        AccountMeta::new(*token_owner, true),
+        AccountMeta::new(*staking_vault, false),
+        AccountMeta::new(*destination_token, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(vault_authority, false),
+        AccountMeta::new(*early_unstake_fee_treasury, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::UnstakeEarly { amount })
+}
+
+/// Creates SetVoteWeightSource instruction
+pub fn set_vote_weight_source(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    realm_authority: &Pubkey,
+    source_mint: &Pubkey,
+    realm_co_signers: &[Pubkey],
+    // Args
+    vote_weight_source: VoteWeightSource,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new_readonly(*source_mint, false),
+    ];
+    for co_signer in realm_co_signers {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+
+    build_instruction(
+        program_id,
+        accounts,
+        LeapfrogInstruction::SetVoteWeightSource { vote_weight_source },
+    )
+}
+
+/// Creates CastDelegatedVotes instruction casting the vote with the delegated voting power of
+/// each of the token owners
+#[allow(clippy::too_many_arguments)]
+pub fn cast_delegated_votes(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governance_delegate: &Pubkey,
+    governing_token_owners: &[Pubkey],
+    // Args
+    vote: Vote,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*governance_delegate, true),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for governing_token_owner in governing_token_owners {
+        let token_owner_record =
+            get_token_owner_record_address(program_id, realm, governing_token_mint, governing_token_owner);
+        let vote_record =
+            get_delegate_vote_record_address(program_id, proposal, &token_owner_record, governance_delegate);
+        accounts.push(AccountMeta::new(token_owner_record, false));
+        accounts.push(AccountMeta::new(vote_record, false));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CastDelegatedVotes { vote })
+}
+
+/// Creates SimulateExecution instruction
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_execution(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    community_mint: &Pubkey,
+    option_index: u8,
+    transaction_index: u8,
+    source_token_accounts: &[Pubkey],
+) -> Instruction {
+    let proposal_transaction = get_proposal_transaction_address(program_id, proposal, option_index, transaction_index);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new_readonly(proposal_transaction, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*community_mint, false),
+    ];
+    accounts.extend(
+        source_token_accounts
+            .iter()
+            .map(|source_token| AccountMeta::new_readonly(*source_token, false)),
+    );
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::SimulateExecution)
+}
+
+/// Creates CastCouncilVote instruction
+#[allow(clippy::too_many_arguments)]
+pub fn cast_council_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    council_mint: &Pubkey,
+    council_member: &Pubkey,
+    council_token_account: &Pubkey,
+    council_vault: &Pubkey,
+    // Args
+    approve: bool,
+) -> Instruction {
+    let council_vote_record = get_council_vote_record_address(program_id, proposal, council_member);
+
+    let accounts = vec![
+        AccountMeta::new(*council_member, true),
+        AccountMeta::new(*council_token_account, false),
+        AccountMeta::new_readonly(*council_mint, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(council_vote_record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*council_vault, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::CastCouncilVote { approve })
+}
+
+/// Creates RelinquishCouncilVote instruction
+pub fn relinquish_council_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    proposal: &Pubkey,
+    council_member: &Pubkey,
+    council_token_account: &Pubkey,
+    council_vault: &Pubkey,
+) -> Instruction {
+    let council_vote_record = get_council_vote_record_address(program_id, proposal, council_member);
+    let (vault_authority, _) = get_vault_authority_address(program_id, realm);
+
+    let accounts = vec![
+        AccountMeta::new(*council_member, true),
+        AccountMeta::new(*council_token_account, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new(council_vote_record, false),
+        AccountMeta::new(*council_vault, false),
+        AccountMeta::new_readonly(vault_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::RelinquishCouncilVote)
+}
+
+/// Creates RelinquishVote instruction relinquishing the vote the voter cast with the token
+/// owner's record, signed by the token owner or by the voter if it's still the governance delegate
+#[allow(clippy::too_many_arguments)]
+pub fn relinquish_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    voter: &Pubkey,
+    vote_authority: &Pubkey,
+) -> Instruction {
+    let token_owner_record =
+        get_token_owner_record_address(program_id, realm, governing_token_mint, governing_token_owner);
+    let vote_record = if voter == governing_token_owner {
+        get_vote_record_address(program_id, proposal, &token_owner_record)
+    } else {
+        get_delegate_vote_record_address(program_id, proposal, &token_owner_record, voter)
+    };
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*vote_authority, true),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new(vote_record, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governing_token_mint, false),
+    ];
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::RelinquishVote)
+}
+
+/// Creates AutoRelinquishFinalized instruction relinquishing the given votes, each a vote
+/// record with the token owner record it was cast with
+pub fn auto_relinquish_finalized(
+    program_id: &Pubkey,
+    // Accounts
+    proposal: &Pubkey,
+    votes: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*proposal, false)];
+    for (vote_record, token_owner_record) in votes {
+        accounts.push(AccountMeta::new(*vote_record, false));
+        accounts.push(AccountMeta::new(*token_owner_record, false));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::AutoRelinquishFinalized)
+}
+
+/// Creates ApplyParameterChange instruction
+pub fn apply_parameter_change(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    prerequisite_proposal: Option<&Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(*governance, false),
+    ];
+    if let Some(prerequisite_proposal) = prerequisite_proposal {
+        accounts.push(AccountMeta::new_readonly(*prerequisite_proposal, false));
+    }
+
+    build_instruction(program_id, accounts, LeapfrogInstruction::ApplyParameterChange)
+}
@@ -0,0 +1,124 @@
+//! Typed account contexts taking and validating the fixed accounts of an instruction
+
+use crate::{
+    context::{CreateProposalAccounts, StakeTokensAccounts},
+    GovernanceError,
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, system_program};
+
+/// Account passed to a context, owned by its test so account infos can borrow it
+struct TestAccount {
+    key: Pubkey,
+    is_signer: bool,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+impl TestAccount {
+    fn new(is_signer: bool, owner: Pubkey) -> Self {
+        Self { key: Pubkey::new_unique(), is_signer, owner, lamports: 0, data: vec![] }
+    }
+
+    /// An account holding the program itself, as program accounts are passed
+    fn program(program_id: Pubkey) -> Self {
+        Self { key: program_id, ..Self::new(false, Pubkey::default()) }
+    }
+}
+
+/// Borrows the accounts as the account infos an instruction receives
+fn account_infos(accounts: &mut [TestAccount]) -> Vec<AccountInfo<'_>> {
+    accounts
+        .iter_mut()
+        .map(|account| {
+            AccountInfo::new(
+                &account.key,
+                account.is_signer,
+                true,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                false,
+                0,
+            )
+        })
+        .collect()
+}
+
+/// The fixed accounts of CreateProposal, in order, followed by a treasury
+fn create_proposal_accounts() -> Vec<TestAccount> {
+    vec![
+        TestAccount::new(true, system_program::id()),
+        TestAccount::new(true, system_program::id()),
+        TestAccount::new(false, crate::id()),
+        TestAccount::new(false, crate::id()),
+        TestAccount::new(true, system_program::id()),
+        TestAccount::program(system_program::id()),
+        TestAccount::new(false, crate::id()),
+        TestAccount::new(false, spl_token::id()),
+    ]
+}
+
+/// Returns the error taking the CreateProposal context fails with, once the account list is
+/// changed
+fn create_proposal_error(change: impl FnOnce(&mut Vec<TestAccount>)) -> ProgramError {
+    let mut accounts = create_proposal_accounts();
+    change(&mut accounts);
+    let account_infos = account_infos(&mut accounts);
+    CreateProposalAccounts::from_accounts(&crate::id(), &account_infos).err().unwrap()
+}
+
+#[test]
+fn context_names_the_accounts_of_a_correct_list() {
+    let mut accounts = create_proposal_accounts();
+    let keys: Vec<Pubkey> = accounts.iter().map(|account| account.key).collect();
+    let account_infos = account_infos(&mut accounts);
+
+    let ctx = CreateProposalAccounts::from_accounts(&crate::id(), &account_infos).unwrap();
+    assert_eq!(
+        [
+            ctx.proposal_owner.key,
+            ctx.proposal.key,
+            ctx.governance.key,
+            ctx.token_owner_record.key,
+            ctx.governance_authority.key,
+            ctx.system_program.key,
+            ctx.realm.key,
+        ]
+        .map(|key| *key),
+        keys[..7]
+    );
+    // Trailing optional accounts are left to the handler
+    assert_eq!(ctx.remaining.iter().map(|account| *account.key).collect::<Vec<_>>(), keys[7..]);
+}
+
+#[test]
+fn context_rejects_an_incorrect_list_by_role() {
+    assert_eq!(create_proposal_error(|accounts| accounts.truncate(6)), GovernanceError::MissingAccount.into());
+    assert_eq!(create_proposal_error(|accounts| accounts[1].is_signer = false), ProgramError::MissingRequiredSignature);
+    assert_eq!(
+        create_proposal_error(|accounts| accounts[2].owner = system_program::id()),
+        GovernanceError::InvalidAccountOwner.into()
+    );
+    assert_eq!(
+        create_proposal_error(|accounts| accounts[5] = TestAccount::program(spl_token::id())),
+        ProgramError::IncorrectProgramId
+    );
+
+    // Token instructions check the token program they are given
+    let mut accounts = vec![
+        TestAccount::new(true, system_program::id()),
+        TestAccount::new(false, spl_token::id()),
+        TestAccount::new(false, spl_token::id()),
+        TestAccount::new(false, crate::id()),
+        TestAccount::program(spl_token::id()),
+        TestAccount::new(false, crate::id()),
+        TestAccount::program(system_program::id()),
+    ];
+    assert!(StakeTokensAccounts::from_accounts(&crate::id(), &account_infos(&mut accounts)).is_ok());
+    accounts[4] = TestAccount::program(Pubkey::new_unique());
+    assert_eq!(
+        StakeTokensAccounts::from_accounts(&crate::id(), &account_infos(&mut accounts)).err(),
+        Some(ProgramError::IncorrectProgramId)
+    );
+}
@@ -0,0 +1,26 @@
+//! Saving accounts too small to hold their data
+
+use super::program_test::{Account, ProgramTest};
+use crate::{instruction, AccountType, Realm};
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+#[test]
+fn undersized_account_fails_with_the_required_and_actual_size() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+
+    // Shrink the realm to its current data, leaving no room for another proposal creator
+    let realm_data = pt.get_account_data::<Realm>(&realm.address);
+    let mut data = AccountType::Realm.get_discriminator().to_vec();
+    data.extend(realm_data.try_to_vec().unwrap());
+    let data_len = data.len();
+    let lamports = pt.lamports(&realm.address);
+    pt.set_account(realm.address, Account { lamports, data, owner: crate::id(), executable: false });
+
+    let add_creator =
+        instruction::add_proposal_creator(&crate::id(), &realm.address, &realm.authority, &[], Pubkey::new_unique());
+    assert_eq!(pt.process(&add_creator), Err(ProgramError::AccountDataTooSmall));
+    let message = format!("Account data too small: {} needs {} bytes, has {}", realm.address, data_len + 32, data_len);
+    assert!(pt.logs().iter().any(|log| log.ends_with(&message)), "{:?}", pt.logs());
+}
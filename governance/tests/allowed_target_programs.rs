@@ -0,0 +1,56 @@
+//! Governance allowlists of the programs proposal transactions can invoke
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, to_instruction_data},
+};
+use crate::{Governance, GovernanceConfig, GovernanceError};
+use solana_program::{system_instruction, system_program};
+
+#[test]
+fn transactions_can_only_target_allowed_programs() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig { allowed_target_programs: vec![spl_token::id()], ..governance_config() },
+    );
+    let grant_mint = pt.create_mint(6);
+    let treasury = pt.create_token_account(&grant_mint, &governance.address, 1_000);
+    let destination_token = pt.create_token_account(&grant_mint, &token_owner.owner, 0);
+    let token_transfer = to_instruction_data(
+        spl_token::instruction::transfer(&spl_token::id(), &treasury, &destination_token, &governance.address, &[], 1_000)
+            .unwrap(),
+    );
+    let lamport_transfer = to_instruction_data(system_instruction::transfer(&governance.address, &token_owner.owner, 1));
+
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+    assert_eq!(
+        pt.insert_transaction(&governance, &proposal, 0, 0, vec![token_transfer.clone(), lamport_transfer]),
+        Err(GovernanceError::TargetProgramNotAllowed.into())
+    );
+    pt.insert_transaction(&governance, &proposal, 0, 0, vec![token_transfer.clone()]).unwrap();
+
+    // The allowlist is checked again at execution
+    let proposal = pt.with_approved_proposal(&realm, &governance, &token_owner, vec![vec![token_transfer.clone()]]);
+    let mut updated = pt.get_account_data::<Governance>(&governance.address);
+    updated.config.allowed_target_programs = vec![system_program::id()];
+    pt.set_account_data(&governance.address, &updated);
+    assert_eq!(
+        pt.execute_transaction(&governance, &proposal, &token_owner, 0, &[token_transfer]),
+        Err(GovernanceError::TargetProgramNotAllowed.into())
+    );
+}
+
+#[test]
+fn empty_allowlist_allows_any_program() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let lamport_transfer = to_instruction_data(system_instruction::transfer(&governance.address, &token_owner.owner, 1));
+
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+    pt.insert_transaction(&governance, &proposal, 0, 0, vec![lamport_transfer]).unwrap();
+}
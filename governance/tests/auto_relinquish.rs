@@ -0,0 +1,187 @@
+//! Relinquishing the votes of finalized proposals in bulk
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_vote_record_address, instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, RealmConfigArgs,
+    TokenOwnerRecord, Vote, VoteRecord, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::entrypoint::ProgramResult;
+
+#[test]
+fn voter_unstakes_right_after_its_finalized_votes_are_relinquished() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs { auto_relinquish_finalized: true, unstaking_cooldown_seconds: 0, ..realm_config_args() },
+    )
+    .unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let unstake = instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        1_000,
+    );
+
+    // Finalization alone leaves the vote outstanding
+    assert_eq!(pt.process(&unstake), Err(GovernanceError::OutstandingVotes.into()));
+    assert!(pt.get_account_data::<Proposal>(&proposal.address).votes_relinquishable);
+
+    let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+    pt.process(&instruction::auto_relinquish_finalized(
+        &crate::id(),
+        &proposal.address,
+        &[(vote_record, token_owner.token_owner_record)],
+    ))
+    .unwrap();
+
+    assert!(pt.get_account_data::<VoteRecord>(&vote_record).is_relinquished);
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).unrelinquished_votes_count, 0);
+    let stored_proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(stored_proposal.vote_results[&0], 1_000);
+    assert_eq!(stored_proposal.state, ProposalState::Approved);
+
+    // Relinquishing again skips the record
+    pt.process(&instruction::auto_relinquish_finalized(
+        &crate::id(),
+        &proposal.address,
+        &[(vote_record, token_owner.token_owner_record)],
+    ))
+    .unwrap();
+
+    pt.process(&unstake).unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+}
+
+#[test]
+fn votes_finalized_without_the_setting_are_not_bulk_relinquished() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+    assert_eq!(
+        pt.process(&instruction::auto_relinquish_finalized(
+            &crate::id(),
+            &proposal.address,
+            &[(vote_record, token_owner.token_owner_record)],
+        )),
+        Err(GovernanceError::AutoRelinquishNotEnabled.into())
+    );
+    assert!(!pt.get_account_data::<VoteRecord>(&vote_record).is_relinquished);
+}
+
+/// Approves a proposal with an empty transaction, backed by one voter and opposed by another,
+/// under a governance delaying execution by an hour
+fn with_time_locked_proposal(
+    lock_stake_until_execution: bool,
+) -> (ProgramTest, RealmCookie, GovernanceCookie, ProposalCookie, [TokenOwnerCookie; 2]) {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            auto_relinquish_finalized: true,
+            lock_stake_until_execution,
+            unstaking_cooldown_seconds: 0,
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    let backer = pt.with_token_owner(&realm, 700);
+    let opponent = pt.with_token_owner(&realm, 300);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { execution_delay_seconds: 3_600, ..governance_config() });
+    let proposal = pt.with_proposal(&realm, &governance, &backer);
+    pt.insert_transaction(&governance, &proposal, 0, 0, vec![]).unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &backer, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &opponent, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Approved);
+
+    (pt, realm, governance, proposal, [backer, opponent])
+}
+
+/// Relinquishes the voters' votes on the proposal in bulk, then unstakes their whole deposits
+fn relinquish_and_unstake(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    proposal: &ProposalCookie,
+    voters: &[&TokenOwnerCookie],
+) -> Vec<ProgramResult> {
+    let vote_records: Vec<_> = voters
+        .iter()
+        .map(|voter| {
+            let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+            (vote_record, voter.token_owner_record)
+        })
+        .collect();
+    pt.process(&instruction::auto_relinquish_finalized(&crate::id(), &proposal.address, &vote_records)).unwrap();
+
+    voters
+        .iter()
+        .map(|voter| {
+            pt.process(&instruction::unstake_tokens(
+                &crate::id(),
+                &realm.address,
+                &realm.community_mint,
+                &voter.owner,
+                &realm.staking_vault,
+                &voter.token_account,
+                pt.token_owner_deposit(voter),
+            ))
+        })
+        .collect()
+}
+
+#[test]
+fn backing_stake_stays_locked_through_the_execution_time_lock() {
+    let (mut pt, realm, governance, proposal, [backer, opponent]) = with_time_locked_proposal(true);
+
+    // Only the stake behind the winning option is held back
+    let results = relinquish_and_unstake(&mut pt, &realm, &proposal, &[&backer, &opponent]);
+    assert_eq!(results, vec![Err(GovernanceError::OutstandingVotes.into()), Ok(())]);
+    let backer_vote_record = get_vote_record_address(&crate::id(), &proposal.address, &backer.token_owner_record);
+    assert!(!pt.get_account_data::<VoteRecord>(&backer_vote_record).is_relinquished);
+
+    pt.advance_clock(3_600);
+    assert_eq!(
+        relinquish_and_unstake(&mut pt, &realm, &proposal, &[&backer]),
+        vec![Err(GovernanceError::OutstandingVotes.into())]
+    );
+
+    // Execution concludes the proposal and releases the stake
+    pt.execute_transaction(&governance, &proposal, &backer, 0, &[]).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+    assert_eq!(relinquish_and_unstake(&mut pt, &realm, &proposal, &[&backer]), vec![Ok(())]);
+    assert_eq!(pt.token_owner_deposit(&backer), 0);
+}
+
+#[test]
+fn backing_stake_unlocks_at_finalization_without_the_setting() {
+    let (mut pt, realm, _, proposal, [backer, opponent]) = with_time_locked_proposal(false);
+
+    let results = relinquish_and_unstake(&mut pt, &realm, &proposal, &[&backer, &opponent]);
+    assert_eq!(results, vec![Ok(()), Ok(())]);
+    assert_eq!(pt.token_owner_deposit(&backer), 0);
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Approved);
+}
@@ -0,0 +1,83 @@
+//! Quorums and thresholds in basis points, and the migration of governances stored in percentages
+
+use super::{
+    program_test::{Account, ProgramTest},
+    setup::governance_config,
+};
+use crate::{
+    apply_bps, has_reached_quorum, instruction, AccountType, Governance, GovernanceError, ImpactTier, OptionTieBreak,
+    Proposal, QuorumDecay,
+};
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn apply_bps_rounds_down_without_overflowing() {
+    for (value, bps, expected) in [
+        (10_000, 3_333, 3_333),
+        (1_000, 3_333, 333),
+        (999, 10_000, 999),
+        (999, 0, 0),
+        (u64::MAX, 10_000, u64::MAX),
+        (u64::MAX, 5_000, u64::MAX / 2),
+        // Basis points past 100% are capped
+        (1_000, u16::MAX, 1_000),
+    ] {
+        assert_eq!(apply_bps(value, bps), expected, "{} bps of {}", bps, value);
+    }
+}
+
+#[test]
+fn basis_point_quorums_are_finer_than_percentages() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+    let mut proposal = pt.get_account_data::<Proposal>(&proposal.address);
+
+    // A third needs 3,333 of 10,000, where the nearest percentage, 33%, took 3,300
+    for (total_vote_weight, reached) in [(3_300, false), (3_332, false), (3_333, true)] {
+        proposal.total_vote_weight = total_vote_weight;
+        assert_eq!(has_reached_quorum(&proposal, 10_000, 3_333, false), reached, "{}", total_vote_weight);
+    }
+    proposal.total_vote_weight = 9_999;
+    assert!(!has_reached_quorum(&proposal, 10_000, 10_000, false));
+    proposal.total_vote_weight = 10_000;
+    assert!(has_reached_quorum(&proposal, 10_000, 10_000, false));
+}
+
+#[test]
+fn migrate_governance_converts_percentages_to_basis_points() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let governance = pt.with_governance(&realm, governance_config());
+    let payer = pt.create_wallet();
+    let current = pt.get_account_data::<Governance>(&governance.address);
+
+    // Rewrite the governance in the percentage layout
+    let mut data = AccountType::Governance.get_discriminator().to_vec();
+    data.extend((AccountType::Governance, current.realm, current.governed_account).try_to_vec().unwrap());
+    data.extend((20u8, 60u8, 1u8, 1u8, None::<u64>, false, None::<u64>, 0u8, 0u64).try_to_vec().unwrap());
+    data.extend((vec![(33u8, 67u8)], 50u8, 0u64, 0u8, 0u16, OptionTieBreak::LowestIndex).try_to_vec().unwrap());
+    data.extend((false, Vec::<Pubkey>::new(), Some((5u8, 10u8))).try_to_vec().unwrap());
+    data.extend((0u8, 0u32, 0u8, [0u8; 64]).try_to_vec().unwrap());
+    let lamports = pt.lamports(&governance.address);
+    pt.set_account(governance.address, Account { lamports, data, owner: crate::id(), executable: false });
+
+    pt.process(&instruction::migrate_governance(&crate::id(), &governance.address, &payer)).unwrap();
+
+    let migrated = pt.get_account_data::<Governance>(&governance.address);
+    assert_eq!(migrated.account_type, AccountType::GovernanceV2);
+    assert_eq!((migrated.realm, migrated.governed_account), (current.realm, current.governed_account));
+    assert_eq!((migrated.config.quorum_bps, migrated.config.vote_threshold_bps), (2_000, 6_000));
+    assert_eq!(migrated.config.impact_tiers, vec![ImpactTier { quorum_bps: 3_300, vote_threshold_bps: 6_700 }]);
+    assert_eq!(migrated.config.denial_threshold_bps, 5_000);
+    assert_eq!(migrated.config.quorum_decay, Some(QuorumDecay { step_bps: 500, floor_bps: 1_000 }));
+
+    // Migrated governances are in the current layout already
+    assert_eq!(
+        pt.process(&instruction::migrate_governance(&crate::id(), &governance.address, &payer)),
+        Err(GovernanceError::InvalidAccountType.into())
+    );
+}
@@ -0,0 +1,74 @@
+//! Canonical bumps stored on PDA accounts and the operations using them
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args},
+};
+use crate::{
+    get_pda_address_with_bump, get_realm_address_seeds, get_vault_authority_address, get_vault_authority_address_seeds,
+    get_vote_record_address, get_vote_record_address_seeds, instruction, GovernanceError, Realm, RealmConfigArgs, Vote,
+    VoteRecord,
+};
+use solana_program::{keccak, pubkey::Pubkey};
+
+#[test]
+fn stored_bumps_are_the_canonical_bumps() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    let stored_realm = pt.get_account_data::<Realm>(&realm.address);
+    let (name_hash, nonce) = (keccak::hash(b"Leapfrog").to_bytes(), 0u64.to_le_bytes());
+    let realm_seeds = get_realm_address_seeds(&name_hash, &realm.community_mint, &nonce);
+    assert_eq!(Pubkey::find_program_address(&realm_seeds, &crate::id()), (realm.address, stored_realm.bump_seed));
+    let (_, vault_authority_bump_seed) = get_vault_authority_address(&crate::id(), &realm.address);
+    assert_eq!(stored_realm.vault_authority_bump_seed, vault_authority_bump_seed);
+
+    let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+    let vote_record_seeds = get_vote_record_address_seeds(&proposal.address, &token_owner.token_owner_record);
+    assert_eq!(
+        Pubkey::find_program_address(&vote_record_seeds, &crate::id()),
+        (vote_record, pt.get_account_data::<VoteRecord>(&vote_record).bump_seed)
+    );
+}
+
+#[test]
+fn unstaking_signs_with_the_stored_vault_authority_bump() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let unstake = |amount| {
+        instruction::unstake_tokens(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            &realm.staking_vault,
+            &token_owner.token_account,
+            amount,
+        )
+    };
+    pt.process(&unstake(100)).unwrap();
+
+    // Any other bump derives another address, which doesn't own the staking vault, or none at all
+    let mut stored_realm = pt.get_account_data::<Realm>(&realm.address);
+    stored_realm.vault_authority_bump_seed = stored_realm.vault_authority_bump_seed.wrapping_sub(1);
+    pt.set_account_data(&realm.address, &stored_realm);
+    let seeds = get_vault_authority_address_seeds(&realm.address);
+    let wrong_bump_address = get_pda_address_with_bump(&seeds, stored_realm.vault_authority_bump_seed, &crate::id());
+    let wrong_bump_error = match wrong_bump_address {
+        Ok(_) => GovernanceError::InvalidStakingVault,
+        Err(_) => GovernanceError::InvalidAccountAddress,
+    };
+    assert_eq!(pt.process(&unstake(100)), Err(wrong_bump_error.into()));
+
+    // Realms created before bumps were stored fall back to searching for it
+    stored_realm.vault_authority_bump_seed = 0;
+    pt.set_account_data(&realm.address, &stored_realm);
+    pt.process(&unstake(100)).unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 800);
+}
@@ -0,0 +1,426 @@
+//! Validation and tallying of cast votes
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, realm_config_args, ProposalArgs, TokenOwnerCookie},
+};
+use crate::{
+    get_vote_record_address, instruction, GovernanceError, OptionCapExcessPolicy, OptionVoteCap, Proposal, ProposalState,
+    RealmConfigArgs, Vote, VoteCast, VoteRecord, VoteType, VoteWeightCurve, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Arguments of a weighted proposal over three options, capping each voter's weight per
+/// option at 500
+fn capped_weighted_proposal_args(excess_policy: OptionCapExcessPolicy) -> ProposalArgs {
+    ProposalArgs {
+        vote_type: VoteType::Weighted,
+        options: vec!["Alpha".to_string(), "Beta".to_string(), "Gamma".to_string()],
+        per_voter_option_cap: Some(OptionVoteCap { max_weight_per_option: 500, excess_policy }),
+        ..proposal_args()
+    }
+}
+
+#[test]
+fn option_weight_above_the_cap_is_discarded() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &voter,
+        capped_weighted_proposal_args(OptionCapExcessPolicy::Discard),
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::Weighted { weights: vec![(0, 80), (1, 20)] }).unwrap();
+
+    let vote_record = pt.get_account_data::<VoteRecord>(&get_vote_record_address(
+        &crate::id(),
+        &proposal.address,
+        &voter.token_owner_record,
+    ));
+    assert_eq!(vote_record.option_vote_weights, vec![(0, 500), (1, 200)]);
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.vote_results.get(&0), Some(&500));
+    assert_eq!(proposal.vote_results.get(&1), Some(&200));
+}
+
+#[test]
+fn option_weight_above_the_cap_is_redistributed_to_the_voters_other_options() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &voter,
+        capped_weighted_proposal_args(OptionCapExcessPolicy::Redistribute),
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::Weighted { weights: vec![(0, 90), (2, 10)] }).unwrap();
+
+    // Gamma takes the excess up to the cap, the rest is dropped and Beta wasn't voted for
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.vote_results.get(&0), Some(&500));
+    assert_eq!(proposal.vote_results.get(&1).copied().unwrap_or(0), 0);
+    assert_eq!(proposal.vote_results.get(&2), Some(&500));
+}
+
+#[test]
+fn votes_past_the_vote_record_cap_are_rejected_until_it_is_raised() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { max_vote_records_per_proposal: 2, ..realm_config_args() }).unwrap();
+    let voters: Vec<_> = (0..4).map(|_| pt.with_token_owner(&realm, 1_000)).collect();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voters[0]);
+
+    for voter in &voters[..2] {
+        pt.cast_vote(&realm, &governance, &proposal, voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+    assert_eq!(
+        pt.cast_vote(&realm, &governance, &proposal, &voters[2], Vote::SingleChoice { option_index: 1 }),
+        Err(GovernanceError::TooManyVoteRecords.into())
+    );
+
+    pt.set_realm_config(&realm, RealmConfigArgs { max_vote_records_per_proposal: 3, ..realm_config_args() }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voters[3], Vote::SingleChoice { option_index: 1 }).unwrap();
+
+    // Finalizing reads the proposal's tallies without any of the vote records
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.vote_record_count, 3);
+    assert_eq!(proposal.vote_results.get(&0), Some(&2_000));
+    assert_eq!(proposal.vote_results.get(&1), Some(&1_000));
+    assert_eq!(proposal.state, ProposalState::Approved);
+}
+
+#[test]
+fn records_younger_than_the_minimum_age_cannot_vote() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { min_record_age_slots: 3, ..realm_config_args() }).unwrap();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let cast_vote = instruction::cast_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.token_account,
+        None,
+        Vote::SingleChoice { option_index: 0 },
+        1_000,
+        None,
+    );
+
+    // Voting in the slots right after the deposit is rejected
+    for _ in 0..3 {
+        assert_eq!(pt.process(&cast_vote), Err(GovernanceError::TokenOwnerRecordTooNew.into()));
+        pt.advance_clock(1);
+    }
+    pt.process(&cast_vote).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).total_vote_weight, 1_000);
+}
+
+#[test]
+fn stake_added_to_an_aged_record_must_age_before_voting() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { min_record_age_slots: 3, ..realm_config_args() }).unwrap();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    for _ in 0..3 {
+        pt.advance_clock(1);
+    }
+
+    // Topping the old record up right before voting restarts its age
+    let token_account = pt.create_token_account(&realm.community_mint, &voter.owner, 5_000);
+    pt.process(&instruction::stake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &voter.owner,
+        &token_account,
+        &realm.staking_vault,
+        5_000,
+    ))
+    .unwrap();
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let cast_vote = instruction::cast_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.token_account,
+        None,
+        Vote::SingleChoice { option_index: 0 },
+        6_000,
+        None,
+    );
+    for _ in 0..3 {
+        assert_eq!(pt.process(&cast_vote), Err(GovernanceError::TokenOwnerRecordTooNew.into()));
+        pt.advance_clock(1);
+    }
+    pt.process(&cast_vote).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).total_vote_weight, 6_000);
+}
+
+#[test]
+fn votes_carrying_no_weight_are_rejected() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { normalize_vote_weight_to_decimals: true, ..realm_config_args() })
+        .unwrap();
+    // Weights are computed in whole tokens of the 6 decimal community mint
+    let voter = pt.with_token_owner(&realm, 2_000_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let cast_vote = |staked_amount| {
+        instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.token_account,
+            None,
+            Vote::SingleChoice { option_index: 0 },
+            staked_amount,
+            None,
+        )
+    };
+
+    // Neither a zero stake nor one short of a whole token adds a voter to the proposal
+    for staked_amount in [0, 999_999] {
+        assert_eq!(pt.process(&cast_vote(staked_amount)), Err(GovernanceError::ZeroStakeVote.into()));
+    }
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).vote_record_count, 0);
+
+    pt.process(&cast_vote(1_000_000)).unwrap();
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.vote_record_count, 1);
+    assert_eq!(proposal.total_vote_weight, 1);
+}
+
+#[test]
+fn votes_are_cast_into_the_voters_own_precreated_record() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let other_voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let cast_vote = |voter: &TokenOwnerCookie| {
+        instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.token_account,
+            None,
+            Vote::SingleChoice { option_index: 0 },
+            1_000,
+            None,
+        )
+    };
+
+    assert_eq!(pt.process(&cast_vote(&voter)), Err(GovernanceError::VoteRecordNotCreated.into()));
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let vote_record_address = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+
+    // The other voter can't cast into a record created for someone else
+    let mut cast_into_other_record = cast_vote(&other_voter);
+    let other_vote_record_address =
+        get_vote_record_address(&crate::id(), &proposal.address, &other_voter.token_owner_record);
+    for account in cast_into_other_record.accounts.iter_mut() {
+        if account.pubkey == other_vote_record_address {
+            account.pubkey = vote_record_address;
+        }
+    }
+    assert_eq!(pt.process(&cast_into_other_record), Err(GovernanceError::VoteRecordMismatch.into()));
+
+    pt.process(&cast_vote(&voter)).unwrap();
+    let vote_record = pt.get_account_data::<VoteRecord>(&vote_record_address);
+    assert_eq!(vote_record.governing_token_owner, voter.owner);
+    assert_eq!(vote_record.vote_weight, 1_000);
+    assert_eq!(pt.process(&cast_vote(&voter)), Err(GovernanceError::VoteAlreadyCast.into()));
+}
+
+#[test]
+fn vote_cast_event_and_record_expose_the_same_stake_weight_curve_and_time() {
+    for (use_quadratic_voting, vote_weight, vote_weight_curve) in
+        [(false, 1_000_000, VoteWeightCurve::Linear), (true, 1_000, VoteWeightCurve::Quadratic)]
+    {
+        let mut pt = ProgramTest::new();
+        let realm = if use_quadratic_voting { pt.with_quadratic_realm() } else { pt.with_realm() };
+        let voter = pt.with_token_owner(&realm, 1_000_000);
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+        pt.advance_clock(60);
+        pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+        let event = pt.last_event::<VoteCast>();
+        let vote_record_address = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+        let vote_record = pt.get_account_data::<VoteRecord>(&vote_record_address);
+        let cast_at = pt.clock().unix_timestamp as u64;
+        assert_eq!(
+            (event.stake_amount, event.vote_weight, event.vote_weight_curve, event.cast_at),
+            (1_000_000, vote_weight, vote_weight_curve, cast_at)
+        );
+        assert_eq!(
+            (vote_record.stake_amount, vote_record.vote_weight, vote_record.vote_weight_curve, vote_record.cast_at),
+            (event.stake_amount, event.vote_weight, event.vote_weight_curve, event.cast_at)
+        );
+        assert_eq!((event.proposal, event.governing_token_owner), (proposal.address, voter.owner));
+    }
+}
+
+#[test]
+fn realm_weight_oracle_vouches_for_the_voting_power() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let oracle = pt.create_wallet();
+    pt.set_realm_config(&realm, RealmConfigArgs { weight_oracle: Some(oracle), ..realm_config_args() }).unwrap();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let cast_vote = |weight_oracle: &Pubkey, staked_amount, oracle_weight| {
+        instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.token_account,
+            Some(weight_oracle),
+            Vote::SingleChoice { option_index: 0 },
+            staked_amount,
+            oracle_weight,
+        )
+    };
+
+    // The oracle must sign the weight it vouches for
+    let mut unsigned = cast_vote(&oracle, 5_000, Some(5_000));
+    unsigned.accounts.last_mut().unwrap().is_signer = false;
+    assert_eq!(pt.process(&unsigned), Err(ProgramError::MissingRequiredSignature));
+    assert_eq!(
+        pt.process(&cast_vote(&Pubkey::new_unique(), 5_000, Some(5_000))),
+        Err(GovernanceError::InvalidWeightOracle.into())
+    );
+    assert_eq!(pt.process(&cast_vote(&oracle, 1_000, None)), Err(GovernanceError::InvalidOracleWeight.into()));
+
+    // The oracle weight replaces the 1,000 token deposit
+    assert_eq!(pt.process(&cast_vote(&oracle, 5_001, Some(5_000))), Err(GovernanceError::InsufficientStake.into()));
+    pt.process(&cast_vote(&oracle, 5_000, Some(5_000))).unwrap();
+    let vote_record_address = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+    assert_eq!(pt.get_account_data::<VoteRecord>(&vote_record_address).vote_weight, 5_000);
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).vote_results.get(&0), Some(&5_000));
+}
+
+#[test]
+fn whale_vote_weight_is_capped_per_voter() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { max_vote_weight_per_voter: Some(500), ..realm_config_args() })
+        .unwrap();
+    let whale = pt.with_token_owner(&realm, 2_000);
+    let voter = pt.with_token_owner(&realm, 300);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &whale);
+    pt.cast_vote(&realm, &governance, &proposal, &whale, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+
+    let votes: Vec<_> = [&whale, &voter]
+        .iter()
+        .map(|voter| {
+            let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+            (vote_record, voter.token_owner_record)
+        })
+        .collect();
+    for ((vote_record, _), stake_amount, vote_weight) in [(votes[0], 2_000, 500), (votes[1], 300, 300)] {
+        let vote_record = pt.get_account_data::<VoteRecord>(&vote_record);
+        assert_eq!((vote_record.stake_amount, vote_record.vote_weight), (stake_amount, vote_weight));
+    }
+    let tallied = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!((tallied.vote_results.get(&0), tallied.vote_results.get(&1)), (Some(&500), Some(&300)));
+    assert_eq!(tallied.total_vote_weight, 800);
+
+    // A tally rebuilt from the vote records keeps the cap
+    let mut uncapped = tallied.clone();
+    uncapped.vote_results.insert(0, 2_000);
+    uncapped.total_vote_weight = 2_300;
+    pt.set_account_data(&proposal.address, &uncapped);
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.process(&instruction::recompute_tally(
+        &crate::id(),
+        &realm.authority,
+        &[],
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &votes,
+    ))
+    .unwrap();
+    let recomputed = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!((recomputed.vote_results, recomputed.total_vote_weight), (tallied.vote_results, 800));
+}
@@ -0,0 +1,120 @@
+//! Time dependent handlers run against a Clock sysvar passed to them
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{instruction, GovernanceError, Proposal, Vote, DEFAULT_UNSTAKING_COOLDOWN_SECONDS};
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+/// Creates the voter's vote record and returns their CastVote instruction for the whole deposit
+fn prepare_vote(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+    voter: &TokenOwnerCookie,
+) -> Instruction {
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+
+    instruction::cast_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.token_account,
+        None,
+        Vote::SingleChoice { option_index: 0 },
+        pt.token_owner_deposit(voter),
+        None,
+    )
+}
+
+fn with_clock_sysvar(mut instruction: Instruction) -> Instruction {
+    instruction.accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    instruction
+}
+
+#[test]
+fn voting_window_closes_at_the_injected_clock() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let late_voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let voting_ends_at = pt.get_account_data::<Proposal>(&proposal.address).voting_ends_at;
+    let cast_vote_ix = prepare_vote(&mut pt, &realm, &governance, &proposal, &voter);
+    let late_cast_vote_ix = prepare_vote(&mut pt, &realm, &governance, &proposal, &late_voter);
+
+    // The runtime clock stays at the start of voting, only the passed sysvar moves
+    pt.inject_clock(Clock { unix_timestamp: voting_ends_at as i64 - 1, ..pt.clock() });
+    pt.process(&with_clock_sysvar(cast_vote_ix)).unwrap();
+
+    pt.inject_clock(Clock { unix_timestamp: voting_ends_at as i64, ..pt.clock() });
+    assert_eq!(
+        pt.process(&with_clock_sysvar(late_cast_vote_ix.clone())),
+        Err(GovernanceError::VotingPeriodEnded.into())
+    );
+
+    // Without the sysvar the runtime clock still has voting open
+    pt.process(&late_cast_vote_ix).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).total_vote_weight, 2_000);
+}
+
+#[test]
+fn unstaking_cooldown_ends_at_the_passed_clock_sysvar() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let unstake_ix = instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        1_000,
+    );
+    let cooldown_ends_at = pt.clock().unix_timestamp + DEFAULT_UNSTAKING_COOLDOWN_SECONDS as i64;
+
+    pt.inject_clock(Clock { unix_timestamp: cooldown_ends_at - 1, ..pt.clock() });
+    assert_eq!(pt.process(&unstake_ix), Err(GovernanceError::UnstakingCooldownActive.into()));
+
+    pt.inject_clock(Clock { unix_timestamp: cooldown_ends_at, ..pt.clock() });
+    pt.process(&unstake_ix).unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+}
+
+#[test]
+fn negative_clock_is_rejected_instead_of_wrapping() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    let create_proposal_ix =
+        with_clock_sysvar(pt.create_proposal_instruction(&realm, &governance, &token_owner, &proposal, proposal_args()));
+
+    pt.inject_clock(Clock { unix_timestamp: -1, ..pt.clock() });
+    assert_eq!(pt.process(&create_proposal_ix), Err(GovernanceError::ClockError.into()));
+
+    pt.inject_clock(pt.clock());
+    pt.process(&create_proposal_ix).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).created_at, pt.clock().unix_timestamp as u64);
+}
@@ -0,0 +1,58 @@
+//! Proposals whose governance account was closed or reassigned while they were live
+
+use super::{
+    program_test::{Account, ProgramTest},
+    setup::{governance_config, GovernanceCookie},
+};
+use crate::{GovernanceError, Vote, DEFAULT_VOTING_PERIOD_DAYS};
+use solana_program::system_program;
+
+/// Closes the governance account, returning its lamports to the system program
+fn close(pt: &mut ProgramTest, governance: &GovernanceCookie) {
+    pt.set_account(
+        governance.address,
+        Account { lamports: 0, data: vec![], owner: system_program::id(), executable: false },
+    );
+}
+
+/// Wipes the governance account's data, leaving it owned by the program
+fn wipe(pt: &mut ProgramTest, governance: &GovernanceCookie) {
+    let lamports = pt.lamports(&governance.address);
+    pt.set_account(governance.address, Account { lamports, data: vec![0; 64], owner: crate::id(), executable: false });
+}
+
+#[test]
+fn voting_on_and_finalizing_a_proposal_of_a_closed_governance_fail_cleanly() {
+    for remove in [close, wipe] {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let voter = pt.with_token_owner(&realm, 1_000);
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+        remove(&mut pt, &governance);
+
+        assert_eq!(
+            pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }),
+            Err(GovernanceError::GovernanceClosed.into())
+        );
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        assert_eq!(pt.finalize_vote(&realm, &governance, &proposal), Err(GovernanceError::GovernanceClosed.into()));
+    }
+}
+
+#[test]
+fn executing_a_proposal_of_a_closed_governance_fails_cleanly() {
+    for remove in [close, wipe] {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let voter = pt.with_token_owner(&realm, 1_000);
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_approved_proposal(&realm, &governance, &voter, vec![vec![]]);
+        remove(&mut pt, &governance);
+
+        assert_eq!(
+            pt.execute_transaction(&governance, &proposal, &voter, 0, &[]),
+            Err(GovernanceError::GovernanceClosed.into())
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! Checked conversions of clock timestamps and amounts
+
+use crate::{to_u64_amount, to_u64_timestamp, GovernanceError};
+
+#[test]
+fn negative_timestamps_are_rejected() {
+    assert_eq!(to_u64_timestamp(i64::MIN), Err(GovernanceError::ClockError.into()));
+    assert_eq!(to_u64_timestamp(-1), Err(GovernanceError::ClockError.into()));
+    assert_eq!(to_u64_timestamp(0), Ok(0));
+    assert_eq!(to_u64_timestamp(i64::MAX), Ok(i64::MAX as u64));
+}
+
+#[test]
+fn amounts_beyond_u64_are_rejected() {
+    assert_eq!(to_u64_amount(0), Ok(0));
+    assert_eq!(to_u64_amount(u64::MAX as u128), Ok(u64::MAX));
+    assert_eq!(to_u64_amount(u64::MAX as u128 + 1), Err(GovernanceError::MathOverflow.into()));
+    assert_eq!(to_u64_amount(u128::MAX), Err(GovernanceError::MathOverflow.into()));
+}
@@ -0,0 +1,74 @@
+//! Conviction proposals passing once sustained support has built enough conviction
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, GovernanceCookie, ProposalArgs, ProposalCookie, RealmCookie},
+};
+use crate::{GovernanceConfig, GovernanceError, Proposal, ProposalState, Vote, VoteType, DEFAULT_VOTING_PERIOD_DAYS};
+
+const HALF_LIFE_SECONDS: u64 = 86_400;
+
+struct ConvictionCookie {
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    proposal: ProposalCookie,
+}
+
+/// Signs off a conviction proposal passing at half of the 1,000 token supply, supported with
+/// the stake as soon as voting opens
+fn with_supported_conviction_proposal(pt: &mut ProgramTest, support: u64) -> ConvictionCookie {
+    let realm = pt.with_realm();
+    let supporter = pt.with_token_owner(&realm, support);
+    let holder = pt.create_wallet();
+    pt.create_token_account(&realm.community_mint, &holder, 1_000 - support);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig {
+            conviction_half_life_seconds: HALF_LIFE_SECONDS,
+            conviction_threshold_bps: 5_000,
+            ..governance_config()
+        },
+    );
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &supporter,
+        ProposalArgs { vote_type: VoteType::Conviction, options: vec!["Support".to_string()], ..proposal_args() },
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &supporter, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    ConvictionCookie { realm, governance, proposal }
+}
+
+#[test]
+fn sustained_support_passes_once_conviction_crosses_the_threshold() {
+    let mut pt = ProgramTest::new();
+    let cookie = with_supported_conviction_proposal(&mut pt, 800);
+    let finalize_vote = |pt: &mut ProgramTest| pt.finalize_vote(&cookie.realm, &cookie.governance, &cookie.proposal);
+
+    // After one half-life the support of 800 has built a conviction of 400, short of 500
+    pt.advance_clock(HALF_LIFE_SECONDS);
+    assert_eq!(finalize_vote(&mut pt), Err(GovernanceError::VotingPeriodNotEnded.into()));
+
+    // Conviction reaches 500 a little under one and a half half-lives in, well before the deadline
+    pt.advance_clock(HALF_LIFE_SECONDS / 2);
+    finalize_vote(&mut pt).unwrap();
+    let proposal = pt.get_account_data::<Proposal>(&cookie.proposal.address);
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert_eq!(proposal.winning_option, Some(0));
+    assert!((500..800).contains(&proposal.conviction));
+    assert!(proposal.conviction_updated_at < proposal.voting_ends_at);
+}
+
+#[test]
+fn support_below_the_threshold_never_builds_enough_conviction() {
+    let mut pt = ProgramTest::new();
+    let cookie = with_supported_conviction_proposal(&mut pt, 499);
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&cookie.realm, &cookie.governance, &cookie.proposal).unwrap();
+    let proposal = pt.get_account_data::<Proposal>(&cookie.proposal.address);
+    assert_eq!(proposal.state, ProposalState::Rejected);
+    assert!(proposal.conviction < 499);
+}
@@ -0,0 +1,200 @@
+//! Council votes of governances requiring council approval
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_council_vote_record_address, get_realm_address, get_vault_authority_address, instruction, GovernanceConfig,
+    GovernanceError, MintMaxVoteWeightSource, Proposal, ProposalState, ProposalThresholdSource, Realm, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS, SUPPLY_FRACTION_BASE,
+};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+/// A governance needing half the council supply to approve, with a proposal the community
+/// votes for
+struct CouncilTest {
+    pt: ProgramTest,
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    proposal: ProposalCookie,
+    council_vault: Pubkey,
+}
+
+struct CouncilMember {
+    wallet: Pubkey,
+    token_account: Pubkey,
+}
+
+impl CouncilTest {
+    fn new() -> Self {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let token_owner = pt.with_token_owner(&realm, 1_000);
+        let governance = pt.with_governance(
+            &realm,
+            GovernanceConfig { council_approval_quorum_bps: Some(5_000), ..governance_config() },
+        );
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+        let (vault_authority, _) = get_vault_authority_address(&crate::id(), &realm.address);
+        let council_vault = pt.create_token_account(&realm.council_mint, &vault_authority, 0);
+
+        let mut test = Self { pt, realm, governance, proposal, council_vault };
+        test.community_vote(&token_owner);
+        test
+    }
+
+    fn community_vote(&mut self, token_owner: &TokenOwnerCookie) {
+        let Self { pt, realm, governance, proposal, .. } = self;
+        pt.cast_vote(realm, governance, proposal, token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+
+    fn with_council_member(&mut self, amount: u64) -> CouncilMember {
+        let wallet = self.pt.create_wallet();
+        let token_account = self.pt.create_token_account(&self.realm.council_mint, &wallet, amount);
+        CouncilMember { wallet, token_account }
+    }
+
+    fn cast_council_vote(&mut self, member: &CouncilMember, approve: bool) -> ProgramResult {
+        self.pt.process(&instruction::cast_council_vote(
+            &crate::id(),
+            &self.realm.address,
+            &self.governance.address,
+            &self.proposal.address,
+            &self.realm.council_mint,
+            &member.wallet,
+            &member.token_account,
+            &self.council_vault,
+            approve,
+        ))
+    }
+
+    fn finalize(&mut self) -> ProposalState {
+        self.pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        self.pt.finalize_vote(&self.realm, &self.governance, &self.proposal).unwrap();
+        self.pt.get_account_data::<Proposal>(&self.proposal.address).state
+    }
+
+    fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        self.pt.get_token_state::<TokenAccount>(token_account).amount
+    }
+}
+
+#[test]
+fn community_approval_without_council_quorum_is_rejected() {
+    let mut test = CouncilTest::new();
+    let member = test.with_council_member(100);
+    test.with_council_member(200);
+
+    // A third of the council supply approves, short of the 50% quorum
+    test.cast_council_vote(&member, true).unwrap();
+    let proposal = test.pt.get_account_data::<Proposal>(&test.proposal.address);
+    assert_eq!(proposal.council_approve_weight, 100);
+    assert!(!proposal.council_approved);
+
+    assert_eq!(test.finalize(), ProposalState::Rejected);
+}
+
+#[test]
+fn community_and_council_approval_is_approved() {
+    let mut test = CouncilTest::new();
+    let member = test.with_council_member(200);
+    let dissenter = test.with_council_member(100);
+
+    test.cast_council_vote(&member, true).unwrap();
+    test.cast_council_vote(&dissenter, false).unwrap();
+    let proposal = test.pt.get_account_data::<Proposal>(&test.proposal.address);
+    assert_eq!((proposal.council_approve_weight, proposal.council_deny_weight), (200, 100));
+    assert!(proposal.council_approved);
+
+    assert_eq!(test.finalize(), ProposalState::Approved);
+}
+
+#[test]
+fn council_tokens_are_locked_until_the_vote_is_relinquished() {
+    let mut test = CouncilTest::new();
+    let member = test.with_council_member(100);
+    test.with_council_member(200);
+
+    // The voted tokens leave the member's wallet, so they can't be moved to another one and
+    // vote again
+    test.cast_council_vote(&member, true).unwrap();
+    assert_eq!(test.token_balance(&member.token_account), 0);
+    assert_eq!(test.token_balance(&test.council_vault), 100);
+
+    let relinquish_ix = instruction::relinquish_council_vote(
+        &crate::id(),
+        &test.realm.address,
+        &test.proposal.address,
+        &member.wallet,
+        &member.token_account,
+        &test.council_vault,
+    );
+    assert_eq!(test.pt.process(&relinquish_ix), Err(GovernanceError::InvalidProposalState.into()));
+
+    test.finalize();
+    test.pt.process(&relinquish_ix).unwrap();
+    assert_eq!(test.token_balance(&member.token_account), 100);
+    assert_eq!(test.token_balance(&test.council_vault), 0);
+    let council_vote_record = get_council_vote_record_address(&crate::id(), &test.proposal.address, &member.wallet);
+    assert_eq!(test.pt.lamports(&council_vote_record), 0);
+}
+
+#[test]
+fn council_sharing_the_community_mint_needs_the_realm_to_allow_it() {
+    let mut pt = ProgramTest::new();
+    let authority = pt.create_wallet();
+    let community_mint = pt.create_mint(6);
+    let initialize_realm = |allow_shared_council_mint| {
+        instruction::initialize_realm(
+            &crate::id(),
+            &authority,
+            &community_mint,
+            &community_mint,
+            "Shared".to_string(),
+            ProposalThresholdSource::Absolute { value: 1 },
+            MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE },
+            false,
+            None,
+            0,
+            allow_shared_council_mint,
+        )
+    };
+    assert_eq!(pt.process(&initialize_realm(false)), Err(GovernanceError::SharedCouncilMintNotAllowed.into()));
+    pt.process(&initialize_realm(true)).unwrap();
+
+    let address = get_realm_address(&crate::id(), "Shared", &community_mint, 0);
+    let (vault_authority, _) = get_vault_authority_address(&crate::id(), &address);
+    let staking_vault = pt.create_token_account(&community_mint, &vault_authority, 0);
+    let council_vault = pt.create_token_account(&community_mint, &vault_authority, 0);
+    let realm = RealmCookie { address, authority, community_mint, council_mint: community_mint, staking_vault };
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt
+        .with_governance(&realm, GovernanceConfig { council_approval_quorum_bps: Some(5_000), ..governance_config() });
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    let member = pt.create_wallet();
+    let member_token_account = pt.create_token_account(&community_mint, &member, 100);
+    let cast_council_vote = instruction::cast_council_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &community_mint,
+        &member,
+        &member_token_account,
+        &council_vault,
+        true,
+    );
+
+    // Council-gated handlers check the flag themselves, not only at realm creation
+    let mut realm_data = pt.get_account_data::<Realm>(&realm.address);
+    realm_data.allow_shared_council_mint = false;
+    pt.set_account_data(&realm.address, &realm_data);
+    assert_eq!(pt.process(&cast_council_vote), Err(GovernanceError::SharedCouncilMintNotAllowed.into()));
+
+    realm_data.allow_shared_council_mint = true;
+    pt.set_account_data(&realm.address, &realm_data);
+    pt.process(&cast_council_vote).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).council_approve_weight, 100);
+}
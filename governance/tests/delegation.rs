@@ -0,0 +1,290 @@
+//! Voting with part of a token owner's voting power delegated to a governance delegate, who
+//! can't move the stake itself nor manage its votes once revoked
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_delegate_vote_record_address, instruction, GovernanceError, Proposal, RealmConfigArgs, TokenOwnerRecord, Vote,
+    VoteRecord, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::{entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+/// Casts the vote of the token owner, or of their delegate, with the staked amount
+fn cast_vote(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+    token_owner: &TokenOwnerCookie,
+    voter: &Pubkey,
+    staked_amount: u64,
+) -> ProgramResult {
+    let vote = Vote::SingleChoice { option_index: 0 };
+    if *voter == token_owner.owner {
+        return pt.process(&instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            voter,
+            &token_owner.token_account,
+            None,
+            vote,
+            staked_amount,
+            None,
+        ));
+    }
+    pt.process(&instruction::cast_delegated_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        voter,
+        &token_owner.token_account,
+        None,
+        vote,
+        staked_amount,
+        None,
+    ))
+}
+
+#[test]
+fn delegate_and_owner_each_vote_with_their_share() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let delegate = pt.create_wallet();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+
+    let set_delegate = |delegated_amount| {
+        instruction::set_governance_delegate(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            Some(delegate),
+            delegated_amount,
+        )
+    };
+    assert_eq!(pt.process(&set_delegate(1_001)), Err(GovernanceError::DelegatedAmountExceedsDeposit.into()));
+    pt.process(&set_delegate(400)).unwrap();
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).delegated_amount, 400);
+
+    for voter in [&delegate, &token_owner.owner] {
+        pt.process(&instruction::create_vote_record(
+            &crate::id(),
+            &realm.address,
+            &proposal.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            voter,
+        ))
+        .unwrap();
+    }
+
+    // Neither can vote with more than their share
+    for (voter, share) in [(&delegate, 400), (&token_owner.owner, 600)] {
+        assert_eq!(
+            cast_vote(&mut pt, &realm, &governance, &proposal, &token_owner, voter, share + 1),
+            Err(GovernanceError::InsufficientStake.into())
+        );
+        cast_vote(&mut pt, &realm, &governance, &proposal, &token_owner, voter, share).unwrap();
+    }
+
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.vote_results.get(&0), Some(&1_000));
+    assert_eq!(proposal.vote_record_count, 2);
+}
+
+#[test]
+fn delegate_cannot_unstake_the_owners_deposit() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let delegate = pt.create_wallet();
+    pt.process(&instruction::set_governance_delegate(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        Some(delegate),
+        1_000,
+    ))
+    .unwrap();
+    let delegate_token = pt.create_token_account(&realm.community_mint, &delegate, 0);
+
+    // The delegate signs in place of the owner for the owner's record, paying out to itself
+    let unstake = |signer: &Pubkey, destination_token: &Pubkey| {
+        let mut unstake = instruction::unstake_tokens(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            &realm.staking_vault,
+            destination_token,
+            1_000,
+        );
+        unstake.accounts[0].pubkey = *signer;
+        unstake
+    };
+    assert_eq!(pt.process(&unstake(&delegate, &delegate_token)), Err(ProgramError::MissingRequiredSignature));
+    assert_eq!(pt.get_token_state::<TokenAccount>(&delegate_token).amount, 0);
+
+    pt.process(&unstake(&token_owner.owner, &token_owner.token_account)).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&token_owner.token_account).amount, 1_000);
+}
+
+#[test]
+fn only_the_owner_relinquishes_the_votes_of_a_revoked_delegate() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let delegate = pt.create_wallet();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.process(&instruction::set_governance_delegate(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        Some(delegate),
+        1_000,
+    ))
+    .unwrap();
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &delegate,
+    ))
+    .unwrap();
+    cast_vote(&mut pt, &realm, &governance, &proposal, &token_owner, &delegate, 1_000).unwrap();
+
+    // The record holds the owner's voting power and remembers the delegate cast it
+    let vote_record_address =
+        get_delegate_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record, &delegate);
+    let vote_record = pt.get_account_data::<VoteRecord>(&vote_record_address);
+    assert_eq!(vote_record.governing_token_owner, token_owner.owner);
+    assert_eq!(vote_record.get_voter_authority(), delegate);
+
+    let set_delegate = |new_governance_delegate| {
+        instruction::set_governance_delegate(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            new_governance_delegate,
+            1_000,
+        )
+    };
+    let relinquish_vote = |vote_authority| {
+        instruction::relinquish_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            &delegate,
+            vote_authority,
+        )
+    };
+
+    // The delegate can only be revoked while the vote is outstanding, which keeps the split it
+    // was weighed against
+    assert_eq!(pt.process(&set_delegate(Some(Pubkey::new_unique()))), Err(GovernanceError::OutstandingVotes.into()));
+    pt.process(&set_delegate(None)).unwrap();
+    let token_owner_record = pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record);
+    assert_eq!((token_owner_record.governance_delegate, token_owner_record.delegated_amount), (None, 1_000));
+    assert_eq!(token_owner_record.get_voter_voting_power(&token_owner.owner), Some(0));
+
+    // Once revoked, the vote is the owner's alone to relinquish
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.process(&relinquish_vote(&delegate)), Err(GovernanceError::NotGovernanceDelegate.into()));
+    pt.process(&relinquish_vote(&token_owner.owner)).unwrap();
+    assert!(pt.get_account_data::<VoteRecord>(&vote_record_address).is_relinquished);
+
+    // Relinquishing the last vote hands the delegated amount back to the owner
+    let token_owner_record = pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record);
+    assert_eq!((token_owner_record.unrelinquished_votes_count, token_owner_record.delegated_amount), (0, 0));
+    pt.process(&instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        1_000,
+    ))
+    .unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+}
+
+#[test]
+fn delegate_votes_for_all_its_delegations_at_once() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let delegate = pt.create_wallet();
+    let delegations = [400, 500, 600];
+    let token_owners: Vec<_> = delegations.iter().map(|_| pt.with_token_owner(&realm, 1_000)).collect();
+    let undelegated_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owners[0]);
+    for (token_owner, delegated_amount) in token_owners.iter().zip(delegations) {
+        pt.process(&instruction::set_governance_delegate(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            Some(delegate),
+            delegated_amount,
+        ))
+        .unwrap();
+    }
+    let cast_delegated_votes = |owners: &[Pubkey]| {
+        instruction::cast_delegated_votes(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &delegate,
+            owners,
+            Vote::SingleChoice { option_index: 0 },
+        )
+    };
+    let mut owners: Vec<Pubkey> = token_owners.iter().map(|token_owner| token_owner.owner).collect();
+
+    // One owner who never delegated to the signer fails the whole batch
+    owners.push(undelegated_owner.owner);
+    assert_eq!(pt.process(&cast_delegated_votes(&owners)), Err(GovernanceError::NotGovernanceDelegate.into()));
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).vote_record_count, 0);
+
+    owners.pop();
+    pt.process(&cast_delegated_votes(&owners)).unwrap();
+    let proposal_data = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal_data.vote_results.get(&0), Some(&1_500));
+    assert_eq!(proposal_data.vote_record_count, 3);
+    for (token_owner, delegated_amount) in token_owners.iter().zip(delegations) {
+        let vote_record = pt.get_account_data::<VoteRecord>(&get_delegate_vote_record_address(
+            &crate::id(),
+            &proposal.address,
+            &token_owner.token_owner_record,
+            &delegate,
+        ));
+        assert_eq!(vote_record.governing_token_owner, token_owner.owner);
+        assert_eq!((vote_record.get_voter_authority(), vote_record.vote_weight), (delegate, delegated_amount));
+    }
+}
@@ -0,0 +1,57 @@
+//! Proposals without options, approved once voting ends unless vetoed with deny votes
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs},
+};
+use crate::{Proposal, ProposalState, Vote, DEFAULT_VOTING_PERIOD_DAYS};
+
+/// Finalizes an approve-by-default proposal of a governance vetoing at 50% of the max vote
+/// weight, with the stakes voting Deny out of a total stake of 1,000
+fn finalize_with_deny_votes(deny_stakes: &[u64]) -> Proposal {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let proposer = pt.with_token_owner(&realm, 1_000 - deny_stakes.iter().sum::<u64>());
+    let deniers: Vec<_> = deny_stakes.iter().map(|stake| pt.with_token_owner(&realm, *stake)).collect();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &proposer,
+        ProposalArgs { options: vec![], use_denial_quorum: true, ..proposal_args() },
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    for denier in &deniers {
+        pt.cast_vote(&realm, &governance, &proposal, denier, Vote::Deny).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    pt.get_account_data::<Proposal>(&proposal.address)
+}
+
+#[test]
+fn unchallenged_proposal_is_approved_by_default() {
+    let proposal = finalize_with_deny_votes(&[]);
+
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert_eq!(proposal.winning_option, Some(0));
+}
+
+#[test]
+fn deny_votes_below_the_denial_threshold_leave_the_proposal_approved() {
+    let proposal = finalize_with_deny_votes(&[300, 199]);
+
+    assert_eq!(proposal.deny_vote_weight, 499);
+    assert_eq!(proposal.state, ProposalState::Approved);
+}
+
+#[test]
+fn deny_votes_reaching_the_denial_threshold_veto_the_proposal() {
+    let proposal = finalize_with_deny_votes(&[300, 200]);
+
+    assert_eq!(proposal.deny_vote_weight, 500);
+    assert_eq!(proposal.state, ProposalState::Rejected);
+    assert_eq!(proposal.winning_option, None);
+}
@@ -0,0 +1,29 @@
+//! Stability of the program's custom error codes
+//!
+//! Clients match on the codes, so new errors are appended to GovernanceError and existing
+//! ones keep their position
+
+use crate::GovernanceError;
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn error_codes_keep_their_position() {
+    let pinned = [
+        (GovernanceError::InvalidAccountOwner, 0),
+        (GovernanceError::InvalidAccountType, 1),
+        (GovernanceError::AccountAlreadyInitialized, 3),
+        (GovernanceError::InvalidProposalState, 11),
+        (GovernanceError::TooManyActiveProposals, 12),
+        (GovernanceError::VoteAlreadyCast, 16),
+        (GovernanceError::MathOverflow, 20),
+        (GovernanceError::InvalidRealmAuthority, 21),
+        (GovernanceError::MissingAccount, 28),
+        (GovernanceError::UnknownInstruction, 122),
+        (GovernanceError::MalformedInstructionData, 123),
+    ];
+
+    for (error, code) in pinned {
+        assert_eq!(error.code(), code, "{:?}", error);
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+    }
+}
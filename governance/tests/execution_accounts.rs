@@ -0,0 +1,61 @@
+//! Checking the accounts passed to ExecuteProposal against the proposal transaction's
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, to_instruction_data},
+};
+use crate::{instruction, GovernanceError, Proposal, ProposalState};
+use solana_program::instruction::AccountMeta;
+use spl_token::state::Account as TokenAccount;
+
+#[test]
+fn execution_accounts_must_match_the_transaction_exactly() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let grant_mint = pt.create_mint(6);
+    let treasury = pt.create_token_account(&grant_mint, &governance.address, 1_000);
+    let grantee = pt.create_wallet();
+    let destination_token = pt.create_token_account(&grant_mint, &grantee, 0);
+    let other_token = pt.create_token_account(&grant_mint, &grantee, 0);
+    let transfer = to_instruction_data(
+        spl_token::instruction::transfer(&spl_token::id(), &treasury, &destination_token, &governance.address, &[], 1_000)
+            .unwrap(),
+    );
+    let proposal = pt.with_approved_proposal(&realm, &governance, &token_owner, vec![vec![transfer.clone()]]);
+
+    let execute = instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &token_owner.token_owner_record,
+        &token_owner.owner,
+        None,
+        0,
+        0,
+        std::slice::from_ref(&transfer),
+    );
+    let destination_index = execute.accounts.iter().position(|meta| meta.pubkey == destination_token).unwrap();
+    let tampered = |tamper: &dyn Fn(&mut AccountMeta)| {
+        let mut execute = execute.clone();
+        tamper(&mut execute.accounts[destination_index]);
+        execute
+    };
+    let mut truncated = execute.clone();
+    truncated.accounts.pop();
+
+    for execute in [
+        tampered(&|destination| destination.is_writable = false),
+        tampered(&|destination| destination.is_signer = true),
+        tampered(&|destination| destination.pubkey = other_token),
+        truncated,
+    ] {
+        assert_eq!(pt.process(&execute), Err(GovernanceError::ExecutionAccountMismatch.into()));
+    }
+    assert_eq!(pt.get_token_state::<TokenAccount>(&treasury).amount, 1_000);
+
+    pt.process(&execute).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&destination_token).amount, 1_000);
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+}
@@ -0,0 +1,140 @@
+//! Fallback options winning proposals no option carries
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs, ProposalCookie},
+};
+use crate::{
+    get_proposal_transaction_address, instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState,
+    ProposalTransaction, Vote, VoteType, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+const FALLBACK_OPTION_INDEX: u8 = 2;
+
+/// Arguments of a proposal choosing between two grants, which does nothing if neither is carried
+fn fallback_proposal_args() -> ProposalArgs {
+    ProposalArgs {
+        options: ["Grant A", "Grant B", "Do nothing"].map(str::to_string).to_vec(),
+        fallback_option_index: Some(FALLBACK_OPTION_INDEX),
+        ..proposal_args()
+    }
+}
+
+#[test]
+fn fallback_wins_on_low_turnout_and_runs_its_transactions() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 50);
+    pt.with_token_owner(&realm, 950);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(&realm, &governance, &voter, fallback_proposal_args());
+    pt.process(&instruction::insert_proposal_transaction(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &proposal.owner,
+        FALLBACK_OPTION_INDEX,
+        0,
+        vec![],
+    ))
+    .unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    // 5% of the supply misses the 10% quorum
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let finalized = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(finalized.state, ProposalState::Approved);
+    assert_eq!(finalized.winning_option, Some(FALLBACK_OPTION_INDEX));
+
+    pt.process(&instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &voter.token_owner_record,
+        &voter.owner,
+        None,
+        FALLBACK_OPTION_INDEX,
+        0,
+        &[],
+    ))
+    .unwrap();
+    let transaction = pt.get_account_data::<ProposalTransaction>(&get_proposal_transaction_address(
+        &crate::id(),
+        &proposal.address,
+        FALLBACK_OPTION_INDEX,
+        0,
+    ));
+    assert_eq!(transaction.executed_at, Some(pt.clock().unix_timestamp as u64));
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+}
+
+#[test]
+fn fallback_without_transactions_is_executed_when_finalized() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 50);
+    pt.with_token_owner(&realm, 950);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(&realm, &governance, &voter, fallback_proposal_args());
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    let finalized = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(finalized.state, ProposalState::Executed);
+    assert_eq!(finalized.winning_option, Some(FALLBACK_OPTION_INDEX));
+}
+
+#[test]
+fn option_clearing_the_bar_wins_over_the_fallback() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 950);
+    pt.with_token_owner(&realm, 50);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(&realm, &governance, &voter, fallback_proposal_args());
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    let finalized = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(finalized.state, ProposalState::Approved);
+    assert_eq!(finalized.winning_option, Some(1));
+}
+
+#[test]
+fn fallback_must_be_one_of_the_options_to_choose_between() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    // Conviction proposals are only taken by governances configured for them
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig {
+            conviction_half_life_seconds: 86_400,
+            conviction_threshold_bps: 5_000,
+            ..governance_config()
+        },
+    );
+
+    for args in [
+        ProposalArgs { fallback_option_index: Some(3), ..fallback_proposal_args() },
+        ProposalArgs {
+            vote_type: VoteType::Conviction,
+            options: vec!["Support".to_string()],
+            fallback_option_index: Some(0),
+            ..proposal_args()
+        },
+    ] {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        assert_eq!(
+            pt.create_proposal_with_args(&realm, &governance, &token_owner, &proposal, args),
+            Err(GovernanceError::InvalidFallbackOption.into())
+        );
+    }
+}
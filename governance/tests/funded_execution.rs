@@ -0,0 +1,118 @@
+//! Execution of approved proposals held back until the treasury covers their transfers, and
+//! simulations previewing those transfers
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, to_instruction_data, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_proposal_transaction_address, instruction, ExecutionSimulation, Governance, GovernanceConfig, GovernanceError,
+    InstructionData, Proposal, ProposalState, TreasuryDelta,
+};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+struct FundedProposalCookie {
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    token_owner: TokenOwnerCookie,
+    proposal: ProposalCookie,
+    treasury: Pubkey,
+    destination_token: Pubkey,
+    transfer: InstructionData,
+}
+
+/// Approves a proposal transferring 1,000 tokens out of a treasury holding the balance, on a
+/// governance requiring funded execution
+fn with_treasury_transfer_proposal(pt: &mut ProgramTest, treasury_balance: u64) -> FundedProposalCookie {
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { require_funded_execution: true, ..governance_config() });
+    let grant_mint = pt.create_mint(6);
+    let treasury = pt.create_token_account(&grant_mint, &governance.address, treasury_balance);
+    let grantee = pt.create_wallet();
+    let destination_token = pt.create_token_account(&grant_mint, &grantee, 0);
+    let transfer = to_instruction_data(
+        spl_token::instruction::transfer(&spl_token::id(), &treasury, &destination_token, &governance.address, &[], 1_000)
+            .unwrap(),
+    );
+    let proposal = pt.with_approved_proposal(&realm, &governance, &token_owner, vec![vec![transfer.clone()]]);
+
+    FundedProposalCookie { realm, governance, token_owner, proposal, treasury, destination_token, transfer }
+}
+
+fn precheck(pt: &mut ProgramTest, cookie: &FundedProposalCookie) -> ProgramResult {
+    pt.process(&instruction::precheck_execution(&crate::id(), &cookie.proposal.address, 0, 0, &[cookie.treasury]))
+}
+
+fn execute(pt: &mut ProgramTest, cookie: &FundedProposalCookie) -> ProgramResult {
+    let transfer = std::slice::from_ref(&cookie.transfer);
+    pt.execute_transaction(&cookie.governance, &cookie.proposal, &cookie.token_owner, 0, transfer)
+}
+
+#[test]
+fn funded_treasury_passes_the_precheck() {
+    let mut pt = ProgramTest::new();
+    let cookie = with_treasury_transfer_proposal(&mut pt, 1_000);
+    assert_eq!(pt.get_account_data::<Proposal>(&cookie.proposal.address).state, ProposalState::UnderfundedApproved);
+
+    precheck(&mut pt, &cookie).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&cookie.proposal.address).state, ProposalState::Approved);
+    execute(&mut pt, &cookie).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&cookie.destination_token).amount, 1_000);
+}
+
+#[test]
+fn underfunded_approval_blocks_execution_until_topped_up() {
+    let mut pt = ProgramTest::new();
+    let cookie = with_treasury_transfer_proposal(&mut pt, 999);
+
+    assert_eq!(precheck(&mut pt, &cookie), Err(GovernanceError::InsufficientTreasuryFunds.into()));
+    assert_eq!(execute(&mut pt, &cookie), Err(GovernanceError::InvalidProposalState.into()));
+    assert_eq!(pt.get_account_data::<Proposal>(&cookie.proposal.address).state, ProposalState::UnderfundedApproved);
+
+    let mut treasury = pt.get_token_state::<TokenAccount>(&cookie.treasury);
+    treasury.amount += 1;
+    pt.set_token_state(cookie.treasury, treasury);
+
+    precheck(&mut pt, &cookie).unwrap();
+    execute(&mut pt, &cookie).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&cookie.destination_token).amount, 1_000);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&cookie.treasury).amount, 0);
+}
+
+#[test]
+fn simulation_logs_the_treasury_delta_without_moving_funds() {
+    let mut pt = ProgramTest::new();
+    let cookie = with_treasury_transfer_proposal(&mut pt, 1_500);
+    let proposal = pt.get_account_data::<Proposal>(&cookie.proposal.address);
+    let governance = pt.get_account_data::<Governance>(&cookie.governance.address);
+
+    pt.process(&instruction::simulate_execution(
+        &crate::id(),
+        &cookie.realm.address,
+        &cookie.governance.address,
+        &cookie.proposal.address,
+        &cookie.realm.community_mint,
+        0,
+        0,
+        &[cookie.treasury],
+    ))
+    .unwrap();
+
+    assert_eq!(
+        pt.last_event::<ExecutionSimulation>(),
+        ExecutionSimulation {
+            proposal_transaction: get_proposal_transaction_address(&crate::id(), &cookie.proposal.address, 0, 0),
+            proposal_state: ProposalState::UnderfundedApproved,
+            executed: false,
+            treasury_deltas: vec![TreasuryDelta { token_account: cookie.treasury, balance: 1_500, amount: 1_000 }],
+            is_funded: true,
+        }
+    );
+    assert_eq!(pt.get_token_state::<TokenAccount>(&cookie.treasury).amount, 1_500);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&cookie.destination_token).amount, 0);
+    assert_eq!(pt.get_account_data::<Proposal>(&cookie.proposal.address), proposal);
+    assert_eq!(pt.get_account_data::<Governance>(&cookie.governance.address), governance);
+}
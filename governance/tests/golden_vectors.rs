@@ -0,0 +1,255 @@
+//! Golden vectors pinning the serialized layout of the program's accounts
+//!
+//! Each test serializes a canonical instance of an account type, discriminator included,
+//! and compares the bytes with the hex fixture checked in under fixtures/. A layout change
+//! fails the test; when the change is intended, regenerate the fixtures with
+//! `UPDATE_GOLDEN_VECTORS=1 cargo test golden_vectors` and review their diff
+
+use crate::{
+    AccountType, GovernanceAccount, LockupVoteWeight, MintMaxVoteWeightSource, MintMigration, OptionCapExcessPolicy,
+    OptionVoteCap, Proposal, ProposalCreationFee, ProposalDeposit, ProposalState, ProposalThresholdSource,
+    QuadraticWeightedVotePolicy, Realm, RealmLifecycle, StakeTranche, TokenOwnerRecord, Vote, VoteRecord, VoteType,
+    VoteWeightCurve, VoteWeightSource,
+};
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+use std::{collections::HashMap, fmt::Debug, fs, path::PathBuf};
+
+/// Bytes per line of a fixture
+const FIXTURE_LINE_LEN: usize = 32;
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(format!("{}.hex", name))
+}
+
+fn to_hex_lines(bytes: &[u8]) -> String {
+    bytes
+        .chunks(FIXTURE_LINE_LEN)
+        .map(|line| line.iter().map(|byte| format!("{:02x}", byte)).collect::<String>() + "\n")
+        .collect()
+}
+
+/// Compares the bytes with the fixture, or rewrites the fixture when UPDATE_GOLDEN_VECTORS is set
+fn assert_golden_vector(name: &str, bytes: &[u8]) {
+    let path = fixture_path(name);
+    let actual = to_hex_lines(bytes);
+    if std::env::var_os("UPDATE_GOLDEN_VECTORS").is_some() {
+        fs::write(&path, &actual).unwrap();
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing fixture {}", path.display()));
+    assert_eq!(actual, expected, "{} doesn't match its golden vector", name);
+}
+
+/// Checks the account's stored bytes against its golden vector and that they read back to
+/// the same account
+fn assert_account_golden_vector<T: GovernanceAccount + BorshSerialize + PartialEq + Debug>(name: &str, account: &T) {
+    let mut bytes = account.account_type().get_discriminator().to_vec();
+    bytes.extend(account.try_to_vec().unwrap());
+    assert_golden_vector(name, &bytes);
+
+    assert_eq!(AccountType::from_discriminator(&bytes), Some(T::ACCOUNT_TYPE));
+    assert_eq!(&T::try_from_slice(&bytes[crate::ACCOUNT_DISCRIMINATOR_LEN..]).unwrap(), account);
+}
+
+fn canonical_realm() -> Realm {
+    Realm {
+        account_type: AccountType::Realm,
+        name: "Leapfrog".to_string(),
+        community_mint: key(1),
+        council_mint: Some(key(2)),
+        min_community_tokens_to_create_proposal: ProposalThresholdSource::Absolute { value: 1_000 },
+        community_mint_max_vote_weight_source: MintMaxVoteWeightSource::SupplyFraction { fraction: 10_000_000_000 },
+        use_quadratic_voting: true,
+        authority: Some(key(3)),
+        unstaking_cooldown_seconds: 86_400,
+        sybil_oracle: Some(key(4)),
+        default_sybil_resistance_score: 100,
+        quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy::PerOptionSquareRoot,
+        voting_power_merkle_root: Some([5; 32]),
+        max_vote_records_per_proposal: 10_000,
+        proposal_creation_fee: Some(ProposalCreationFee {
+            lamports: 1_000_000,
+            treasury: key(6),
+            deposit_lamports: 5_000_000,
+        }),
+        nonce: 7,
+        abstain_counts_for_quorum: true,
+        voting_extends_cooldown: true,
+        min_record_age_slots: 150,
+        allow_shared_council_mint: false,
+        community_mint_decimals: 6,
+        normalize_vote_weight_to_decimals: true,
+        mint_migration: Some(MintMigration { old_mint: key(1), new_mint: key(8), swap_reserve: key(12), unswapped_amount: 1_000_000 }),
+        lockup_vote_weight: Some(LockupVoteWeight { max_lock_seconds: 31_536_000, min_multiplier_percentage: 50 }),
+        allow_plain_text_description_link: false,
+        authority_set: vec![key(9), key(10)],
+        authority_threshold: 2,
+        total_staked: 5_000_000,
+        governance_count: 3,
+        inactivity_threshold_seconds: Some(7_776_000),
+        last_activity_at: 1_700_000_000,
+        quadratic_credit_budget: None,
+        permissioned_proposal_creation: true,
+        proposal_creators: vec![key(11)],
+        weight_oracle: None,
+        lifecycle: RealmLifecycle::Active,
+        early_unstake_fee: None,
+        vote_weight_source: VoteWeightSource::CommunityToken,
+        max_vote_weight_per_voter: Some(250_000),
+        default_voting_period_days: 7,
+        auto_relinquish_finalized: true,
+        lock_stake_until_execution: false,
+        bump_seed: 254,
+        vault_authority_bump_seed: 253,
+        used_reserved: 1,
+        reserved: [0; 63],
+    }
+}
+
+fn canonical_proposal() -> Proposal {
+    // Inserted out of order, the stored entries are still sorted by option index
+    let vote_results = HashMap::from([(2, 300), (0, 1_000), (1, 0)]);
+
+    Proposal {
+        account_type: AccountType::Proposal,
+        governance: key(20),
+        proposal_owner: key(21),
+        name: "Fund the mesh network".to_string(),
+        description_link: "ipfs://bafy".to_string(),
+        created_at: 1_700_000_000,
+        state: ProposalState::Active,
+        vote_type: VoteType::MultiChoice { max_voter_options: 2 },
+        options: vec!["Alpha".to_string(), "Beta".to_string(), "Gamma".to_string()],
+        use_denial_quorum: false,
+        per_voter_option_cap: Some(OptionVoteCap { max_weight_per_option: 500, excess_policy: OptionCapExcessPolicy::Discard }),
+        max_vote_weight_source: Some(MintMaxVoteWeightSource::Absolute { value: 10_000 }),
+        voting_starts_at: 1_700_000_100,
+        voting_ends_at: 1_700_604_900,
+        vote_results,
+        total_vote_weight: 1_300,
+        abstain_vote_weight: 50,
+        vote_record_count: 4,
+        winning_option: None,
+        ranked_ballots: vec![],
+        executable_at: None,
+        sponsors: vec![key(22)],
+        is_executing: false,
+        impact_tier: Some(1),
+        deny_vote_weight: 0,
+        conviction: 0,
+        conviction_updated_at: 1_700_000_000,
+        creation_fee_deposit: Some(ProposalDeposit { lamports: 5_000_000, treasury: key(6) }),
+        option_transaction_counts: vec![1, 0, 2],
+        executed_transaction_count: 0,
+        option_lead_reached_at: vec![1_700_000_200, 0, 1_700_000_300],
+        depends_on: None,
+        description_hash: Some([23; 32]),
+        bundle: None,
+        is_sybil_flagged: false,
+        sybil_reviewed_by: None,
+        vote_weight_source: None,
+        council_approve_weight: 0,
+        council_deny_weight: 0,
+        council_approved: false,
+        votes_relinquishable: false,
+        parameter_change: None,
+        parameter_change_applied_at: None,
+        fallback_option_index: Some(1),
+        locks_backing_stake: false,
+        used_reserved: 1,
+        reserved: [0; 63],
+    }
+}
+
+fn canonical_token_owner_record() -> TokenOwnerRecord {
+    TokenOwnerRecord {
+        account_type: AccountType::TokenOwnerRecord,
+        realm: key(30),
+        governing_token_mint: key(1),
+        governing_token_owner: key(31),
+        governing_token_deposit_amount: 40_000,
+        unrelinquished_votes_count: 2,
+        earliest_unstaking_time: 1_700_086_400,
+        stake_tranches: vec![
+            StakeTranche { amount: 30_000, unlock_time: 1_700_086_400 },
+            StakeTranche { amount: 10_000, unlock_time: 1_700_172_800 },
+        ],
+        sybil_resistance_score: Some(80),
+        claimed_voting_power: 5_000,
+        governance_delegate: Some(key(32)),
+        delegated_amount: 15_000,
+        last_deposit_slot: 42,
+        quadratic_credits_spent: 9,
+        credit_period_started_at: 1_699_999_000,
+        cumulative_stake_seconds: 123_456_789_000,
+        stake_seconds_updated_at: 1_700_000_000,
+        used_reserved: 1,
+        reserved: [0; 63],
+    }
+}
+
+fn canonical_vote_record() -> VoteRecord {
+    VoteRecord {
+        account_type: AccountType::VoteRecord,
+        proposal: key(40),
+        governing_token_owner: key(31),
+        vote: Vote::Weighted { weights: vec![(0, 70), (2, 30)] },
+        stake_amount: 40_000,
+        vote_weight: 200,
+        is_relinquished: false,
+        option_vote_weights: vec![(0, 140), (2, 60)],
+        voter_authority: key(32),
+        vote_weight_curve: VoteWeightCurve::Quadratic,
+        cast_at: 1_700_000_500,
+        bump_seed: 252,
+        used_reserved: 43,
+        reserved: [0; 21],
+    }
+}
+
+#[test]
+fn realm_golden_vector() {
+    assert_account_golden_vector("realm", &canonical_realm());
+}
+
+#[test]
+fn proposal_golden_vector() {
+    assert_account_golden_vector("proposal", &canonical_proposal());
+}
+
+#[test]
+fn token_owner_record_golden_vector() {
+    assert_account_golden_vector("token_owner_record", &canonical_token_owner_record());
+}
+
+#[test]
+fn vote_record_golden_vector() {
+    assert_account_golden_vector("vote_record", &canonical_vote_record());
+}
+
+#[test]
+fn vote_results_golden_vector() {
+    let vote_results = canonical_proposal().vote_results;
+    let bytes = vote_results.try_to_vec().unwrap();
+    assert_golden_vector("vote_results", &bytes);
+
+    // A u32 entry count, then (option index, weight) pairs sorted by option index
+    let mut expected = 3u32.to_le_bytes().to_vec();
+    for (option_index, weight) in [(0u8, 1_000u64), (1, 0), (2, 300)] {
+        expected.push(option_index);
+        expected.extend(weight.to_le_bytes());
+    }
+    assert_eq!(bytes, expected);
+
+    // The order the options were tallied in doesn't matter
+    let mut reinserted = HashMap::new();
+    for (option_index, weight) in [(1u8, 0u64), (2, 300), (0, 1_000)] {
+        reinserted.insert(option_index, weight);
+    }
+    assert_eq!(reinserted.try_to_vec().unwrap(), bytes);
+}
@@ -0,0 +1,105 @@
+//! Quorum and vote threshold of proposals raised with their impact tier
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs, ProposalCookie},
+};
+use crate::{
+    instruction, GovernanceConfig, GovernanceError, ImpactTier, Proposal, ProposalState, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+/// Tier 0 keeps the governance's 10% quorum, tier 1 needs half of the max vote weight
+fn tiered_governance_config() -> GovernanceConfig {
+    GovernanceConfig {
+        max_concurrent_proposals: 2,
+        impact_tiers: vec![
+            ImpactTier { quorum_bps: 1_000, vote_threshold_bps: 5_000 },
+            ImpactTier { quorum_bps: 5_000, vote_threshold_bps: 6_000 },
+        ],
+        ..governance_config()
+    }
+}
+
+#[test]
+fn same_turnout_passes_a_low_tier_and_fails_a_high_tier() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 2_000);
+    let holder = pt.create_wallet();
+    pt.create_token_account(&realm.community_mint, &holder, 8_000);
+    let governance = pt.with_governance(&realm, tiered_governance_config());
+
+    let mut tiered_proposal = |impact_tier| {
+        let proposal = pt.with_proposal_args(
+            &realm,
+            &governance,
+            &voter,
+            ProposalArgs { impact_tier: Some(impact_tier), ..proposal_args() },
+        );
+        pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+        proposal
+    };
+    let low = tiered_proposal(0);
+    let high = tiered_proposal(1);
+
+    // 20% of the supply votes Yes on both
+    for proposal in [&low, &high] {
+        pt.cast_vote(&realm, &governance, proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    for proposal in [&low, &high] {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    assert_eq!(pt.get_account_data::<Proposal>(&low.address).state, ProposalState::Approved);
+    assert_eq!(pt.get_account_data::<Proposal>(&high.address).state, ProposalState::Expired);
+}
+
+#[test]
+fn impact_tier_must_exist_on_the_governance() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, tiered_governance_config());
+
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: voter.owner };
+    assert_eq!(
+        pt.create_proposal_with_args(
+            &realm,
+            &governance,
+            &voter,
+            &proposal,
+            ProposalArgs { impact_tier: Some(2), ..proposal_args() }
+        ),
+        Err(GovernanceError::InvalidImpactTier.into())
+    );
+}
+
+#[test]
+fn higher_tiers_cannot_lower_the_quorum_or_threshold() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+
+    for impact_tiers in [
+        vec![ImpactTier { quorum_bps: 999, vote_threshold_bps: 5_000 }],
+        vec![
+            ImpactTier { quorum_bps: 5_000, vote_threshold_bps: 6_000 },
+            ImpactTier { quorum_bps: 6_000, vote_threshold_bps: 5_999 },
+        ],
+    ] {
+        assert_eq!(
+            pt.process(&instruction::create_governance(
+                &crate::id(),
+                &realm.address,
+                &realm.authority,
+                None,
+                &[],
+                Pubkey::new_unique(),
+                GovernanceConfig { impact_tiers, ..governance_config() },
+            )),
+            Err(GovernanceError::InvalidGovernanceConfig.into())
+        );
+    }
+}
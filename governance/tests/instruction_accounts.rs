@@ -0,0 +1,307 @@
+//! Handling of instructions given too few accounts
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args},
+};
+use crate::{
+    GovernanceError, LeapfrogInstruction, MintMaxVoteWeightSource, ProposalThresholdSource, RealmLifecycle, Vote,
+    VoteType, VoteWeightSource,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Each instruction with the roles of its fixed accounts, in order
+fn fixed_account_roles() -> Vec<(LeapfrogInstruction, &'static [&'static str])> {
+    const REALM_AUTHORITY: &[&str] = &["realm", "realm_authority"];
+    const UNSTAKE: &[&str] = &[
+        "token_owner",
+        "staking_vault",
+        "destination_token",
+        "token_owner_record",
+        "token_program",
+        "clock",
+        "realm",
+        "vault_authority",
+    ];
+    const COUNCIL_REVIEW: &[&str] = &["council_member", "council_token_account", "realm", "governance", "proposal"];
+
+    vec![
+        (
+            LeapfrogInstruction::InitializeRealm {
+                name: "Leapfrog".to_string(),
+                min_community_tokens_to_create_proposal: ProposalThresholdSource::Absolute { value: 1 },
+                community_mint_max_vote_weight_source: MintMaxVoteWeightSource::Absolute { value: 1 },
+                use_quadratic_voting: false,
+                voting_power_merkle_root: None,
+                nonce: 0,
+                allow_shared_council_mint: false,
+            },
+            // The optional council mint takes the fourth account, so the system program is
+            // missing from a list of three or four
+            &["funder", "realm", "community_mint", "system_program"],
+        ),
+        (
+            LeapfrogInstruction::CreateProposal {
+                name: "Proposal".to_string(),
+                description_link: "https://leapfrogdao.org".to_string(),
+                vote_type: VoteType::SingleChoice,
+                options: vec!["Yes".to_string(), "No".to_string()],
+                use_denial_quorum: false,
+                voting_period_days: 0,
+                per_voter_option_cap: None,
+                impact_tier: None,
+                depends_on: None,
+                description_hash: None,
+                parameter_change: None,
+                fallback_option_index: None,
+            },
+            &[
+                "proposal_owner",
+                "proposal",
+                "governance",
+                "token_owner_record",
+                "governance_authority",
+                "system_program",
+                "realm",
+            ],
+        ),
+        (
+            LeapfrogInstruction::CastVote {
+                vote: Vote::SingleChoice { option_index: 0 },
+                staked_amount: 1,
+                oracle_weight: None,
+            },
+            &[
+                "governance_authority",
+                "proposal",
+                "token_owner_record",
+                "voter_token_account",
+                "vote_record",
+                "governance",
+                "realm",
+                "community_mint",
+            ],
+        ),
+        (
+            LeapfrogInstruction::ExecuteProposal,
+            &["governance_authority", "proposal", "token_owner_record", "governance", "proposal_transaction"],
+        ),
+        (
+            LeapfrogInstruction::StakeTokens { amount: 1 },
+            &[
+                "token_owner",
+                "source_token",
+                "staking_vault",
+                "token_owner_record",
+                "token_program",
+                "realm",
+                "system_program",
+            ],
+        ),
+        (LeapfrogInstruction::UnstakeTokens { amount: 1 }, UNSTAKE),
+        (
+            LeapfrogInstruction::CreateGovernance { governed_account: Pubkey::new_unique(), config: governance_config() },
+            &["funder", "governance", "realm", "system_program"],
+        ),
+        (LeapfrogInstruction::SignOffProposal, &["proposal_owner", "proposal", "governance", "realm"]),
+        (
+            LeapfrogInstruction::FinalizeVote,
+            &[
+                "proposal",
+                "governance",
+                "realm",
+                "community_mint",
+                "proposal_archive",
+                "proposal_certificate",
+                "payer",
+                "system_program",
+            ],
+        ),
+        (
+            LeapfrogInstruction::InsertProposalTransaction { option_index: 0, instructions: vec![] },
+            &["proposal_owner", "proposal", "proposal_transaction", "system_program", "governance"],
+        ),
+        (LeapfrogInstruction::SetRealmConfig { config_args: realm_config_args() }, REALM_AUTHORITY),
+        (LeapfrogInstruction::SetSybilScore { score: 1 }, &["sybil_oracle", "realm", "token_owner_record"]),
+        (
+            LeapfrogInstruction::SetMaxVoteWeightSource {
+                max_vote_weight_source: MintMaxVoteWeightSource::Absolute { value: 1 },
+            },
+            REALM_AUTHORITY,
+        ),
+        (
+            LeapfrogInstruction::ClaimInitialVotingPower { leaf_index: 0, amount: 1, proof: vec![] },
+            &["claimant", "realm", "token_owner_record", "voting_power_claim", "system_program"],
+        ),
+        (
+            LeapfrogInstruction::CreateVestingStream {
+                recipient: Pubkey::new_unique(),
+                total_amount: 1,
+                start_time: 0,
+                cliff_time: 0,
+                duration_seconds: 1,
+                nonce: 0,
+            },
+            &["governance", "vesting_stream", "treasury", "payer", "system_program"],
+        ),
+        (
+            LeapfrogInstruction::ClaimVestedFunds,
+            &["recipient", "vesting_stream", "governance", "treasury", "destination_token", "token_program"],
+        ),
+        (LeapfrogInstruction::PrecheckExecution, &["proposal", "proposal_transaction"]),
+        (LeapfrogInstruction::CancelVestingStream, &["governance", "vesting_stream"]),
+        (
+            LeapfrogInstruction::SetGovernanceDelegate { new_governance_delegate: None, delegated_amount: 0 },
+            &["token_owner", "token_owner_record"],
+        ),
+        (LeapfrogInstruction::TallySnapshot, &["proposal", "governance", "realm", "community_mint"]),
+        (LeapfrogInstruction::ExpireProposal, &["proposal", "governance"]),
+        (LeapfrogInstruction::AddSponsor, &["sponsor", "proposal", "governance", "token_owner_record"]),
+        (
+            LeapfrogInstruction::FinalizeVotes,
+            &["realm", "community_mint", "proposal_archive", "payer", "system_program", "next_proposal_archive"],
+        ),
+        (
+            LeapfrogInstruction::MigrateGoverningMint,
+            &["realm", "realm_authority", "new_community_mint", "swap_reserve"],
+        ),
+        (
+            LeapfrogInstruction::SwapDeposit,
+            &[
+                "token_owner",
+                "realm",
+                "old_token_owner_record",
+                "new_token_owner_record",
+                "system_program",
+                "old_staking_vault",
+                "new_staking_vault",
+                "swap_reserve",
+                "old_community_mint",
+                "vault_authority",
+                "token_program",
+            ],
+        ),
+        (LeapfrogInstruction::RecomputeTally, &["realm_authority", "realm", "governance", "proposal"]),
+        (LeapfrogInstruction::ReleaseCreationFeeDeposit, &["proposal", "governance", "proposal_owner", "treasury"]),
+        (LeapfrogInstruction::SetRealmAuthority { authority_set: vec![], authority_threshold: 0 }, REALM_AUTHORITY),
+        (LeapfrogInstruction::CloseRealm, &["realm", "realm_authority", "beneficiary"]),
+        (LeapfrogInstruction::EmergencyUnstake { amount: 1 }, UNSTAKE),
+        (LeapfrogInstruction::AddProposalCreator { creator: Pubkey::new_unique() }, REALM_AUTHORITY),
+        (LeapfrogInstruction::RemoveProposalCreator { creator: Pubkey::new_unique() }, REALM_AUTHORITY),
+        (LeapfrogInstruction::MigrateGovernance, &["governance", "payer", "system_program"]),
+        (
+            LeapfrogInstruction::CreateVoteRecord,
+            &["voter", "proposal", "token_owner_record", "vote_record", "system_program"],
+        ),
+        (
+            LeapfrogInstruction::CreateProposalBundle,
+            &["creator", "proposal_bundle", "governance", "system_program"],
+        ),
+        (LeapfrogInstruction::ExecuteProposalBundle, &["proposal_bundle", "governance", "keeper"]),
+        (LeapfrogInstruction::AddAccountDiscriminator, &["account", "payer", "system_program"]),
+        (LeapfrogInstruction::SetRealmLifecycle { lifecycle: RealmLifecycle::Paused }, REALM_AUTHORITY),
+        (LeapfrogInstruction::VetoProposal, COUNCIL_REVIEW),
+        (LeapfrogInstruction::ReviewSybilFlag, COUNCIL_REVIEW),
+        (LeapfrogInstruction::UnstakeEarly { amount: 1 }, UNSTAKE),
+        (
+            LeapfrogInstruction::SetVoteWeightSource { vote_weight_source: VoteWeightSource::CommunityToken },
+            &["realm", "realm_authority", "source_mint"],
+        ),
+        (
+            LeapfrogInstruction::CastDelegatedVotes { vote: Vote::SingleChoice { option_index: 0 } },
+            &["governance_delegate", "proposal", "governance", "realm", "system_program"],
+        ),
+        (
+            LeapfrogInstruction::SimulateExecution,
+            &["proposal", "proposal_transaction", "governance", "realm", "community_mint"],
+        ),
+        (
+            LeapfrogInstruction::CastCouncilVote { approve: true },
+            &[
+                "council_member",
+                "council_token_account",
+                "council_mint",
+                "realm",
+                "governance",
+                "proposal",
+                "council_vote_record",
+                "system_program",
+                "council_vault",
+                "token_program",
+            ],
+        ),
+        (LeapfrogInstruction::AutoRelinquishFinalized, &["proposal"]),
+        (LeapfrogInstruction::ApplyParameterChange, &["proposal", "governance"]),
+        (
+            LeapfrogInstruction::RelinquishCouncilVote,
+            &[
+                "council_member",
+                "council_token_account",
+                "realm",
+                "proposal",
+                "council_vote_record",
+                "council_vault",
+                "vault_authority",
+                "token_program",
+            ],
+        ),
+        (
+            LeapfrogInstruction::RelinquishVote,
+            &[
+                "vote_authority",
+                "proposal",
+                "token_owner_record",
+                "vote_record",
+                "governance",
+                "realm",
+                "community_mint",
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn every_instruction_is_covered() {
+    assert_eq!(fixed_account_roles().len(), LeapfrogInstruction::COUNT as usize);
+}
+
+#[test]
+fn truncated_account_lists_report_the_missing_role() {
+    for (instruction, roles) in fixed_account_roles() {
+        let data = instruction.try_to_vec().unwrap();
+        for (account_count, role) in roles.iter().enumerate() {
+            let mut pt = ProgramTest::new();
+            let accounts = (0..account_count).map(|_| AccountMeta::new(Pubkey::new_unique(), true)).collect();
+            let result = pt.process(&Instruction { program_id: crate::id(), accounts, data: data.clone() });
+
+            assert_eq!(
+                result,
+                Err(GovernanceError::MissingAccount.into()),
+                "{:?} with {} accounts",
+                instruction,
+                account_count
+            );
+            assert!(
+                pt.logs().contains(&format!("Missing account: {}", role)),
+                "{:?} with {} accounts logged {:?}",
+                instruction,
+                account_count,
+                pt.logs()
+            );
+        }
+    }
+}
+
+#[test]
+fn missing_account_keeps_its_error_code() {
+    assert_eq!(
+        ProgramError::from(GovernanceError::MissingAccount),
+        ProgramError::Custom(GovernanceError::MissingAccount as u32)
+    );
+    assert_eq!(GovernanceError::MissingAccount.code(), GovernanceError::MissingAccount as u32);
+}
@@ -0,0 +1,114 @@
+//! Instructions built for clients by the instruction module
+
+use crate::{get_token_owner_record_address, get_vote_record_address, instruction, LeapfrogInstruction, Vote};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+
+fn unpack(instruction: &Instruction) -> LeapfrogInstruction {
+    assert_eq!(instruction.program_id, crate::id());
+    LeapfrogInstruction::unpack(&instruction.data).unwrap()
+}
+
+#[test]
+fn stake_tokens_has_the_documented_accounts() {
+    let (realm, mint, token_owner, source_token, staking_vault) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let stake = instruction::stake_tokens(&crate::id(), &realm, &mint, &token_owner, &source_token, &staking_vault, 42);
+
+    assert_eq!(unpack(&stake), LeapfrogInstruction::StakeTokens { amount: 42 });
+    assert_eq!(
+        stake.accounts,
+        vec![
+            AccountMeta::new(token_owner, true),
+            AccountMeta::new(source_token, false),
+            AccountMeta::new(staking_vault, false),
+            AccountMeta::new(get_token_owner_record_address(&crate::id(), &realm, &mint, &token_owner), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(realm, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
+    );
+}
+
+#[test]
+fn cast_vote_appends_the_signing_weight_oracle_when_given_one() {
+    let (realm, governance, proposal, mint, voter, voter_token_account, weight_oracle) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let token_owner_record = get_token_owner_record_address(&crate::id(), &realm, &mint, &voter);
+    let cast_vote = |weight_oracle, oracle_weight| {
+        instruction::cast_vote(
+            &crate::id(),
+            &realm,
+            &governance,
+            &proposal,
+            &mint,
+            &voter,
+            &voter_token_account,
+            weight_oracle,
+            Vote::SingleChoice { option_index: 1 },
+            100,
+            oracle_weight,
+        )
+    };
+    let fixed_accounts = vec![
+        AccountMeta::new_readonly(voter, true),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(token_owner_record, false),
+        AccountMeta::new_readonly(voter_token_account, false),
+        AccountMeta::new(get_vote_record_address(&crate::id(), &proposal, &token_owner_record), false),
+        AccountMeta::new_readonly(governance, false),
+        AccountMeta::new_readonly(realm, false),
+        AccountMeta::new_readonly(mint, false),
+    ];
+
+    let without_oracle = cast_vote(None, None);
+    assert_eq!(
+        unpack(&without_oracle),
+        LeapfrogInstruction::CastVote {
+            vote: Vote::SingleChoice { option_index: 1 },
+            staked_amount: 100,
+            oracle_weight: None
+        }
+    );
+    assert_eq!(without_oracle.accounts, fixed_accounts);
+
+    let with_oracle = cast_vote(Some(&weight_oracle), Some(60));
+    assert_eq!(
+        unpack(&with_oracle),
+        LeapfrogInstruction::CastVote {
+            vote: Vote::SingleChoice { option_index: 1 },
+            staked_amount: 100,
+            oracle_weight: Some(60)
+        }
+    );
+    assert_eq!(with_oracle.accounts[..fixed_accounts.len()], fixed_accounts[..]);
+    assert_eq!(with_oracle.accounts[fixed_accounts.len()..], [AccountMeta::new_readonly(weight_oracle, true)]);
+}
+
+#[test]
+fn sign_off_proposal_is_signed_by_the_proposal_owner() {
+    let (realm, governance, proposal, proposal_owner) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let sign_off = instruction::sign_off_proposal(&crate::id(), &realm, &governance, &proposal, &proposal_owner);
+
+    assert_eq!(unpack(&sign_off), LeapfrogInstruction::SignOffProposal);
+    assert_eq!(
+        sign_off.accounts,
+        vec![
+            AccountMeta::new_readonly(proposal_owner, true),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new(governance, false),
+            AccountMeta::new_readonly(realm, false),
+        ]
+    );
+}
@@ -0,0 +1,32 @@
+//! Decoding of instruction data
+
+use crate::{GovernanceError, LeapfrogInstruction};
+use borsh::BorshSerialize;
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn count_is_one_past_the_last_instruction() {
+    let last = LeapfrogInstruction::RelinquishVote;
+    assert_eq!(last.try_to_vec().unwrap(), vec![LeapfrogInstruction::COUNT - 1]);
+    assert_eq!(LeapfrogInstruction::unpack(&[LeapfrogInstruction::COUNT - 1]), Ok(last));
+
+    assert_eq!(
+        LeapfrogInstruction::unpack(&[LeapfrogInstruction::COUNT]),
+        Err(GovernanceError::UnknownInstruction.into())
+    );
+}
+
+#[test]
+fn unknown_instructions_are_told_apart_from_malformed_data() {
+    assert_eq!(LeapfrogInstruction::unpack(&[]), Err(ProgramError::from(GovernanceError::UnknownInstruction)));
+    assert_eq!(LeapfrogInstruction::unpack(&[u8::MAX]), Err(GovernanceError::UnknownInstruction.into()));
+
+    // StakeTokens with a truncated amount, and with trailing bytes
+    let stake = LeapfrogInstruction::StakeTokens { amount: 1 }.try_to_vec().unwrap();
+    assert_eq!(LeapfrogInstruction::unpack(&stake[..4]), Err(GovernanceError::MalformedInstructionData.into()));
+    let mut trailing = stake.clone();
+    trailing.push(0);
+    assert_eq!(LeapfrogInstruction::unpack(&trailing), Err(GovernanceError::MalformedInstructionData.into()));
+
+    assert_eq!(LeapfrogInstruction::unpack(&stake), Ok(LeapfrogInstruction::StakeTokens { amount: 1 }));
+}
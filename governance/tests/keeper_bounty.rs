@@ -0,0 +1,234 @@
+//! Bounties paid to the keepers finalizing and executing proposals
+
+use super::{
+    program_test::{ProgramTest, WALLET_LAMPORTS},
+    setup::{governance_config, realm_config_args, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    instruction, GovernanceConfig, Proposal, ProposalCreationFee, ProposalDeposit, ProposalState, RealmConfigArgs,
+    Vote, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+const CREATION_DEPOSIT: u64 = 10_000_000;
+const FINALIZER_BOUNTY: u64 = 1_000_000;
+const EXECUTOR_BOUNTY: u64 = 2_000_000;
+
+/// Holds a creation deposit for proposals of a new realm, returning the realm, the treasury, a
+/// governance paying the given bounties and a token owner
+fn with_bounties(
+    pt: &mut ProgramTest,
+    finalizer_bounty_lamports: u64,
+    executor_bounty_lamports: u64,
+) -> (RealmCookie, Pubkey, GovernanceCookie, TokenOwnerCookie) {
+    let realm = pt.with_realm();
+    let treasury = pt.create_wallet();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            proposal_creation_fee: Some(ProposalCreationFee {
+                lamports: 0,
+                treasury,
+                deposit_lamports: CREATION_DEPOSIT,
+            }),
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig {
+            max_concurrent_proposals: 2,
+            finalizer_bounty_lamports,
+            executor_bounty_lamports,
+            ..governance_config()
+        },
+    );
+
+    (realm, treasury, governance, token_owner)
+}
+
+/// Creates a proposal whose Yes option runs the given number of empty transactions, and
+/// signs it off
+fn with_executable_proposal(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    owner: &TokenOwnerCookie,
+    transaction_count: u8,
+) -> ProposalCookie {
+    let proposal = pt.with_proposal(realm, governance, owner);
+    for transaction_index in 0..transaction_count {
+        pt.process(&instruction::insert_proposal_transaction(
+            &crate::id(),
+            &governance.address,
+            &proposal.address,
+            &proposal.owner,
+            0,
+            transaction_index,
+            vec![],
+        ))
+        .unwrap();
+    }
+    pt.sign_off_proposal(realm, governance, &proposal).unwrap();
+    proposal
+}
+
+fn finalize_vote(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+    payer: &Pubkey,
+) {
+    pt.process(&instruction::finalize_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        payer,
+        0,
+    ))
+    .unwrap();
+}
+
+fn execute_transaction(
+    pt: &mut ProgramTest,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+    owner: &TokenOwnerCookie,
+    keeper: &Pubkey,
+    transaction_index: u8,
+) {
+    pt.process(&instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &owner.token_owner_record,
+        keeper,
+        None,
+        0,
+        transaction_index,
+        &[],
+    ))
+    .unwrap();
+}
+
+fn deposit_lamports(pt: &ProgramTest, proposal: &ProposalCookie) -> u64 {
+    pt.get_account_data::<Proposal>(&proposal.address).creation_fee_deposit.unwrap().lamports
+}
+
+#[test]
+fn keepers_are_paid_out_of_the_proposal_deposit() {
+    let mut pt = ProgramTest::new();
+    let (realm, treasury, governance, token_owner) = with_bounties(&mut pt, FINALIZER_BOUNTY, EXECUTOR_BOUNTY);
+    let proposal = with_executable_proposal(&mut pt, &realm, &governance, &token_owner, 2);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    let proposal_lamports = pt.lamports(&proposal.address);
+    let governance_lamports = pt.lamports(&governance.address);
+
+    let finalizer = pt.create_wallet();
+    finalize_vote(&mut pt, &realm, &governance, &proposal, &finalizer);
+    // The finalizer also paid for the certificate and archive page it created
+    let finalizer_lamports = pt.lamports(&finalizer);
+    assert_eq!(pt.lamports(&proposal.address), proposal_lamports - FINALIZER_BOUNTY);
+    assert_eq!(deposit_lamports(&pt, &proposal), CREATION_DEPOSIT - FINALIZER_BOUNTY);
+
+    // Only the transaction leaving the proposal Executed pays the executor bounty
+    let executor = pt.create_wallet();
+    execute_transaction(&mut pt, &governance, &proposal, &token_owner, &executor, 0);
+    assert_eq!(pt.lamports(&executor), WALLET_LAMPORTS);
+    execute_transaction(&mut pt, &governance, &proposal, &token_owner, &executor, 1);
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+    assert_eq!(pt.lamports(&executor), WALLET_LAMPORTS + EXECUTOR_BOUNTY);
+
+    let remaining_deposit = CREATION_DEPOSIT - FINALIZER_BOUNTY - EXECUTOR_BOUNTY;
+    assert_eq!(deposit_lamports(&pt, &proposal), remaining_deposit);
+    assert_eq!(pt.lamports(&proposal.address), proposal_lamports - FINALIZER_BOUNTY - EXECUTOR_BOUNTY);
+    assert_eq!(pt.lamports(&finalizer), finalizer_lamports);
+    assert_eq!(pt.lamports(&governance.address), governance_lamports);
+
+    // Releasing the deposit refunds what the bounties left of it
+    let owner_lamports = pt.lamports(&token_owner.owner);
+    pt.process(&instruction::release_creation_fee_deposit(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &token_owner.owner,
+        &treasury,
+    ))
+    .unwrap();
+    assert_eq!(pt.lamports(&token_owner.owner), owner_lamports + remaining_deposit);
+    assert_eq!(pt.lamports(&treasury), WALLET_LAMPORTS);
+}
+
+#[test]
+fn bundle_keeper_is_paid_the_executor_bounty_of_each_proposal() {
+    let mut pt = ProgramTest::new();
+    let (realm, _, governance, token_owner) = with_bounties(&mut pt, 0, EXECUTOR_BOUNTY);
+    let proposals: Vec<_> = (0..2).map(|_| pt.with_proposal(&realm, &governance, &token_owner)).collect();
+    for proposal in &proposals {
+        pt.process(&instruction::insert_proposal_transaction(
+            &crate::id(),
+            &governance.address,
+            &proposal.address,
+            &proposal.owner,
+            0,
+            0,
+            vec![],
+        ))
+        .unwrap();
+    }
+    let proposal_addresses: Vec<_> = proposals.iter().map(|proposal| proposal.address).collect();
+    pt.process(&instruction::create_proposal_bundle(
+        &crate::id(),
+        &token_owner.owner,
+        &governance.address,
+        &proposal_addresses,
+    ))
+    .unwrap();
+    for proposal in &proposals {
+        pt.sign_off_proposal(&realm, &governance, proposal).unwrap();
+        pt.cast_vote(&realm, &governance, proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    for proposal in &proposals {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    let keeper = pt.create_wallet();
+    let bundled = proposal_addresses.iter().map(|proposal| (*proposal, 0, vec![vec![]])).collect::<Vec<_>>();
+    pt.process(&instruction::execute_proposal_bundle(&crate::id(), &governance.address, &keeper, &bundled)).unwrap();
+
+    assert_eq!(pt.lamports(&keeper), WALLET_LAMPORTS + 2 * EXECUTOR_BOUNTY);
+    for proposal in &proposals {
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+        assert_eq!(deposit_lamports(&pt, proposal), CREATION_DEPOSIT - EXECUTOR_BOUNTY);
+    }
+}
+
+#[test]
+fn zero_bounties_pay_nothing() {
+    let mut pt = ProgramTest::new();
+    let (realm, treasury, governance, token_owner) = with_bounties(&mut pt, 0, 0);
+    let proposal = with_executable_proposal(&mut pt, &realm, &governance, &token_owner, 1);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+
+    let finalizer = pt.create_wallet();
+    finalize_vote(&mut pt, &realm, &governance, &proposal, &finalizer);
+    let proposal_lamports = pt.lamports(&proposal.address);
+    let executor = pt.create_wallet();
+    execute_transaction(&mut pt, &governance, &proposal, &token_owner, &executor, 0);
+
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+    assert_eq!(pt.lamports(&executor), WALLET_LAMPORTS);
+    assert_eq!(pt.lamports(&proposal.address), proposal_lamports);
+    assert_eq!(
+        pt.get_account_data::<Proposal>(&proposal.address).creation_fee_deposit,
+        Some(ProposalDeposit { lamports: CREATION_DEPOSIT, treasury })
+    );
+}
@@ -0,0 +1,39 @@
+//! Vote weights scaled by the time left until the voter's stake unlocks
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args},
+};
+use crate::{get_vote_record_address, LockupVoteWeight, RealmConfigArgs, Vote, VoteRecord};
+
+const DAY: u64 = 86_400;
+
+#[test]
+fn more_remaining_lock_carries_more_weight_down_to_the_floor() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            unstaking_cooldown_seconds: 100 * DAY,
+            lockup_vote_weight: Some(LockupVoteWeight { max_lock_seconds: 100 * DAY, min_multiplier_percentage: 25 }),
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+
+    // Equal stakes left locked for 10, 60 and 100 days when the vote is cast
+    let earliest = pt.with_token_owner(&realm, 1_000);
+    pt.advance_clock(50 * DAY);
+    let middle = pt.with_token_owner(&realm, 1_000);
+    pt.advance_clock(40 * DAY);
+    let latest = pt.with_token_owner(&realm, 1_000);
+
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &latest);
+    for (voter, vote_weight) in [(&earliest, 250), (&middle, 600), (&latest, 1_000)] {
+        pt.cast_vote(&realm, &governance, &proposal, voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+        assert_eq!(pt.get_account_data::<VoteRecord>(&vote_record).vote_weight, vote_weight);
+    }
+}
@@ -0,0 +1,177 @@
+//! Migration of a realm's community mint and swapping deposits to the new mint
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_token_owner_record_address, get_vault_authority_address, instruction, GovernanceError, Proposal, Realm,
+    TokenOwnerRecord, Vote, DEFAULT_UNSTAKING_COOLDOWN_SECONDS,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use spl_token::{
+    error::TokenError,
+    state::{Account as TokenAccount, Mint},
+};
+
+/// A realm migrated to a new mint, with its new staking vault and swap reserve
+struct MigrationCookie {
+    new_mint: Pubkey,
+    new_staking_vault: Pubkey,
+    swap_reserve: Pubkey,
+}
+
+/// Migrates the realm to a new mint whose swap reserve holds the given amount
+fn migrate(pt: &mut ProgramTest, realm: &RealmCookie, reserve_amount: u64) -> MigrationCookie {
+    let new_mint = pt.create_mint(6);
+    let (vault_authority, _) = get_vault_authority_address(&crate::id(), &realm.address);
+    let new_staking_vault = pt.create_token_account(&new_mint, &vault_authority, 0);
+    let swap_reserve = pt.create_token_account(&new_mint, &vault_authority, reserve_amount);
+    pt.process(&instruction::migrate_governing_mint(
+        &crate::id(),
+        &realm.address,
+        &realm.authority,
+        &[],
+        &new_mint,
+        &swap_reserve,
+    ))
+    .unwrap();
+
+    MigrationCookie { new_mint, new_staking_vault, swap_reserve }
+}
+
+fn swap_deposit(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    migration: &MigrationCookie,
+    token_owner: &TokenOwnerCookie,
+) -> Result<(), ProgramError> {
+    pt.process(&instruction::swap_deposit(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &migration.new_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &migration.new_staking_vault,
+        &migration.swap_reserve,
+    ))
+}
+
+fn token_amount(pt: &ProgramTest, token_account: &Pubkey) -> u64 {
+    pt.get_token_state::<TokenAccount>(token_account).amount
+}
+
+#[test]
+fn swap_deposit_moves_the_tokens_and_keeps_voting_power() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let other_token_owner = pt.with_token_owner(&realm, 500);
+    let voting_power = pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).get_voting_power();
+
+    let migration = migrate(&mut pt, &realm, 1_500);
+    let migrated_realm = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(migrated_realm.community_mint, migration.new_mint);
+    assert_eq!(migrated_realm.total_staked, 0);
+    assert_eq!(migrated_realm.mint_migration.unwrap().unswapped_amount, 1_500);
+
+    swap_deposit(&mut pt, &realm, &migration, &token_owner).unwrap();
+
+    // The old tokens are burned and as many new ones back the deposit
+    assert_eq!(token_amount(&pt, &realm.staking_vault), 500);
+    assert_eq!(pt.get_token_state::<Mint>(&realm.community_mint).supply, 500);
+    assert_eq!(token_amount(&pt, &migration.swap_reserve), 500);
+    assert_eq!(token_amount(&pt, &migration.new_staking_vault), 1_000);
+    let migrated_realm = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(migrated_realm.total_staked, 1_000);
+    assert_eq!(migrated_realm.mint_migration.unwrap().unswapped_amount, 500);
+
+    let new_token_owner_record =
+        get_token_owner_record_address(&crate::id(), &realm.address, &migration.new_mint, &token_owner.owner);
+    let new_record = pt.get_account_data::<TokenOwnerRecord>(&new_token_owner_record);
+    assert_eq!(new_record.governing_token_deposit_amount, 1_000);
+    assert_eq!(new_record.get_voting_power(), voting_power);
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+
+    // The swapped deposit votes with its whole weight under the new mint
+    let new_realm = RealmCookie {
+        address: realm.address,
+        authority: realm.authority,
+        community_mint: migration.new_mint,
+        council_mint: realm.council_mint,
+        staking_vault: migration.new_staking_vault,
+    };
+    let new_token_owner = TokenOwnerCookie {
+        owner: token_owner.owner,
+        token_account: pt.create_token_account(&migration.new_mint, &token_owner.owner, 0),
+        token_owner_record: new_token_owner_record,
+    };
+    let governance = pt.with_governance(&new_realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&new_realm, &governance, &new_token_owner);
+    pt.cast_vote(&new_realm, &governance, &proposal, &new_token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).vote_results[&0], 1_000);
+
+    // Unswapped deposits can't leave through the new staking vault, nor can the swap reserve
+    // be unstaked from
+    pt.advance_clock(DEFAULT_UNSTAKING_COOLDOWN_SECONDS);
+    let destination = pt.create_token_account(&migration.new_mint, &other_token_owner.owner, 0);
+    assert_eq!(
+        pt.process(&instruction::unstake_tokens(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &other_token_owner.owner,
+            &migration.new_staking_vault,
+            &destination,
+            500,
+        )),
+        Err(GovernanceError::InvalidTokenOwnerRecord.into())
+    );
+    swap_deposit(&mut pt, &realm, &migration, &other_token_owner).unwrap();
+    assert_eq!(
+        pt.process(&instruction::unstake_tokens(
+            &crate::id(),
+            &realm.address,
+            &migration.new_mint,
+            &other_token_owner.owner,
+            &migration.swap_reserve,
+            &destination,
+            500,
+        )),
+        Err(GovernanceError::InvalidStakingVault.into())
+    );
+    pt.process(&instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &migration.new_mint,
+        &other_token_owner.owner,
+        &migration.new_staking_vault,
+        &destination,
+        500,
+    ))
+    .unwrap();
+    assert_eq!(token_amount(&pt, &destination), 500);
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).total_staked, 1_000);
+}
+
+#[test]
+fn swap_deposit_fails_until_the_swap_reserve_is_funded() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let migration = migrate(&mut pt, &realm, 999);
+
+    assert_eq!(
+        swap_deposit(&mut pt, &realm, &migration, &token_owner),
+        Err(TokenError::InsufficientFunds.into())
+    );
+    assert_eq!(pt.token_owner_deposit(&token_owner), 1_000);
+    assert_eq!(token_amount(&pt, &realm.staking_vault), 1_000);
+
+    let mut swap_reserve = pt.get_token_state::<TokenAccount>(&migration.swap_reserve);
+    swap_reserve.amount += 1;
+    pt.set_token_state(migration.swap_reserve, swap_reserve);
+    swap_deposit(&mut pt, &realm, &migration, &token_owner).unwrap();
+    assert_eq!(token_amount(&pt, &migration.new_staking_vault), 1_000);
+}
@@ -0,0 +1,55 @@
+//! Tests of the governance program, run on the host with `cargo test`
+
+mod account_contexts;
+mod account_data_size;
+mod allowed_target_programs;
+mod auto_relinquish;
+mod basis_points;
+mod bump_seeds;
+mod cast_vote;
+mod clock;
+mod closed_governance;
+mod conversions;
+mod conviction_voting;
+mod council_approval;
+mod delegation;
+mod denial_quorum;
+mod error_codes;
+mod execution_accounts;
+mod fallback_option;
+mod funded_execution;
+mod golden_vectors;
+mod impact_tier;
+mod instruction_accounts;
+mod instruction_builders;
+mod instruction_data;
+mod keeper_bounty;
+mod lockup_vote_weight;
+mod mint_migration;
+mod parameter_change;
+mod program_ids;
+mod program_test;
+mod proposal_archive;
+mod proposal_bundle;
+mod proposal_certificate;
+mod proposal_creation;
+mod proposal_dependency;
+mod proposal_lifecycle;
+mod quadratic_voting;
+mod quorum;
+mod ranked_choice;
+mod realm_closure;
+mod realm_config;
+mod realm_creation;
+mod realm_lifecycle;
+mod relinquish_vote;
+mod reserved_space;
+mod setup;
+mod size_estimates;
+mod sponsorship;
+mod staking;
+mod sybil_vote_check;
+mod vesting_stream;
+mod veto_window;
+mod voting_period;
+mod voting_power_claim;
@@ -0,0 +1,94 @@
+//! Proposals changing a governance parameter through ApplyParameterChange
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs, ProposalCookie},
+};
+use crate::{
+    instruction, Governance, GovernanceError, GovernanceParameter, ParameterChange, Proposal, ProposalState, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS, MAX_BASIS_POINTS, MAX_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+/// Yes / No proposal arguments whose Yes option changes the parameter to the value
+fn parameter_change_args(parameter: GovernanceParameter, value: u64) -> ProposalArgs {
+    ProposalArgs {
+        parameter_change: Some(ParameterChange { option_index: 0, parameter, value }),
+        ..proposal_args()
+    }
+}
+
+#[test]
+fn in_range_change_applies_once_its_option_wins() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        parameter_change_args(GovernanceParameter::QuorumBps, 2_500),
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    let apply_parameter_change =
+        instruction::apply_parameter_change(&crate::id(), &governance.address, &proposal.address, None);
+
+    // Nothing is applied before the option wins
+    assert_eq!(pt.process(&apply_parameter_change), Err(GovernanceError::InvalidProposalState.into()));
+
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    pt.process(&apply_parameter_change).unwrap();
+
+    let stored_governance = pt.get_account_data::<Governance>(&governance.address);
+    assert_eq!(stored_governance.config.quorum_bps, 2_500);
+    assert_eq!(stored_governance.config.vote_threshold_bps, governance_config().vote_threshold_bps);
+    // The option carries no transactions, so applying the change executes the proposal
+    let stored_proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(stored_proposal.state, ProposalState::Executed);
+    assert_eq!(stored_proposal.parameter_change_applied_at, Some(pt.clock().unix_timestamp as u64));
+
+    assert_eq!(
+        pt.process(&apply_parameter_change),
+        Err(GovernanceError::ParameterChangeAlreadyApplied.into())
+    );
+}
+
+#[test]
+fn out_of_range_value_is_rejected_at_creation() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+
+    for (parameter, value) in [
+        (GovernanceParameter::QuorumBps, MAX_BASIS_POINTS as u64 + 1),
+        (GovernanceParameter::VoteThresholdBps, 0),
+        (GovernanceParameter::MaxConcurrentProposals, 256),
+        (GovernanceParameter::MinVotingPeriodDays, MAX_VOTING_PERIOD_DAYS as u64 + 1),
+    ] {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        assert_eq!(
+            pt.create_proposal_with_args(
+                &realm,
+                &governance,
+                &token_owner,
+                &proposal,
+                parameter_change_args(parameter, value),
+            ),
+            Err(GovernanceError::InvalidParameterChange.into()),
+            "{parameter:?} = {value}"
+        );
+    }
+
+    // The change must name one of the proposal's options
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    let mut args = parameter_change_args(GovernanceParameter::QuorumBps, 2_500);
+    args.parameter_change = args.parameter_change.map(|change| ParameterChange { option_index: 2, ..change });
+    assert_eq!(
+        pt.create_proposal_with_args(&realm, &governance, &token_owner, &proposal, args),
+        Err(GovernanceError::InvalidParameterChange.into())
+    );
+}
@@ -0,0 +1,113 @@
+//! System and token program accounts checked before they are invoked
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalCookie},
+};
+use crate::{
+    instruction, MintMaxVoteWeightSource, ProposalThresholdSource, Vote, DEFAULT_UNSTAKING_COOLDOWN_SECONDS,
+    DEFAULT_VOTING_PERIOD_DAYS, SUPPLY_FRACTION_BASE,
+};
+use solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, system_program};
+
+/// Swaps the program account of the instruction for an account posing as it
+fn with_spoofed_program(mut instruction: Instruction, program_id: &Pubkey) -> Instruction {
+    let spoofed_program = Pubkey::new_unique();
+    for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == *program_id) {
+        meta.pubkey = spoofed_program;
+    }
+    instruction
+}
+
+#[test]
+fn spoofed_system_program_is_rejected() {
+    let mut pt = ProgramTest::new();
+    let authority = pt.create_wallet();
+    let community_mint = pt.create_mint(6);
+    let council_mint = pt.create_mint(0);
+    let initialize_realm = instruction::initialize_realm(
+        &crate::id(),
+        &authority,
+        &community_mint,
+        &council_mint,
+        "Leapfrog".to_string(),
+        ProposalThresholdSource::Absolute { value: 1 },
+        MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE },
+        false,
+        None,
+        0,
+        false,
+    );
+    assert_eq!(
+        pt.process(&with_spoofed_program(initialize_realm, &system_program::id())),
+        Err(ProgramError::IncorrectProgramId)
+    );
+
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    let create_proposal = pt.create_proposal_instruction(&realm, &governance, &token_owner, &proposal, proposal_args());
+    assert_eq!(
+        pt.process(&with_spoofed_program(create_proposal, &system_program::id())),
+        Err(ProgramError::IncorrectProgramId)
+    );
+
+    // The archive and certificate accounts of finalization are created with the same check
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    let payer = pt.create_wallet();
+    let finalize_vote = instruction::finalize_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        &payer,
+        0,
+    );
+    assert_eq!(
+        pt.process(&with_spoofed_program(finalize_vote.clone(), &system_program::id())),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    pt.process(&finalize_vote).unwrap();
+}
+
+#[test]
+fn spoofed_token_program_is_rejected_by_staking() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let source_token = pt.create_token_account(&realm.community_mint, &token_owner.owner, 500);
+    let stake_tokens = instruction::stake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &source_token,
+        &realm.staking_vault,
+        500,
+    );
+    assert_eq!(
+        pt.process(&with_spoofed_program(stake_tokens, &spl_token::id())),
+        Err(ProgramError::IncorrectProgramId)
+    );
+
+    pt.advance_clock(DEFAULT_UNSTAKING_COOLDOWN_SECONDS);
+    let unstake_tokens = instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        1_000,
+    );
+    assert_eq!(
+        pt.process(&with_spoofed_program(unstake_tokens.clone(), &spl_token::id())),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    pt.process(&unstake_tokens).unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+}
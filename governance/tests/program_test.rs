@@ -0,0 +1,405 @@
+//! A minimal stand-in for the runtime, running the program's instructions against accounts
+//! held in memory
+//!
+//! Accounts are passed to the program in the loader's serialized input format, so resizing
+//! and reassigning them behaves as on chain. Invocations of the system program, the token
+//! program and this program are carried out, and the clock is a fixed time tests advance
+//! by hand. A Clock sysvar account passed to an instruction can hold a different, injected
+//! clock
+
+use crate::{process_instruction, GovernanceAccount, ACCOUNT_DISCRIMINATOR_LEN};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{self, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::{IsInitialized, Pack},
+    program_stubs::{self, SyscallStubs},
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::SystemInstruction,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use std::{cell::RefCell, collections::HashMap, mem::size_of, sync::Once};
+
+/// Unix timestamp every test starts at
+pub const START_TIME: i64 = 1_700_000_000;
+
+/// Lamports given to the wallets tests create
+pub const WALLET_LAMPORTS: u64 = 100_000_000_000;
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(vec![]) };
+}
+
+/// An account as stored between instructions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// The accounts and clock the program runs against
+pub struct ProgramTest {
+    accounts: HashMap<Pubkey, Account>,
+    injected_clock: Option<Clock>,
+}
+
+impl ProgramTest {
+    pub fn new() -> Self {
+        static INSTALL_STUBS: Once = Once::new();
+        INSTALL_STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        });
+        CLOCK.with(|clock| {
+            *clock.borrow_mut() = Clock { slot: 1, unix_timestamp: START_TIME, ..Clock::default() }
+        });
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        EVENTS.with(|events| events.borrow_mut().clear());
+
+        Self { accounts: HashMap::new(), injected_clock: None }
+    }
+
+    /// Returns the current clock
+    pub fn clock(&self) -> Clock {
+        CLOCK.with(|clock| clock.borrow().clone())
+    }
+
+    /// Moves the clock forward by the given number of seconds, one slot at a time
+    pub fn advance_clock(&mut self, seconds: u64) {
+        CLOCK.with(|clock| {
+            let mut clock = clock.borrow_mut();
+            clock.unix_timestamp += seconds as i64;
+            clock.slot += 1;
+        });
+    }
+
+    /// Makes Clock sysvar accounts passed to instructions hold the given clock instead of the
+    /// current one, which Clock::get keeps returning
+    pub fn inject_clock(&mut self, clock: Clock) {
+        self.injected_clock = Some(clock);
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |account| account.lamports)
+    }
+
+    /// Creates a system owned wallet holding WALLET_LAMPORTS
+    pub fn create_wallet(&mut self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.fund(&wallet, WALLET_LAMPORTS);
+        wallet
+    }
+
+    /// Adds lamports to the account, creating it as a system owned account if needed
+    pub fn fund(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_insert_with(|| Account {
+            owner: system_program::id(),
+            ..Account::default()
+        });
+        account.lamports += lamports;
+    }
+
+    /// Deserializes a governance program account
+    pub fn get_account_data<T: GovernanceAccount>(&self, key: &Pubkey) -> T {
+        let account = self.accounts.get(key).unwrap_or_else(|| panic!("missing account {}", key));
+        assert_eq!(account.owner, crate::id(), "account {} isn't owned by the program", key);
+        T::deserialize(&mut &account.data[ACCOUNT_DISCRIMINATOR_LEN..]).unwrap()
+    }
+
+    /// Overwrites the data of a governance program account, keeping its size
+    pub fn set_account_data<T: GovernanceAccount + BorshSerialize>(&mut self, key: &Pubkey, data: &T) {
+        let account = self.accounts.get_mut(key).unwrap_or_else(|| panic!("missing account {}", key));
+        let serialized_data = data.try_to_vec().unwrap();
+        account.data[ACCOUNT_DISCRIMINATOR_LEN..ACCOUNT_DISCRIMINATOR_LEN + serialized_data.len()]
+            .copy_from_slice(&serialized_data);
+    }
+
+    /// Creates a token mint without authorities
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let state = Mint { mint_authority: COption::None, supply: 0, decimals, is_initialized: true, freeze_authority: COption::None };
+        self.set_token_state(mint, state);
+        mint
+    }
+
+    /// Creates a token account holding the amount, minted by adding it to the mint's supply
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let token_account = Pubkey::new_unique();
+        let state = TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        self.set_token_state(token_account, state);
+
+        let mut mint_state = self.get_token_state::<Mint>(mint);
+        mint_state.supply += amount;
+        self.set_token_state(*mint, mint_state);
+
+        token_account
+    }
+
+    pub fn get_token_state<T: Pack + IsInitialized>(&self, key: &Pubkey) -> T {
+        T::unpack(&self.accounts[key].data).unwrap()
+    }
+
+    pub fn set_token_state<T: Pack>(&mut self, key: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+        self.set_account(
+            key,
+            Account { lamports: Rent::default().minimum_balance(T::LEN), data, owner: spl_token::id(), executable: false },
+        );
+    }
+
+    /// Returns the messages the last instruction logged
+    pub fn logs(&self) -> Vec<String> {
+        LOGS.with(|logs| logs.borrow().clone())
+    }
+
+    /// Returns the last event the last instruction logged with sol_log_data, decoded as the
+    /// given type
+    pub fn last_event<T: BorshDeserialize>(&self) -> T {
+        let event = EVENTS.with(|events| events.borrow().last().cloned()).expect("no event logged");
+        T::try_from_slice(&event).unwrap()
+    }
+
+    /// Runs the instruction, keeping its account changes only if it succeeds
+    ///
+    /// Accounts the instruction names but the harness doesn't hold are passed as empty
+    /// system accounts, and the Clock sysvar as the current clock. Accounts left without
+    /// lamports are removed, as the runtime does
+    pub fn process(&mut self, instruction: &Instruction) -> ProgramResult {
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        EVENTS.with(|events| events.borrow_mut().clear());
+
+        let mut input = Vec::new();
+        let mut unique_accounts: Vec<(Pubkey, usize)> = vec![];
+        input.extend_from_slice(&(instruction.accounts.len() as u64).to_le_bytes());
+        for (index, meta) in instruction.accounts.iter().enumerate() {
+            if let Some(first) = instruction.accounts[..index].iter().position(|other| other.pubkey == meta.pubkey) {
+                input.push(first as u8);
+                input.extend_from_slice(&[0; 7]);
+                continue;
+            }
+
+            // Flags are merged over every occurrence of the account, as for a transaction
+            let occurrences = instruction.accounts.iter().filter(|other| other.pubkey == meta.pubkey);
+            let is_signer = occurrences.clone().any(|other| other.is_signer);
+            let is_writable = occurrences.clone().any(|other| other.is_writable);
+            let account = if sysvar::clock::check_id(&meta.pubkey) {
+                self.clock_sysvar_account()
+            } else {
+                self.accounts.get(&meta.pubkey).cloned().unwrap_or_else(|| Account {
+                    owner: system_program::id(),
+                    ..Account::default()
+                })
+            };
+
+            input.extend_from_slice(&[NON_DUP_MARKER, is_signer as u8, is_writable as u8, account.executable as u8]);
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(meta.pubkey.as_ref());
+            unique_accounts.push((meta.pubkey, input.len()));
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            input.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        input.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+        input.extend_from_slice(&instruction.data);
+        input.extend_from_slice(instruction.program_id.as_ref());
+
+        // The loader hands the program an aligned buffer
+        let mut buffer = vec![0u64; input.len().div_ceil(size_of::<u64>())];
+        let buffer_bytes = buffer.as_mut_ptr() as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping(input.as_ptr(), buffer_bytes, input.len()) };
+
+        let result = {
+            let (program_id, account_infos, instruction_data) = unsafe { entrypoint::deserialize(buffer_bytes) };
+            process_instruction(program_id, &account_infos, instruction_data)
+        };
+        if let Err(error) = &result {
+            println!("Instruction failed: {}", error);
+            return result;
+        }
+
+        let output = unsafe { std::slice::from_raw_parts(buffer_bytes, input.len()) };
+        let read_u64 = |offset: usize| u64::from_le_bytes(output[offset..offset + 8].try_into().unwrap());
+        let lamports_before: u128 = unique_accounts
+            .iter()
+            .filter(|(key, _)| !sysvar::clock::check_id(key))
+            .map(|(key, _)| self.lamports(key) as u128)
+            .sum();
+        let mut lamports_after = 0u128;
+        for (key, owner_offset) in unique_accounts {
+            if sysvar::clock::check_id(&key) {
+                continue;
+            }
+            let lamports = read_u64(owner_offset + 32);
+            let data_len = read_u64(owner_offset + 40) as usize;
+            let data_offset = owner_offset + 48;
+            let account = Account {
+                lamports,
+                data: output[data_offset..data_offset + data_len].to_vec(),
+                owner: Pubkey::try_from(&output[owner_offset..owner_offset + 32]).unwrap(),
+                executable: self.accounts.get(&key).is_some_and(|account| account.executable),
+            };
+            lamports_after += lamports as u128;
+            if lamports == 0 {
+                self.accounts.remove(&key);
+            } else {
+                self.accounts.insert(key, account);
+            }
+        }
+        assert_eq!(lamports_before, lamports_after, "instruction created or destroyed lamports");
+
+        result
+    }
+
+    /// Returns the Clock sysvar account holding the injected clock, or else the current one
+    fn clock_sysvar_account(&self) -> Account {
+        let clock = self.injected_clock.clone().unwrap_or_else(|| self.clock());
+        let mut data = Vec::with_capacity(Clock::size_of());
+        data.extend_from_slice(&clock.slot.to_le_bytes());
+        data.extend_from_slice(&clock.epoch_start_timestamp.to_le_bytes());
+        data.extend_from_slice(&clock.epoch.to_le_bytes());
+        data.extend_from_slice(&clock.leader_schedule_epoch.to_le_bytes());
+        data.extend_from_slice(&clock.unix_timestamp.to_le_bytes());
+
+        Account { lamports: Rent::default().minimum_balance(data.len()), data, owner: sysvar::id(), executable: false }
+    }
+}
+
+/// Syscalls of the program when running on the host
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+    fn sol_log(&self, message: &str) {
+        println!("Program log: {}", message);
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        CLOCK.with(|clock| unsafe { *(var_addr as *mut Clock) = clock.borrow().clone() });
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &crate::id()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        // The callee gets the accounts in the instruction's order and with its flags, which
+        // can't exceed the caller's privileges
+        let mut callee_account_infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let account_info = account_infos
+                .iter()
+                .find(|account_info| *account_info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if meta.is_signer && !account_info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !account_info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let mut account_info = account_info.clone();
+            account_info.is_signer = meta.is_signer;
+            account_info.is_writable = meta.is_writable;
+            callee_account_infos.push(account_info);
+        }
+
+        match instruction.program_id {
+            program_id if program_id == system_program::id() => {
+                process_system_instruction(&callee_account_infos, &instruction.data)
+            }
+            program_id if program_id == spl_token::id() => {
+                spl_token::processor::Processor::process(&program_id, &callee_account_infos, &instruction.data)
+            }
+            program_id if program_id == crate::id() => {
+                process_instruction(&program_id, &callee_account_infos, &instruction.data)
+            }
+            _ => Err(ProgramError::IncorrectProgramId),
+        }
+    }
+}
+
+/// Carries out the system program instructions the governance program invokes
+fn process_system_instruction(account_infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        limited_deserialize(data, 1232).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let [from_info, to_info] = account_infos else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if !to_info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to_info.lamports() > 0 || !to_info.data_is_empty() || *to_info.owner != system_program::id() {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer_lamports(from_info, to_info, lamports)?;
+            to_info.realloc(space as usize, true)?;
+            to_info.assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let [from_info, to_info] = account_infos else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            transfer_lamports(from_info, to_info, lamports)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Moves lamports out of a signing system account without data
+fn transfer_lamports(from_info: &AccountInfo, to_info: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *from_info.owner != system_program::id() || !from_info.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let from_lamports = from_info.lamports().checked_sub(lamports).ok_or(ProgramError::InsufficientFunds)?;
+    **from_info.lamports.borrow_mut() = from_lamports;
+    **to_info.lamports.borrow_mut() += lamports;
+    Ok(())
+}
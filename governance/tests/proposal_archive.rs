@@ -0,0 +1,74 @@
+//! Recording of finalized proposals in the realm's paginated proposal archive
+
+use super::{program_test::ProgramTest, setup::governance_config};
+use crate::{
+    get_proposal_archive_address, ArchivedProposal, GovernanceConfig, ProposalArchive, ProposalState, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS, PROPOSAL_ARCHIVE_PAGE_SIZE,
+};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn finalized_proposals_are_archived_with_their_outcome() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 3, ..governance_config() });
+    let approved = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let rejected = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let expired = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.cast_vote(&realm, &governance, &approved, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    // Abstentions reach quorum without backing any option
+    pt.cast_vote(&realm, &governance, &rejected, &voter, Vote::Abstain).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+
+    for proposal in [&approved, &rejected, &expired] {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    let archive = pt.get_account_data::<ProposalArchive>(&get_proposal_archive_address(&crate::id(), &realm.address, 0));
+    assert_eq!(archive.realm, realm.address);
+    assert_eq!(archive.page_index, 0);
+    assert_eq!(
+        archive.proposals,
+        vec![
+            ArchivedProposal { proposal: approved.address, state: ProposalState::Approved, winning_option: Some(0) },
+            ArchivedProposal { proposal: rejected.address, state: ProposalState::Rejected, winning_option: None },
+            ArchivedProposal { proposal: expired.address, state: ProposalState::Expired, winning_option: None },
+        ]
+    );
+}
+
+#[test]
+fn full_archive_page_rolls_over_into_the_next_page() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let first = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let last = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &first).unwrap();
+
+    // Fill the rest of the first page
+    let first_page = get_proposal_archive_address(&crate::id(), &realm.address, 0);
+    let mut archive = pt.get_account_data::<ProposalArchive>(&first_page);
+    while archive.proposals.len() < PROPOSAL_ARCHIVE_PAGE_SIZE {
+        archive.proposals.push(ArchivedProposal {
+            proposal: Pubkey::new_unique(),
+            state: ProposalState::Rejected,
+            winning_option: None,
+        });
+    }
+    pt.set_account_data(&first_page, &archive);
+
+    pt.finalize_vote(&realm, &governance, &last).unwrap();
+
+    assert_eq!(pt.get_account_data::<ProposalArchive>(&first_page).proposals, archive.proposals);
+    let next_page =
+        pt.get_account_data::<ProposalArchive>(&get_proposal_archive_address(&crate::id(), &realm.address, 1));
+    assert_eq!(next_page.page_index, 1);
+    assert_eq!(
+        next_page.proposals,
+        vec![ArchivedProposal { proposal: last.address, state: ProposalState::Expired, winning_option: None }]
+    );
+}
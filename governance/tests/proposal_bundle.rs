@@ -0,0 +1,109 @@
+//! Proposal bundles, executed atomically once every bundled proposal passed its own vote
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, to_instruction_data, GovernanceCookie, ProposalCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_proposal_bundle_address, instruction, GovernanceConfig, GovernanceError, InstructionData, Proposal,
+    ProposalBundle, ProposalState, Vote, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+/// A bundle of two proposals granting 400 then 600 tokens from the governance's treasury
+struct BundleCookie {
+    governance: GovernanceCookie,
+    owner: TokenOwnerCookie,
+    proposals: Vec<(ProposalCookie, InstructionData)>,
+    destination_token: Pubkey,
+}
+
+/// Bundles and signs off two grant proposals, votes Yes on those whose index is in
+/// approved_indexes and finalizes them all once voting has ended
+fn with_voted_bundle(pt: &mut ProgramTest, approved_indexes: &[usize]) -> BundleCookie {
+    let realm = pt.with_realm();
+    let owner = pt.with_token_owner(&realm, 1_000);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let grant_mint = pt.create_mint(6);
+    let treasury = pt.create_token_account(&grant_mint, &governance.address, 1_000);
+    let destination_token = pt.create_token_account(&grant_mint, &owner.owner, 0);
+
+    let mut proposals = vec![];
+    for amount in [400, 600] {
+        let grant = to_instruction_data(
+            spl_token::instruction::transfer(
+                &spl_token::id(),
+                &treasury,
+                &destination_token,
+                &governance.address,
+                &[],
+                amount,
+            )
+            .unwrap(),
+        );
+        let proposal = pt.with_proposal(&realm, &governance, &owner);
+        pt.insert_transaction(&governance, &proposal, 0, 0, vec![grant.clone()]).unwrap();
+        proposals.push((proposal, grant));
+    }
+    let proposal_addresses: Vec<Pubkey> = proposals.iter().map(|(proposal, _)| proposal.address).collect();
+    let create_bundle =
+        instruction::create_proposal_bundle(&crate::id(), &owner.owner, &governance.address, &proposal_addresses);
+    pt.process(&create_bundle).unwrap();
+
+    for (index, (proposal, _)) in proposals.iter().enumerate() {
+        pt.sign_off_proposal(&realm, &governance, proposal).unwrap();
+        if approved_indexes.contains(&index) {
+            pt.cast_vote(&realm, &governance, proposal, &owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+        }
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    for (proposal, _) in &proposals {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    BundleCookie { governance, owner, proposals, destination_token }
+}
+
+/// Executes the bundle with the grant of each bundled proposal
+fn execute_bundle(pt: &mut ProgramTest, bundle: &BundleCookie) -> Result<(), ProgramError> {
+    let proposals: Vec<_> =
+        bundle.proposals.iter().map(|(proposal, grant)| (proposal.address, 0, vec![vec![grant.clone()]])).collect();
+    let keeper = pt.create_wallet();
+    pt.process(&instruction::execute_proposal_bundle(&crate::id(), &bundle.governance.address, &keeper, &proposals))
+}
+
+#[test]
+fn bundle_does_not_execute_unless_every_proposal_passed() {
+    let mut pt = ProgramTest::new();
+    let bundle = with_voted_bundle(&mut pt, &[0]);
+    let (approved, grant) = &bundle.proposals[0];
+    assert_eq!(pt.get_account_data::<Proposal>(&bundle.proposals[1].0.address).state, ProposalState::Expired);
+
+    assert_eq!(execute_bundle(&mut pt, &bundle), Err(GovernanceError::ProposalBundleNotApproved.into()));
+    // Nor can the approved proposal be executed on its own
+    assert_eq!(
+        pt.execute_transaction(&bundle.governance, approved, &bundle.owner, 0, std::slice::from_ref(grant)),
+        Err(GovernanceError::ProposalBundled.into())
+    );
+    assert_eq!(pt.get_token_state::<TokenAccount>(&bundle.destination_token).amount, 0);
+    assert_eq!(pt.get_account_data::<Proposal>(&approved.address).state, ProposalState::Approved);
+}
+
+#[test]
+fn bundle_executes_every_proposal_at_once() {
+    let mut pt = ProgramTest::new();
+    let bundle = with_voted_bundle(&mut pt, &[0, 1]);
+
+    execute_bundle(&mut pt, &bundle).unwrap();
+
+    assert_eq!(pt.get_token_state::<TokenAccount>(&bundle.destination_token).amount, 1_000);
+    for (proposal, _) in &bundle.proposals {
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+    }
+    let bundle_address = get_proposal_bundle_address(&crate::id(), &bundle.proposals[0].0.address);
+    let executed_at = pt.get_account_data::<ProposalBundle>(&bundle_address).executed_at;
+    assert_eq!(executed_at, Some(pt.clock().unix_timestamp as u64));
+    assert_eq!(execute_bundle(&mut pt, &bundle), Err(GovernanceError::ProposalBundleAlreadyExecuted.into()));
+}
@@ -0,0 +1,56 @@
+//! Certificates of finalized proposal outcomes, verifiable by relayers
+
+use super::{program_test::ProgramTest, setup::governance_config};
+use crate::{
+    get_proposal_certificate_address, GovernanceError, Proposal, ProposalCertificate, ProposalState, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn finalization_certifies_the_outcome_and_tallies() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let yes_voter = pt.with_token_owner(&realm, 700);
+    let no_voter = pt.with_token_owner(&realm, 300);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &yes_voter);
+    pt.cast_vote(&realm, &governance, &proposal, &yes_voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &no_voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    let certificate_address = get_proposal_certificate_address(&crate::id(), &proposal.address);
+    let certificate = pt.get_account_data::<ProposalCertificate>(&certificate_address);
+    let finalized = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(finalized.state, ProposalState::Approved);
+    assert_eq!((certificate.proposal, certificate.governance), (proposal.address, governance.address));
+    assert_eq!((certificate.state, certificate.winning_option), (ProposalState::Approved, Some(0)));
+    assert_eq!(certificate.vote_results, vec![(0, 700), (1, 300)]);
+    assert_eq!((certificate.total_vote_weight, certificate.max_vote_weight), (1_000, 1_000));
+    assert_eq!(certificate.finalized_at, pt.clock().unix_timestamp as u64);
+    certificate.verify(&crate::id(), &certificate_address, &proposal.address, &finalized).unwrap();
+
+    // Any change to the certified outcome breaks the attestation
+    let mut tampered = certificate.clone();
+    tampered.vote_results = vec![(0, 300), (1, 700)];
+    tampered.winning_option = Some(1);
+    let mut tampered_proposal = finalized.clone();
+    tampered_proposal.vote_results = tampered.vote_results.iter().copied().collect();
+    tampered_proposal.winning_option = Some(1);
+    assert_eq!(
+        tampered.verify(&crate::id(), &certificate_address, &proposal.address, &tampered_proposal),
+        Err(GovernanceError::InvalidProposalCertificate.into())
+    );
+
+    // Nor does the certificate vouch for another proposal or address
+    let other_proposal = Pubkey::new_unique();
+    assert_eq!(
+        certificate.verify(&crate::id(), &certificate_address, &other_proposal, &finalized),
+        Err(GovernanceError::InvalidProposalCertificate.into())
+    );
+    assert_eq!(
+        certificate.verify(&crate::id(), &Pubkey::new_unique(), &proposal.address, &finalized),
+        Err(GovernanceError::InvalidProposalCertificate.into())
+    );
+}
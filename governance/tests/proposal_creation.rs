@@ -0,0 +1,461 @@
+//! Creation of proposals
+
+use super::{
+    program_test::{Account, ProgramTest, WALLET_LAMPORTS},
+    setup::{
+        governance_config, proposal_args, realm_config_args, GovernanceCookie, ProposalArgs, ProposalCookie, RealmCookie,
+        TokenOwnerCookie,
+    },
+};
+use crate::{
+    instruction, Governance, GovernanceConfig, GovernanceError, Proposal, ProposalCreationFee, ProposalDeposit,
+    ProposalOption, ProposalState, ProposalThresholdSource, RealmConfigArgs, Vote, DEFAULT_VOTING_PERIOD_DAYS,
+    MAX_DESCRIPTION_LINK_LENGTH,
+};
+use solana_program::{instruction::AccountMeta, keccak, pubkey::Pubkey, system_program};
+
+const CREATION_FEE: u64 = 1_000_000;
+const CREATION_DEPOSIT: u64 = 10_000_000;
+
+#[test]
+fn create_proposal_rejects_an_existing_proposal_account() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    // Neither the owner nor anyone else can reset the proposal and its votes
+    let other_owner = pt.with_token_owner(&realm, 1_000);
+    for owner in [&token_owner, &other_owner] {
+        assert_eq!(
+            pt.create_proposal(&realm, &governance, owner, &proposal),
+            Err(GovernanceError::AccountAlreadyInitialized.into())
+        );
+    }
+
+    let stored = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(stored.state, ProposalState::Active);
+    assert_eq!(stored.proposal_owner, token_owner.owner);
+    assert_eq!(stored.vote_results[&0], 1_000);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).open_proposal_count, 1);
+}
+
+/// Charges the fee and deposit for proposals of a new realm, returning the realm, the
+/// treasury, a governance refunding the given share of rejected deposits and a token owner
+fn with_creation_fee(
+    pt: &mut ProgramTest,
+    rejection_refund_bps: u16,
+) -> (RealmCookie, Pubkey, GovernanceCookie, TokenOwnerCookie) {
+    let realm = pt.with_realm();
+    let treasury = pt.create_wallet();
+    pt.process(&instruction::set_realm_config(
+        &crate::id(),
+        &realm.address,
+        &realm.authority,
+        &[],
+        RealmConfigArgs {
+            proposal_creation_fee: Some(ProposalCreationFee {
+                lamports: CREATION_FEE,
+                treasury,
+                deposit_lamports: CREATION_DEPOSIT,
+            }),
+            ..realm_config_args()
+        },
+    ))
+    .unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { rejection_refund_bps, ..governance_config() });
+
+    (realm, treasury, governance, token_owner)
+}
+
+#[test]
+fn creation_fee_is_paid_to_the_treasury_and_the_deposit_held() {
+    let mut pt = ProgramTest::new();
+    let (realm, treasury, governance, token_owner) = with_creation_fee(&mut pt, 5_000);
+    let owner_lamports = pt.lamports(&token_owner.owner);
+
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+
+    // The fee is paid even though rejections refund part of the deposit
+    assert_eq!(pt.lamports(&treasury), WALLET_LAMPORTS + CREATION_FEE);
+    let proposal_rent = pt.lamports(&proposal.address) - CREATION_DEPOSIT;
+    assert_eq!(pt.lamports(&token_owner.owner), owner_lamports - CREATION_FEE - CREATION_DEPOSIT - proposal_rent);
+    assert_eq!(
+        pt.get_account_data::<Proposal>(&proposal.address).creation_fee_deposit,
+        Some(ProposalDeposit { lamports: CREATION_DEPOSIT, treasury })
+    );
+}
+
+#[test]
+fn creation_fee_needs_the_fee_and_deposit_in_lamports() {
+    let mut pt = ProgramTest::new();
+    let (realm, treasury, governance, token_owner) = with_creation_fee(&mut pt, 0);
+    let owner_lamports = CREATION_FEE + CREATION_DEPOSIT - 1;
+    pt.set_account(
+        token_owner.owner,
+        Account { lamports: owner_lamports, data: vec![], owner: system_program::id(), executable: false },
+    );
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+
+    assert_eq!(
+        pt.create_proposal(&realm, &governance, &token_owner, &proposal),
+        Err(GovernanceError::InsufficientFundsForFee.into())
+    );
+    assert_eq!(pt.lamports(&treasury), WALLET_LAMPORTS);
+    assert_eq!(pt.lamports(&token_owner.owner), owner_lamports);
+    assert_eq!(pt.lamports(&proposal.address), 0);
+}
+
+#[test]
+fn rejected_deposit_is_split_between_owner_and_treasury() {
+    for (rejection_refund_bps, refund_lamports) in [(10_000, CREATION_DEPOSIT), (0, 0), (2_500, CREATION_DEPOSIT / 4)] {
+        let mut pt = ProgramTest::new();
+        let (realm, treasury, governance, token_owner) = with_creation_fee(&mut pt, rejection_refund_bps);
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+        // Abstaining reaches quorum without backing an option
+        pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::Abstain).unwrap();
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Rejected);
+
+        let owner_lamports = pt.lamports(&token_owner.owner);
+        let treasury_lamports = pt.lamports(&treasury);
+        pt.process(&instruction::release_creation_fee_deposit(
+            &crate::id(),
+            &governance.address,
+            &proposal.address,
+            &token_owner.owner,
+            &treasury,
+        ))
+        .unwrap();
+
+        assert_eq!(pt.lamports(&token_owner.owner), owner_lamports + refund_lamports, "{} bps", rejection_refund_bps);
+        assert_eq!(
+            pt.lamports(&treasury),
+            treasury_lamports + CREATION_DEPOSIT - refund_lamports,
+            "{} bps",
+            rejection_refund_bps
+        );
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).creation_fee_deposit, None);
+    }
+}
+
+#[test]
+fn approved_proposal_gets_its_whole_deposit_back() {
+    let mut pt = ProgramTest::new();
+    let (realm, treasury, governance, token_owner) = with_creation_fee(&mut pt, 0);
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    let release = instruction::release_creation_fee_deposit(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &token_owner.owner,
+        &treasury,
+    );
+    assert_eq!(pt.process(&release), Err(GovernanceError::InvalidProposalState.into()));
+
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let owner_lamports = pt.lamports(&token_owner.owner);
+    pt.process(&release).unwrap();
+
+    assert_eq!(pt.lamports(&token_owner.owner), owner_lamports + CREATION_DEPOSIT);
+    assert_eq!(pt.lamports(&treasury), WALLET_LAMPORTS + CREATION_FEE);
+    assert_eq!(pt.process(&release), Err(GovernanceError::NoCreationFeeDeposit.into()));
+}
+
+#[test]
+fn governance_threshold_overrides_the_realm_threshold() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let realm_threshold = ProposalThresholdSource::Absolute { value: 200 };
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs { min_community_tokens_to_create_proposal: realm_threshold, ..realm_config_args() },
+    )
+    .unwrap();
+    let small_holder = pt.with_token_owner(&realm, 200);
+    let large_holder = pt.with_token_owner(&realm, 500);
+    let social = pt.with_governance(&realm, governance_config());
+    let treasury = pt.with_governance(
+        &realm,
+        GovernanceConfig {
+            min_community_tokens_to_create_proposal: Some(ProposalThresholdSource::Absolute { value: 500 }),
+            ..governance_config()
+        },
+    );
+
+    // The treasury governance is stricter than the realm, the social one falls back to it
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: small_holder.owner };
+    assert_eq!(
+        pt.create_proposal(&realm, &treasury, &small_holder, &proposal),
+        Err(GovernanceError::NotEnoughTokensToCreateProposal.into())
+    );
+    pt.with_proposal(&realm, &treasury, &large_holder);
+    pt.with_proposal(&realm, &social, &small_holder);
+
+    let below_realm_threshold = pt.with_token_owner(&realm, 199);
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: below_realm_threshold.owner };
+    assert_eq!(
+        pt.create_proposal(&realm, &social, &below_realm_threshold, &proposal),
+        Err(GovernanceError::NotEnoughTokensToCreateProposal.into())
+    );
+}
+
+#[test]
+fn choice_proposals_need_at_least_two_options() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+
+    for options in [vec![], vec!["Yes".to_string()]] {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        assert_eq!(
+            pt.create_proposal_with_args(
+                &realm,
+                &governance,
+                &token_owner,
+                &proposal,
+                ProposalArgs { options, ..proposal_args() }
+            ),
+            Err(GovernanceError::TooFewOptions.into())
+        );
+    }
+
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.options.len(), 2);
+    assert_eq!(proposal.vote_results.len(), 2);
+}
+
+#[test]
+fn denial_quorum_proposal_needs_no_options() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        ProposalArgs { options: vec![], use_denial_quorum: true, ..proposal_args() },
+    );
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert!(proposal.is_approve_by_default());
+    assert_eq!(proposal.get_executable_option_count(), 1);
+}
+
+#[test]
+fn description_link_must_point_off_chain_within_the_length_cap() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let create_proposal = |pt: &mut ProgramTest, description_link: String| {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        pt.create_proposal_with_args(
+            &realm,
+            &governance,
+            &token_owner,
+            &proposal,
+            ProposalArgs { description_link, ..proposal_args() },
+        )
+    };
+    let at_length = |prefix: &str, length| format!("{}{}", prefix, "a".repeat(length - prefix.len()));
+
+    create_proposal(&mut pt, "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string()).unwrap();
+    create_proposal(&mut pt, at_length("https://", MAX_DESCRIPTION_LINK_LENGTH)).unwrap();
+    for description_link in [
+        "ftp://leapfrogdao.org/proposal".to_string(),
+        "ar://".to_string(),
+        "Inline proposal text".to_string(),
+        at_length("https://", MAX_DESCRIPTION_LINK_LENGTH + 1),
+    ] {
+        assert_eq!(create_proposal(&mut pt, description_link), Err(GovernanceError::InvalidDescriptionLink.into()));
+    }
+
+    // Realms allowing plain text still cap its length
+    pt.set_realm_config(&realm, RealmConfigArgs { allow_plain_text_description_link: true, ..realm_config_args() })
+        .unwrap();
+    create_proposal(&mut pt, "Inline proposal text".to_string()).unwrap();
+    assert_eq!(
+        create_proposal(&mut pt, at_length("", MAX_DESCRIPTION_LINK_LENGTH + 1)),
+        Err(GovernanceError::InvalidDescriptionLink.into())
+    );
+}
+
+#[test]
+fn labeled_options_track_their_inserted_transactions() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let labels = ["Fund", "Defer", "Reject"];
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        ProposalArgs { options: labels.iter().map(|label| label.to_string()).collect(), ..proposal_args() },
+    );
+
+    for (option_index, transaction_count) in [(0, 2), (1, 1)] {
+        for transaction_index in 0..transaction_count {
+            pt.insert_transaction(&governance, &proposal, option_index, transaction_index, vec![]).unwrap();
+        }
+    }
+    assert_eq!(
+        pt.insert_transaction(&governance, &proposal, 3, 0, vec![]),
+        Err(GovernanceError::InvalidProposalTransaction.into())
+    );
+
+    let options = pt.get_account_data::<Proposal>(&proposal.address).get_options();
+    assert_eq!(
+        options,
+        [("Fund", 2), ("Defer", 1), ("Reject", 0)]
+            .map(|(label, transaction_count)| ProposalOption { label: label.to_string(), transaction_count })
+    );
+}
+
+#[test]
+fn description_hash_is_stored_and_required_when_the_governance_says_so() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { require_description_hash: true, ..governance_config() });
+
+    // Neither a missing nor a zeroed hash commits to anything
+    for description_hash in [None, Some([0; 32])] {
+        let unhashed = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        assert_eq!(
+            pt.create_proposal_with_args(
+                &realm,
+                &governance,
+                &token_owner,
+                &unhashed,
+                ProposalArgs { description_hash, ..proposal_args() },
+            ),
+            Err(GovernanceError::MissingDescriptionHash.into())
+        );
+    }
+
+    let description_hash = Some(keccak::hash(b"Fund the Leapfrog grants program").to_bytes());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        ProposalArgs { description_hash, ..proposal_args() },
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).description_hash, description_hash);
+
+    // Other governances take the hash as it's passed
+    let optional = pt.with_governance(&realm, governance_config());
+    for description_hash in [None, Some([0; 32])] {
+        let args = ProposalArgs { description_hash, ..proposal_args() };
+        let proposal = pt.with_proposal_args(&realm, &optional, &token_owner, args);
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).description_hash, description_hash);
+    }
+}
+
+#[test]
+fn permissioned_realms_only_take_proposals_from_allowlisted_creators() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            min_community_tokens_to_create_proposal: ProposalThresholdSource::Absolute { value: 200 },
+            permissioned_proposal_creation: true,
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    let creator = pt.with_token_owner(&realm, 1_000);
+    let small_creator = pt.with_token_owner(&realm, 100);
+    let stranger = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 4, ..governance_config() });
+    for owner in [&creator, &small_creator] {
+        let add_creator =
+            instruction::add_proposal_creator(&crate::id(), &realm.address, &realm.authority, &[], owner.owner);
+        pt.process(&add_creator).unwrap();
+    }
+
+    let create_proposal = |pt: &mut ProgramTest, owner: &TokenOwnerCookie| {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: owner.owner };
+        pt.create_proposal(&realm, &governance, owner, &proposal)
+    };
+    create_proposal(&mut pt, &creator).unwrap();
+    assert_eq!(create_proposal(&mut pt, &stranger), Err(GovernanceError::ProposalCreatorNotAllowed.into()));
+    // Allowlisted creators still need the token threshold
+    assert_eq!(
+        create_proposal(&mut pt, &small_creator),
+        Err(GovernanceError::NotEnoughTokensToCreateProposal.into())
+    );
+
+    let remove_creator =
+        instruction::remove_proposal_creator(&crate::id(), &realm.address, &realm.authority, &[], creator.owner);
+    pt.process(&remove_creator).unwrap();
+    assert_eq!(create_proposal(&mut pt, &creator), Err(GovernanceError::ProposalCreatorNotAllowed.into()));
+
+    // Permissionless, anyone with the threshold can propose
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            min_community_tokens_to_create_proposal: ProposalThresholdSource::Absolute { value: 200 },
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    create_proposal(&mut pt, &stranger).unwrap();
+    assert_eq!(
+        create_proposal(&mut pt, &small_creator),
+        Err(GovernanceError::NotEnoughTokensToCreateProposal.into())
+    );
+}
+
+#[test]
+fn supply_fraction_threshold_follows_the_community_supply() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let holder = pt.with_token_owner(&realm, 200);
+    pt.create_token_account(&realm.community_mint, &Pubkey::new_unique(), 800);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 4, ..governance_config() });
+
+    // A supply fraction threshold reads the community mint, passed last
+    let create_proposal = |pt: &mut ProgramTest, community_mint: Option<Pubkey>| {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: holder.owner };
+        let mut create_proposal =
+            pt.create_proposal_instruction(&realm, &governance, &holder, &proposal, proposal_args());
+        create_proposal.accounts.extend(community_mint.map(|mint| AccountMeta::new_readonly(mint, false)));
+        pt.process(&create_proposal)
+    };
+    let set_threshold = |pt: &mut ProgramTest, min_community_tokens_to_create_proposal| {
+        let config_args = RealmConfigArgs { min_community_tokens_to_create_proposal, ..realm_config_args() };
+        pt.set_realm_config(&realm, config_args).unwrap();
+    };
+
+    // 10% of the 1,000 supply
+    set_threshold(&mut pt, ProposalThresholdSource::SupplyFraction { bps: 1_000 });
+    create_proposal(&mut pt, Some(realm.community_mint)).unwrap();
+    assert_eq!(
+        create_proposal(&mut pt, Some(Pubkey::new_unique())),
+        Err(GovernanceError::InvalidGoverningTokenMint.into())
+    );
+
+    // Once the supply grows to 4,000, the holder's 200 fall short of 10%
+    pt.create_token_account(&realm.community_mint, &Pubkey::new_unique(), 3_000);
+    assert_eq!(
+        create_proposal(&mut pt, Some(realm.community_mint)),
+        Err(GovernanceError::NotEnoughTokensToCreateProposal.into())
+    );
+
+    // An absolute threshold ignores the supply
+    set_threshold(&mut pt, ProposalThresholdSource::Absolute { value: 200 });
+    create_proposal(&mut pt, None).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Proposals executable only once the proposal they depend on has been executed
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, GovernanceCookie, ProposalArgs, ProposalCookie, TokenOwnerCookie},
+};
+use crate::{instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, Vote, DEFAULT_VOTING_PERIOD_DAYS};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+
+fn execute(
+    pt: &mut ProgramTest,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+    owner: &TokenOwnerCookie,
+    depends_on: Option<&Pubkey>,
+) -> ProgramResult {
+    pt.process(&instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &owner.token_owner_record,
+        &owner.owner,
+        depends_on,
+        0,
+        0,
+        &[],
+    ))
+}
+
+#[test]
+fn dependent_proposal_executes_only_after_its_prerequisite() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let prerequisite = pt.with_proposal(&realm, &governance, &token_owner);
+    let dependent = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        ProposalArgs { depends_on: Some(prerequisite.address), ..proposal_args() },
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&dependent.address).depends_on, Some(prerequisite.address));
+
+    for proposal in [&prerequisite, &dependent] {
+        pt.insert_transaction(&governance, proposal, 0, 0, vec![]).unwrap();
+        pt.sign_off_proposal(&realm, &governance, proposal).unwrap();
+        pt.cast_vote(&realm, &governance, proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    for proposal in [&prerequisite, &dependent] {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    assert_eq!(
+        execute(&mut pt, &governance, &dependent, &token_owner, Some(&prerequisite.address)),
+        Err(GovernanceError::ProposalDependencyNotExecuted.into())
+    );
+    assert_eq!(
+        execute(&mut pt, &governance, &dependent, &token_owner, Some(&dependent.address)),
+        Err(GovernanceError::InvalidProposalDependency.into())
+    );
+
+    execute(&mut pt, &governance, &prerequisite, &token_owner, None).unwrap();
+    execute(&mut pt, &governance, &dependent, &token_owner, Some(&prerequisite.address)).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&dependent.address).state, ProposalState::Executed);
+}
+
+#[test]
+fn dependency_must_belong_to_the_same_governance() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let other_governance = pt.with_governance(&realm, governance_config());
+    let prerequisite = pt.with_proposal(&realm, &other_governance, &token_owner);
+
+    let dependent = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    assert_eq!(
+        pt.create_proposal_with_args(
+            &realm,
+            &governance,
+            &token_owner,
+            &dependent,
+            ProposalArgs { depends_on: Some(prerequisite.address), ..proposal_args() },
+        ),
+        Err(GovernanceError::InvalidProposalDependency.into())
+    );
+}
@@ -0,0 +1,579 @@
+//! Sign-off, finalization and expiry of proposals
+
+use super::{
+    program_test::{ProgramTest, START_TIME},
+    setup::{governance_config, realm_config_args, to_instruction_data, GovernanceCookie, ProposalCookie, RealmCookie},
+};
+use crate::{
+    get_proposal_archive_address, get_proposal_certificate_address, get_proposal_transaction_address,
+    get_vote_record_address, instruction, ArchivedProposal, ExecutionSimulation, Governance, GovernanceConfig,
+    GovernanceError, OptionTieBreak, Proposal, ProposalArchive, ProposalCertificate, ProposalState,
+    ProposalTransaction, RealmConfigArgs, TallySnapshot, Vote, VoteRecord, DEFAULT_UNSTAKING_COOLDOWN_SECONDS,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+
+/// Mints community tokens nobody stakes, so a vote of 1,000 staked tokens stays below the 10% quorum
+fn dilute_community_supply(pt: &mut ProgramTest, realm: &RealmCookie) {
+    let holder = pt.create_wallet();
+    pt.create_token_account(&realm.community_mint, &holder, 1_000_000);
+}
+
+/// Asserts the touch left the proposal Active, and that FinalizeVote then expires, archives and certifies it
+fn assert_expired_by_finalize_vote(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    proposal: &ProposalCookie,
+) {
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Active);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).active_proposal_count, 1);
+
+    pt.finalize_vote(realm, governance, proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Expired);
+    let governance = pt.get_account_data::<Governance>(&governance.address);
+    assert_eq!(governance.active_proposal_count, 0);
+    assert_eq!(governance.quorum_failure_count, 1);
+    let archive = pt.get_account_data::<ProposalArchive>(&get_proposal_archive_address(&crate::id(), &realm.address, 0));
+    assert_eq!(
+        archive.proposals,
+        vec![ArchivedProposal { proposal: proposal.address, state: ProposalState::Expired, winning_option: None }]
+    );
+    let certificate_address = get_proposal_certificate_address(&crate::id(), &proposal.address);
+    assert_eq!(pt.get_account_data::<ProposalCertificate>(&certificate_address).state, ProposalState::Expired);
+}
+
+#[test]
+fn sign_off_is_capped_at_max_concurrent_proposals() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+
+    let first = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    let third = pt.with_proposal(&realm, &governance, &token_owner);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).active_proposal_count, 2);
+
+    assert_eq!(
+        pt.sign_off_proposal(&realm, &governance, &third),
+        Err(GovernanceError::TooManyActiveProposals.into())
+    );
+
+    // Finalizing an active proposal frees its slot
+    pt.cast_vote(&realm, &governance, &first, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &first).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&first.address).state, ProposalState::Approved);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).active_proposal_count, 1);
+
+    pt.sign_off_proposal(&realm, &governance, &third).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&third.address).state, ProposalState::Active);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).active_proposal_count, 2);
+}
+
+#[test]
+fn sign_off_waits_for_the_draft_period() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { min_draft_period_seconds: 3_600, ..governance_config() });
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+
+    pt.advance_clock(3_599);
+    assert_eq!(
+        pt.sign_off_proposal(&realm, &governance, &proposal),
+        Err(GovernanceError::DraftPeriodNotElapsed.into())
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Draft);
+
+    pt.advance_clock(1);
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Active);
+}
+
+#[test]
+fn vote_after_the_deadline_without_quorum_fails_with_voting_time_expired() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let late_voter = pt.with_token_owner(&realm, 1_000);
+    dilute_community_supply(&mut pt, &realm);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    assert_eq!(
+        pt.cast_vote(&realm, &governance, &proposal, &late_voter, Vote::SingleChoice { option_index: 0 }),
+        Err(GovernanceError::VotingTimeExpired.into())
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).total_vote_weight, 1_000);
+    assert_expired_by_finalize_vote(&mut pt, &realm, &governance, &proposal);
+}
+
+#[test]
+fn vote_after_the_deadline_fails_once_quorum_is_reached() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let late_voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    assert_eq!(
+        pt.cast_vote(&realm, &governance, &proposal, &late_voter, Vote::SingleChoice { option_index: 0 }),
+        Err(GovernanceError::VotingPeriodEnded.into())
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Active);
+
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Approved);
+}
+
+#[test]
+fn tally_snapshot_after_the_deadline_projects_the_expiry_without_quorum() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    dilute_community_supply(&mut pt, &realm);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let tally_snapshot_ix = instruction::tally_snapshot(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+    );
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.process(&tally_snapshot_ix).unwrap();
+    assert_eq!(pt.last_event::<TallySnapshot>().projected_state, ProposalState::Expired);
+    assert_expired_by_finalize_vote(&mut pt, &realm, &governance, &proposal);
+}
+
+#[test]
+fn simulate_execution_after_the_deadline_reports_the_expiry_without_quorum() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    dilute_community_supply(&mut pt, &realm);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &voter);
+    pt.process(&instruction::insert_proposal_transaction(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &proposal.owner,
+        0,
+        0,
+        vec![],
+    ))
+    .unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.process(&instruction::simulate_execution(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+        0,
+        0,
+        &[],
+    ))
+    .unwrap();
+    assert_eq!(pt.last_event::<ExecutionSimulation>().proposal_state, ProposalState::Expired);
+    assert_expired_by_finalize_vote(&mut pt, &realm, &governance, &proposal);
+}
+
+#[test]
+fn approved_proposal_executes_its_inserted_transaction() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &voter);
+    pt.process(&instruction::insert_proposal_transaction(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &proposal.owner,
+        0,
+        0,
+        vec![],
+    ))
+    .unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    pt.process(&instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &voter.token_owner_record,
+        &voter.owner,
+        None,
+        0,
+        0,
+        &[],
+    ))
+    .unwrap();
+
+    let transaction = pt.get_account_data::<ProposalTransaction>(&get_proposal_transaction_address(
+        &crate::id(),
+        &proposal.address,
+        0,
+        0,
+    ));
+    assert_eq!(transaction.executed_at, Some(pt.clock().unix_timestamp as u64));
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Executed);
+}
+
+#[test]
+fn transaction_calling_back_into_execute_proposal_is_rejected() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &voter);
+
+    // The proposal's only transaction executes that same transaction again, signed by the governance
+    let reenter = to_instruction_data(instruction::execute_proposal(
+        &crate::id(),
+        &governance.address,
+        &proposal.address,
+        &voter.token_owner_record,
+        &governance.address,
+        None,
+        0,
+        0,
+        &[],
+    ));
+    pt.insert_transaction(&governance, &proposal, 0, 0, vec![reenter.clone()]).unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    assert_eq!(
+        pt.execute_transaction(&governance, &proposal, &voter, 0, &[reenter]),
+        Err(GovernanceError::ProposalExecutionInProgress.into())
+    );
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert!(!proposal.is_executing);
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert_eq!(proposal.executed_transaction_count, 0);
+}
+
+#[test]
+fn finalize_votes_finalizes_only_the_ended_proposals_of_the_batch() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 3, ..governance_config() });
+    let approved = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    let expired = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.cast_vote(&realm, &governance, &approved, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    // The last proposal is signed off halfway through the others' voting period
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 43_200);
+    let active = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 43_200);
+
+    let keeper = pt.create_wallet();
+    let proposals: Vec<_> =
+        [&approved, &active, &expired].iter().map(|proposal| (proposal.address, governance.address)).collect();
+    pt.process(&instruction::finalize_votes(&crate::id(), &realm.address, &realm.community_mint, &keeper, &proposals, 0))
+        .unwrap();
+
+    assert!(pt.logs().contains(&"Finalized 2 proposals".to_string()));
+    let state = |pt: &ProgramTest, proposal: &ProposalCookie| pt.get_account_data::<Proposal>(&proposal.address).state;
+    assert_eq!(state(&pt, &approved), ProposalState::Approved);
+    assert_eq!(state(&pt, &expired), ProposalState::Expired);
+    assert_eq!(state(&pt, &active), ProposalState::Active);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).active_proposal_count, 1);
+}
+
+#[test]
+fn recompute_tally_rebuilds_a_corrupted_tally_from_every_vote_record() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let yes_voter = pt.with_token_owner(&realm, 600);
+    let no_voter = pt.with_token_owner(&realm, 400);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &yes_voter);
+    pt.cast_vote(&realm, &governance, &proposal, &yes_voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &no_voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+    let votes: Vec<_> = [&yes_voter, &no_voter]
+        .iter()
+        .map(|voter| {
+            let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+            (vote_record, voter.token_owner_record)
+        })
+        .collect();
+    let recompute_tally = |authority, votes: &[(Pubkey, Pubkey)]| {
+        instruction::recompute_tally(
+            &crate::id(),
+            authority,
+            &[],
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            votes,
+        )
+    };
+
+    // The corruption flips the outcome of the vote
+    let tallied = pt.get_account_data::<Proposal>(&proposal.address);
+    let mut corrupted = tallied.clone();
+    corrupted.vote_results.insert(1, 4_000);
+    corrupted.total_vote_weight = 4_600;
+    pt.set_account_data(&proposal.address, &corrupted);
+
+    assert_eq!(
+        pt.process(&recompute_tally(&realm.authority, &votes)),
+        Err(GovernanceError::VotingPeriodNotEnded.into())
+    );
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    let outsider = pt.create_wallet();
+    assert_eq!(
+        pt.process(&recompute_tally(&outsider, &votes)),
+        Err(GovernanceError::InvalidRealmAuthority.into())
+    );
+    assert_eq!(
+        pt.process(&recompute_tally(&realm.authority, &votes[..1])),
+        Err(GovernanceError::IncompleteVoteRecords.into())
+    );
+    assert_eq!(
+        pt.process(&recompute_tally(&realm.authority, &[votes[0], votes[0]])),
+        Err(GovernanceError::IncompleteVoteRecords.into())
+    );
+
+    pt.process(&recompute_tally(&realm.authority, &votes)).unwrap();
+    let recomputed = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(recomputed.vote_results, tallied.vote_results);
+    assert_eq!(recomputed.total_vote_weight, 1_000);
+
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Approved);
+}
+
+#[test]
+fn recompute_tally_weighs_votes_again_with_the_remaining_deposit() {
+    const INACTIVITY_THRESHOLD_SECONDS: u64 = 86_400;
+    const TOKEN: u64 = 1_000_000_000;
+
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm_decimals(9);
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            normalize_vote_weight_to_decimals: true,
+            max_vote_weight_per_voter: Some(80),
+            inactivity_threshold_seconds: Some(INACTIVITY_THRESHOLD_SECONDS),
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    let whale = pt.with_token_owner(&realm, 10_000 * TOKEN);
+    let voter = pt.with_token_owner(&realm, 2_500 * TOKEN);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &whale);
+    pt.cast_vote(&realm, &governance, &proposal, &whale, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+    let votes: Vec<_> = [&whale, &voter]
+        .iter()
+        .map(|voter| {
+            let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+            (vote_record, voter.token_owner_record)
+        })
+        .collect();
+    assert_eq!(pt.get_account_data::<VoteRecord>(&votes[0].0).vote_weight, 80);
+
+    // Once the realm goes inactive, an emergency unstake drops the whale's deposit below its vote's stake
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.advance_clock(INACTIVITY_THRESHOLD_SECONDS.max(DEFAULT_UNSTAKING_COOLDOWN_SECONDS));
+    pt.process(&instruction::emergency_unstake(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &whale.owner,
+        &realm.staking_vault,
+        &whale.token_account,
+        5_100 * TOKEN,
+    ))
+    .unwrap();
+    pt.process(&instruction::recompute_tally(
+        &crate::id(),
+        &realm.authority,
+        &[],
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &votes,
+    ))
+    .unwrap();
+
+    // The remaining 4,900 tokens weigh 70 under the curve, below the cap the stake was cut to
+    let whale_vote = pt.get_account_data::<VoteRecord>(&votes[0].0);
+    assert_eq!((whale_vote.stake_amount, whale_vote.vote_weight), (4_900 * TOKEN, 70));
+    assert_eq!(whale_vote.option_vote_weights, vec![(0, 70)]);
+    let voter_vote = pt.get_account_data::<VoteRecord>(&votes[1].0);
+    assert_eq!((voter_vote.stake_amount, voter_vote.vote_weight), (2_500 * TOKEN, 50));
+
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let finalized = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!((finalized.vote_results[&0], finalized.vote_results[&1]), (70, 50));
+    assert_eq!(finalized.total_vote_weight, 120);
+    assert_eq!(finalized.state, ProposalState::Approved);
+}
+
+#[test]
+fn abstentions_count_towards_quorum_only_when_the_realm_says_so() {
+    for (abstain_counts_for_quorum, state) in [(true, ProposalState::Approved), (false, ProposalState::Expired)] {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        pt.set_realm_config(&realm, RealmConfigArgs { abstain_counts_for_quorum, ..realm_config_args() }).unwrap();
+        let supporter = pt.with_token_owner(&realm, 50);
+        let abstainer = pt.with_token_owner(&realm, 950);
+        let idle_holder = pt.create_wallet();
+        pt.create_token_account(&realm.community_mint, &idle_holder, 9_000);
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &supporter);
+
+        // Only with the abstentions do the votes reach the 10% quorum of 10,000 tokens
+        pt.cast_vote(&realm, &governance, &proposal, &supporter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        pt.cast_vote(&realm, &governance, &proposal, &abstainer, Vote::Abstain).unwrap();
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+        assert_eq!(
+            pt.get_account_data::<Proposal>(&proposal.address).state,
+            state,
+            "abstain_counts_for_quorum: {}",
+            abstain_counts_for_quorum
+        );
+    }
+}
+
+#[test]
+fn tally_snapshot_projects_the_finalization_of_the_current_tallies() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let first_supporter = pt.with_token_owner(&realm, 1_000);
+    let opponent = pt.with_token_owner(&realm, 500);
+    let second_supporter = pt.with_token_owner(&realm, 1_000);
+    let idle_holder = pt.create_wallet();
+    pt.create_token_account(&realm.community_mint, &idle_holder, 20_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &first_supporter);
+    let tally_snapshot_ix = instruction::tally_snapshot(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &realm.community_mint,
+    );
+
+    // 1,000 of 22,500 tokens are below the 10% quorum
+    pt.cast_vote(&realm, &governance, &proposal, &first_supporter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.process(&tally_snapshot_ix).unwrap();
+    assert_eq!(
+        pt.last_event::<TallySnapshot>(),
+        TallySnapshot {
+            vote_results: vec![(0, 1_000), (1, 0)],
+            total_vote_weight: 1_000,
+            abstain_vote_weight: 0,
+            quorum_reached: false,
+            projected_state: ProposalState::Expired,
+            projected_winning_option: None,
+        }
+    );
+
+    pt.cast_vote(&realm, &governance, &proposal, &opponent, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &second_supporter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.process(&tally_snapshot_ix).unwrap();
+    let snapshot = pt.last_event::<TallySnapshot>();
+    assert_eq!(
+        snapshot,
+        TallySnapshot {
+            vote_results: vec![(0, 2_000), (1, 500)],
+            total_vote_weight: 2_500,
+            abstain_vote_weight: 0,
+            quorum_reached: true,
+            projected_state: ProposalState::Approved,
+            projected_winning_option: Some(0),
+        }
+    );
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Active);
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.state, snapshot.projected_state);
+    assert_eq!(proposal.winning_option, snapshot.projected_winning_option);
+}
+
+#[test]
+fn execution_window_closes_then_the_proposal_expires() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { execution_window_seconds: Some(86_400), ..governance_config() });
+    let expire_proposal = |proposal: &ProposalCookie| {
+        instruction::expire_proposal(&crate::id(), &governance.address, &proposal.address)
+    };
+
+    // Executable up to the last second of the window
+    let executed = pt.with_approved_proposal(&realm, &governance, &voter, vec![vec![]]);
+    pt.advance_clock(86_400);
+    assert_eq!(pt.process(&expire_proposal(&executed)), Err(GovernanceError::ExecutionWindowOpen.into()));
+    pt.execute_transaction(&governance, &executed, &voter, 0, &[]).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&executed.address).state, ProposalState::Executed);
+
+    let stale = pt.with_approved_proposal(&realm, &governance, &voter, vec![vec![]]);
+    pt.advance_clock(86_401);
+    assert_eq!(
+        pt.execute_transaction(&governance, &stale, &voter, 0, &[]),
+        Err(GovernanceError::ExecutionWindowElapsed.into())
+    );
+    let open_proposal_count = pt.get_account_data::<Governance>(&governance.address).open_proposal_count;
+    pt.process(&expire_proposal(&stale)).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&stale.address).state, ProposalState::Expired);
+    assert_eq!(pt.get_account_data::<Governance>(&governance.address).open_proposal_count, open_proposal_count - 1);
+}
+
+#[test]
+fn tied_options_are_broken_by_the_earliest_lead_when_configured() {
+    // Finalizes a vote where No reaches 500 an hour before Yes ties it
+    let winning_option = |option_tie_break| {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let (yes_voter, no_voter) = (pt.with_token_owner(&realm, 500), pt.with_token_owner(&realm, 500));
+        let governance = pt.with_governance(&realm, GovernanceConfig { option_tie_break, ..governance_config() });
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &yes_voter);
+
+        pt.cast_vote(&realm, &governance, &proposal, &no_voter, Vote::SingleChoice { option_index: 1 }).unwrap();
+        pt.advance_clock(3_600);
+        pt.cast_vote(&realm, &governance, &proposal, &yes_voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+        let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+        assert_eq!(proposal.option_lead_reached_at, vec![START_TIME as u64 + 3_600, START_TIME as u64]);
+        assert_eq!(proposal.state, ProposalState::Approved);
+        proposal.winning_option
+    };
+
+    assert_eq!(winning_option(OptionTieBreak::EarliestLead), Some(1));
+    assert_eq!(winning_option(OptionTieBreak::LowestIndex), Some(0));
+}
@@ -0,0 +1,269 @@
+//! Quadratic vote weights and the sybil resistance scores scaling them
+
+use super::{
+    program_test::{ProgramTest, START_TIME},
+    setup::{
+        governance_config, proposal_args, realm_config_args, ProposalArgs, ProposalCookie, RealmCookie, TokenOwnerCookie,
+    },
+};
+use crate::{
+    get_vote_record_address, instruction, GovernanceConfig, GovernanceError, Proposal, QuadraticCreditBudget,
+    QuadraticWeightedVotePolicy, Realm, RealmConfigArgs, TokenOwnerRecord, Vote, VoteRecord, VoteType, VoteWeightCurve,
+};
+use solana_program::pubkey::Pubkey;
+
+/// Creates a quadratic realm giving each option of a weighted vote the square root of the
+/// stake allocated to it
+fn with_per_option_square_root_realm(pt: &mut ProgramTest) -> RealmCookie {
+    let realm = pt.with_quadratic_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy::PerOptionSquareRoot,
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    realm
+}
+
+fn vote_weight(pt: &ProgramTest, proposal: &ProposalCookie, voter: &TokenOwnerCookie) -> u64 {
+    pt.get_account_data::<VoteRecord>(&get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record))
+        .vote_weight
+}
+
+#[test]
+fn low_sybil_score_scales_down_the_quadratic_vote_weight() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm();
+    let sybil_oracle = pt.create_wallet();
+    pt.set_realm_config(&realm, RealmConfigArgs { sybil_oracle: Some(sybil_oracle), ..realm_config_args() }).unwrap();
+    let verified = pt.with_token_owner(&realm, 10_000);
+    let unverified = pt.with_token_owner(&realm, 10_000);
+    let governance = pt.with_governance(&realm, governance_config());
+
+    // Only the realm's oracle sets scores
+    let set_score = |oracle| {
+        instruction::set_sybil_score(&crate::id(), &realm.address, oracle, &unverified.token_owner_record, 25)
+    };
+    let outsider = pt.create_wallet();
+    assert_eq!(pt.process(&set_score(&outsider)), Err(GovernanceError::InvalidSybilOracle.into()));
+    pt.process(&set_score(&sybil_oracle)).unwrap();
+    assert_eq!(
+        pt.get_account_data::<TokenOwnerRecord>(&unverified.token_owner_record).sybil_resistance_score,
+        Some(25)
+    );
+
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &verified);
+    for voter in [&verified, &unverified] {
+        pt.cast_vote(&realm, &governance, &proposal, voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+
+    // Both stakes weigh sqrt(10,000) = 100, scaled by the default score of 100 and by 25
+    assert_eq!(vote_weight(&pt, &proposal, &verified), 100);
+    assert_eq!(vote_weight(&pt, &proposal, &unverified), 25);
+}
+
+#[test]
+fn weighted_proposals_are_rejected_under_quadratic_voting_by_default() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm();
+    let token_owner = pt.with_token_owner(&realm, 10_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+
+    assert_eq!(
+        pt.create_proposal_with_args(
+            &realm,
+            &governance,
+            &token_owner,
+            &proposal,
+            ProposalArgs { vote_type: VoteType::Weighted, ..proposal_args() },
+        ),
+        Err(GovernanceError::WeightedVoteNotAllowed.into())
+    );
+}
+
+#[test]
+fn per_option_square_root_weighs_each_allocation_separately() {
+    let mut pt = ProgramTest::new();
+    let realm = with_per_option_square_root_realm(&mut pt);
+    let splitter = pt.with_token_owner(&realm, 10_000);
+    let concentrator = pt.with_token_owner(&realm, 10_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let weighted = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &splitter,
+        ProposalArgs { vote_type: VoteType::Weighted, ..proposal_args() },
+    );
+    pt.sign_off_proposal(&realm, &governance, &weighted).unwrap();
+    let single_choice = pt.with_signed_off_proposal(&realm, &governance, &concentrator);
+
+    pt.cast_vote(&realm, &governance, &weighted, &splitter, Vote::Weighted { weights: vec![(0, 50), (1, 50)] })
+        .unwrap();
+    pt.cast_vote(&realm, &governance, &single_choice, &concentrator, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    // Splitting 10,000 evenly gives each option sqrt(5,000) = 70, less than the
+    // sqrt(10,000) = 100 the whole stake gives a single option
+    let split_record = pt.get_account_data::<VoteRecord>(&get_vote_record_address(
+        &crate::id(),
+        &weighted.address,
+        &splitter.token_owner_record,
+    ));
+    assert_eq!(split_record.option_vote_weights, vec![(0, 70), (1, 70)]);
+    assert_eq!(split_record.vote_weight, 140);
+    assert_eq!(split_record.vote_weight_curve, VoteWeightCurve::PerOptionSquareRoot);
+    assert_eq!(vote_weight(&pt, &single_choice, &concentrator), 100);
+    assert_eq!(pt.get_account_data::<Proposal>(&single_choice.address).vote_results.get(&0), Some(&100));
+}
+
+#[test]
+fn concentrated_stake_outweighs_the_same_stake_split() {
+    let mut pt = ProgramTest::new();
+    let realm = with_per_option_square_root_realm(&mut pt);
+    let splitter = pt.with_token_owner(&realm, 10_000);
+    let concentrator = pt.with_token_owner(&realm, 10_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &splitter,
+        ProposalArgs {
+            vote_type: VoteType::Weighted,
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            ..proposal_args()
+        },
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    pt.cast_vote(&realm, &governance, &proposal, &splitter, Vote::Weighted { weights: vec![(0, 50), (1, 50)] })
+        .unwrap();
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposal.address,
+        &realm.community_mint,
+        &concentrator.owner,
+        &concentrator.owner,
+    ))
+    .unwrap();
+    let concentrated_vote = |staked_amount| {
+        instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &concentrator.owner,
+            &concentrator.token_account,
+            None,
+            Vote::Weighted { weights: vec![(2, 100)] },
+            staked_amount,
+            None,
+        )
+    };
+    // The stake allocated over the options can't exceed the voter's deposit
+    assert_eq!(pt.process(&concentrated_vote(10_001)), Err(GovernanceError::InsufficientStake.into()));
+    pt.process(&concentrated_vote(10_000)).unwrap();
+
+    // Neither of the splitter's options gets close to the concentrator's
+    let vote_results = pt.get_account_data::<Proposal>(&proposal.address).vote_results;
+    assert_eq!(vote_results.get(&0), Some(&70));
+    assert_eq!(vote_results.get(&1), Some(&70));
+    assert_eq!(vote_results.get(&2), Some(&100));
+}
+
+#[test]
+fn normalized_quadratic_weights_match_across_mint_decimals() {
+    // Square root of the vote on a realm whose community mint has the decimals, with a stake
+    // of 10,000 whole tokens
+    let quadratic_vote_weight = |decimals: u8, normalize_vote_weight_to_decimals| {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_quadratic_realm_decimals(decimals);
+        pt.set_realm_config(&realm, RealmConfigArgs { normalize_vote_weight_to_decimals, ..realm_config_args() })
+            .unwrap();
+        let voter = pt.with_token_owner(&realm, 10_000 * 10u64.pow(decimals as u32));
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+        pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        vote_weight(&pt, &proposal, &voter)
+    };
+
+    assert_eq!(quadratic_vote_weight(0, true), 100);
+    assert_eq!(quadratic_vote_weight(9, true), 100);
+
+    // Without normalizing, the square root is taken of the base units
+    assert_eq!(quadratic_vote_weight(0, false), 100);
+    assert_eq!(quadratic_vote_weight(9, false), 3_162_277);
+}
+
+#[test]
+fn normalized_weights_round_down_to_whole_tokens() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm_decimals(9);
+    let realm_data = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(realm_data.community_mint_decimals, 9);
+    let normalized = Realm { normalize_vote_weight_to_decimals: true, ..realm_data };
+
+    assert_eq!(normalized.get_vote_weight(3_999_999_999), 1);
+    assert_eq!(normalized.get_vote_weight(4_000_000_000), 2);
+    assert_eq!(normalized.get_vote_weight(999_999_999), 0);
+}
+
+#[test]
+fn votes_past_the_credit_budget_wait_for_the_next_period() {
+    const PERIOD_SECONDS: u64 = 30 * 86_400;
+
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_quadratic_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs {
+            quadratic_credit_budget: Some(QuadraticCreditBudget {
+                credits_per_period: 25_000,
+                period_seconds: PERIOD_SECONDS,
+            }),
+            ..realm_config_args()
+        },
+    )
+    .unwrap();
+    // Each vote of the 10,000 token stake weighs 100, costing 10,000 credits
+    let voter = pt.with_token_owner(&realm, 10_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 3, ..governance_config() });
+    let proposals: Vec<_> = (0..3).map(|_| pt.with_signed_off_proposal(&realm, &governance, &voter)).collect();
+    let vote = Vote::SingleChoice { option_index: 0 };
+
+    for proposal in &proposals[..2] {
+        pt.cast_vote(&realm, &governance, proposal, &voter, vote.clone()).unwrap();
+    }
+    pt.process(&instruction::create_vote_record(
+        &crate::id(),
+        &realm.address,
+        &proposals[2].address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.owner,
+    ))
+    .unwrap();
+    let cast_third_vote = instruction::cast_vote(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposals[2].address,
+        &realm.community_mint,
+        &voter.owner,
+        &voter.token_account,
+        None,
+        vote,
+        10_000,
+        None,
+    );
+    assert_eq!(pt.process(&cast_third_vote), Err(GovernanceError::QuadraticCreditsExhausted.into()));
+
+    // The budget is replenished once the next period starts, before the third vote ends
+    pt.advance_clock(PERIOD_SECONDS - START_TIME as u64 % PERIOD_SECONDS);
+    pt.process(&cast_third_vote).unwrap();
+    let record = pt.get_account_data::<TokenOwnerRecord>(&voter.token_owner_record);
+    assert_eq!(record.quadratic_credits_spent, 10_000);
+}
@@ -0,0 +1,90 @@
+//! Quorum checks on a proposal's tallies, and the quorum decay after consecutive failures
+
+use super::{program_test::ProgramTest, setup::governance_config};
+use crate::{
+    has_reached_quorum, Governance, GovernanceConfig, Proposal, ProposalState, QuorumDecay, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+
+/// Returns a draft proposal, whose tallies the tests fill in
+fn proposal() -> Proposal {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &token_owner);
+    pt.get_account_data::<Proposal>(&proposal.address)
+}
+
+#[test]
+fn quorum_is_reached_from_the_exact_boundary() {
+    let mut proposal = proposal();
+    for (total_vote_weight, reached) in [(999, false), (1_000, true), (1_001, true)] {
+        proposal.total_vote_weight = total_vote_weight;
+        assert_eq!(has_reached_quorum(&proposal, 10_000, 1_000, false), reached, "{}", total_vote_weight);
+    }
+
+    // 1 bps of 9,999 is 0.9999, which a single vote weight unit reaches without rounding down
+    proposal.total_vote_weight = 0;
+    assert!(!has_reached_quorum(&proposal, 9_999, 1, false));
+    proposal.total_vote_weight = 1;
+    assert!(has_reached_quorum(&proposal, 9_999, 1, false));
+}
+
+#[test]
+fn abstentions_count_only_when_asked_to() {
+    let mut proposal = proposal();
+    proposal.total_vote_weight = 400;
+    proposal.abstain_vote_weight = 600;
+
+    assert!(has_reached_quorum(&proposal, 10_000, 1_000, true));
+    assert!(!has_reached_quorum(&proposal, 10_000, 1_000, false));
+}
+
+#[test]
+fn large_weights_do_not_overflow() {
+    let mut proposal = proposal();
+    proposal.total_vote_weight = u64::MAX / 2;
+    proposal.abstain_vote_weight = u64::MAX / 2 + 1;
+
+    assert!(has_reached_quorum(&proposal, u64::MAX, 10_000, true));
+    assert!(!has_reached_quorum(&proposal, u64::MAX, 10_000, false));
+    // u64::MAX / 2 falls half a unit short of half of the odd u64::MAX
+    assert!(!has_reached_quorum(&proposal, u64::MAX, 5_000, false));
+    proposal.total_vote_weight += 1;
+    assert!(has_reached_quorum(&proposal, u64::MAX, 5_000, false));
+}
+
+#[test]
+fn consecutive_quorum_failures_lower_the_quorum_down_to_the_floor() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    // The voter holds a tenth of the supply, and turns out alone for every proposal
+    let voter = pt.with_token_owner(&realm, 100);
+    pt.with_token_owner(&realm, 900);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig {
+            quorum_bps: 2_000,
+            quorum_decay: Some(QuorumDecay { step_bps: 500, floor_bps: 1_000 }),
+            ..governance_config()
+        },
+    );
+
+    for (expected_state, quorum_failure_count) in
+        [(ProposalState::Expired, 1), (ProposalState::Expired, 2), (ProposalState::Approved, 0)]
+    {
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+        pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+        assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, expected_state);
+        let governance = pt.get_account_data::<Governance>(&governance.address);
+        assert_eq!(governance.quorum_failure_count, quorum_failure_count);
+    }
+
+    // Further failures stop lowering the quorum at the floor
+    let config = pt.get_account_data::<Governance>(&governance.address).config;
+    assert_eq!(config.get_vote_thresholds(None, 5), (1_000, 5_000));
+}
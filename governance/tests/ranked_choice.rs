@@ -0,0 +1,56 @@
+//! Instant-runoff tallying of ranked choice proposals
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs},
+};
+use crate::{Proposal, ProposalState, Vote, VoteType, DEFAULT_VOTING_PERIOD_DAYS};
+
+/// Finalizes a ranked choice proposal over options A, B and C voted on with the stakes and
+/// rankings, returning the finalized proposal
+fn run_off(ballots: &[(u64, [u8; 3])]) -> Proposal {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voters: Vec<_> = ballots.iter().map(|(stake, _)| pt.with_token_owner(&realm, *stake)).collect();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &voters[0],
+        ProposalArgs {
+            vote_type: VoteType::RankedChoice,
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            ..proposal_args()
+        },
+    );
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+
+    for (voter, (_, ranking)) in voters.iter().zip(ballots) {
+        pt.cast_vote(&realm, &governance, &proposal, voter, Vote::RankedChoice { ranking: ranking.to_vec() }).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    pt.get_account_data::<Proposal>(&proposal.address)
+}
+
+#[test]
+fn runoff_winner_differs_from_the_plurality_winner() {
+    let proposal = run_off(&[(400, [0, 1, 2]), (350, [1, 0, 2]), (250, [2, 1, 0])]);
+
+    // A leads the first preferences, but once C is eliminated its votes carry B past half
+    assert_eq!(proposal.vote_results.get(&0), Some(&400));
+    assert_eq!(proposal.vote_results.get(&1), Some(&350));
+    assert_eq!(proposal.vote_results.get(&2), Some(&250));
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert_eq!(proposal.winning_option, Some(1));
+}
+
+#[test]
+fn highest_index_is_eliminated_first_on_ties() {
+    // B and C tie for last; eliminating B instead of C would hand A the win
+    let proposal = run_off(&[(400, [0, 2, 1]), (300, [1, 0, 2]), (300, [2, 1, 0])]);
+
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert_eq!(proposal.winning_option, Some(1));
+}
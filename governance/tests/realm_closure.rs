@@ -0,0 +1,74 @@
+//! Closing abandoned realms once nothing is staked in them or open for a vote
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, GovernanceCookie, RealmCookie},
+};
+use crate::{instruction, GovernanceError, RealmConfigArgs, DEFAULT_VOTING_PERIOD_DAYS};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+
+fn close_realm(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    beneficiary: &Pubkey,
+    governances: &[Pubkey],
+) -> ProgramResult {
+    pt.process(&instruction::close_realm(&crate::id(), &realm.address, &realm.authority, &[], beneficiary, governances))
+}
+
+#[test]
+fn empty_realm_is_closed_and_its_rent_refunded() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let GovernanceCookie { address: governance } = pt.with_governance(&realm, governance_config());
+    let beneficiary = pt.create_wallet();
+    let (realm_lamports, beneficiary_lamports) = (pt.lamports(&realm.address), pt.lamports(&beneficiary));
+
+    let outsider = pt.create_wallet();
+    assert_eq!(
+        pt.process(&instruction::close_realm(&crate::id(), &realm.address, &outsider, &[], &beneficiary, &[governance])),
+        Err(GovernanceError::InvalidRealmAuthority.into())
+    );
+    // Every governance of the realm is checked for open proposals
+    assert_eq!(close_realm(&mut pt, &realm, &beneficiary, &[]), Err(GovernanceError::IncompleteRealmGovernances.into()));
+
+    close_realm(&mut pt, &realm, &beneficiary, &[governance]).unwrap();
+    assert_eq!(pt.lamports(&realm.address), 0);
+    assert_eq!(pt.lamports(&beneficiary), beneficiary_lamports + realm_lamports);
+}
+
+#[test]
+fn realm_with_staked_tokens_or_open_proposals_stays_open() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let beneficiary = pt.create_wallet();
+
+    assert_eq!(
+        close_realm(&mut pt, &realm, &beneficiary, &[governance.address]),
+        Err(GovernanceError::RealmHasStakedTokens.into())
+    );
+
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.process(&instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        1_000,
+    ))
+    .unwrap();
+    assert_eq!(
+        close_realm(&mut pt, &realm, &beneficiary, &[governance.address]),
+        Err(GovernanceError::RealmHasOpenProposals.into())
+    );
+
+    // Expiring without votes makes the proposal terminal
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    close_realm(&mut pt, &realm, &beneficiary, &[governance.address]).unwrap();
+}
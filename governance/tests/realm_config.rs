@@ -0,0 +1,321 @@
+//! Realm config updates by the bootstrap authority and through the realm governance
+
+use super::{
+    program_test::ProgramTest,
+    setup::{
+        governance_config, proposal_args, realm_config_args, to_instruction_data, GovernanceCookie, ProposalArgs,
+        RealmCookie,
+    },
+};
+use crate::{
+    get_governance_address, instruction, validate_realm_config, EarlyUnstakeFee, GovernanceConfig, GovernanceError,
+    LockupVoteWeight, MintMaxVoteWeightSource, Proposal, ProposalCreationFee, ProposalState, ProposalThresholdSource,
+    QuadraticCreditBudget, Realm, RealmConfigArgs, RealmLifecycle, Vote, VoteWeightSource, DEFAULT_VOTING_PERIOD_DAYS,
+    MAX_BASIS_POINTS, MAX_VOTING_PERIOD_DAYS, SUPPLY_FRACTION_BASE,
+};
+use solana_program::pubkey::Pubkey;
+
+/// Sets a realm config field to an invalid value, given a spare address for fields holding one
+type Invalidate = fn(&mut Realm, Pubkey);
+
+/// Creates the realm governance, taking the realm config over from the bootstrap authority
+fn with_realm_governance(pt: &mut ProgramTest, realm: &RealmCookie) -> GovernanceCookie {
+    pt.process(&instruction::create_governance(
+        &crate::id(),
+        &realm.address,
+        &realm.authority,
+        Some(&realm.authority),
+        &[],
+        realm.address,
+        governance_config(),
+    ))
+    .unwrap();
+    GovernanceCookie { address: get_governance_address(&crate::id(), &realm.address, &realm.address) }
+}
+
+#[test]
+fn bootstrap_authority_sets_the_realm_config_until_the_realm_governance_exists() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let config_args = RealmConfigArgs { unstaking_cooldown_seconds: 3_600, ..realm_config_args() };
+
+    let outsider = pt.create_wallet();
+    assert_eq!(
+        pt.process(&instruction::set_realm_config(&crate::id(), &realm.address, &outsider, &[], config_args.clone())),
+        Err(GovernanceError::InvalidRealmAuthority.into())
+    );
+    pt.set_realm_config(&realm, config_args.clone()).unwrap();
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).unstaking_cooldown_seconds, 3_600);
+
+    with_realm_governance(&mut pt, &realm);
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).authority, None);
+    assert_eq!(pt.set_realm_config(&realm, config_args), Err(GovernanceError::InvalidRealmAuthority.into()));
+}
+
+#[test]
+fn realm_config_changes_through_a_realm_governance_proposal() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = with_realm_governance(&mut pt, &realm);
+
+    let set_realm_config = to_instruction_data(instruction::set_realm_config(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &[],
+        RealmConfigArgs { unstaking_cooldown_seconds: 3_600, max_vote_records_per_proposal: 50, ..realm_config_args() },
+    ));
+    let proposal = pt.with_approved_proposal(&realm, &governance, &token_owner, vec![vec![set_realm_config.clone()]]);
+    pt.execute_transaction(&governance, &proposal, &token_owner, 0, &[set_realm_config]).unwrap();
+
+    let realm = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(realm.unstaking_cooldown_seconds, 3_600);
+    assert_eq!(realm.max_vote_records_per_proposal, 50);
+}
+
+#[test]
+fn max_vote_weight_source_update_leaves_signed_off_proposals_on_their_snapshot() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let signed_off_before = pt.with_signed_off_proposal(&realm, &governance, &voter);
+
+    // 1,000 staked tokens are below the 10% quorum of an absolute max vote weight of 100,000
+    let updated_source = MintMaxVoteWeightSource::Absolute { value: 100_000 };
+    let set_source = |authority| {
+        instruction::set_max_vote_weight_source(&crate::id(), &realm.address, authority, &[], updated_source)
+    };
+    let outsider = pt.create_wallet();
+    assert_eq!(pt.process(&set_source(&outsider)), Err(GovernanceError::InvalidRealmAuthority.into()));
+    pt.process(&set_source(&realm.authority)).unwrap();
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).community_mint_max_vote_weight_source, updated_source);
+
+    let signed_off_after = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    for proposal in [&signed_off_before, &signed_off_after] {
+        pt.cast_vote(&realm, &governance, proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    }
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    for proposal in [&signed_off_before, &signed_off_after] {
+        pt.finalize_vote(&realm, &governance, proposal).unwrap();
+    }
+
+    let before = pt.get_account_data::<Proposal>(&signed_off_before.address);
+    assert_eq!(
+        before.max_vote_weight_source,
+        Some(MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE })
+    );
+    assert_eq!(before.state, ProposalState::Approved);
+    let after = pt.get_account_data::<Proposal>(&signed_off_after.address);
+    assert_eq!(after.max_vote_weight_source, Some(updated_source));
+    assert_eq!(after.state, ProposalState::Expired);
+}
+
+#[test]
+fn each_invalid_realm_config_field_is_rejected() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let valid = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(validate_realm_config(&valid), Ok(()));
+
+    let treasury = pt.create_wallet();
+    let invalid_fields: Vec<(Invalidate, GovernanceError)> = vec![
+        (
+            |realm, _| realm.community_mint_max_vote_weight_source = MintMaxVoteWeightSource::Absolute { value: 0 },
+            GovernanceError::InvalidMaxVoteWeightSource,
+        ),
+        (
+            |realm, _| {
+                realm.min_community_tokens_to_create_proposal =
+                    ProposalThresholdSource::SupplyFraction { bps: MAX_BASIS_POINTS + 1 }
+            },
+            GovernanceError::InvalidProposalThreshold,
+        ),
+        (|realm, _| realm.default_sybil_resistance_score = 101, GovernanceError::InvalidSybilResistanceScore),
+        (|realm, _| realm.max_vote_records_per_proposal = 0, GovernanceError::InvalidMaxVoteRecords),
+        (
+            |realm, treasury| {
+                realm.proposal_creation_fee = Some(ProposalCreationFee { lamports: 0, treasury, deposit_lamports: 0 })
+            },
+            GovernanceError::InvalidProposalCreationFee,
+        ),
+        (
+            |realm, _| {
+                realm.lockup_vote_weight =
+                    Some(LockupVoteWeight { max_lock_seconds: 0, min_multiplier_percentage: 50 })
+            },
+            GovernanceError::InvalidLockupVoteWeight,
+        ),
+        (
+            |realm, _| {
+                realm.lockup_vote_weight =
+                    Some(LockupVoteWeight { max_lock_seconds: 86_400, min_multiplier_percentage: 101 })
+            },
+            GovernanceError::InvalidLockupVoteWeight,
+        ),
+        (|realm, _| realm.inactivity_threshold_seconds = Some(0), GovernanceError::InvalidInactivityThreshold),
+        (
+            |realm, _| {
+                realm.quadratic_credit_budget =
+                    Some(QuadraticCreditBudget { credits_per_period: 0, period_seconds: 86_400 })
+            },
+            GovernanceError::InvalidQuadraticCreditBudget,
+        ),
+        (
+            |realm, _| {
+                realm.quadratic_credit_budget =
+                    Some(QuadraticCreditBudget { credits_per_period: 100, period_seconds: 0 })
+            },
+            GovernanceError::InvalidQuadraticCreditBudget,
+        ),
+        (
+            |realm, treasury| realm.early_unstake_fee = Some(EarlyUnstakeFee { fee_bps: 0, treasury }),
+            GovernanceError::InvalidEarlyUnstakeFee,
+        ),
+        (
+            |realm, treasury| {
+                realm.early_unstake_fee = Some(EarlyUnstakeFee { fee_bps: MAX_BASIS_POINTS + 1, treasury })
+            },
+            GovernanceError::InvalidEarlyUnstakeFee,
+        ),
+        (|realm, _| realm.max_vote_weight_per_voter = Some(0), GovernanceError::InvalidMaxVoteWeightPerVoter),
+        (|realm, _| realm.default_voting_period_days = 0, GovernanceError::InvalidDefaultVotingPeriod),
+        (
+            |realm, _| realm.default_voting_period_days = MAX_VOTING_PERIOD_DAYS + 1,
+            GovernanceError::InvalidDefaultVotingPeriod,
+        ),
+        (|realm, _| realm.council_mint = Some(realm.community_mint), GovernanceError::SharedCouncilMintNotAllowed),
+        (|realm, _| realm.authority_threshold = 1, GovernanceError::InvalidRealmAuthoritySet),
+        (
+            |realm, member| {
+                realm.authority_set = vec![realm.authority.unwrap(), member];
+                realm.authority_threshold = 3;
+            },
+            GovernanceError::InvalidRealmAuthoritySet,
+        ),
+    ];
+    for (invalidate, error) in invalid_fields {
+        let mut realm = valid.clone();
+        invalidate(&mut realm, treasury);
+        assert_eq!(validate_realm_config(&realm), Err(error));
+    }
+
+    // The same checks guard realm config updates
+    assert_eq!(
+        pt.set_realm_config(&realm, RealmConfigArgs { max_vote_records_per_proposal: 0, ..realm_config_args() }),
+        Err(GovernanceError::InvalidMaxVoteRecords.into())
+    );
+    assert_eq!(
+        pt.set_realm_config(&realm, RealmConfigArgs { default_sybil_resistance_score: 101, ..realm_config_args() }),
+        Err(GovernanceError::InvalidSybilResistanceScore.into())
+    );
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address), valid);
+}
+
+#[test]
+fn two_of_three_authority_set_needs_two_signers_for_privileged_instructions() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let (second, third) = (pt.create_wallet(), pt.create_wallet());
+    pt.process(&instruction::set_realm_authority(
+        &crate::id(),
+        &realm.address,
+        &realm.authority,
+        &[],
+        vec![realm.authority, second, third],
+        2,
+    ))
+    .unwrap();
+
+    let config_args = RealmConfigArgs { unstaking_cooldown_seconds: 3_600, ..realm_config_args() };
+    let set_config = |authority, co_signers: &[Pubkey]| {
+        instruction::set_realm_config(&crate::id(), &realm.address, authority, co_signers, config_args.clone())
+    };
+    assert_eq!(pt.process(&set_config(&realm.authority, &[])), Err(GovernanceError::InvalidRealmAuthority.into()));
+    // A signer outside the set doesn't count towards the threshold
+    let outsider = pt.create_wallet();
+    assert_eq!(
+        pt.process(&set_config(&realm.authority, &[outsider])),
+        Err(GovernanceError::InvalidRealmAuthority.into())
+    );
+    pt.process(&set_config(&realm.authority, &[third])).unwrap();
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).unstaking_cooldown_seconds, 3_600);
+
+    let pause = |authority, co_signers: &[Pubkey]| {
+        instruction::set_realm_lifecycle(&crate::id(), &realm.address, authority, co_signers, RealmLifecycle::Paused)
+    };
+    assert_eq!(pt.process(&pause(&second, &[])), Err(GovernanceError::InvalidRealmAuthority.into()));
+    pt.process(&pause(&second, &[third])).unwrap();
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).lifecycle, RealmLifecycle::Paused);
+
+    // Handing the authority to a single member falls back to single-authority mode
+    let transfer = |authority, co_signers: &[Pubkey]| {
+        instruction::set_realm_authority(&crate::id(), &realm.address, authority, co_signers, vec![third], 0)
+    };
+    assert_eq!(pt.process(&transfer(&third, &[])), Err(GovernanceError::InvalidRealmAuthority.into()));
+    pt.process(&transfer(&third, &[second])).unwrap();
+    let updated = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!((updated.authority, updated.authority_set, updated.authority_threshold), (Some(third), vec![], 0));
+    pt.process(&set_config(&third, &[])).unwrap();
+}
+
+#[test]
+fn vote_weight_source_changes_through_a_proposal_without_affecting_open_proposals() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let treasury = pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 2, ..governance_config() });
+    let weight_oracle = pt.create_wallet();
+    let collection_mint = pt.create_mint(0);
+    pt.create_token_account(&collection_mint, &weight_oracle, 1);
+    let nft_source = VoteWeightSource::NftCollection { collection_mint, weight_oracle };
+
+    // The source's mint must be a collection NFT, with no decimals and a supply of one
+    let fungible_mint = pt.create_mint(6);
+    pt.create_token_account(&fungible_mint, &weight_oracle, 1);
+    let invalid_source = VoteWeightSource::NftCollection { collection_mint: fungible_mint, weight_oracle };
+    assert_eq!(
+        pt.process(&instruction::set_vote_weight_source(
+            &crate::id(),
+            &realm.address,
+            &realm.authority,
+            &fungible_mint,
+            &[],
+            invalid_source,
+        )),
+        Err(GovernanceError::InvalidVoteWeightSource.into())
+    );
+
+    // Open long enough to be voted on once the switch has been executed
+    let open_before = pt.with_proposal_args(
+        &realm,
+        &treasury,
+        &voter,
+        ProposalArgs { voting_period_days: DEFAULT_VOTING_PERIOD_DAYS * 2, ..proposal_args() },
+    );
+    pt.sign_off_proposal(&realm, &treasury, &open_before).unwrap();
+
+    let governance = with_realm_governance(&mut pt, &realm);
+    let set_source = to_instruction_data(instruction::set_vote_weight_source(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &collection_mint,
+        &[],
+        nft_source,
+    ));
+    let proposal = pt.with_approved_proposal(&realm, &governance, &voter, vec![vec![set_source.clone()]]);
+    pt.execute_transaction(&governance, &proposal, &voter, 0, &[set_source]).unwrap();
+    assert_eq!(pt.get_account_data::<Realm>(&realm.address).vote_weight_source, nft_source);
+
+    // The open proposal keeps being voted on with community tokens, new ones need the oracle
+    pt.cast_vote(&realm, &treasury, &open_before, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&open_before.address).vote_results.get(&0), Some(&1_000));
+    let signed_off_after = pt.with_signed_off_proposal(&realm, &treasury, &voter);
+    assert_eq!(pt.get_account_data::<Proposal>(&signed_off_after.address).vote_weight_source, Some(nft_source));
+    assert_eq!(
+        pt.cast_vote(&realm, &treasury, &signed_off_after, &voter, Vote::SingleChoice { option_index: 0 }),
+        Err(GovernanceError::MissingAccount.into())
+    );
+}
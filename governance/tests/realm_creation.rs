@@ -0,0 +1,101 @@
+//! Creation of realms at addresses derived from their name, community mint and nonce
+
+use super::program_test::{Account, ProgramTest};
+use crate::{
+    get_realm_address, instruction, GovernanceError, MintMaxVoteWeightSource, ProposalThresholdSource, Realm,
+    SUPPLY_FRACTION_BASE,
+};
+use solana_program::{entrypoint::ProgramResult, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Mint;
+
+fn initialize_realm(pt: &mut ProgramTest, community_mint: &Pubkey, name: &str, nonce: u64) -> ProgramResult {
+    let council_mint = pt.create_mint(0);
+    initialize_realm_with_council(pt, community_mint, &council_mint, name, nonce)
+}
+
+fn initialize_realm_with_council(
+    pt: &mut ProgramTest,
+    community_mint: &Pubkey,
+    council_mint: &Pubkey,
+    name: &str,
+    nonce: u64,
+) -> ProgramResult {
+    let funder = pt.create_wallet();
+    pt.process(&instruction::initialize_realm(
+        &crate::id(),
+        &funder,
+        community_mint,
+        council_mint,
+        name.to_string(),
+        ProposalThresholdSource::Absolute { value: 1 },
+        MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE },
+        false,
+        None,
+        nonce,
+        false,
+    ))
+}
+
+#[test]
+fn realms_sharing_a_name_and_mint_are_told_apart_by_their_nonce() {
+    let mut pt = ProgramTest::new();
+    let community_mint = pt.create_mint(6);
+    initialize_realm(&mut pt, &community_mint, "Leapfrog", 0).unwrap();
+    initialize_realm(&mut pt, &community_mint, "Leapfrog", 1).unwrap();
+
+    let first = get_realm_address(&crate::id(), "Leapfrog", &community_mint, 0);
+    let second = get_realm_address(&crate::id(), "Leapfrog", &community_mint, 1);
+    assert_ne!(first, second);
+    for (address, nonce) in [(first, 0), (second, 1)] {
+        let realm = pt.get_account_data::<Realm>(&address);
+        assert_eq!(realm.name, "Leapfrog");
+        assert_eq!(realm.nonce, nonce);
+    }
+
+    assert_eq!(
+        initialize_realm(&mut pt, &community_mint, "Leapfrog", 1),
+        Err(GovernanceError::AccountAlreadyInitialized.into())
+    );
+}
+
+#[test]
+fn same_name_needs_no_nonce_on_another_community_mint() {
+    let mut pt = ProgramTest::new();
+    let (community_mint, other_community_mint) = (pt.create_mint(6), pt.create_mint(6));
+    initialize_realm(&mut pt, &community_mint, "Leapfrog", 0).unwrap();
+    initialize_realm(&mut pt, &other_community_mint, "Leapfrog", 0).unwrap();
+
+    assert_ne!(
+        get_realm_address(&crate::id(), "Leapfrog", &community_mint, 0),
+        get_realm_address(&crate::id(), "Leapfrog", &other_community_mint, 0)
+    );
+}
+
+#[test]
+fn governing_mints_must_be_spl_token_mints() {
+    let mut pt = ProgramTest::new();
+    let wallet = pt.create_wallet();
+    let mint = pt.create_mint(6);
+    let token_account = pt.create_token_account(&mint, &wallet, 0);
+
+    // An account of another program laid out as a mint
+    let impostor_mint = Pubkey::new_unique();
+    let mut data = vec![0; Mint::LEN];
+    Mint { decimals: 6, is_initialized: true, ..Mint::default() }.pack_into_slice(&mut data);
+    pt.set_account(impostor_mint, Account { lamports: 1_000_000, data, owner: Pubkey::new_unique(), executable: false });
+
+    for not_a_mint in [wallet, token_account, impostor_mint] {
+        assert_eq!(
+            initialize_realm(&mut pt, &not_a_mint, "Leapfrog", 0),
+            Err(GovernanceError::InvalidMintAccount.into())
+        );
+        assert_eq!(
+            initialize_realm_with_council(&mut pt, &mint, &not_a_mint, "Leapfrog", 0),
+            Err(GovernanceError::InvalidMintAccount.into())
+        );
+    }
+
+    // Mints are accepted before any tokens are minted
+    initialize_realm(&mut pt, &mint, "Leapfrog", 0).unwrap();
+    assert_eq!(pt.get_token_state::<Mint>(&mint).supply, 0);
+}
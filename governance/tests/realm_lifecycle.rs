@@ -0,0 +1,62 @@
+//! Realm lifecycle stages, and what each lets token owners do
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, ProposalCookie},
+};
+use crate::{
+    instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, RealmConfigArgs, RealmLifecycle, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::pubkey::Pubkey;
+use spl_token::state::Account as TokenAccount;
+
+#[test]
+fn only_active_realms_accept_new_proposals() {
+    for lifecycle in [RealmLifecycle::Active, RealmLifecycle::Paused, RealmLifecycle::Closing] {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() })
+            .unwrap();
+        let token_owner = pt.with_token_owner(&realm, 1_000);
+        let staker = pt.with_token_owner(&realm, 500);
+        let governance =
+            pt.with_governance(&realm, GovernanceConfig { max_concurrent_proposals: 3, ..governance_config() });
+        let draft = pt.with_proposal(&realm, &governance, &token_owner);
+        let voted = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+        pt.cast_vote(&realm, &governance, &voted, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+        pt.process(&instruction::set_realm_lifecycle(&crate::id(), &realm.address, &realm.authority, &[], lifecycle))
+            .unwrap();
+
+        let accepts_proposals = if lifecycle == RealmLifecycle::Active {
+            Ok(())
+        } else {
+            Err(GovernanceError::RealmNotAcceptingProposals.into())
+        };
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        assert_eq!(
+            pt.create_proposal(&realm, &governance, &token_owner, &proposal),
+            accepts_proposals,
+            "{:?}",
+            lifecycle
+        );
+        assert_eq!(pt.sign_off_proposal(&realm, &governance, &draft), accepts_proposals, "{:?}", lifecycle);
+
+        // Proposals already voting on are finalized, and stakes withdrawn, in every stage
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &voted).unwrap();
+        assert_eq!(pt.get_account_data::<Proposal>(&voted.address).state, ProposalState::Approved);
+        pt.process(&instruction::unstake_tokens(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &staker.owner,
+            &realm.staking_vault,
+            &staker.token_account,
+            500,
+        ))
+        .unwrap();
+        assert_eq!(pt.get_token_state::<TokenAccount>(&staker.token_account).amount, 500);
+    }
+}
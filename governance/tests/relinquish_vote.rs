@@ -0,0 +1,110 @@
+//! Relinquishing a vote once its proposal is finalized, releasing the stake behind it
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_vote_record_address, instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, RealmConfigArgs,
+    TokenOwnerRecord, Vote, VoteRecord, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::entrypoint::ProgramResult;
+
+/// Unstakes the token owner's whole deposit
+fn unstake_all(pt: &mut ProgramTest, realm: &RealmCookie, token_owner: &TokenOwnerCookie) -> ProgramResult {
+    pt.process(&instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &token_owner.token_account,
+        pt.token_owner_deposit(token_owner),
+    ))
+}
+
+#[test]
+fn vote_is_relinquished_once_its_proposal_is_finalized() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    // The vote counts while voting is open
+    assert_eq!(
+        pt.relinquish_vote(&realm, &governance, &proposal, &token_owner),
+        Err(GovernanceError::InvalidProposalState.into())
+    );
+    assert_eq!(unstake_all(&mut pt, &realm, &token_owner), Err(GovernanceError::OutstandingVotes.into()));
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    pt.relinquish_vote(&realm, &governance, &proposal, &token_owner).unwrap();
+
+    let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+    assert!(pt.get_account_data::<VoteRecord>(&vote_record).is_relinquished);
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).unrelinquished_votes_count, 0);
+    assert_eq!(
+        pt.relinquish_vote(&realm, &governance, &proposal, &token_owner),
+        Err(GovernanceError::VoteAlreadyRelinquished.into())
+    );
+
+    // The finalized outcome keeps the relinquished vote's weight
+    let stored_proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!((stored_proposal.state, stored_proposal.vote_results[&0]), (ProposalState::Approved, 1_000));
+    unstake_all(&mut pt, &realm, &token_owner).unwrap();
+    assert_eq!(pt.token_owner_deposit(&token_owner), 0);
+}
+
+#[test]
+fn vote_on_a_proposal_ended_without_quorum_is_relinquished_before_finalization() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    // Tokens nobody stakes keep the vote below the 10% quorum
+    let holder = pt.create_wallet();
+    pt.create_token_account(&realm.community_mint, &holder, 1_000_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.relinquish_vote(&realm, &governance, &proposal, &token_owner).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Active);
+
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).state, ProposalState::Expired);
+}
+
+#[test]
+fn backing_stake_is_relinquished_once_the_proposal_is_executed() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { lock_stake_until_execution: true, ..realm_config_args() }).unwrap();
+    let backer = pt.with_token_owner(&realm, 700);
+    let opponent = pt.with_token_owner(&realm, 300);
+    let governance =
+        pt.with_governance(&realm, GovernanceConfig { execution_delay_seconds: 3_600, ..governance_config() });
+    let proposal = pt.with_proposal(&realm, &governance, &backer);
+    pt.insert_transaction(&governance, &proposal, 0, 0, vec![]).unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &backer, Vote::SingleChoice { option_index: 0 }).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &opponent, Vote::SingleChoice { option_index: 1 }).unwrap();
+    pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+    pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+    // Only the stake behind the winning option is held back
+    pt.relinquish_vote(&realm, &governance, &proposal, &opponent).unwrap();
+    assert_eq!(
+        pt.relinquish_vote(&realm, &governance, &proposal, &backer),
+        Err(GovernanceError::BackingStakeLocked.into())
+    );
+
+    pt.advance_clock(3_600);
+    pt.execute_transaction(&governance, &proposal, &backer, 0, &[]).unwrap();
+    pt.relinquish_vote(&realm, &governance, &proposal, &backer).unwrap();
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&backer.token_owner_record).unrelinquished_votes_count, 0);
+}
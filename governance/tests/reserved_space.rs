@@ -0,0 +1,56 @@
+//! Fields carved out of the accounts' reserved blocks
+
+use super::{program_test::ProgramTest, setup::governance_config};
+use crate::{
+    get_used_reserved_space, get_vote_record_address, take_reserved_space, GovernanceAccount, GovernanceError, Proposal,
+    Realm, TokenOwnerRecord, Vote, VoteRecord, ACCOUNT_RESERVED_SIZE, USED_RESERVED_SIZE,
+};
+
+#[test]
+fn migrations_are_counted_and_fail_when_overrunning_the_reserved_block() {
+    // A new account only holds the counter
+    let used_reserved = Realm::get_used_reserved().unwrap();
+    assert_eq!(used_reserved, USED_RESERVED_SIZE as u8);
+
+    // A first migration carves out a pubkey, a second a u64
+    let used_reserved = take_reserved_space(used_reserved, 32).unwrap();
+    assert_eq!(used_reserved, 33);
+    let used_reserved = take_reserved_space(used_reserved, 8).unwrap();
+    assert_eq!(used_reserved, 41);
+    assert_eq!(get_used_reserved_space(&[USED_RESERVED_SIZE, 32, 8]), Ok(41));
+
+    // A second pubkey still fits, exactly filling the block, a third doesn't
+    assert_eq!(get_used_reserved_space(&[USED_RESERVED_SIZE, 32, 31]), Ok(ACCOUNT_RESERVED_SIZE as u8));
+    assert_eq!(take_reserved_space(33, 32), Err(GovernanceError::ReservedSpaceExhausted.into()));
+    assert_eq!(
+        get_used_reserved_space(&[USED_RESERVED_SIZE, 32, 32]),
+        Err(GovernanceError::ReservedSpaceExhausted.into())
+    );
+    assert_eq!(take_reserved_space(u8::MAX, usize::MAX), Err(GovernanceError::ReservedSpaceExhausted.into()));
+}
+
+#[test]
+fn accounts_are_created_with_their_reserved_usage() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+
+    // Fields appended to the body of realms, proposals and token owner records don't take any
+    // of their reserved block
+    let stored_realm = pt.get_account_data::<Realm>(&realm.address);
+    assert_eq!(stored_realm.used_reserved, 1);
+    assert_eq!(stored_realm.used_reserved as usize + stored_realm.reserved.len(), ACCOUNT_RESERVED_SIZE);
+    assert_eq!(pt.get_account_data::<Proposal>(&proposal.address).used_reserved, 1);
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).used_reserved, 1);
+
+    // The voter authority, curve, cast time, bump and counter were carved out of the vote
+    // record's block
+    let vote_record_address =
+        get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+    let vote_record = pt.get_account_data::<VoteRecord>(&vote_record_address);
+    assert_eq!(vote_record.used_reserved, 32 + 1 + 8 + 1 + 1);
+    assert_eq!(vote_record.used_reserved as usize + vote_record.reserved.len(), ACCOUNT_RESERVED_SIZE);
+}
@@ -0,0 +1,485 @@
+//! Realms, governances, token owners and proposals for tests to start from, created through
+//! the program's instructions
+//!
+//! Each helper returns a cookie with the addresses and keys later instructions need
+
+use super::program_test::ProgramTest;
+use crate::{
+    get_governance_address, get_realm_address, get_token_owner_record_address, get_vault_authority_address,
+    instruction, AccountMetaData, GovernanceConfig, InstructionData, MintMaxVoteWeightSource, OptionTieBreak,
+    OptionVoteCap, ParameterChange, ProposalThresholdSource, QuadraticWeightedVotePolicy, Realm, RealmConfigArgs,
+    Vote, VoteType,
+    DEFAULT_MAX_VOTE_RECORDS_PER_PROPOSAL, DEFAULT_UNSTAKING_COOLDOWN_SECONDS, DEFAULT_VOTING_PERIOD_DAYS,
+    SUPPLY_FRACTION_BASE,
+};
+use solana_program::{entrypoint::ProgramResult, instruction::Instruction, pubkey::Pubkey};
+
+pub struct RealmCookie {
+    pub address: Pubkey,
+    pub authority: Pubkey,
+    pub community_mint: Pubkey,
+    pub council_mint: Pubkey,
+    pub staking_vault: Pubkey,
+}
+
+pub struct TokenOwnerCookie {
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+    pub token_owner_record: Pubkey,
+}
+
+pub struct GovernanceCookie {
+    pub address: Pubkey,
+}
+
+pub struct ProposalCookie {
+    pub address: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// Arguments of CreateProposal tests choose
+pub struct ProposalArgs {
+    pub description_link: String,
+    pub vote_type: VoteType,
+    pub options: Vec<String>,
+    pub use_denial_quorum: bool,
+    pub voting_period_days: u8,
+    pub per_voter_option_cap: Option<OptionVoteCap>,
+    pub impact_tier: Option<u8>,
+    pub depends_on: Option<Pubkey>,
+    pub description_hash: Option<[u8; 32]>,
+    pub parameter_change: Option<ParameterChange>,
+    pub fallback_option_index: Option<u8>,
+}
+
+/// Arguments of a Yes / No proposal voted on for the realm's default voting period
+pub fn proposal_args() -> ProposalArgs {
+    ProposalArgs {
+        description_link: "https://leapfrogdao.org/proposal".to_string(),
+        vote_type: VoteType::SingleChoice,
+        options: vec!["Yes".to_string(), "No".to_string()],
+        use_denial_quorum: false,
+        voting_period_days: 0,
+        per_voter_option_cap: None,
+        impact_tier: None,
+        depends_on: None,
+        description_hash: None,
+        parameter_change: None,
+        fallback_option_index: None,
+    }
+}
+
+/// Config of a governance deciding by simple majority, with a 10% quorum and a single active
+/// proposal at a time
+pub fn governance_config() -> GovernanceConfig {
+    GovernanceConfig {
+        quorum_bps: 1_000,
+        vote_threshold_bps: 5_000,
+        max_concurrent_proposals: 1,
+        min_voting_period_days: 1,
+        min_community_tokens_to_create_proposal: None,
+        require_funded_execution: false,
+        execution_window_seconds: None,
+        required_sponsors: 0,
+        min_sponsor_tokens: 0,
+        impact_tiers: vec![],
+        denial_threshold_bps: 5_000,
+        conviction_half_life_seconds: 0,
+        conviction_threshold_bps: 0,
+        rejection_refund_bps: 0,
+        option_tie_break: OptionTieBreak::LowestIndex,
+        require_description_hash: false,
+        allowed_target_programs: vec![],
+        quorum_decay: None,
+        veto_window_seconds: 0,
+        execution_delay_seconds: 0,
+        sybil_vote_check: None,
+        council_approval_quorum_bps: None,
+        min_draft_period_seconds: 0,
+        finalizer_bounty_lamports: 0,
+        executor_bounty_lamports: 0,
+    }
+}
+
+/// Stores the instruction the way a proposal transaction holds it
+pub fn to_instruction_data(instruction: Instruction) -> InstructionData {
+    InstructionData {
+        program_id: instruction.program_id,
+        accounts: instruction
+            .accounts
+            .into_iter()
+            .map(|meta| AccountMetaData { pubkey: meta.pubkey, is_signer: meta.is_signer, is_writable: meta.is_writable })
+            .collect(),
+        data: instruction.data,
+    }
+}
+
+/// Realm config matching the one realms are initialized with
+pub fn realm_config_args() -> RealmConfigArgs {
+    RealmConfigArgs {
+        min_community_tokens_to_create_proposal: ProposalThresholdSource::Absolute { value: 1 },
+        unstaking_cooldown_seconds: DEFAULT_UNSTAKING_COOLDOWN_SECONDS,
+        sybil_oracle: None,
+        default_sybil_resistance_score: 100,
+        quadratic_weighted_vote_policy: QuadraticWeightedVotePolicy::Disallowed,
+        max_vote_records_per_proposal: DEFAULT_MAX_VOTE_RECORDS_PER_PROPOSAL,
+        proposal_creation_fee: None,
+        abstain_counts_for_quorum: true,
+        voting_extends_cooldown: false,
+        min_record_age_slots: 0,
+        normalize_vote_weight_to_decimals: false,
+        lockup_vote_weight: None,
+        allow_plain_text_description_link: false,
+        inactivity_threshold_seconds: None,
+        quadratic_credit_budget: None,
+        permissioned_proposal_creation: false,
+        weight_oracle: None,
+        early_unstake_fee: None,
+        max_vote_weight_per_voter: None,
+        default_voting_period_days: DEFAULT_VOTING_PERIOD_DAYS,
+        auto_relinquish_finalized: false,
+        lock_stake_until_execution: false,
+    }
+}
+
+impl ProgramTest {
+    /// Creates a realm with community and council mints, whose max vote weight is the
+    /// community mint's supply
+    pub fn with_realm(&mut self) -> RealmCookie {
+        self.create_realm(false, 6)
+    }
+
+    /// Creates a realm as with_realm, whose vote weights are the square root of the stake
+    pub fn with_quadratic_realm(&mut self) -> RealmCookie {
+        self.create_realm(true, 6)
+    }
+
+    /// Creates a quadratic realm as with_quadratic_realm, over a community mint with the decimals
+    pub fn with_quadratic_realm_decimals(&mut self, community_mint_decimals: u8) -> RealmCookie {
+        self.create_realm(true, community_mint_decimals)
+    }
+
+    fn create_realm(&mut self, use_quadratic_voting: bool, community_mint_decimals: u8) -> RealmCookie {
+        let authority = self.create_wallet();
+        let community_mint = self.create_mint(community_mint_decimals);
+        let council_mint = self.create_mint(0);
+        let name = "Leapfrog".to_string();
+        self.process(&instruction::initialize_realm(
+            &crate::id(),
+            &authority,
+            &community_mint,
+            &council_mint,
+            name.clone(),
+            ProposalThresholdSource::Absolute { value: 1 },
+            MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE },
+            use_quadratic_voting,
+            None,
+            0,
+            false,
+        ))
+        .unwrap();
+
+        let address = get_realm_address(&crate::id(), &name, &community_mint, 0);
+        let (vault_authority, _) = get_vault_authority_address(&crate::id(), &address);
+        let staking_vault = self.create_token_account(&community_mint, &vault_authority, 0);
+
+        RealmCookie { address, authority, community_mint, council_mint, staking_vault }
+    }
+
+    /// Creates a wallet holding the amount of community tokens and stakes all of them
+    pub fn with_token_owner(&mut self, realm: &RealmCookie, amount: u64) -> TokenOwnerCookie {
+        let owner = self.create_wallet();
+        let token_account = self.create_token_account(&realm.community_mint, &owner, amount);
+        self.process(&instruction::stake_tokens(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &owner,
+            &token_account,
+            &realm.staking_vault,
+            amount,
+        ))
+        .unwrap();
+
+        let token_owner_record =
+            get_token_owner_record_address(&crate::id(), &realm.address, &realm.community_mint, &owner);
+        TokenOwnerCookie { owner, token_account, token_owner_record }
+    }
+
+    /// Creates a governance of a new governed account, funded by the realm authority
+    pub fn with_governance(&mut self, realm: &RealmCookie, config: GovernanceConfig) -> GovernanceCookie {
+        let governed_account = Pubkey::new_unique();
+        self.process(&instruction::create_governance(
+            &crate::id(),
+            &realm.address,
+            &realm.authority,
+            None,
+            &[],
+            governed_account,
+            config,
+        ))
+        .unwrap();
+
+        let address = get_governance_address(&crate::id(), &realm.address, &governed_account);
+        GovernanceCookie { address }
+    }
+
+    /// Creates a draft Yes / No proposal owned by the token owner
+    pub fn with_proposal(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+    ) -> ProposalCookie {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: owner.owner };
+        self.create_proposal(realm, governance, owner, &proposal).unwrap();
+        proposal
+    }
+
+    /// Creates a draft Yes / No proposal owned by the token owner at the cookie's address,
+    /// paying the realm's creation fee if it charges one
+    pub fn create_proposal(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+        proposal: &ProposalCookie,
+    ) -> ProgramResult {
+        self.create_proposal_with_args(realm, governance, owner, proposal, proposal_args())
+    }
+
+    /// Creates a draft proposal with the arguments, owned by the token owner
+    pub fn with_proposal_args(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+        args: ProposalArgs,
+    ) -> ProposalCookie {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: owner.owner };
+        self.create_proposal_with_args(realm, governance, owner, &proposal, args).unwrap();
+        proposal
+    }
+
+    /// Creates a draft proposal with the arguments at the cookie's address, paying the realm's
+    /// creation fee if it charges one
+    pub fn create_proposal_with_args(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+        proposal: &ProposalCookie,
+        args: ProposalArgs,
+    ) -> ProgramResult {
+        let create_proposal_ix = self.create_proposal_instruction(realm, governance, owner, proposal, args);
+        self.process(&create_proposal_ix)
+    }
+
+    /// Returns the CreateProposal instruction of create_proposal_with_args
+    pub fn create_proposal_instruction(
+        &self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+        proposal: &ProposalCookie,
+        args: ProposalArgs,
+    ) -> Instruction {
+        let treasury = self.get_account_data::<Realm>(&realm.address).proposal_creation_fee.map(|fee| fee.treasury);
+        instruction::create_proposal(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &owner.owner,
+            &realm.community_mint,
+            &owner.owner,
+            treasury.as_ref(),
+            None,
+            "Proposal".to_string(),
+            args.description_link,
+            args.vote_type,
+            args.options,
+            args.use_denial_quorum,
+            args.voting_period_days,
+            args.per_voter_option_cap,
+            args.impact_tier,
+            args.depends_on,
+            args.description_hash,
+            args.parameter_change,
+            args.fallback_option_index,
+        )
+    }
+
+    /// Creates a proposal and signs it off, opening it for voting
+    pub fn with_signed_off_proposal(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+    ) -> ProposalCookie {
+        let proposal = self.with_proposal(realm, governance, owner);
+        self.sign_off_proposal(realm, governance, &proposal).unwrap();
+        proposal
+    }
+
+    pub fn sign_off_proposal(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+    ) -> ProgramResult {
+        self.process(&instruction::sign_off_proposal(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &proposal.owner,
+        ))
+    }
+
+    /// Creates the voter's vote record and casts the vote with their whole deposit
+    pub fn cast_vote(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+        voter: &TokenOwnerCookie,
+        vote: Vote,
+    ) -> ProgramResult {
+        self.process(&instruction::create_vote_record(
+            &crate::id(),
+            &realm.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.owner,
+        ))?;
+
+        let staked_amount = self.token_owner_deposit(voter);
+        self.process(&instruction::cast_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.token_account,
+            None,
+            vote,
+            staked_amount,
+            None,
+        ))
+    }
+
+    /// Relinquishes the vote the token owner cast with their own deposit
+    pub fn relinquish_vote(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+        voter: &TokenOwnerCookie,
+    ) -> ProgramResult {
+        self.process(&instruction::relinquish_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &voter.owner,
+            &voter.owner,
+            &voter.owner,
+        ))
+    }
+
+    /// Finalizes the vote, paid by a new wallet, once the realm has archived fewer than a
+    /// page of proposals
+    pub fn finalize_vote(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+    ) -> ProgramResult {
+        let payer = self.create_wallet();
+        self.process(&instruction::finalize_vote(
+            &crate::id(),
+            &realm.address,
+            &governance.address,
+            &proposal.address,
+            &realm.community_mint,
+            &payer,
+            0,
+        ))
+    }
+
+    /// Inserts the instructions as the transaction of the proposal's option at the index
+    pub fn insert_transaction(
+        &mut self,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+        option_index: u8,
+        transaction_index: u8,
+        instructions: Vec<InstructionData>,
+    ) -> ProgramResult {
+        self.process(&instruction::insert_proposal_transaction(
+            &crate::id(),
+            &governance.address,
+            &proposal.address,
+            &proposal.owner,
+            option_index,
+            transaction_index,
+            instructions,
+        ))
+    }
+
+    /// Creates a proposal whose Yes option runs each of the given transactions, then signs it
+    /// off, votes Yes with the owner's whole deposit and finalizes it once voting has ended
+    pub fn with_approved_proposal(
+        &mut self,
+        realm: &RealmCookie,
+        governance: &GovernanceCookie,
+        owner: &TokenOwnerCookie,
+        transactions: Vec<Vec<InstructionData>>,
+    ) -> ProposalCookie {
+        let proposal = self.with_proposal(realm, governance, owner);
+        for (transaction_index, instructions) in transactions.into_iter().enumerate() {
+            self.insert_transaction(governance, &proposal, 0, transaction_index as u8, instructions).unwrap();
+        }
+        self.sign_off_proposal(realm, governance, &proposal).unwrap();
+        self.cast_vote(realm, governance, &proposal, owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+        self.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        self.finalize_vote(realm, governance, &proposal).unwrap();
+        proposal
+    }
+
+    /// Executes the transaction of the proposal's Yes option at the index, signed by its owner
+    pub fn execute_transaction(
+        &mut self,
+        governance: &GovernanceCookie,
+        proposal: &ProposalCookie,
+        owner: &TokenOwnerCookie,
+        transaction_index: u8,
+        instructions: &[InstructionData],
+    ) -> ProgramResult {
+        self.process(&instruction::execute_proposal(
+            &crate::id(),
+            &governance.address,
+            &proposal.address,
+            &owner.token_owner_record,
+            &owner.owner,
+            None,
+            0,
+            transaction_index,
+            instructions,
+        ))
+    }
+
+    /// Sets the realm config as its bootstrap authority
+    pub fn set_realm_config(&mut self, realm: &RealmCookie, config_args: RealmConfigArgs) -> ProgramResult {
+        self.process(&instruction::set_realm_config(&crate::id(), &realm.address, &realm.authority, &[], config_args))
+    }
+
+    pub fn token_owner_deposit(&self, token_owner: &TokenOwnerCookie) -> u64 {
+        self.get_account_data::<crate::TokenOwnerRecord>(&token_owner.token_owner_record)
+            .governing_token_deposit_amount
+    }
+}
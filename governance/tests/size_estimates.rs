@@ -0,0 +1,34 @@
+//! Account sizes clients compute to fund the rent of the accounts instructions create
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, ProposalArgs},
+};
+use crate::{
+    estimate_proposal_size, estimate_realm_size, estimate_vote_record_size, get_vote_record_address, Proposal, Vote,
+    VoteType,
+};
+use solana_program::rent::Rent;
+
+#[test]
+fn estimates_fund_the_rent_of_the_created_accounts() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    assert_eq!(pt.lamports(&realm.address), Rent::default().minimum_balance(estimate_realm_size("Leapfrog").unwrap()));
+
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let options = vec!["Alpha".to_string(), "Beta".to_string(), "Gamma".to_string()];
+    let args = ProposalArgs { vote_type: VoteType::RankedChoice, options: options.clone(), ..proposal_args() };
+    let description_link = args.description_link.clone();
+    let proposal = pt.with_proposal_args(&realm, &governance, &voter, args);
+    let proposal_size =
+        estimate_proposal_size("Proposal", &description_link, &VoteType::RankedChoice, &options).unwrap();
+    assert_eq!(pt.lamports(&proposal.address), Rent::default().minimum_balance(proposal_size));
+
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::RankedChoice { ranking: vec![2, 0, 1] }).unwrap();
+    let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &voter.token_owner_record);
+    let vote_record_size = estimate_vote_record_size(&pt.get_account_data::<Proposal>(&proposal.address));
+    assert_eq!(pt.lamports(&vote_record), Rent::default().minimum_balance(vote_record_size));
+}
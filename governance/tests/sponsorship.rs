@@ -0,0 +1,74 @@
+//! Co-sponsorship of proposals by token owners before they can be signed off
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, MAX_PROPOSAL_SPONSORS};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+
+fn add_sponsor(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    proposal: &ProposalCookie,
+    governance: &Pubkey,
+    sponsor: &TokenOwnerCookie,
+) -> ProgramResult {
+    pt.process(&instruction::add_sponsor(
+        &crate::id(),
+        &realm.address,
+        governance,
+        &proposal.address,
+        &realm.community_mint,
+        &sponsor.owner,
+    ))
+}
+
+#[test]
+fn sign_off_waits_for_enough_distinct_sponsors_with_the_min_stake() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let proposer = pt.with_token_owner(&realm, 1_000);
+    let (first, second) = (pt.with_token_owner(&realm, 100), pt.with_token_owner(&realm, 100));
+    let small_holder = pt.with_token_owner(&realm, 99);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig { required_sponsors: 2, min_sponsor_tokens: 100, ..governance_config() },
+    );
+    let proposal = pt.with_proposal(&realm, &governance, &proposer);
+    let sponsor = |pt: &mut ProgramTest, sponsor| add_sponsor(pt, &realm, &proposal, &governance.address, sponsor);
+
+    assert_eq!(pt.sign_off_proposal(&realm, &governance, &proposal), Err(GovernanceError::NotEnoughSponsors.into()));
+    assert_eq!(sponsor(&mut pt, &proposer), Err(GovernanceError::InvalidSponsor.into()));
+    assert_eq!(sponsor(&mut pt, &small_holder), Err(GovernanceError::NotEnoughTokensToSponsor.into()));
+
+    sponsor(&mut pt, &first).unwrap();
+    assert_eq!(sponsor(&mut pt, &first), Err(GovernanceError::InvalidSponsor.into()));
+    assert_eq!(pt.sign_off_proposal(&realm, &governance, &proposal), Err(GovernanceError::NotEnoughSponsors.into()));
+
+    sponsor(&mut pt, &second).unwrap();
+    pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+    let signed_off = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(signed_off.sponsors, vec![first.owner, second.owner]);
+    assert_eq!(signed_off.state, ProposalState::Active);
+    assert_eq!(sponsor(&mut pt, &small_holder), Err(GovernanceError::InvalidProposalState.into()));
+}
+
+#[test]
+fn sponsors_are_capped_per_proposal() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let proposer = pt.with_token_owner(&realm, 1_000);
+    let sponsors: Vec<_> = (0..=MAX_PROPOSAL_SPONSORS).map(|_| pt.with_token_owner(&realm, 1)).collect();
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_proposal(&realm, &governance, &proposer);
+
+    let (last, sponsors) = sponsors.split_last().unwrap();
+    for sponsor in sponsors {
+        add_sponsor(&mut pt, &realm, &proposal, &governance.address, sponsor).unwrap();
+    }
+    assert_eq!(
+        add_sponsor(&mut pt, &realm, &proposal, &governance.address, last),
+        Err(GovernanceError::TooManySponsors.into())
+    );
+}
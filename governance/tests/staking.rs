@@ -0,0 +1,308 @@
+//! Staking into and unstaking out of a realm's staking vault
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, realm_config_args, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_average_stake, get_vault_authority_address, get_vote_record_address, instruction, EarlyUnstakeFee,
+    GovernanceError, Proposal, Realm, RealmConfigArgs, StakeTranche, TokenOwnerRecord, Vote,
+    DEFAULT_UNSTAKING_COOLDOWN_SECONDS, DEFAULT_VOTING_PERIOD_DAYS, MAX_STAKE_TRANCHES,
+};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+/// Stakes more of the token owner's tokens, given to them in a new token account
+fn stake_more(pt: &mut ProgramTest, realm: &RealmCookie, token_owner: &TokenOwnerCookie, amount: u64) {
+    let source_token = pt.create_token_account(&realm.community_mint, &token_owner.owner, amount);
+    pt.process(&instruction::stake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &source_token,
+        &realm.staking_vault,
+        amount,
+    ))
+    .unwrap();
+}
+
+fn unstake(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    token_owner: &TokenOwnerCookie,
+    destination_token: &Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    pt.process(&instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        destination_token,
+        amount,
+    ))
+}
+
+#[test]
+fn only_matured_tranches_are_unstaked() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let first_stake_time = pt.clock().unix_timestamp as u64;
+    let token_owner = pt.with_token_owner(&realm, 600);
+    pt.advance_clock(DEFAULT_UNSTAKING_COOLDOWN_SECONDS / 2);
+    stake_more(&mut pt, &realm, &token_owner, 400);
+
+    let unlock_times = [first_stake_time, first_stake_time + DEFAULT_UNSTAKING_COOLDOWN_SECONDS / 2]
+        .map(|stake_time| stake_time + DEFAULT_UNSTAKING_COOLDOWN_SECONDS);
+    assert_eq!(
+        pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).stake_tranches,
+        vec![
+            StakeTranche { amount: 600, unlock_time: unlock_times[0] },
+            StakeTranche { amount: 400, unlock_time: unlock_times[1] },
+        ]
+    );
+
+    // Once the first tranche has matured, only its tokens can be taken out
+    pt.advance_clock(DEFAULT_UNSTAKING_COOLDOWN_SECONDS / 2);
+    assert_eq!(
+        unstake(&mut pt, &realm, &token_owner, &token_owner.token_account, 601),
+        Err(GovernanceError::UnstakingCooldownActive.into())
+    );
+    unstake(&mut pt, &realm, &token_owner, &token_owner.token_account, 600).unwrap();
+
+    let token_owner_record = pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record);
+    assert_eq!(token_owner_record.governing_token_deposit_amount, 400);
+    assert_eq!(token_owner_record.stake_tranches, vec![StakeTranche { amount: 400, unlock_time: unlock_times[1] }]);
+    assert_eq!(token_owner_record.earliest_unstaking_time, unlock_times[1]);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&token_owner.token_account).amount, 600);
+}
+
+#[test]
+fn stakes_past_the_tranche_cap_are_consolidated_into_the_newest_tranche() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 100);
+    for _ in 1..MAX_STAKE_TRANCHES {
+        pt.advance_clock(100);
+        stake_more(&mut pt, &realm, &token_owner, 100);
+    }
+    let newest_unlock_time = pt.clock().unix_timestamp as u64 + DEFAULT_UNSTAKING_COOLDOWN_SECONDS;
+
+    // 300 tokens unlocking 400 seconds after the newest tranche's 100 move its unlock time
+    // forward by three quarters of that
+    pt.advance_clock(400);
+    stake_more(&mut pt, &realm, &token_owner, 300);
+
+    let stake_tranches = pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).stake_tranches;
+    assert_eq!(stake_tranches.len(), MAX_STAKE_TRANCHES);
+    assert_eq!(stake_tranches.last(), Some(&StakeTranche { amount: 400, unlock_time: newest_unlock_time + 300 }));
+}
+
+#[test]
+fn voting_extends_the_cooldown_past_the_proposal_only_under_the_flag() {
+    for voting_extends_cooldown in [true, false] {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        pt.set_realm_config(
+            &realm,
+            RealmConfigArgs { voting_extends_cooldown, auto_relinquish_finalized: true, ..realm_config_args() },
+        )
+        .unwrap();
+        let token_owner = pt.with_token_owner(&realm, 1_000);
+        let staked_unlock_time = pt.clock().unix_timestamp as u64 + DEFAULT_UNSTAKING_COOLDOWN_SECONDS;
+        let governance = pt.with_governance(&realm, governance_config());
+        let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+        let voting_ends_at = pt.get_account_data::<Proposal>(&proposal.address).voting_ends_at;
+
+        pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+        let earliest_unstaking_time =
+            pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).earliest_unstaking_time;
+
+        // Once the vote is relinquished only the cooldown holds the stake back
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+        let vote_record = get_vote_record_address(&crate::id(), &proposal.address, &token_owner.token_owner_record);
+        pt.process(&instruction::auto_relinquish_finalized(
+            &crate::id(),
+            &proposal.address,
+            &[(vote_record, token_owner.token_owner_record)],
+        ))
+        .unwrap();
+        assert!(pt.clock().unix_timestamp as u64 >= staked_unlock_time);
+        let unstake_result = unstake(&mut pt, &realm, &token_owner, &token_owner.token_account, 1_000);
+
+        if voting_extends_cooldown {
+            assert_eq!(earliest_unstaking_time, voting_ends_at + DEFAULT_UNSTAKING_COOLDOWN_SECONDS);
+            assert_eq!(unstake_result, Err(GovernanceError::UnstakingCooldownActive.into()));
+        } else {
+            assert_eq!(earliest_unstaking_time, staked_unlock_time);
+            unstake_result.unwrap();
+        }
+    }
+}
+
+#[test]
+fn emergency_unstake_opens_once_the_realm_has_been_inactive_long_enough() {
+    const INACTIVITY_THRESHOLD_SECONDS: u64 = 30 * 86_400;
+
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(
+        &realm,
+        RealmConfigArgs { inactivity_threshold_seconds: Some(INACTIVITY_THRESHOLD_SECONDS), ..realm_config_args() },
+    )
+    .unwrap();
+    let voter = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &voter);
+    pt.cast_vote(&realm, &governance, &proposal, &voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+    let emergency_unstake = |pt: &mut ProgramTest, token_owner: &TokenOwnerCookie, amount| {
+        pt.process(&instruction::emergency_unstake(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            &realm.staking_vault,
+            &token_owner.token_account,
+            amount,
+        ))
+    };
+
+    // Past the cooldown, the vote on the never finalized proposal still holds the stake
+    pt.advance_clock(INACTIVITY_THRESHOLD_SECONDS - 1);
+    assert_eq!(
+        unstake(&mut pt, &realm, &voter, &voter.token_account, 1_000),
+        Err(GovernanceError::OutstandingVotes.into())
+    );
+    assert_eq!(emergency_unstake(&mut pt, &voter, 1_000), Err(GovernanceError::RealmNotInactive.into()));
+
+    // Inactivity doesn't lift the cooldown of a recent stake
+    let late_staker = pt.with_token_owner(&realm, 500);
+    pt.advance_clock(1);
+    assert_eq!(emergency_unstake(&mut pt, &late_staker, 500), Err(GovernanceError::UnstakingCooldownActive.into()));
+
+    emergency_unstake(&mut pt, &voter, 1_000).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&voter.token_account).amount, 1_000);
+    let record = pt.get_account_data::<TokenOwnerRecord>(&voter.token_owner_record);
+    assert_eq!((record.governing_token_deposit_amount, record.unrelinquished_votes_count), (0, 0));
+}
+
+#[test]
+fn unstaking_pays_out_signed_by_the_derived_vault_authority() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let destination_token = pt.create_token_account(&realm.community_mint, &token_owner.owner, 0);
+
+    // Only the realm's vault authority can sign for the vault
+    let mut impostor_unstake = instruction::unstake_tokens(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        &token_owner.owner,
+        &realm.staking_vault,
+        &destination_token,
+        1_000,
+    );
+    impostor_unstake.accounts.last_mut().unwrap().pubkey = Pubkey::new_unique();
+    assert_eq!(pt.process(&impostor_unstake), Err(GovernanceError::InvalidAccountAddress.into()));
+
+    // Realms created before the bump was stored derive it
+    let mut stored_realm = pt.get_account_data::<Realm>(&realm.address);
+    let vault_authority_bump_seed = stored_realm.vault_authority_bump_seed;
+    assert_eq!(vault_authority_bump_seed, get_vault_authority_address(&crate::id(), &realm.address).1);
+    stored_realm.vault_authority_bump_seed = 0;
+    pt.set_account_data(&realm.address, &stored_realm);
+    unstake(&mut pt, &realm, &token_owner, &destination_token, 400).unwrap();
+
+    stored_realm.vault_authority_bump_seed = vault_authority_bump_seed;
+    pt.set_account_data(&realm.address, &stored_realm);
+    unstake(&mut pt, &realm, &token_owner, &destination_token, 600).unwrap();
+
+    assert_eq!(pt.get_token_state::<TokenAccount>(&destination_token).amount, 1_000);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&realm.staking_vault).amount, 0);
+}
+
+#[test]
+fn early_unstake_pays_the_fee_to_the_treasury() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let patient_owner = pt.with_token_owner(&realm, 500);
+    let treasury = pt.create_token_account(&realm.community_mint, &Pubkey::new_unique(), 0);
+    let destination_token = pt.create_token_account(&realm.community_mint, &token_owner.owner, 0);
+    let unstake_early = |amount| {
+        instruction::unstake_early(
+            &crate::id(),
+            &realm.address,
+            &realm.community_mint,
+            &token_owner.owner,
+            &realm.staking_vault,
+            &destination_token,
+            &treasury,
+            amount,
+        )
+    };
+    assert_eq!(pt.process(&unstake_early(400)), Err(GovernanceError::EarlyUnstakeNotAllowed.into()));
+
+    let early_unstake_fee = Some(EarlyUnstakeFee { fee_bps: 250, treasury });
+    pt.set_realm_config(&realm, RealmConfigArgs { early_unstake_fee, ..realm_config_args() }).unwrap();
+    assert_eq!(
+        unstake(&mut pt, &realm, &token_owner, &destination_token, 400),
+        Err(GovernanceError::UnstakingCooldownActive.into())
+    );
+    pt.process(&unstake_early(400)).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&destination_token).amount, 390);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&treasury).amount, 10);
+    assert_eq!(pt.token_owner_deposit(&token_owner), 600);
+
+    // Votes still hold the stake
+    let governance = pt.with_governance(&realm, governance_config());
+    let proposal = pt.with_signed_off_proposal(&realm, &governance, &token_owner);
+    pt.cast_vote(&realm, &governance, &proposal, &token_owner, Vote::SingleChoice { option_index: 0 }).unwrap();
+    assert_eq!(pt.process(&unstake_early(600)), Err(GovernanceError::OutstandingVotes.into()));
+
+    // Once the cooldown has ended, unstaking is fee-free
+    pt.advance_clock(DEFAULT_UNSTAKING_COOLDOWN_SECONDS);
+    unstake(&mut pt, &realm, &patient_owner, &patient_owner.token_account, 500).unwrap();
+    assert_eq!(pt.get_token_state::<TokenAccount>(&patient_owner.token_account).amount, 500);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&treasury).amount, 10);
+}
+
+#[test]
+fn stake_seconds_give_the_time_weighted_average_stake() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    pt.set_realm_config(&realm, RealmConfigArgs { unstaking_cooldown_seconds: 0, ..realm_config_args() }).unwrap();
+    let started_at = pt.clock().unix_timestamp as u64;
+    let token_owner = pt.with_token_owner(&realm, 600);
+    let stake_seconds_at = |pt: &ProgramTest, now: u64| {
+        pt.get_account_data::<TokenOwnerRecord>(&token_owner.token_owner_record).get_cumulative_stake_seconds(now)
+    };
+    assert_eq!(stake_seconds_at(&pt, started_at), 0);
+
+    // 600 held for 100 seconds, 1000 for 100 seconds, then 500 for 100 seconds
+    pt.advance_clock(100);
+    stake_more(&mut pt, &realm, &token_owner, 400);
+    let restaked_at = started_at + 100;
+    let restaked_stake_seconds = stake_seconds_at(&pt, restaked_at);
+    assert_eq!(restaked_stake_seconds, 60_000);
+    pt.advance_clock(100);
+    unstake(&mut pt, &realm, &token_owner, &token_owner.token_account, 500).unwrap();
+    assert_eq!(stake_seconds_at(&pt, started_at + 200), 160_000);
+    pt.advance_clock(100);
+    let ended_at = started_at + 300;
+    assert_eq!(pt.clock().unix_timestamp as u64, ended_at);
+    let ended_stake_seconds = stake_seconds_at(&pt, ended_at);
+    assert_eq!(ended_stake_seconds, 210_000);
+
+    assert_eq!(get_average_stake(0, started_at, ended_stake_seconds, ended_at), Ok(700));
+    assert_eq!(get_average_stake(restaked_stake_seconds, restaked_at, ended_stake_seconds, ended_at), Ok(750));
+    assert_eq!(
+        get_average_stake(ended_stake_seconds, ended_at, ended_stake_seconds, ended_at),
+        Err(GovernanceError::InvalidStakeInterval.into())
+    );
+}
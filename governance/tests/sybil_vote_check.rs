@@ -0,0 +1,98 @@
+//! Flagging of proposals finalized with a suspiciously low vote weight per voter
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState, SybilFlagRaised, SybilVoteCheck, Vote,
+    DEFAULT_VOTING_PERIOD_DAYS,
+};
+
+/// A governance flagging proposals with less than 50 vote weight per voter, and requiring
+/// council review of flagged proposals before they execute
+struct SybilTest {
+    pt: ProgramTest,
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    proposal: ProposalCookie,
+    owner: TokenOwnerCookie,
+}
+
+impl SybilTest {
+    /// Finalizes a proposal each voter voted Yes on with their deposit
+    fn new(deposits: &[u64]) -> Self {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let mut voters: Vec<_> = deposits.iter().map(|deposit| pt.with_token_owner(&realm, *deposit)).collect();
+        let governance = pt.with_governance(
+            &realm,
+            GovernanceConfig {
+                sybil_vote_check: Some(SybilVoteCheck { min_vote_weight_per_voter: 50, require_council_review: true }),
+                ..governance_config()
+            },
+        );
+        let proposal = pt.with_proposal(&realm, &governance, &voters[0]);
+        pt.insert_transaction(&governance, &proposal, 0, 0, vec![]).unwrap();
+        pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+        for voter in &voters {
+            pt.cast_vote(&realm, &governance, &proposal, voter, Vote::SingleChoice { option_index: 0 }).unwrap();
+        }
+        pt.advance_clock(DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400);
+        pt.finalize_vote(&realm, &governance, &proposal).unwrap();
+
+        let owner = voters.swap_remove(0);
+        Self { pt, realm, governance, proposal, owner }
+    }
+
+    fn proposal(&self) -> Proposal {
+        self.pt.get_account_data::<Proposal>(&self.proposal.address)
+    }
+}
+
+#[test]
+fn many_dust_voters_flag_the_proposal_for_council_review() {
+    // Twenty voters of 10 tokens each make up the quorum
+    let mut test = SybilTest::new(&[10; 20]);
+    let proposal = test.proposal();
+    assert_eq!(proposal.state, ProposalState::Approved);
+    assert!(proposal.is_sybil_flagged);
+    assert_eq!(
+        test.pt.last_event::<SybilFlagRaised>(),
+        SybilFlagRaised {
+            proposal: test.proposal.address,
+            total_vote_weight: 200,
+            unique_voter_count: 20,
+            min_vote_weight_per_voter: 50,
+        }
+    );
+
+    let SybilTest { pt, realm, governance, proposal, owner } = &mut test;
+    assert_eq!(
+        pt.execute_transaction(governance, proposal, owner, 0, &[]),
+        Err(GovernanceError::SybilReviewPending.into())
+    );
+    let council_member = pt.create_wallet();
+    let council_token_account = pt.create_token_account(&realm.council_mint, &council_member, 1);
+    pt.process(&instruction::review_sybil_flag(
+        &crate::id(),
+        &realm.address,
+        &governance.address,
+        &proposal.address,
+        &council_member,
+        &council_token_account,
+    ))
+    .unwrap();
+    pt.execute_transaction(governance, proposal, owner, 0, &[]).unwrap();
+    assert_eq!(test.proposal().sybil_reviewed_by, Some(council_member));
+}
+
+#[test]
+fn normal_turnout_is_not_flagged() {
+    let mut test = SybilTest::new(&[600, 300, 100]);
+    assert!(!test.proposal().is_sybil_flagged);
+
+    let SybilTest { pt, governance, proposal, owner, .. } = &mut test;
+    pt.execute_transaction(governance, proposal, owner, 0, &[]).unwrap();
+    assert_eq!(test.proposal().state, ProposalState::Executed);
+}
@@ -0,0 +1,139 @@
+//! Treasury payouts streamed to a recipient by an executed proposal
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, to_instruction_data, GovernanceCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{
+    get_vesting_stream_address, instruction, GovernanceError, InstructionData, VestingStream, DEFAULT_VOTING_PERIOD_DAYS,
+};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+const VOTING_PERIOD_SECONDS: u64 = DEFAULT_VOTING_PERIOD_DAYS as u64 * 86_400;
+
+struct VestingCookie {
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    token_owner: TokenOwnerCookie,
+    treasury: Pubkey,
+    recipient: Pubkey,
+    destination_token: Pubkey,
+    address: Pubkey,
+    start_time: u64,
+}
+
+/// Streams 4,000 tokens from a governance treasury, starting once the proposal creating the
+/// stream has passed
+fn with_vesting_stream(pt: &mut ProgramTest, cliff_seconds: u64, duration_seconds: u64) -> VestingCookie {
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, governance_config());
+    let grant_mint = pt.create_mint(6);
+    let treasury = pt.create_token_account(&grant_mint, &governance.address, 10_000);
+    let recipient = pt.create_wallet();
+    let destination_token = pt.create_token_account(&grant_mint, &recipient, 0);
+
+    let start_time = pt.clock().unix_timestamp as u64 + VOTING_PERIOD_SECONDS;
+    let create_stream = to_instruction_data(instruction::create_vesting_stream(
+        &crate::id(),
+        &governance.address,
+        &treasury,
+        &token_owner.owner,
+        recipient,
+        4_000,
+        start_time,
+        start_time + cliff_seconds,
+        duration_seconds,
+        0,
+    ));
+    execute_proposal(pt, &realm, &governance, &token_owner, create_stream);
+
+    VestingCookie {
+        address: get_vesting_stream_address(&crate::id(), &governance.address, &recipient, 0),
+        realm,
+        governance,
+        token_owner,
+        treasury,
+        recipient,
+        destination_token,
+        start_time,
+    }
+}
+
+/// Passes a proposal running the instruction and executes it
+fn execute_proposal(
+    pt: &mut ProgramTest,
+    realm: &RealmCookie,
+    governance: &GovernanceCookie,
+    token_owner: &TokenOwnerCookie,
+    instruction: InstructionData,
+) {
+    let proposal = pt.with_approved_proposal(realm, governance, token_owner, vec![vec![instruction.clone()]]);
+    pt.execute_transaction(governance, &proposal, token_owner, 0, &[instruction]).unwrap();
+}
+
+fn claim(pt: &mut ProgramTest, stream: &VestingCookie) -> ProgramResult {
+    pt.process(&instruction::claim_vested_funds(
+        &crate::id(),
+        &stream.address,
+        &stream.governance.address,
+        &stream.treasury,
+        &stream.recipient,
+        &stream.destination_token,
+    ))
+}
+
+fn claimed_balance(pt: &ProgramTest, stream: &VestingCookie) -> u64 {
+    pt.get_token_state::<TokenAccount>(&stream.destination_token).amount
+}
+
+#[test]
+fn vested_funds_are_claimed_from_the_cliff_until_maturity() {
+    let mut pt = ProgramTest::new();
+    let stream = with_vesting_stream(&mut pt, 100_000, 400_000);
+    assert_eq!(pt.clock().unix_timestamp as u64, stream.start_time);
+
+    assert_eq!(claim(&mut pt, &stream), Err(GovernanceError::NothingToClaim.into()));
+
+    // At the cliff a quarter of the duration has vested at once
+    pt.advance_clock(100_000);
+    claim(&mut pt, &stream).unwrap();
+    assert_eq!(claimed_balance(&pt, &stream), 1_000);
+
+    // Partway through only what vested since the last claim is released
+    pt.advance_clock(150_000);
+    claim(&mut pt, &stream).unwrap();
+    assert_eq!(claimed_balance(&pt, &stream), 2_500);
+    assert_eq!(claim(&mut pt, &stream), Err(GovernanceError::NothingToClaim.into()));
+
+    pt.advance_clock(200_000);
+    claim(&mut pt, &stream).unwrap();
+    assert_eq!(claimed_balance(&pt, &stream), 4_000);
+    assert_eq!(pt.get_token_state::<TokenAccount>(&stream.treasury).amount, 6_000);
+    assert_eq!(pt.get_account_data::<VestingStream>(&stream.address).claimed_amount, 4_000);
+    assert_eq!(claim(&mut pt, &stream), Err(GovernanceError::NothingToClaim.into()));
+}
+
+#[test]
+fn cancelled_stream_stops_vesting() {
+    let mut pt = ProgramTest::new();
+    let stream = with_vesting_stream(&mut pt, 0, 4 * VOTING_PERIOD_SECONDS);
+
+    // The cancelling proposal passes a quarter of the way through the stream
+    let cancel_stream = to_instruction_data(instruction::cancel_vesting_stream(
+        &crate::id(),
+        &stream.governance.address,
+        &stream.address,
+    ));
+    execute_proposal(&mut pt, &stream.realm, &stream.governance, &stream.token_owner, cancel_stream);
+    assert_eq!(
+        pt.get_account_data::<VestingStream>(&stream.address).cancelled_at,
+        Some(stream.start_time + VOTING_PERIOD_SECONDS)
+    );
+
+    pt.advance_clock(3 * VOTING_PERIOD_SECONDS);
+    claim(&mut pt, &stream).unwrap();
+    assert_eq!(claimed_balance(&pt, &stream), 1_000);
+    assert_eq!(claim(&mut pt, &stream), Err(GovernanceError::NothingToClaim.into()));
+}
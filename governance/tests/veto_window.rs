@@ -0,0 +1,91 @@
+//! Council vetoes of approved proposals, and the execution delay running alongside the veto
+//! window
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, GovernanceCookie, ProposalCookie, RealmCookie, TokenOwnerCookie},
+};
+use crate::{instruction, GovernanceConfig, GovernanceError, Proposal, ProposalState};
+use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// An approved proposal of a governance with the veto window and execution delay, and a
+/// council member who can veto it
+struct VetoTest {
+    pt: ProgramTest,
+    realm: RealmCookie,
+    governance: GovernanceCookie,
+    owner: TokenOwnerCookie,
+    proposal: ProposalCookie,
+    council_member: Pubkey,
+    council_token_account: Pubkey,
+}
+
+impl VetoTest {
+    fn new(veto_window_seconds: u64, execution_delay_seconds: u64) -> Self {
+        let mut pt = ProgramTest::new();
+        let realm = pt.with_realm();
+        let owner = pt.with_token_owner(&realm, 1_000);
+        let governance = pt.with_governance(
+            &realm,
+            GovernanceConfig { veto_window_seconds, execution_delay_seconds, ..governance_config() },
+        );
+        let proposal = pt.with_approved_proposal(&realm, &governance, &owner, vec![vec![]]);
+        let council_member = pt.create_wallet();
+        let council_token_account = pt.create_token_account(&realm.council_mint, &council_member, 1);
+        Self { pt, realm, governance, owner, proposal, council_member, council_token_account }
+    }
+
+    fn veto(&mut self) -> ProgramResult {
+        self.pt.process(&instruction::veto_proposal(
+            &crate::id(),
+            &self.realm.address,
+            &self.governance.address,
+            &self.proposal.address,
+            &self.council_member,
+            &self.council_token_account,
+        ))
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        self.pt.execute_transaction(&self.governance, &self.proposal, &self.owner, 0, &[])
+    }
+
+    fn proposal_state(&self) -> ProposalState {
+        self.pt.get_account_data::<Proposal>(&self.proposal.address).state
+    }
+}
+
+#[test]
+fn veto_within_the_window_blocks_execution() {
+    let mut test = VetoTest::new(1_000, 3_000);
+
+    assert_eq!(test.execute(), Err(GovernanceError::ExecutionLocked.into()));
+    test.pt.advance_clock(999);
+
+    // Only holders of council tokens can veto
+    let council_token_account = test.council_token_account;
+    test.council_token_account = test.pt.create_token_account(&test.realm.council_mint, &test.council_member, 0);
+    assert_eq!(test.veto(), Err(GovernanceError::InvalidCouncilTokenAccount.into()));
+    test.council_token_account = council_token_account;
+    test.veto().unwrap();
+    assert_eq!(test.proposal_state(), ProposalState::Vetoed);
+
+    test.pt.advance_clock(3_000);
+    assert_eq!(test.execute(), Err(GovernanceError::InvalidProposalState.into()));
+}
+
+#[test]
+fn execution_waits_for_the_longer_of_the_veto_window_and_delay() {
+    for (veto_window_seconds, execution_delay_seconds) in [(3_000, 1_000), (1_000, 3_000)] {
+        let mut test = VetoTest::new(veto_window_seconds, execution_delay_seconds);
+
+        test.pt.advance_clock(2_999);
+        assert_eq!(test.execute(), Err(GovernanceError::ExecutionLocked.into()));
+        test.pt.advance_clock(1);
+        if veto_window_seconds > execution_delay_seconds {
+            assert_eq!(test.veto(), Err(GovernanceError::VetoWindowElapsed.into()));
+        }
+        test.execute().unwrap();
+        assert_eq!(test.proposal_state(), ProposalState::Executed);
+    }
+}
@@ -0,0 +1,103 @@
+//! Voting periods of proposals, resolved from the realm default and bounded by the governance's
+//! minimum at creation and sign-off
+
+use super::{
+    program_test::ProgramTest,
+    setup::{governance_config, proposal_args, realm_config_args, ProposalArgs, ProposalCookie},
+};
+use crate::{Governance, GovernanceConfig, GovernanceError, Proposal, RealmConfigArgs, MAX_VOTING_PERIOD_DAYS};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn voting_period_below_the_governance_floor_is_rejected() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let parameter_governance = pt.with_governance(&realm, governance_config());
+    let treasury_governance =
+        pt.with_governance(&realm, GovernanceConfig { min_voting_period_days: 3, ..governance_config() });
+    let two_days = || ProposalArgs { voting_period_days: 2, ..proposal_args() };
+
+    // Each governance sets its own floor
+    pt.with_proposal_args(&realm, &parameter_governance, &token_owner, two_days());
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    assert_eq!(
+        pt.create_proposal_with_args(&realm, &treasury_governance, &token_owner, &proposal, two_days()),
+        Err(GovernanceError::VotingPeriodTooShort.into())
+    );
+
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &treasury_governance,
+        &token_owner,
+        ProposalArgs { voting_period_days: 3, ..proposal_args() },
+    );
+    pt.sign_off_proposal(&realm, &treasury_governance, &proposal).unwrap();
+
+    let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+    assert_eq!(proposal.voting_ends_at - proposal.voting_starts_at, 3 * 86_400);
+}
+
+#[test]
+fn floor_raised_after_creation_is_enforced_at_sign_off() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(&realm, GovernanceConfig { min_voting_period_days: 3, ..governance_config() });
+    let proposal = pt.with_proposal_args(
+        &realm,
+        &governance,
+        &token_owner,
+        ProposalArgs { voting_period_days: 3, ..proposal_args() },
+    );
+
+    let mut governance_data = pt.get_account_data::<Governance>(&governance.address);
+    governance_data.config.min_voting_period_days = 4;
+    pt.set_account_data(&governance.address, &governance_data);
+
+    assert_eq!(
+        pt.sign_off_proposal(&realm, &governance, &proposal),
+        Err(GovernanceError::VotingPeriodTooShort.into())
+    );
+}
+
+#[test]
+fn omitted_voting_period_falls_back_to_the_realm_default() {
+    let mut pt = ProgramTest::new();
+    let realm = pt.with_realm();
+    let token_owner = pt.with_token_owner(&realm, 1_000);
+    let governance = pt.with_governance(
+        &realm,
+        GovernanceConfig { min_voting_period_days: 3, max_concurrent_proposals: 3, ..governance_config() },
+    );
+    for default_voting_period_days in [0, MAX_VOTING_PERIOD_DAYS + 1] {
+        assert_eq!(
+            pt.set_realm_config(&realm, RealmConfigArgs { default_voting_period_days, ..realm_config_args() }),
+            Err(GovernanceError::InvalidDefaultVotingPeriod.into())
+        );
+    }
+    pt.set_realm_config(&realm, RealmConfigArgs { default_voting_period_days: 5, ..realm_config_args() }).unwrap();
+
+    for (voting_period_days, resolved_days) in [(0, 5), (10, 10), (MAX_VOTING_PERIOD_DAYS, MAX_VOTING_PERIOD_DAYS)] {
+        let args = ProposalArgs { voting_period_days, ..proposal_args() };
+        let proposal = pt.with_proposal_args(&realm, &governance, &token_owner, args);
+        pt.sign_off_proposal(&realm, &governance, &proposal).unwrap();
+        let proposal = pt.get_account_data::<Proposal>(&proposal.address);
+        assert_eq!(proposal.voting_ends_at - proposal.voting_starts_at, resolved_days as u64 * 86_400);
+    }
+
+    // Explicit and default periods are both bounded
+    for (voting_period_days, error) in
+        [(2, GovernanceError::VotingPeriodTooShort), (MAX_VOTING_PERIOD_DAYS + 1, GovernanceError::VotingPeriodTooLong)]
+    {
+        let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+        let args = ProposalArgs { voting_period_days, ..proposal_args() };
+        assert_eq!(pt.create_proposal_with_args(&realm, &governance, &token_owner, &proposal, args), Err(error.into()));
+    }
+    pt.set_realm_config(&realm, RealmConfigArgs { default_voting_period_days: 2, ..realm_config_args() }).unwrap();
+    let proposal = ProposalCookie { address: Pubkey::new_unique(), owner: token_owner.owner };
+    assert_eq!(
+        pt.create_proposal_with_args(&realm, &governance, &token_owner, &proposal, proposal_args()),
+        Err(GovernanceError::VotingPeriodTooShort.into())
+    );
+}
@@ -0,0 +1,95 @@
+//! Claims of voting power from a realm's initial voting power snapshot
+
+use super::program_test::ProgramTest;
+use crate::{
+    get_realm_address, get_token_owner_record_address, get_voting_power_claim_leaf, instruction, GovernanceError,
+    MintMaxVoteWeightSource, ProposalThresholdSource, TokenOwnerRecord, SUPPLY_FRACTION_BASE,
+};
+use solana_program::{entrypoint::ProgramResult, keccak, pubkey::Pubkey};
+
+struct SnapshotRealm {
+    address: Pubkey,
+    community_mint: Pubkey,
+}
+
+/// Creates a realm whose snapshot is the merkle tree of the two leaves
+fn with_snapshot_realm(pt: &mut ProgramTest, leaves: [[u8; 32]; 2]) -> SnapshotRealm {
+    let funder = pt.create_wallet();
+    let community_mint = pt.create_mint(6);
+    let council_mint = pt.create_mint(0);
+    let [low, high] = if leaves[0] <= leaves[1] { leaves } else { [leaves[1], leaves[0]] };
+    let root = keccak::hashv(&[&low, &high]).to_bytes();
+    let name = "Snapshot".to_string();
+    pt.process(&instruction::initialize_realm(
+        &crate::id(),
+        &funder,
+        &community_mint,
+        &council_mint,
+        name.clone(),
+        ProposalThresholdSource::Absolute { value: 1 },
+        MintMaxVoteWeightSource::SupplyFraction { fraction: SUPPLY_FRACTION_BASE },
+        false,
+        Some(root),
+        0,
+        false,
+    ))
+    .unwrap();
+
+    SnapshotRealm { address: get_realm_address(&crate::id(), &name, &community_mint, 0), community_mint }
+}
+
+fn claim(
+    pt: &mut ProgramTest,
+    realm: &SnapshotRealm,
+    claimant: &Pubkey,
+    leaf_index: u32,
+    amount: u64,
+    proof: [u8; 32],
+) -> ProgramResult {
+    pt.process(&instruction::claim_initial_voting_power(
+        &crate::id(),
+        &realm.address,
+        &realm.community_mint,
+        claimant,
+        leaf_index,
+        amount,
+        vec![proof],
+    ))
+}
+
+#[test]
+fn snapshot_amount_is_credited_once() {
+    let mut pt = ProgramTest::new();
+    let (alice, bob) = (pt.create_wallet(), pt.create_wallet());
+    let leaves = [get_voting_power_claim_leaf(0, &alice, 500), get_voting_power_claim_leaf(1, &bob, 250)];
+    let realm = with_snapshot_realm(&mut pt, leaves);
+
+    claim(&mut pt, &realm, &alice, 0, 500, leaves[1]).unwrap();
+    let token_owner_record = get_token_owner_record_address(&crate::id(), &realm.address, &realm.community_mint, &alice);
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner_record).claimed_voting_power, 500);
+
+    assert_eq!(
+        claim(&mut pt, &realm, &alice, 0, 500, leaves[1]),
+        Err(GovernanceError::VotingPowerAlreadyClaimed.into())
+    );
+    assert_eq!(pt.get_account_data::<TokenOwnerRecord>(&token_owner_record).claimed_voting_power, 500);
+}
+
+#[test]
+fn claim_not_in_the_snapshot_is_rejected() {
+    let mut pt = ProgramTest::new();
+    let (alice, bob) = (pt.create_wallet(), pt.create_wallet());
+    let leaves = [get_voting_power_claim_leaf(0, &alice, 500), get_voting_power_claim_leaf(1, &bob, 250)];
+    let realm = with_snapshot_realm(&mut pt, leaves);
+
+    // An inflated amount, someone else's leaf and a wrong sibling all miss the root
+    for (claimant, leaf_index, amount, proof) in
+        [(&bob, 1, 2_500, leaves[0]), (&bob, 0, 500, leaves[1]), (&alice, 0, 500, [7; 32])]
+    {
+        assert_eq!(
+            claim(&mut pt, &realm, claimant, leaf_index, amount, proof),
+            Err(GovernanceError::InvalidMerkleProof.into())
+        );
+    }
+    claim(&mut pt, &realm, &bob, 1, 250, leaves[0]).unwrap();
+}